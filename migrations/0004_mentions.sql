@@ -0,0 +1,17 @@
+-- People mentioned as @name in bullet content
+CREATE TABLE IF NOT EXISTS mentions (
+    bullet_id INTEGER NOT NULL,
+    date DATE NOT NULL,
+    name TEXT NOT NULL -- lowercased, without the leading @
+);
+
+CREATE INDEX IF NOT EXISTS idx_mentions_name ON mentions(name);
+CREATE INDEX IF NOT EXISTS idx_mentions_date ON mentions(date);
+
+-- Backfill mentions for bullets written before this migration
+INSERT INTO mentions (bullet_id, date, name)
+SELECT id, date, lower(name)
+FROM (
+    SELECT id, date, unnest(regexp_extract_all(content, '(?:^|[^@\w])@([A-Za-z][\w-]*)', 1)) AS name
+    FROM bullets
+);
@@ -0,0 +1,12 @@
+-- Read-only events imported from external calendars (.ics)
+CREATE TABLE IF NOT EXISTS external_events (
+    source TEXT NOT NULL, -- file path or URL the event was imported from
+    uid TEXT,
+    date DATE NOT NULL,
+    start_time TIME, -- NULL for all-day events
+    end_time TIME,
+    summary TEXT NOT NULL,
+    location TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_external_events_date ON external_events(date);
@@ -0,0 +1,19 @@
+-- Tasks waiting for other tasks, written as !after:word or !after:"several words"
+CREATE TABLE IF NOT EXISTS task_dependencies (
+    bullet_id INTEGER NOT NULL, -- the dependent task
+    date DATE NOT NULL,
+    blocker TEXT NOT NULL -- lowercased text matched against other tasks
+);
+
+CREATE INDEX IF NOT EXISTS idx_task_dependencies_date ON task_dependencies(date);
+
+-- Backfill dependencies for tasks written before this migration
+INSERT INTO task_dependencies (bullet_id, date, blocker)
+SELECT id, date, lower(blocker)
+FROM (
+    SELECT id, date, unnest(regexp_extract_all(content, '!after:"([^"]+)"', 1)) AS blocker
+    FROM bullets
+    UNION ALL
+    SELECT id, date, unnest(regexp_extract_all(content, '!after:([^"\s]\S*)', 1)) AS blocker
+    FROM bullets
+);
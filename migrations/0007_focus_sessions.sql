@@ -0,0 +1,10 @@
+-- Time tracked in focus mode
+CREATE TABLE IF NOT EXISTS focus_sessions (
+    date DATE NOT NULL,
+    task TEXT NOT NULL,
+    started_at TIMESTAMP NOT NULL,
+    duration_seconds INTEGER NOT NULL,
+    completed BOOLEAN NOT NULL DEFAULT FALSE -- whether the task was marked done
+);
+
+CREATE INDEX IF NOT EXISTS idx_focus_sessions_date ON focus_sessions(date);
@@ -0,0 +1,17 @@
+-- URLs found in bullet content, collected for the read-later list
+CREATE TABLE IF NOT EXISTS links (
+    bullet_id INTEGER NOT NULL,
+    date DATE NOT NULL,
+    url TEXT NOT NULL,
+    title TEXT -- page title, only set once fetched with `links --fetch-titles`
+);
+
+CREATE INDEX IF NOT EXISTS idx_links_date ON links(date);
+
+-- Backfill links for bullets written before this migration
+INSERT INTO links (bullet_id, date, url)
+SELECT id, date, url
+FROM (
+    SELECT id, date, unnest(regexp_extract_all(content, 'https?://[^\s<>"]*[^\s<>".,;:!?)\]]')) AS url
+    FROM bullets
+);
@@ -0,0 +1,7 @@
+-- Bumped on every save or delete of an entry, so an editor session can tell
+-- whether someone else changed the entry since it was loaded
+CREATE TABLE IF NOT EXISTS entry_versions (
+    date DATE PRIMARY KEY,
+    version BIGINT NOT NULL,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
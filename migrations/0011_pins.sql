@@ -0,0 +1,5 @@
+-- Days pinned as favorites with `journo pin` or `*` in the week view
+CREATE TABLE IF NOT EXISTS pins (
+    date DATE PRIMARY KEY,
+    pinned_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,2 @@
+-- End of time blocks written as `10:00-11:30 Deep work`
+ALTER TABLE bullets ADD COLUMN IF NOT EXISTS end_time TIME;
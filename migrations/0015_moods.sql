@@ -0,0 +1,8 @@
+-- Morning and evening 1-5 mood check-ins
+CREATE TABLE IF NOT EXISTS moods (
+    date DATE NOT NULL,
+    period TEXT NOT NULL, -- 'morning' or 'evening'
+    score INTEGER NOT NULL CHECK (score BETWEEN 1 AND 5),
+    recorded_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (date, period)
+);
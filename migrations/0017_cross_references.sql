@@ -0,0 +1,11 @@
+-- Bullets recorded as following up on a bullet of an earlier day, e.g. notes
+-- from a recurring meeting. Both sides are identified by date and normalized
+-- content, as bullet ids change whenever an entry is saved.
+CREATE TABLE IF NOT EXISTS cross_references (
+    date DATE NOT NULL,
+    content TEXT NOT NULL,
+    target_date DATE NOT NULL,
+    target_content TEXT NOT NULL,
+    recorded_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (date, content)
+);
@@ -0,0 +1,7 @@
+-- Journaling prompts handed out by `journo prompt` and `journo new --prompt`,
+-- so recent ones aren't repeated
+CREATE TABLE IF NOT EXISTS prompt_history (
+    prompt TEXT NOT NULL,
+    shown_on DATE NOT NULL,
+    PRIMARY KEY (prompt, shown_on)
+);
@@ -0,0 +1,2 @@
+-- `journo stale` scans pending tasks by date
+CREATE INDEX IF NOT EXISTS idx_bullets_task_state_date ON bullets(task_state, date);
@@ -0,0 +1,6 @@
+-- Weeks whose rollover review of last week's pending tasks was offered, so
+-- it only comes up on the first launch of a week
+CREATE TABLE IF NOT EXISTS migration_reviews (
+    week_start DATE PRIMARY KEY,
+    reviewed_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,17 @@
+-- Numeric measurements written as #name:value in bullet content
+CREATE TABLE IF NOT EXISTS metrics (
+    bullet_id INTEGER NOT NULL,
+    date DATE NOT NULL,
+    name TEXT NOT NULL, -- lowercased, without the leading #
+    value DOUBLE NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_metrics_name ON metrics(name, date);
+
+-- Backfill metrics for bullets written before this migration
+INSERT INTO metrics (bullet_id, date, name, value)
+SELECT id, date, lower(split_part(metric, ':', 1)), CAST(split_part(metric, ':', 2) AS DOUBLE)
+FROM (
+    SELECT id, date, unnest(regexp_extract_all(content, '(?:^|\s)#([\w-]+:[-+]?\d+(?:\.\d+)?)', 1)) AS metric
+    FROM bullets
+);
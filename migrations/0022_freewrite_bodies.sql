@@ -0,0 +1,2 @@
+-- Marks the note holding an entry's free-written body, kept below its bullets
+ALTER TABLE bullets ADD COLUMN IF NOT EXISTS body BOOLEAN DEFAULT FALSE;
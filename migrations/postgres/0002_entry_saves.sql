@@ -0,0 +1,11 @@
+CREATE SEQUENCE IF NOT EXISTS entry_save_id_seq;
+
+CREATE TABLE IF NOT EXISTS entry_saves (
+    id BIGINT PRIMARY KEY DEFAULT nextval('entry_save_id_seq'),
+    date DATE NOT NULL,
+    saved_at TIMESTAMP NOT NULL, -- local wall-clock time of the save
+    word_count BIGINT NOT NULL,
+    word_delta BIGINT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_entry_saves_date ON entry_saves(date);
@@ -0,0 +1,10 @@
+-- People mentioned as @name in bullet content. PostgreSQL databases are
+-- created with the full schema, so there are no older bullets to backfill.
+CREATE TABLE IF NOT EXISTS mentions (
+    bullet_id BIGINT NOT NULL,
+    date DATE NOT NULL,
+    name TEXT NOT NULL -- lowercased, without the leading @
+);
+
+CREATE INDEX IF NOT EXISTS idx_mentions_name ON mentions(name);
+CREATE INDEX IF NOT EXISTS idx_mentions_date ON mentions(date);
@@ -0,0 +1,2 @@
+-- Nested bullets point at the bullet they're indented under
+ALTER TABLE bullets ADD COLUMN IF NOT EXISTS parent_id BIGINT;
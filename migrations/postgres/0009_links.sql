@@ -0,0 +1,9 @@
+-- URLs found in bullet content, collected for the read-later list
+CREATE TABLE IF NOT EXISTS links (
+    bullet_id BIGINT NOT NULL,
+    date DATE NOT NULL,
+    url TEXT NOT NULL,
+    title TEXT -- page title, only set once fetched with `links --fetch-titles`
+);
+
+CREATE INDEX IF NOT EXISTS idx_links_date ON links(date);
@@ -0,0 +1,14 @@
+-- GTD-style contexts written as @@work in bullet content
+CREATE TABLE IF NOT EXISTS contexts (
+    bullet_id BIGINT NOT NULL,
+    date DATE NOT NULL,
+    name TEXT NOT NULL -- lowercased, without the leading @@
+);
+
+CREATE INDEX IF NOT EXISTS idx_contexts_name ON contexts(name);
+CREATE INDEX IF NOT EXISTS idx_contexts_date ON contexts(date);
+
+-- Backfill contexts for bullets written before this migration
+INSERT INTO contexts (bullet_id, date, name)
+SELECT id, date, lower(m[1])
+FROM bullets, regexp_matches(content, '(?:^|\s)@@([\w-]+)', 'g') AS m;
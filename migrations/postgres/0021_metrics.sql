@@ -0,0 +1,14 @@
+-- Numeric measurements written as #name:value in bullet content
+CREATE TABLE IF NOT EXISTS metrics (
+    bullet_id BIGINT NOT NULL,
+    date DATE NOT NULL,
+    name TEXT NOT NULL, -- lowercased, without the leading #
+    value DOUBLE PRECISION NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_metrics_name ON metrics(name, date);
+
+-- Backfill metrics for bullets written before this migration
+INSERT INTO metrics (bullet_id, date, name, value)
+SELECT id, date, lower(m[1]), CAST(m[2] AS DOUBLE PRECISION)
+FROM bullets, regexp_matches(content, '(?:^|\s)#([\w-]+):([-+]?\d+(?:\.\d+)?)', 'g') AS m;
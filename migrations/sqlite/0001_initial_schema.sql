@@ -0,0 +1,12 @@
+-- SQLite has no sequences; INTEGER PRIMARY KEY aliases the rowid instead
+CREATE TABLE IF NOT EXISTS bullets (
+    id INTEGER PRIMARY KEY,
+    date DATE NOT NULL,
+    content TEXT NOT NULL,
+    type TEXT NOT NULL, -- task, event, note, priority, inspiration, insight, misstep
+    task_state TEXT, -- pending, completed, migrated, scheduled (only for task types)
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_bullets_date ON bullets(date);
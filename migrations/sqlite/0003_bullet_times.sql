@@ -0,0 +1,2 @@
+-- Optional time of day for bullets written as `09:30 Standup`
+ALTER TABLE bullets ADD COLUMN time TIME;
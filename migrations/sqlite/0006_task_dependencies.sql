@@ -0,0 +1,8 @@
+-- Tasks waiting for other tasks, written as !after:word or !after:"several words"
+CREATE TABLE IF NOT EXISTS task_dependencies (
+    bullet_id INTEGER NOT NULL, -- the dependent task
+    date DATE NOT NULL,
+    blocker TEXT NOT NULL -- lowercased text matched against other tasks
+);
+
+CREATE INDEX IF NOT EXISTS idx_task_dependencies_date ON task_dependencies(date);
@@ -0,0 +1,11 @@
+-- GTD-style contexts written as @@work in bullet content. SQLite has no
+-- regular expressions to backfill with; older bullets get their contexts the
+-- next time their entry is saved.
+CREATE TABLE IF NOT EXISTS contexts (
+    bullet_id INTEGER NOT NULL,
+    date DATE NOT NULL,
+    name TEXT NOT NULL -- lowercased, without the leading @@
+);
+
+CREATE INDEX IF NOT EXISTS idx_contexts_name ON contexts(name);
+CREATE INDEX IF NOT EXISTS idx_contexts_date ON contexts(date);
@@ -0,0 +1,11 @@
+-- When an entry was first saved; cleared when it is deleted. Entries saved
+-- before this migration take their first recorded editor save, if any.
+ALTER TABLE entry_versions ADD COLUMN created_at TIMESTAMP;
+
+UPDATE entry_versions
+SET created_at = COALESCE(
+    (SELECT MIN(saved_at) FROM entry_saves WHERE entry_saves.date = entry_versions.date),
+    updated_at
+)
+WHERE created_at IS NULL
+  AND EXISTS (SELECT 1 FROM bullets WHERE bullets.date = entry_versions.date);
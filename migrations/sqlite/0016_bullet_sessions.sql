@@ -0,0 +1,3 @@
+-- Start of the `## HH:MM` session a bullet was written in, for days
+-- journaled in several sittings
+ALTER TABLE bullets ADD COLUMN session TIME;
@@ -0,0 +1,11 @@
+-- Numeric measurements written as #name:value in bullet content. SQLite has
+-- no regular expressions to backfill with; older bullets get their metrics
+-- the next time their entry is saved.
+CREATE TABLE IF NOT EXISTS metrics (
+    bullet_id INTEGER NOT NULL,
+    date DATE NOT NULL,
+    name TEXT NOT NULL, -- lowercased, without the leading #
+    value REAL NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_metrics_name ON metrics(name, date);
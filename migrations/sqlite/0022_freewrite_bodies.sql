@@ -0,0 +1,2 @@
+-- Marks the note holding an entry's free-written body, kept below its bullets
+ALTER TABLE bullets ADD COLUMN body BOOLEAN DEFAULT FALSE;
@@ -0,0 +1,289 @@
+use crate::application::JournalApp;
+use crate::entities::{BulletType, DateRange, TaskState};
+use anyhow::{Result, anyhow};
+use chrono::NaiveDate;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::io::{BufRead, Write};
+
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const SERVER_ERROR: i64 = -32000;
+
+#[derive(Deserialize)]
+struct Request {
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Serialize)]
+struct Response {
+    jsonrpc: &'static str,
+    id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<RpcError>,
+}
+
+#[derive(Serialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct DateParams {
+    date: NaiveDate,
+}
+
+#[derive(Deserialize)]
+struct SaveEntryParams {
+    date: NaiveDate,
+    markdown: String,
+    /// Save tasks completed before their blockers anyway
+    #[serde(default)]
+    force: bool,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct RangeParams {
+    start: Option<NaiveDate>,
+    end: Option<NaiveDate>,
+}
+
+impl RangeParams {
+    /// Missing bounds fall back to the first and last entry in storage
+    fn to_range(&self, app: &JournalApp) -> Result<Option<DateRange>> {
+        let Some(span) = app.resolve_range(None)? else {
+            return Ok(None);
+        };
+        Ok(Some(DateRange::between(
+            self.start.unwrap_or(span.start),
+            self.end.unwrap_or(span.end),
+        )))
+    }
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct SearchParams {
+    query: String,
+    types: Vec<String>,
+    states: Vec<String>,
+    #[serde(flatten)]
+    range: RangeParams,
+}
+
+/// Serve JSON-RPC 2.0 requests, one per line on stdin, answering each with a
+/// single line on stdout until stdin is closed.
+///
+/// Methods:
+/// - `get_entry {date}` → `{date, exists, markdown, entry}`
+/// - `save_entry {date, markdown, force?}` → `{date, word_count, word_delta, warnings}`
+/// - `search {query?, types?, states?, start?, end?}` → list of `{date, bullet}`
+/// - `list_dates {start?, end?}` → list of dates with entries
+pub fn run(app: &mut JournalApp) -> Result<()> {
+    let stdin = std::io::stdin();
+    let mut stdout = std::io::stdout().lock();
+
+    for line in stdin.lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        writeln!(stdout, "{}", handle_line(app, &line))?;
+        stdout.flush()?;
+    }
+
+    Ok(())
+}
+
+/// Handle a single request line and return the serialized response
+pub fn handle_line(app: &mut JournalApp, line: &str) -> String {
+    let response = match serde_json::from_str::<Request>(line) {
+        Ok(request) => {
+            let outcome = dispatch(app, &request.method, request.params);
+            match outcome {
+                Ok(result) => Response {
+                    jsonrpc: "2.0",
+                    id: request.id,
+                    result: Some(result),
+                    error: None,
+                },
+                Err(error) => Response {
+                    jsonrpc: "2.0",
+                    id: request.id,
+                    result: None,
+                    error: Some(error),
+                },
+            }
+        }
+        Err(e) => Response {
+            jsonrpc: "2.0",
+            id: Value::Null,
+            result: None,
+            error: Some(RpcError::new(PARSE_ERROR, e.to_string())),
+        },
+    };
+
+    serde_json::to_string(&response).expect("JSON-RPC response is always serializable")
+}
+
+fn dispatch(app: &mut JournalApp, method: &str, params: Value) -> Result<Value, RpcError> {
+    let server_error = |e: anyhow::Error| RpcError::new(SERVER_ERROR, format!("{:#}", e));
+
+    match method {
+        "get_entry" => {
+            let DateParams { date } = parse_params(params)?;
+            let entry = app.storage().load_entry(date).map_err(server_error)?;
+            let markdown = match &entry {
+                Some(entry) => app
+                    .parser()
+                    .serialize_for_editing(entry)
+                    .map_err(server_error)?,
+                None => app.parser().empty_template(),
+            };
+            Ok(json!({
+                "date": date,
+                "exists": entry.is_some(),
+                "markdown": markdown,
+                "entry": entry,
+            }))
+        }
+        "save_entry" => {
+            let SaveEntryParams {
+                date,
+                markdown,
+                force,
+            } = parse_params(params)?;
+            let (word_count, word_delta) = app
+                .save_markdown(date, &markdown, force)
+                .map_err(server_error)?;
+            Ok(json!({
+                "date": date,
+                "word_count": word_count,
+                "word_delta": word_delta,
+                "warnings": app.save_warnings(),
+            }))
+        }
+        "search" => {
+            let params: SearchParams = parse_params(params)?;
+            let types = parse_all::<BulletType>(&params.types)?;
+            let states = parse_all::<TaskState>(&params.states)?;
+            let range = params.range.to_range(app).map_err(server_error)?;
+            let hits = app
+                .storage()
+                .search_bullets(&params.query, &types, &states, range)
+                .map_err(server_error)?;
+            Ok(json!(hits))
+        }
+        "list_dates" => {
+            let params: RangeParams = parse_params(params)?;
+            let dates = match params.to_range(app).map_err(server_error)? {
+                Some(range) => app.storage().list_dates(range).map_err(server_error)?,
+                None => Vec::new(),
+            };
+            Ok(json!(dates))
+        }
+        _ => Err(RpcError::new(
+            METHOD_NOT_FOUND,
+            format!("unknown method: {}", method),
+        )),
+    }
+}
+
+fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    // Absent params behave like an empty object so optional fields default
+    let params = if params.is_null() { json!({}) } else { params };
+    serde_json::from_value(params).map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))
+}
+
+fn parse_all<T>(values: &[String]) -> Result<Vec<T>, RpcError>
+where
+    T: std::str::FromStr<Err = String>,
+{
+    values
+        .iter()
+        .map(|v| v.parse().map_err(|e: String| anyhow!(e)))
+        .collect::<Result<Vec<T>>>()
+        .map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))
+}
+
+#[cfg(all(test, feature = "duckdb"))]
+mod tests {
+    use super::*;
+    use crate::application::Config;
+    use crate::infrastructure::DuckDbStorage;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    fn call(app: &mut JournalApp, request: Value) -> Value {
+        serde_json::from_str(&handle_line(app, &request.to_string())).unwrap()
+    }
+
+    #[test]
+    fn test_api_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = Arc::new(DuckDbStorage::in_memory().unwrap());
+        let mut app = JournalApp::with_storage(storage, Config::load(temp_dir.path().into()));
+
+        let saved = call(
+            &mut app,
+            json!({"jsonrpc": "2.0", "id": 1, "method": "save_entry",
+                   "params": {"date": "2024-03-15", "markdown": "# Tasks\nShip the plugin\n"}}),
+        );
+        assert_eq!(saved["id"], 1);
+        assert_eq!(saved["result"]["word_count"], 3);
+
+        let entry = call(
+            &mut app,
+            json!({"jsonrpc": "2.0", "id": 2, "method": "get_entry",
+                   "params": {"date": "2024-03-15"}}),
+        );
+        assert_eq!(entry["result"]["exists"], true);
+        assert!(
+            entry["result"]["markdown"]
+                .as_str()
+                .unwrap()
+                .contains("Ship the plugin")
+        );
+
+        let hits = call(
+            &mut app,
+            json!({"jsonrpc": "2.0", "id": 3, "method": "search",
+                   "params": {"query": "plugin", "types": ["task"]}}),
+        );
+        assert_eq!(hits["result"].as_array().unwrap().len(), 1);
+
+        let dates = call(
+            &mut app,
+            json!({"jsonrpc": "2.0", "id": 4, "method": "list_dates"}),
+        );
+        assert_eq!(dates["result"], json!(["2024-03-15"]));
+
+        let unknown = call(
+            &mut app,
+            json!({"jsonrpc": "2.0", "id": 5, "method": "nope"}),
+        );
+        assert_eq!(unknown["error"]["code"], METHOD_NOT_FOUND);
+
+        let malformed: Value = serde_json::from_str(&handle_line(&mut app, "{not json")).unwrap();
+        assert_eq!(malformed["error"]["code"], PARSE_ERROR);
+    }
+}
@@ -0,0 +1,229 @@
+use crate::application::{ColorMode, Config, JournalApp};
+use crate::infrastructure::storage::JournalStorage;
+use crate::infrastructure::{
+    Exporter, ExporterRegistry, FormatHook, HookRegistry, MarkdownMirrorHook, MarkdownParser,
+    SnapshotHook, SpellcheckHook, WriteHook,
+};
+use anyhow::Result;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tracing::warn;
+
+/// Assembles a [`JournalApp`] for programs embedding the journal, such as a
+/// GUI, without going through the `journo` command line:
+///
+/// ```no_run
+/// use journo::{ColorMode, JournalBuilder};
+///
+/// let mut app = JournalBuilder::new()
+///     .journal_dir("/home/me/journal")
+///     .color_mode(ColorMode::Never)
+///     .nudges(false)
+///     .build()?;
+/// let today = app.today();
+/// let entry = app.journal.get_entry(today)?;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+///
+/// Anything not set falls back to what `journo` itself uses: the config in
+/// `$JOURNAL_DIR` (or the default journal directory) and the storage backend
+/// it selects.
+pub struct JournalBuilder {
+    config: Option<Config>,
+    journal_dir: Option<PathBuf>,
+    storage: Option<Arc<dyn JournalStorage>>,
+    parser: MarkdownParser,
+    hooks: HookRegistry,
+    exporters: ExporterRegistry,
+    default_plugins: bool,
+    strict_parsing: bool,
+    color_mode: ColorMode,
+    nudges: bool,
+}
+
+impl JournalBuilder {
+    pub fn new() -> Self {
+        Self {
+            config: None,
+            journal_dir: None,
+            storage: None,
+            parser: MarkdownParser::new(),
+            hooks: HookRegistry::new(),
+            exporters: ExporterRegistry::with_defaults(),
+            default_plugins: false,
+            strict_parsing: false,
+            color_mode: ColorMode::Auto,
+            nudges: true,
+        }
+    }
+
+    /// Load the config from this journal directory instead of `$JOURNAL_DIR`
+    pub fn journal_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.journal_dir = Some(dir.into());
+        self
+    }
+
+    /// Use a config built in code; takes precedence over `journal_dir`
+    pub fn config(mut self, config: Config) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Use an already opened storage backend instead of the configured one
+    pub fn storage(mut self, storage: Arc<dyn JournalStorage>) -> Self {
+        self.storage = Some(storage);
+        self
+    }
+
+    pub fn parser(mut self, parser: MarkdownParser) -> Self {
+        self.parser = parser;
+        self
+    }
+
+    /// Run a hook after every entry write
+    pub fn hook<H>(mut self, hook: H) -> Self
+    where
+        H: WriteHook + 'static,
+    {
+        self.hooks.register(hook);
+        self
+    }
+
+    /// Make a custom format available to `JournalApp::export`
+    pub fn exporter<E>(mut self, exporter: E) -> Self
+    where
+        E: Exporter + 'static,
+    {
+        self.exporters.register(exporter);
+        self
+    }
+
+    /// Also register the plugins `journo` enables from the config, such as
+    /// snapshots every `backup_every` saves and the `[spellcheck]` plugin
+    pub fn default_plugins(mut self, enabled: bool) -> Self {
+        self.default_plugins = enabled;
+        self
+    }
+
+    pub fn strict_parsing(mut self, strict: bool) -> Self {
+        self.strict_parsing = strict;
+        self
+    }
+
+    /// Whether printed output uses ANSI colors; a GUI will want `Never`
+    pub fn color_mode(mut self, mode: ColorMode) -> Self {
+        self.color_mode = mode;
+        self
+    }
+
+    pub fn nudges(mut self, nudges: bool) -> Self {
+        self.nudges = nudges;
+        self
+    }
+
+    /// Open the storage, if none was given, and assemble the app
+    pub fn build(self) -> Result<JournalApp> {
+        let config = match (self.config, self.journal_dir) {
+            (Some(config), _) => config,
+            (None, Some(dir)) => Config::load(dir),
+            (None, None) => Config::from_env(),
+        };
+        let storage = match self.storage {
+            Some(storage) => storage,
+            None => config.open_storage()?,
+        };
+
+        let mut hooks = self.hooks;
+        if self.default_plugins && config.mirror_markdown {
+            hooks.register(MarkdownMirrorHook::new(config.file_repository()));
+        }
+        // PostgreSQL has no database file to copy
+        if self.default_plugins
+            && config.backup_every > 0
+            && config.storage_backend.file_name().is_some()
+        {
+            hooks.register(SnapshotHook::new(
+                config.database_path(),
+                config.backup_every,
+                config.backup_retention,
+            ));
+        }
+        if self.default_plugins
+            && let Some(wordlist) = &config.spellcheck_wordlist
+        {
+            match SpellcheckHook::from_wordlist(wordlist) {
+                Ok(hook) => {
+                    hooks.register_pre_write(hook.with_custom_dictionary(&config.journal_dir))
+                }
+                Err(e) => warn!("spellcheck disabled: {:#}", e),
+            }
+        }
+
+        if self.default_plugins {
+            for step in &config.format_steps {
+                hooks.register_pre_write(FormatHook::new(*step));
+            }
+        }
+
+        let mut app = JournalApp::from_parts(storage, config, self.parser, hooks, self.exporters);
+        app.set_strict_parsing(self.strict_parsing);
+        app.set_color_mode(self.color_mode);
+        app.set_nudges(self.nudges);
+        Ok(app)
+    }
+}
+
+impl Default for JournalBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(all(test, feature = "duckdb"))]
+mod tests {
+    use super::*;
+    use crate::entities::{Bullet, BulletType, Entry};
+    use crate::infrastructure::{DuckDbStorage, WriteContext};
+    use chrono::NaiveDate;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tempfile::TempDir;
+
+    struct CountingHook(Arc<AtomicUsize>);
+
+    impl WriteHook for CountingHook {
+        fn on_entry_written(&self, _context: &WriteContext, _entry: &Entry) -> Result<()> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+
+        fn name(&self) -> &str {
+            "counting"
+        }
+    }
+
+    #[test]
+    fn test_builder_wires_storage_and_hooks() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage: Arc<dyn JournalStorage> = Arc::new(DuckDbStorage::in_memory().unwrap());
+        let writes = Arc::new(AtomicUsize::new(0));
+        let mut app = JournalBuilder::new()
+            .journal_dir(temp_dir.path())
+            .storage(storage.clone())
+            .hook(CountingHook(writes.clone()))
+            .color_mode(ColorMode::Never)
+            .nudges(false)
+            .build()
+            .unwrap();
+
+        let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        app.append_bullet(date, Bullet::new("From the GUI", BulletType::Note))
+            .unwrap();
+
+        assert_eq!(writes.load(Ordering::SeqCst), 1);
+        assert_eq!(app.config().journal_dir, temp_dir.path());
+        assert_eq!(
+            storage.load_entry(date).unwrap().unwrap().total_bullets(),
+            1
+        );
+    }
+}
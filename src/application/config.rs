@@ -1,32 +1,1173 @@
-use std::path::PathBuf;
+use crate::application::journaling_prompts::BUILTIN_PROMPTS;
+use crate::entities::{BulletSymbols, BulletType, DateRange, Prompt};
+#[cfg(feature = "duckdb")]
+use crate::infrastructure::DuckDbStorage;
+#[cfg(feature = "postgres")]
+use crate::infrastructure::PostgresStorage;
+#[cfg(feature = "sqlite")]
+use crate::infrastructure::SqliteStorage;
+use crate::infrastructure::storage::JournalStorage;
+use crate::infrastructure::{
+    DEFAULT_FILE_LAYOUT, DEFAULT_WORDLIST, FileSystemRepository, FormatStep, HOLIDAY_COUNTRIES,
+    Holidays, MarkdownParser, Query, RetentionPolicy, validate_layout,
+};
+#[cfg(any(feature = "duckdb", feature = "sqlite", feature = "postgres"))]
+use anyhow::Context;
+use chrono::format::{Item, StrftimeItems};
+use chrono::{Datelike, Duration, Local, NaiveDate, NaiveDateTime, Weekday};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::warn;
+
+/// Template used by `journo reflect` unless another one is given
+pub const DEFAULT_REFLECTION_TEMPLATE: &str = "evening-reflection";
+
+/// Share of a day's bullets an editor save may remove without asking
+const DEFAULT_MAX_EDIT_LOSS: f64 = 0.5;
+
+/// Names accepted by the `theme` config key, the default first
+pub const THEME_NAMES: [&str; 3] = ["dark", "light", "high-contrast"];
+
+/// Database file of journals created before each backend had its own file name,
+/// all of them DuckDB
+const LEGACY_DATABASE_FILE: &str = "journal.db";
+
+/// strftime-style formats used when displaying dates
+#[derive(Debug, Clone, PartialEq)]
+pub struct DateFormats {
+    /// Full date, e.g. in the week view status line (`%A, %B %d, %Y`)
+    pub long: String,
+    /// Month heading, e.g. the week view title (`%B %Y`)
+    pub month: String,
+}
+
+impl Default for DateFormats {
+    fn default() -> Self {
+        Self {
+            long: "%A, %B %d, %Y".to_string(),
+            month: "%B %Y".to_string(),
+        }
+    }
+}
 
 pub struct Config {
     pub journal_dir: PathBuf,
     pub data_dir: PathBuf,
     pub indexes_dir: PathBuf,
     pub editor: String,
+    pub date_formats: DateFormats,
+    /// Hour (0-23) at which a new journal day starts; writing at 1am with a
+    /// rollover hour of 3 still counts as the previous day
+    pub day_rollover_hour: u32,
+    /// `.ics` file path or URL imported by `calendar sync`
+    pub calendar_source: Option<String>,
+    /// Prompt templates by name, including the built-in evening reflection
+    pub templates: HashMap<String, Vec<Prompt>>,
+    /// Snapshot the database to `backups/` every this many writes (0 disables)
+    pub backup_every: u32,
+    pub backup_retention: RetentionPolicy,
+    /// strftime pattern of markdown entry paths relative to `data_dir`
+    pub file_layout: String,
+    pub digest: DigestConfig,
+    /// Glyphs for bullet types and task states in the TUI and printed output
+    pub symbols: BulletSymbols,
+    /// Database engine entries are stored in
+    pub storage_backend: StorageBackend,
+    pub postgres: PostgresConfig,
+    /// Day the week view and `--range week` start on
+    pub week_start: Weekday,
+    /// Whether the week view shows ISO week numbers and marks quarter boundaries
+    pub week_numbers: bool,
+    /// Whether the week view opens with the month mini-map beside the calendar
+    pub month_minimap: bool,
+    /// Where moving by months or years in the week view lands
+    pub month_jump: MonthJump,
+    /// Name of the TUI color theme, one of `THEME_NAMES`
+    pub theme: String,
+    /// Soft cap on the bullets of a type per day; saving more prints a nudge
+    pub daily_limits: HashMap<BulletType, usize>,
+    /// Words a day's entry should reach, shown as progress and goal streaks
+    pub word_goal: Option<usize>,
+    /// Bearer token required by `serve --api-only`, and the web interface's
+    /// password when it's served beyond loopback
+    pub api_token: Option<String>,
+    /// What to do when a save adds a bullet repeating another of its type;
+    /// types not listed use `DuplicatePolicy::default()`
+    pub duplicates: HashMap<BulletType, DuplicatePolicy>,
+    /// Wordlist the spellcheck plugin checks saved bullets against; `None`
+    /// unless `[spellcheck]` is enabled
+    pub spellcheck_wordlist: Option<PathBuf>,
+    pub mood: MoodConfig,
+    /// Saved searches by name, used as `view:name` in queries
+    pub views: HashMap<String, String>,
+    /// Order sections are written in entries, templates and the TUI
+    pub section_order: Vec<BulletType>,
+    /// Normalizations applied to bullet content on save, in order
+    pub format_steps: Vec<FormatStep>,
+    /// Whether every save also writes the entry's markdown file under `data_dir`
+    pub mirror_markdown: bool,
+    /// Markdown file new entries start from instead of the empty sections
+    pub entry_template: Option<PathBuf>,
+    /// Whether `{{cmd:...}}` placeholders in `entry_template` run their command
+    pub template_commands: bool,
+    /// Reflective prompts `journo prompt` picks from, built-in ones first
+    pub journaling_prompts: Vec<String>,
+    /// Whether `journo new` always inserts a journaling prompt under Notes
+    pub prompt_on_new: bool,
+    /// Whether the first launch of a week reviews last week's pending tasks
+    pub migration_review: bool,
+    /// Share (0-1) of a day's bullets one editor save may remove without
+    /// asking first; emptying a day always asks
+    pub max_edit_loss: f64,
+    pub nag: NagConfig,
+    /// `[colors]` overrides of the theme's bullet colors by type or task
+    /// state name, checked when the TUI theme is built
+    pub bullet_colors: HashMap<String, String>,
+    pub holidays: HolidaysConfig,
+}
+
+/// Server used when `storage_backend = "postgres"`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PostgresConfig {
+    /// libpq-style connection string or `postgres://` URL; add
+    /// `sslmode=require` to refuse unencrypted connections
+    pub url: Option<String>,
+    /// PEM certificate of a private CA that signed the server certificate,
+    /// relative to the journal directory
+    pub ca_cert: Option<PathBuf>,
+}
+
+/// Morning and evening mood check-ins, set under `[mood]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoodConfig {
+    /// Ask for a 1-5 mood when today's entry is created, and again when it's
+    /// saved after `evening_hour`
+    pub prompts: bool,
+    /// Hour (0-23) from which saving today's entry asks for the evening mood
+    pub evening_hour: u32,
+}
+
+impl Default for MoodConfig {
+    fn default() -> Self {
+        Self {
+            prompts: false,
+            evening_hour: 18,
+        }
+    }
+}
+
+/// When `journo nag` reminds you to write, set under `[nag]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct NagConfig {
+    /// Unwritten days in a row, counting today, that pass without a reminder
+    pub grace_days: u32,
+    /// Hours (start, end) during which `journo nag` stays silent; the range
+    /// wraps past midnight when start is after end, e.g. 22-7
+    pub quiet_hours: Option<(u32, u32)>,
+    /// Command `nag --notify` runs with the reminder as its last argument
+    pub notify_command: String,
+}
+
+impl NagConfig {
+    /// Whether `hour` (0-23) falls within the quiet hours
+    pub fn is_quiet(&self, hour: u32) -> bool {
+        match self.quiet_hours {
+            Some((start, end)) if start <= end => (start..end).contains(&hour),
+            Some((start, end)) => hour >= start || hour < end,
+            None => false,
+        }
+    }
+}
+
+impl Default for NagConfig {
+    fn default() -> Self {
+        Self {
+            grace_days: 1,
+            quiet_hours: None,
+            notify_command: "notify-send journo".to_string(),
+        }
+    }
+}
+
+/// Public holidays highlighted in the week view and noted in `agenda`, set
+/// under `[holidays]`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HolidaysConfig {
+    /// Built-in country pack, one of `HOLIDAY_COUNTRIES`
+    pub country: Option<String>,
+    /// `.ics` file whose all-day events are holidays too
+    pub file: Option<PathBuf>,
+}
+
+impl HolidaysConfig {
+    /// Load the configured holidays, reading the custom file if there is one
+    pub fn load(&self) -> anyhow::Result<Holidays> {
+        Holidays::load(self.country.as_deref(), self.file.as_deref())
+    }
+}
+
+/// Database engines journo can store entries in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StorageBackend {
+    /// Only available when built with the `duckdb` feature, which is on by default
+    #[default]
+    DuckDb,
+    /// Lighter alternative, only available when built with the `sqlite` feature
+    Sqlite,
+    /// Remote server shared between machines, only available when built with
+    /// the `postgres` feature
+    Postgres,
+}
+
+impl StorageBackend {
+    /// Backends compiled into this build
+    pub fn available() -> Vec<StorageBackend> {
+        let mut backends = Vec::new();
+        if cfg!(feature = "duckdb") {
+            backends.push(StorageBackend::DuckDb);
+        }
+        if cfg!(feature = "sqlite") {
+            backends.push(StorageBackend::Sqlite);
+        }
+        if cfg!(feature = "postgres") {
+            backends.push(StorageBackend::Postgres);
+        }
+        backends
+    }
+
+    /// Value of the `storage_backend` config key
+    pub fn name(&self) -> &'static str {
+        match self {
+            StorageBackend::DuckDb => "duckdb",
+            StorageBackend::Sqlite => "sqlite",
+            StorageBackend::Postgres => "postgres",
+        }
+    }
+
+    /// Name of the database file in the journal directory, `None` for backends
+    /// that don't keep one
+    pub fn file_name(&self) -> Option<&'static str> {
+        match self {
+            StorageBackend::DuckDb => Some("journal.duckdb"),
+            StorageBackend::Sqlite => Some("journal.sqlite"),
+            StorageBackend::Postgres => None,
+        }
+    }
+}
+
+impl std::str::FromStr for StorageBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "duckdb" => Ok(StorageBackend::DuckDb),
+            "sqlite" => Ok(StorageBackend::Sqlite),
+            "postgres" | "postgresql" => Ok(StorageBackend::Postgres),
+            other => Err(format!(
+                "unknown storage backend '{}' (expected duckdb, sqlite or postgres)",
+                other
+            )),
+        }
+    }
+}
+
+/// Handling of a bullet that repeats another of the same type on the same day,
+/// ignoring case and extra whitespace
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DuplicatePolicy {
+    /// Keep both
+    Allow,
+    /// Ask whether to drop the repeat when saving from the editor, log it otherwise
+    #[default]
+    Warn,
+    /// Drop the repeat without asking
+    Dedupe,
+}
+
+impl std::str::FromStr for DuplicatePolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "allow" => Ok(DuplicatePolicy::Allow),
+            "warn" => Ok(DuplicatePolicy::Warn),
+            "dedupe" => Ok(DuplicatePolicy::Dedupe),
+            other => Err(format!(
+                "unknown duplicate policy '{}' (expected allow, warn or dedupe)",
+                other
+            )),
+        }
+    }
+}
+
+/// Where moving by months or years in the week view lands
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MonthJump {
+    /// The same day of the month, or the month's last day when it's shorter
+    #[default]
+    Day,
+    /// The same weekday of the same week of the month (e.g. the second
+    /// Tuesday), or the month's last such weekday when there's no fifth one
+    Weekday,
+}
+
+impl MonthJump {
+    /// `date` moved by `months`, backwards when negative
+    pub fn shift(self, date: NaiveDate, months: i32) -> NaiveDate {
+        let months0 = date.year() * 12 + date.month0() as i32 + months;
+        let (year, month) = (months0.div_euclid(12), months0.rem_euclid(12) as u32 + 1);
+        let first = NaiveDate::from_ymd_opt(year, month, 1).expect("first of month exists");
+        let last = DateRange::month(year, month).end();
+        match self {
+            MonthJump::Day => first.with_day(date.day()).unwrap_or(last),
+            MonthJump::Weekday => {
+                let offset = (7 + date.weekday().num_days_from_monday()
+                    - first.weekday().num_days_from_monday())
+                    % 7;
+                let nth = (date.day0() / 7) as i64;
+                let target = first + Duration::days(offset as i64) + Duration::weeks(nth);
+                if target > last {
+                    target - Duration::weeks(1)
+                } else {
+                    target
+                }
+            }
+        }
+    }
+}
+
+impl std::str::FromStr for MonthJump {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "day" => Ok(MonthJump::Day),
+            "weekday" => Ok(MonthJump::Weekday),
+            other => Err(format!(
+                "unknown month jump '{}' (expected day or weekday)",
+                other
+            )),
+        }
+    }
+}
+
+/// How `journo digest` renders and delivers summaries
+#[derive(Debug, Clone, PartialEq)]
+pub struct DigestConfig {
+    /// Markdown template with `{{variable}}` placeholders, relative to the
+    /// journal directory; the built-in layout is used when unset
+    pub template: Option<PathBuf>,
+    /// Recipient of `digest --email`
+    pub email_to: Option<String>,
+    pub email_from: Option<String>,
+    /// sendmail-compatible command that delivers the message (e.g. `msmtp -t`
+    /// for an SMTP relay); it has to be installed, journo doesn't speak SMTP
+    pub sendmail: String,
+}
+
+impl Default for DigestConfig {
+    fn default() -> Self {
+        Self {
+            template: None,
+            email_to: None,
+            email_from: None,
+            sendmail: "sendmail -t".to_string(),
+        }
+    }
+}
+
+/// Optional settings read from `$JOURNAL_DIR/config.toml`
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ConfigFile {
+    date_format: Option<String>,
+    month_format: Option<String>,
+    day_rollover_hour: Option<u32>,
+    calendar: Option<String>,
+    templates: HashMap<String, TemplateFile>,
+    backups: BackupsFile,
+    file_layout: Option<String>,
+    digest: DigestFile,
+    symbols: SymbolsFile,
+    storage_backend: Option<String>,
+    postgres: PostgresFile,
+    editor: Option<String>,
+    week_start: Option<String>,
+    week_numbers: Option<bool>,
+    month_minimap: Option<bool>,
+    month_jump: Option<String>,
+    theme: Option<String>,
+    /// `[limits]` table: bullet type name to daily maximum
+    limits: HashMap<String, usize>,
+    word_goal: Option<usize>,
+    server: ServerFile,
+    /// `[duplicates]` table: bullet type name to `allow`, `warn` or `dedupe`
+    duplicates: HashMap<String, String>,
+    spellcheck: SpellcheckFile,
+    mood: MoodFile,
+    /// `[views]` table: view name to saved query
+    views: HashMap<String, String>,
+    /// Bullet type names in the order their sections should appear
+    sections: Vec<String>,
+    format: FormatFile,
+    mirror_markdown: Option<bool>,
+    entry_template: Option<PathBuf>,
+    template_commands: Option<bool>,
+    prompts: JournalingPromptsFile,
+    migration_review: Option<bool>,
+    max_edit_loss: Option<f64>,
+    nag: NagFile,
+    /// `[colors]` table: bullet type or task state name to color
+    colors: HashMap<String, String>,
+    holidays: HolidaysFile,
+}
+
+/// `[server]` table in the config file
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ServerFile {
+    api_token: Option<String>,
+}
+
+/// `[spellcheck]` table in the config file
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct SpellcheckFile {
+    enabled: bool,
+    wordlist: Option<PathBuf>,
+}
+
+/// `[format]` table in the config file
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct FormatFile {
+    steps: Vec<String>,
+    max_length: Option<usize>,
+    /// `warn` or `wrap` for bullets over `max_length`
+    overflow: Option<String>,
+}
+
+/// `[prompts]` table in the config file
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct JournalingPromptsFile {
+    extra: Vec<String>,
+    on_new: bool,
+}
+
+/// `[mood]` table in the config file
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct MoodFile {
+    prompts: bool,
+    evening_hour: Option<u32>,
+}
+
+/// `[nag]` table in the config file
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct NagFile {
+    grace_days: Option<u32>,
+    /// `start-end` hours, e.g. `22-7`
+    quiet_hours: Option<String>,
+    notify_command: Option<String>,
+}
+
+/// `[holidays]` table in the config file
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct HolidaysFile {
+    country: Option<String>,
+    /// Relative to the journal directory
+    file: Option<PathBuf>,
+}
+
+/// `[postgres]` table in the config file
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct PostgresFile {
+    url: Option<String>,
+    ca_cert: Option<PathBuf>,
+}
+
+/// `[symbols]` table in the config file; unset glyphs keep their default
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct SymbolsFile {
+    task: Option<String>,
+    event: Option<String>,
+    note: Option<String>,
+    priority: Option<String>,
+    inspiration: Option<String>,
+    insight: Option<String>,
+    misstep: Option<String>,
+    completed: Option<String>,
+    migrated: Option<String>,
+    scheduled: Option<String>,
+}
+
+impl SymbolsFile {
+    fn into_symbols(self) -> BulletSymbols {
+        let defaults = BulletSymbols::default();
+        BulletSymbols {
+            task: self.task.unwrap_or(defaults.task),
+            event: self.event.unwrap_or(defaults.event),
+            note: self.note.unwrap_or(defaults.note),
+            priority: self.priority.unwrap_or(defaults.priority),
+            inspiration: self.inspiration.unwrap_or(defaults.inspiration),
+            insight: self.insight.unwrap_or(defaults.insight),
+            misstep: self.misstep.unwrap_or(defaults.misstep),
+            completed: self.completed.unwrap_or(defaults.completed),
+            migrated: self.migrated.unwrap_or(defaults.migrated),
+            scheduled: self.scheduled.unwrap_or(defaults.scheduled),
+        }
+    }
+}
+
+/// `[digest]` table in the config file
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct DigestFile {
+    template: Option<PathBuf>,
+    email_to: Option<String>,
+    email_from: Option<String>,
+    sendmail: Option<String>,
+}
+
+/// `[backups]` table in the config file
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct BackupsFile {
+    every: Option<u32>,
+    keep_daily: Option<usize>,
+    keep_weekly: Option<usize>,
+}
+
+/// `[templates.<name>]` table in the config file
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct TemplateFile {
+    prompts: Vec<PromptFile>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PromptFile {
+    question: String,
+    #[serde(rename = "type")]
+    bullet_type: String,
 }
 
 impl Config {
     pub fn from_env() -> Self {
-        let journal_dir = std::env::var("JOURNAL_DIR")
+        Self::load(Self::default_journal_dir())
+    }
+
+    /// `$JOURNAL_DIR`, or `journo` in the platform's local data directory
+    pub fn default_journal_dir() -> PathBuf {
+        std::env::var("JOURNAL_DIR")
             .map(PathBuf::from)
             .unwrap_or_else(|_| {
                 dirs::data_local_dir()
                     .unwrap_or_else(|| PathBuf::from("."))
                     .join("journo")
-            });
+            })
+    }
 
+    /// Configuration for a specific journal directory, reading its config file if present
+    pub fn load(journal_dir: PathBuf) -> Self {
         let data_dir = journal_dir.join("data");
         let indexes_dir = journal_dir.join("indexes");
 
-        let editor = std::env::var("EDITOR").unwrap_or_else(|_| "nano".to_string());
+        let file = Self::read_config_file(&journal_dir.join("config.toml"));
+
+        let editor = file
+            .editor
+            .or_else(|| std::env::var("EDITOR").ok())
+            .unwrap_or_else(|| "nano".to_string());
+
+        let defaults = DateFormats::default();
+        let date_formats = DateFormats {
+            long: checked_date_format("date_format", file.date_format, defaults.long),
+            month: checked_date_format("month_format", file.month_format, defaults.month),
+        };
+
+        let day_rollover_hour = match file.day_rollover_hour {
+            Some(hour) if hour < 24 => hour,
+            Some(hour) => {
+                warn!("ignoring day_rollover_hour = {} (must be 0-23)", hour);
+                0
+            }
+            None => 0,
+        };
+
+        let default_mood = MoodConfig::default();
+        let evening_hour = match file.mood.evening_hour {
+            Some(hour) if hour < 24 => hour,
+            Some(hour) => {
+                warn!("ignoring evening_hour = {} (must be 0-23)", hour);
+                default_mood.evening_hour
+            }
+            None => default_mood.evening_hour,
+        };
+
+        let mut templates = HashMap::from([(
+            DEFAULT_REFLECTION_TEMPLATE.to_string(),
+            evening_reflection_prompts(),
+        )]);
+        for (name, template) in file.templates {
+            let prompts = template
+                .prompts
+                .into_iter()
+                .filter_map(|p| match p.bullet_type.parse::<BulletType>() {
+                    Ok(bullet_type) => Some(Prompt::new(p.question, bullet_type)),
+                    Err(e) => {
+                        warn!("ignoring prompt in template '{}': {}", name, e);
+                        None
+                    }
+                })
+                .collect();
+            templates.insert(name, prompts);
+        }
+
+        let default_retention = RetentionPolicy::default();
+        let default_digest = DigestConfig::default();
+        let digest_template = file.digest.template.map(|t| journal_dir.join(t));
+        let postgres_ca_cert = file.postgres.ca_cert.map(|c| journal_dir.join(c));
+        let holiday_file = file.holidays.file.map(|f| journal_dir.join(f));
+        let holiday_country = file.holidays.country.filter(|country| {
+            let known = HOLIDAY_COUNTRIES.contains(&country.to_ascii_lowercase().as_str());
+            if !known {
+                warn!(
+                    "ignoring holidays country '{}': expected one of {}",
+                    country,
+                    HOLIDAY_COUNTRIES.join(", ")
+                );
+            }
+            known
+        });
+        let entry_template = file.entry_template.map(|t| journal_dir.join(t));
+        let spellcheck_wordlist = file.spellcheck.enabled.then(|| {
+            file.spellcheck
+                .wordlist
+                .map_or_else(|| DEFAULT_WORDLIST.into(), |w| journal_dir.join(w))
+        });
+
+        let file_layout = match file.file_layout {
+            Some(layout) => match validate_layout(&layout) {
+                Ok(()) => layout,
+                Err(e) => {
+                    warn!("ignoring file_layout: {}", e);
+                    DEFAULT_FILE_LAYOUT.to_string()
+                }
+            },
+            None => DEFAULT_FILE_LAYOUT.to_string(),
+        };
+
+        let storage_backend = match file.storage_backend.map(|b| b.parse::<StorageBackend>()) {
+            Some(Ok(backend)) => backend,
+            Some(Err(e)) => {
+                warn!("ignoring storage_backend: {}", e);
+                StorageBackend::default()
+            }
+            None => StorageBackend::default(),
+        };
+
+        let month_jump = match file.month_jump.map(|j| j.parse::<MonthJump>()) {
+            Some(Ok(jump)) => jump,
+            Some(Err(e)) => {
+                warn!("ignoring month_jump: {}", e);
+                MonthJump::default()
+            }
+            None => MonthJump::default(),
+        };
+
+        let week_start = match file.week_start.map(|d| d.parse::<Weekday>()) {
+            Some(Ok(day)) => day,
+            Some(Err(_)) => {
+                warn!("ignoring week_start (expected a day such as \"monday\")");
+                Weekday::Sun
+            }
+            None => Weekday::Sun,
+        };
+
+        let theme = match file.theme {
+            Some(name) if THEME_NAMES.contains(&name.as_str()) => name,
+            Some(name) => {
+                warn!(
+                    "ignoring theme = \"{}\" (expected one of {})",
+                    name,
+                    THEME_NAMES.join(", ")
+                );
+                THEME_NAMES[0].to_string()
+            }
+            None => THEME_NAMES[0].to_string(),
+        };
+
+        let mut daily_limits = HashMap::new();
+        for (name, limit) in file.limits {
+            match name.parse::<BulletType>() {
+                Ok(bullet_type) => {
+                    daily_limits.insert(bullet_type, limit);
+                }
+                Err(e) => warn!("ignoring limit: {}", e),
+            }
+        }
+
+        let mut duplicates = HashMap::new();
+        for (name, policy) in file.duplicates {
+            match (
+                name.parse::<BulletType>(),
+                policy.parse::<DuplicatePolicy>(),
+            ) {
+                (Ok(bullet_type), Ok(policy)) => {
+                    duplicates.insert(bullet_type, policy);
+                }
+                (Err(e), _) | (_, Err(e)) => warn!("ignoring duplicates setting: {}", e),
+            }
+        }
+
+        let mut preferred_sections = Vec::new();
+        for name in file.sections {
+            match BulletType::from_name(&name) {
+                Ok(bullet_type) => preferred_sections.push(bullet_type),
+                Err(e) => warn!("ignoring section: {}", e),
+            }
+        }
+
+        let wrap = match file.format.overflow.as_deref() {
+            None | Some("warn") => false,
+            Some("wrap") => true,
+            Some(other) => {
+                warn!("ignoring overflow = \"{}\" (expected warn or wrap)", other);
+                false
+            }
+        };
+        let mut format_steps = Vec::new();
+        for name in &file.format.steps {
+            match name.parse::<FormatStep>() {
+                Ok(FormatStep::MaxLength { limit, .. }) => {
+                    format_steps.push(FormatStep::MaxLength {
+                        limit: file.format.max_length.unwrap_or(limit),
+                        wrap,
+                    })
+                }
+                Ok(step) => format_steps.push(step),
+                Err(e) => warn!("ignoring format step: {}", e),
+            }
+        }
+
+        let mut journaling_prompts: Vec<String> =
+            BUILTIN_PROMPTS.iter().map(|p| p.to_string()).collect();
+        journaling_prompts.extend(file.prompts.extra);
+
+        let max_edit_loss = match file.max_edit_loss {
+            Some(share) if (0.0..=1.0).contains(&share) => share,
+            Some(share) => {
+                warn!("ignoring max_edit_loss = {} (must be 0-1)", share);
+                DEFAULT_MAX_EDIT_LOSS
+            }
+            None => DEFAULT_MAX_EDIT_LOSS,
+        };
+
+        let default_nag = NagConfig::default();
+        let quiet_hours = file.nag.quiet_hours.and_then(|hours| {
+            let parsed = hours.split_once('-').and_then(|(start, end)| {
+                let start: u32 = start.trim().parse().ok()?;
+                let end: u32 = end.trim().parse().ok()?;
+                (start < 24 && end < 24).then_some((start, end))
+            });
+            if parsed.is_none() {
+                warn!(
+                    "ignoring quiet_hours = {:?} (use start-end hours, e.g. 22-7)",
+                    hours
+                );
+            }
+            parsed
+        });
+
+        let mut views = file.views;
+        views.retain(|name, query| match Query::parse(query) {
+            Ok(_) => true,
+            Err(e) => {
+                warn!("ignoring view '{}': {}", name, e);
+                false
+            }
+        });
 
         Self {
             journal_dir,
             data_dir,
             indexes_dir,
             editor,
+            date_formats,
+            day_rollover_hour,
+            calendar_source: file.calendar,
+            templates,
+            backup_every: file.backups.every.unwrap_or(10),
+            backup_retention: RetentionPolicy {
+                keep_daily: file
+                    .backups
+                    .keep_daily
+                    .unwrap_or(default_retention.keep_daily),
+                keep_weekly: file
+                    .backups
+                    .keep_weekly
+                    .unwrap_or(default_retention.keep_weekly),
+            },
+            file_layout,
+            digest: DigestConfig {
+                template: digest_template,
+                email_to: file.digest.email_to,
+                email_from: file.digest.email_from,
+                sendmail: file.digest.sendmail.unwrap_or(default_digest.sendmail),
+            },
+            symbols: file.symbols.into_symbols(),
+            storage_backend,
+            postgres: PostgresConfig {
+                url: file.postgres.url,
+                ca_cert: postgres_ca_cert,
+            },
+            week_start,
+            week_numbers: file.week_numbers.unwrap_or(false),
+            month_minimap: file.month_minimap.unwrap_or(false),
+            month_jump,
+            theme,
+            daily_limits,
+            word_goal: file.word_goal.filter(|goal| *goal > 0),
+            api_token: file.server.api_token,
+            duplicates,
+            spellcheck_wordlist,
+            mood: MoodConfig {
+                prompts: file.mood.prompts,
+                evening_hour,
+            },
+            views,
+            section_order: BulletType::ordered(&preferred_sections),
+            format_steps,
+            mirror_markdown: file.mirror_markdown.unwrap_or(true),
+            entry_template,
+            template_commands: file.template_commands.unwrap_or(true),
+            journaling_prompts,
+            prompt_on_new: file.prompts.on_new,
+            migration_review: file.migration_review.unwrap_or(true),
+            max_edit_loss,
+            nag: NagConfig {
+                grace_days: file.nag.grace_days.unwrap_or(default_nag.grace_days),
+                quiet_hours,
+                notify_command: file
+                    .nag
+                    .notify_command
+                    .unwrap_or(default_nag.notify_command),
+            },
+            bullet_colors: file.colors,
+            holidays: HolidaysConfig {
+                country: holiday_country,
+                file: holiday_file,
+            },
         }
     }
+
+    fn read_config_file(path: &Path) -> ConfigFile {
+        let Ok(content) = std::fs::read_to_string(path) else {
+            return ConfigFile::default();
+        };
+
+        toml::from_str(&content).unwrap_or_else(|e| {
+            warn!("ignoring invalid {}: {}", path.display(), e);
+            ConfigFile::default()
+        })
+    }
+
+    /// Repository for the markdown files in the data directory
+    pub fn file_repository(&self) -> FileSystemRepository {
+        FileSystemRepository::new(self.data_dir.clone(), self.journal_dir.clone())
+            .with_layout(self.file_layout.clone())
+            .with_parser(MarkdownParser::new().with_section_order(&self.section_order))
+    }
+
+    /// Path of the configured backend's database file. DuckDB journals that
+    /// still have a `journal.db` keep using it; PostgreSQL has no file and gets
+    /// the journal directory itself
+    pub fn database_path(&self) -> PathBuf {
+        let Some(file_name) = self.storage_backend.file_name() else {
+            return self.journal_dir.clone();
+        };
+        let legacy = self.journal_dir.join(LEGACY_DATABASE_FILE);
+        if self.storage_backend == StorageBackend::DuckDb && legacy.exists() {
+            return legacy;
+        }
+        self.journal_dir.join(file_name)
+    }
+
+    /// Open the configured storage backend, running pending migrations
+    pub fn open_storage(&self) -> anyhow::Result<Arc<dyn JournalStorage>> {
+        match self.storage_backend {
+            #[cfg(feature = "duckdb")]
+            StorageBackend::DuckDb => Ok(Arc::new(
+                DuckDbStorage::new(self.database_path())
+                    .context("Failed to initialize DuckDB storage")?,
+            )),
+            #[cfg(not(feature = "duckdb"))]
+            StorageBackend::DuckDb => anyhow::bail!(
+                "storage_backend = \"duckdb\" needs journo built with `--features duckdb`"
+            ),
+            #[cfg(feature = "sqlite")]
+            StorageBackend::Sqlite => Ok(Arc::new(
+                SqliteStorage::new(self.database_path())
+                    .context("Failed to initialize SQLite storage")?,
+            )),
+            #[cfg(not(feature = "sqlite"))]
+            StorageBackend::Sqlite => anyhow::bail!(
+                "storage_backend = \"sqlite\" needs journo built with `--features sqlite`"
+            ),
+            #[cfg(feature = "postgres")]
+            StorageBackend::Postgres => {
+                let url = self
+                    .postgres
+                    .url
+                    .as_deref()
+                    .context("storage_backend = \"postgres\" needs `url` in [postgres]")?;
+                Ok(Arc::new(PostgresStorage::connect(
+                    url,
+                    self.postgres.ca_cert.as_deref(),
+                )?))
+            }
+            #[cfg(not(feature = "postgres"))]
+            StorageBackend::Postgres => anyhow::bail!(
+                "storage_backend = \"postgres\" needs journo built with `--features postgres`"
+            ),
+        }
+    }
+
+    /// How repeated bullets of `bullet_type` are handled on save
+    pub fn duplicate_policy(&self, bullet_type: &BulletType) -> DuplicatePolicy {
+        self.duplicates
+            .get(bullet_type)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// The journal day it currently is, taking the rollover hour into account
+    pub fn today(&self) -> NaiveDate {
+        journal_date(Local::now().naive_local(), self.day_rollover_hour)
+    }
+}
+
+/// `format` if it can format a date, otherwise `default` with a warning:
+/// unknown specifiers and time fields such as `%H` fail at display time
+fn checked_date_format(key: &str, format: Option<String>, default: String) -> String {
+    let Some(format) = format else {
+        return default;
+    };
+    let sample = NaiveDate::from_ymd_opt(2024, 3, 15).expect("valid date");
+    let valid = !StrftimeItems::new(&format).any(|item| item == Item::Error)
+        && write!(String::new(), "{}", sample.format(&format)).is_ok();
+    if valid {
+        format
+    } else {
+        warn!("ignoring {} = \"{}\" (not a date format)", key, format);
+        default
+    }
+}
+
+fn evening_reflection_prompts() -> Vec<Prompt> {
+    vec![
+        Prompt::new("What went well today?", BulletType::Insight),
+        Prompt::new("What would you do differently?", BulletType::Misstep),
+        Prompt::new("What are you grateful for?", BulletType::Note),
+        Prompt::new("What matters most tomorrow?", BulletType::Priority),
+    ]
+}
+
+/// Journal day for a wall-clock time given the hour at which days roll over
+pub fn journal_date(now: NaiveDateTime, day_rollover_hour: u32) -> NaiveDate {
+    (now - Duration::hours(day_rollover_hour as i64)).date()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_journal_date_respects_rollover_hour() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let previous = NaiveDate::from_ymd_opt(2024, 3, 14).unwrap();
+
+        let late_night = date.and_hms_opt(1, 30, 0).unwrap();
+        assert_eq!(journal_date(late_night, 0), date);
+        assert_eq!(journal_date(late_night, 3), previous);
+
+        let morning = date.and_hms_opt(3, 0, 0).unwrap();
+        assert_eq!(journal_date(morning, 3), date);
+    }
+
+    #[test]
+    fn test_month_jump_shift() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+
+        assert_eq!(
+            MonthJump::Day.shift(date(2024, 1, 31), 1),
+            date(2024, 2, 29)
+        );
+        assert_eq!(
+            MonthJump::Day.shift(date(2024, 3, 31), -1),
+            date(2024, 2, 29)
+        );
+        assert_eq!(
+            MonthJump::Day.shift(date(2024, 12, 15), 1),
+            date(2025, 1, 15)
+        );
+        assert_eq!(
+            MonthJump::Day.shift(date(2024, 1, 15), -1),
+            date(2023, 12, 15)
+        );
+        assert_eq!(
+            MonthJump::Day.shift(date(2024, 2, 29), 12),
+            date(2025, 2, 28)
+        );
+
+        // Second Tuesday to second Tuesday
+        assert_eq!(
+            MonthJump::Weekday.shift(date(2024, 3, 12), 1),
+            date(2024, 4, 9)
+        );
+        // No fifth Wednesday in February: the last one
+        assert_eq!(
+            MonthJump::Weekday.shift(date(2024, 1, 31), 1),
+            date(2024, 2, 28)
+        );
+        assert_eq!("weekday".parse(), Ok(MonthJump::Weekday));
+    }
+
+    #[test]
+    fn test_config_file_nag() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("config.toml"),
+            "[nag]\ngrace_days = 2\nquiet_hours = \"22-7\"\n",
+        )
+        .unwrap();
+
+        let nag = Config::load(temp_dir.path().into()).nag;
+        assert_eq!(nag.grace_days, 2);
+        assert!(nag.is_quiet(23) && nag.is_quiet(0) && nag.is_quiet(6));
+        assert!(!nag.is_quiet(7) && !nag.is_quiet(21));
+        assert!(!NagConfig::default().is_quiet(3));
+    }
+
+    #[test]
+    fn test_config_file_parsing() {
+        let file: ConfigFile =
+            toml::from_str("date_format = \"%d %B %Y\"\nday_rollover_hour = 3\n").unwrap();
+        assert_eq!(file.date_format.as_deref(), Some("%d %B %Y"));
+        assert_eq!(file.month_format, None);
+        assert_eq!(file.day_rollover_hour, Some(3));
+    }
+
+    #[test]
+    fn test_config_file_date_formats() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let formats = |contents: &str| {
+            std::fs::write(temp_dir.path().join("config.toml"), contents).unwrap();
+            Config::load(temp_dir.path().into()).date_formats
+        };
+
+        let custom = formats("date_format = \"%d %B %Y\"\nmonth_format = \"%m/%Y\"\n");
+        assert_eq!(custom.long, "%d %B %Y");
+        assert_eq!(custom.month, "%m/%Y");
+
+        // Time fields and unknown specifiers would fail when a date is shown
+        let invalid = formats("date_format = \"%H:%M\"\nmonth_format = \"%Q %Y\"\n");
+        assert_eq!(invalid, DateFormats::default());
+    }
+
+    #[test]
+    fn test_config_file_templates() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("config.toml"),
+            "[templates.morning]\nprompts = [\n  { question = \"What's the plan?\", type = \"task\" },\n  { question = \"Bad type\", type = \"chore\" },\n]\n",
+        )
+        .unwrap();
+
+        let config = Config::load(temp_dir.path().into());
+        assert_eq!(
+            config.templates["morning"],
+            vec![Prompt::new("What's the plan?", BulletType::Task)]
+        );
+        assert!(config.templates.contains_key(DEFAULT_REFLECTION_TEMPLATE));
+    }
+
+    #[test]
+    fn test_config_file_symbols() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("config.toml"),
+            "[symbols]
+task = \"☐\"
+completed = \"☑\"
+",
+        )
+        .unwrap();
+
+        let symbols = Config::load(temp_dir.path().into()).symbols;
+        let task = crate::entities::Bullet::new("Write", BulletType::Task);
+        assert_eq!(symbols.symbol(&task), "☐");
+        assert_eq!(symbols.symbol(&task.complete()), "☑");
+        assert_eq!(symbols.for_type(&BulletType::Event), "○");
+    }
+
+    #[test]
+    fn test_config_file_section_order() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("config.toml"),
+            "sections = [\"Notes\", \"insight\", \"chores\"]\n",
+        )
+        .unwrap();
+
+        let order = Config::load(temp_dir.path().into()).section_order;
+        assert_eq!(
+            order,
+            vec![
+                BulletType::Note,
+                BulletType::Insight,
+                BulletType::Task,
+                BulletType::Event,
+                BulletType::Priority,
+                BulletType::Inspiration,
+                BulletType::Misstep,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_config_file_format_steps() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("config.toml"),
+            "[format]
+steps = [\"trim\", \"max-length\", \"shout\", \"iso-dates\"]
+max_length = 80
+overflow = \"wrap\"
+",
+        )
+        .unwrap();
+
+        let steps = Config::load(temp_dir.path().into()).format_steps;
+        assert_eq!(
+            steps,
+            vec![
+                FormatStep::Trim,
+                FormatStep::MaxLength {
+                    limit: 80,
+                    wrap: true
+                },
+                FormatStep::IsoDates,
+            ]
+        );
+    }
 }
@@ -0,0 +1,145 @@
+use crate::application::JournalApp;
+use crate::entities::{Bullet, BulletType};
+use crate::infrastructure::MarkdownParser;
+use anyhow::{Context, Result, anyhow, bail};
+use chrono::NaiveDate;
+use std::path::Path;
+use std::time::Duration;
+
+/// How long a connection may stay silent before the daemon hangs up, so an
+/// idle client can't keep others waiting
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A bullet captured over the daemon socket
+#[derive(Debug, Clone, PartialEq)]
+pub struct Capture {
+    pub date: NaiveDate,
+    pub bullet: Bullet,
+}
+
+/// Parse a protocol line of the form `ADD <date> <type> <content>`.
+///
+/// `<date>` is `YYYY-MM-DD` or `today`, `<type>` is a bullet type name and
+/// `<content>` may start with an `HH:MM` time like in the editor.
+pub fn parse_command(line: &str, today: NaiveDate) -> Result<Capture> {
+    let mut parts = line.trim().splitn(4, ' ');
+    let command = parts.next().unwrap_or_default();
+    if !command.eq_ignore_ascii_case("ADD") {
+        bail!("unknown command: {}", command);
+    }
+
+    let (Some(date), Some(bullet_type), Some(content)) = (parts.next(), parts.next(), parts.next())
+    else {
+        bail!("expected: ADD <date> <type> <content>");
+    };
+
+    let date = if date.eq_ignore_ascii_case("today") {
+        today
+    } else {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .with_context(|| format!("invalid date: {}", date))?
+    };
+    let bullet_type: BulletType = bullet_type.parse().map_err(|e: String| anyhow!(e))?;
+
+    let (time, end_time, content) = MarkdownParser::split_time_range_prefix(content.trim());
+    if content.is_empty() {
+        bail!("empty bullet content");
+    }
+    let mut bullet = Bullet::new(content, bullet_type);
+    bullet.time = time;
+    bullet.end_time = end_time;
+
+    Ok(Capture { date, bullet })
+}
+
+/// Listen on a Unix socket and append every captured bullet to the journal.
+///
+/// Each connection may send any number of lines; every line is answered with
+/// `OK` or `ERR <reason>`. Connections are served one at a time, so captures
+/// never race with each other. The journal is opened with `open_app` for each
+/// capture and closed again, leaving it free for the editor and the TUI in
+/// between; a connection silent for `READ_TIMEOUT` is dropped.
+#[cfg(unix)]
+pub fn run(socket_path: &Path, open_app: impl Fn() -> Result<JournalApp>) -> Result<()> {
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::net::UnixListener;
+
+    if socket_path.exists() {
+        // A stale socket from a previous run would make bind fail
+        std::fs::remove_file(socket_path)
+            .with_context(|| format!("Failed to remove {}", socket_path.display()))?;
+    }
+    let listener = UnixListener::bind(socket_path)
+        .with_context(|| format!("Failed to bind {}", socket_path.display()))?;
+    println!("Listening on {}", socket_path.display());
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                tracing::warn!("Failed to accept connection: {}", e);
+                continue;
+            }
+        };
+        if let Err(e) = stream.set_read_timeout(Some(READ_TIMEOUT)) {
+            tracing::warn!("Failed to set read timeout: {}", e);
+            continue;
+        }
+        let mut writer = stream.try_clone()?;
+
+        // A timed-out read ends the connection like a hangup does
+        for line in BufReader::new(stream).lines() {
+            let Ok(line) = line else { break };
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let response = match open_app().and_then(|mut app| {
+                let capture = parse_command(&line, app.today())?;
+                app.append_bullet(capture.date, capture.bullet)
+            }) {
+                Ok(()) => "OK".to_string(),
+                Err(e) => format!("ERR {:#}", e),
+            };
+            if writeln!(writer, "{}", response).is_err() {
+                break;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn run(socket_path: &Path, _open_app: impl Fn() -> Result<JournalApp>) -> Result<()> {
+    bail!(
+        "the capture daemon needs Unix domain sockets, which aren't available here ({})",
+        socket_path.display()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveTime;
+
+    #[test]
+    fn test_parse_add_command() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+
+        let capture = parse_command("ADD today task 09:30 Call the bank", today).unwrap();
+        assert_eq!(capture.date, today);
+        assert_eq!(capture.bullet.bullet_type, BulletType::Task);
+        assert_eq!(capture.bullet.content, "Call the bank");
+        assert_eq!(capture.bullet.time, NaiveTime::from_hms_opt(9, 30, 0));
+
+        let capture = parse_command("add 2024-01-02 note Snow all day", today).unwrap();
+        assert_eq!(capture.date, NaiveDate::from_ymd_opt(2024, 1, 2).unwrap());
+        assert_eq!(capture.bullet.content, "Snow all day");
+
+        assert!(parse_command("ADD today chore Laundry", today).is_err());
+        assert!(parse_command("ADD 2024-13-01 note Nope", today).is_err());
+        assert!(parse_command("ADD today note", today).is_err());
+        assert!(parse_command("DEL today note x", today).is_err());
+    }
+}
@@ -0,0 +1,263 @@
+use crate::application::{Config, StorageBackend};
+use crate::entities::DateRange;
+use crate::infrastructure::storage::JournalStorage;
+use crossterm::style::{StyledContent, Stylize};
+use crossterm::tty::IsTty;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    Ok,
+    Warning,
+    Error,
+}
+
+/// Outcome of a single diagnostic check
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+    /// Suggested fix when the check didn't pass
+    pub fix: Option<String>,
+}
+
+impl Check {
+    fn ok(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Ok,
+            detail: detail.into(),
+            fix: None,
+        }
+    }
+
+    fn warning(name: &'static str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Warning,
+            detail: detail.into(),
+            fix: Some(fix.into()),
+        }
+    }
+
+    fn error(name: &'static str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Error,
+            detail: detail.into(),
+            fix: Some(fix.into()),
+        }
+    }
+}
+
+/// Run every diagnostic check against the given configuration
+pub fn run_diagnostics(config: &Config) -> Vec<Check> {
+    let mut checks = vec![check_journal_dir(&config.journal_dir)];
+
+    let location = match config.storage_backend {
+        StorageBackend::Postgres => "the PostgreSQL server".to_string(),
+        _ => config.database_path().display().to_string(),
+    };
+    match config.open_storage() {
+        Ok(storage) => {
+            checks.push(Check::ok(
+                "database",
+                format!("{} ({})", location, storage.backend_info()),
+            ));
+            checks.push(check_migrations(storage.as_ref()));
+            checks.push(check_orphaned_files(config, storage.as_ref()));
+        }
+        Err(e) => checks.push(Check::error(
+            "database",
+            format!("cannot open {}: {:#}", location, e),
+            "check that the file isn't locked by another journo process and is readable",
+        )),
+    }
+
+    checks.push(check_editor(&config.editor));
+    checks.push(check_tty());
+    checks
+}
+
+/// Print check results with actionable fixes; returns whether everything passed
+pub fn print_report(checks: &[Check], color: bool) -> bool {
+    for check in checks {
+        let (label, paint): (&str, fn(String) -> StyledContent<String>) = match check.status {
+            CheckStatus::Ok => ("ok", <String as Stylize>::green),
+            CheckStatus::Warning => ("warn", <String as Stylize>::yellow),
+            CheckStatus::Error => ("FAIL", <String as Stylize>::red),
+        };
+        let label = format!("{:>4}", label);
+        let label = if color {
+            paint(label).to_string()
+        } else {
+            label
+        };
+        println!("[{}] {}: {}", label, check.name, check.detail);
+        if let Some(fix) = &check.fix {
+            println!("       fix: {}", fix);
+        }
+    }
+
+    checks.iter().all(|c| c.status != CheckStatus::Error)
+}
+
+fn check_journal_dir(journal_dir: &Path) -> Check {
+    if let Err(e) = std::fs::create_dir_all(journal_dir) {
+        return Check::error(
+            "journal dir",
+            format!("cannot create {}: {}", journal_dir.display(), e),
+            "set JOURNAL_DIR to a writable location",
+        );
+    }
+
+    match tempfile::NamedTempFile::new_in(journal_dir) {
+        Ok(_) => Check::ok(
+            "journal dir",
+            format!("{} (writable)", journal_dir.display()),
+        ),
+        Err(e) => Check::error(
+            "journal dir",
+            format!("{} is not writable: {}", journal_dir.display(), e),
+            format!(
+                "fix permissions with `chmod u+rwx {}`",
+                journal_dir.display()
+            ),
+        ),
+    }
+}
+
+fn check_migrations(storage: &dyn JournalStorage) -> Check {
+    match storage.migration_status() {
+        Ok(status) => {
+            let pending = status.pending();
+            let level = status
+                .latest_applied()
+                .map_or("none".to_string(), |v| v.to_string());
+            if pending.is_empty() {
+                Check::ok("migrations", format!("up to date (level {})", level))
+            } else {
+                Check::error(
+                    "migrations",
+                    format!("level {}, pending: {:?}", level, pending),
+                    "run any journo command to apply migrations, and check the log if they fail",
+                )
+            }
+        }
+        Err(e) => Check::error(
+            "migrations",
+            format!("cannot read migration state: {:#}", e),
+            "the migrations table may be corrupted; restore the database from a backup",
+        ),
+    }
+}
+
+fn check_orphaned_files(config: &Config, storage: &dyn JournalStorage) -> Check {
+    let files = config.file_repository();
+    let file_dates = match files.all_dates() {
+        Ok(dates) => dates,
+        Err(e) => {
+            return Check::warning(
+                "markdown files",
+                format!("cannot scan {}: {}", config.data_dir.display(), e),
+                "check permissions of the data directory",
+            );
+        }
+    };
+
+    let (Some(first), Some(last)) = (file_dates.first(), file_dates.last()) else {
+        return Check::ok("markdown files", "no markdown files to check");
+    };
+
+    let db_dates = match storage.list_dates(DateRange::between(*first, *last)) {
+        Ok(dates) => dates,
+        Err(e) => {
+            return Check::error(
+                "markdown files",
+                format!("cannot list database dates: {:#}", e),
+                "restore the database from a backup",
+            );
+        }
+    };
+
+    let orphaned: Vec<String> = file_dates
+        .iter()
+        .filter(|d| !db_dates.contains(d))
+        .map(|d| d.to_string())
+        .collect();
+
+    if orphaned.is_empty() {
+        Check::ok(
+            "markdown files",
+            format!("{} files, all present in the database", file_dates.len()),
+        )
+    } else {
+        Check::warning(
+            "markdown files",
+            format!(
+                "{} files not in the database: {}",
+                orphaned.len(),
+                orphaned.join(", ")
+            ),
+            "run `journo sync-files` to import them",
+        )
+    }
+}
+
+fn check_editor(editor: &str) -> Check {
+    let program = editor.split_whitespace().next().unwrap_or_default();
+    match resolve_program(program) {
+        Some(path) => Check::ok("editor", format!("{} ({})", editor, path.display())),
+        None => Check::error(
+            "editor",
+            format!("`{}` not found on PATH", program),
+            "set $EDITOR to an installed editor, e.g. `export EDITOR=vim`",
+        ),
+    }
+}
+
+fn resolve_program(program: &str) -> Option<PathBuf> {
+    if program.is_empty() {
+        return None;
+    }
+    if program.contains(std::path::MAIN_SEPARATOR) {
+        let path = PathBuf::from(program);
+        return path.is_file().then_some(path);
+    }
+
+    std::env::var_os("PATH").and_then(|paths| {
+        std::env::split_paths(&paths)
+            .map(|dir| dir.join(program))
+            .find(|candidate| candidate.is_file())
+    })
+}
+
+fn check_tty() -> Check {
+    let stdin_tty = std::io::stdin().is_tty();
+    let stdout_tty = std::io::stdout().is_tty();
+    if !stdin_tty || !stdout_tty {
+        return Check::warning(
+            "terminal",
+            format!("stdin tty: {}, stdout tty: {}", stdin_tty, stdout_tty),
+            "run journo directly in a terminal to use the week view",
+        );
+    }
+
+    let term = std::env::var("TERM").unwrap_or_default();
+    match crossterm::terminal::size() {
+        Ok((cols, rows)) if cols >= 78 && rows >= 26 => {
+            Check::ok("terminal", format!("TERM={} ({}x{})", term, cols, rows))
+        }
+        Ok((cols, rows)) => Check::warning(
+            "terminal",
+            format!("TERM={} ({}x{}) is smaller than 78x26", term, cols, rows),
+            "enlarge the terminal window so the week view fits",
+        ),
+        Err(e) => Check::warning(
+            "terminal",
+            format!("cannot query terminal size: {}", e),
+            "check that TERM is set correctly",
+        ),
+    }
+}
@@ -0,0 +1,247 @@
+use crate::application::{Config, JournalApp, StorageBackend, THEME_NAMES};
+use crate::infrastructure::import_obsidian;
+use anyhow::Context;
+use chrono::Weekday;
+use std::fs;
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+
+const WEEKDAY_NAMES: [&str; 7] = [
+    "monday",
+    "tuesday",
+    "wednesday",
+    "thursday",
+    "friday",
+    "saturday",
+    "sunday",
+];
+
+/// Settings chosen during `journo init`
+#[derive(Debug, Clone, PartialEq)]
+pub struct InitAnswers {
+    pub journal_dir: PathBuf,
+    pub editor: String,
+    pub week_start: Weekday,
+    pub theme: String,
+    pub storage_backend: StorageBackend,
+    pub postgres_url: Option<String>,
+}
+
+impl InitAnswers {
+    /// Contents of the `config.toml` these answers produce
+    pub fn config_toml(&self) -> String {
+        let quote = |value: &str| toml::Value::String(value.to_string()).to_string();
+        let mut config = format!(
+            "# Created by `journo init`\neditor = {}\nweek_start = {}\ntheme = {}\nstorage_backend = {}\n",
+            quote(&self.editor),
+            quote(WEEKDAY_NAMES[self.week_start.num_days_from_monday() as usize]),
+            quote(&self.theme),
+            quote(self.storage_backend.name()),
+        );
+        if let Some(url) = &self.postgres_url {
+            config.push_str(&format!("\n[postgres]\nurl = {}\n", quote(url)));
+        }
+        config
+    }
+}
+
+/// Walk through setting up a journal: ask for its directory and preferences,
+/// write `config.toml`, create the database and optionally import existing
+/// daily notes. Pressing Enter (or closing `input`) accepts each default.
+pub fn run_wizard(input: &mut impl BufRead, output: &mut impl Write) -> anyhow::Result<()> {
+    writeln!(
+        output,
+        "Setting up journo. Press Enter to accept the [default]."
+    )?;
+
+    let default_dir = Config::default_journal_dir();
+    let journal_dir = PathBuf::from(ask(
+        input,
+        output,
+        "Journal directory",
+        &default_dir.display().to_string(),
+    )?);
+    let config_path = journal_dir.join("config.toml");
+    if config_path.exists() {
+        let overwrite = ask(
+            input,
+            output,
+            &format!("{} exists. Overwrite it? (y/n)", config_path.display()),
+            "n",
+        )?;
+        if !overwrite.eq_ignore_ascii_case("y") {
+            writeln!(output, "Keeping the existing configuration")?;
+            return Ok(());
+        }
+    }
+
+    let default_editor = std::env::var("EDITOR").unwrap_or_else(|_| "nano".to_string());
+    let editor = ask(input, output, "Editor", &default_editor)?;
+
+    let week_start = loop {
+        let answer = ask(input, output, "First day of the week", "sunday")?;
+        match answer.parse::<Weekday>() {
+            Ok(day) => break day,
+            Err(_) => writeln!(output, "  Enter a day such as monday or sunday")?,
+        }
+    };
+
+    let theme = loop {
+        let question = format!("Theme ({})", THEME_NAMES.join("/"));
+        let answer = ask(input, output, &question, THEME_NAMES[0])?.to_lowercase();
+        if THEME_NAMES.contains(&answer.as_str()) {
+            break answer;
+        }
+        writeln!(output, "  Unknown theme '{}'", answer)?;
+    };
+
+    let backends = StorageBackend::available();
+    anyhow::ensure!(
+        !backends.is_empty(),
+        "journo was built without a storage backend; enable duckdb, sqlite or postgres"
+    );
+    let storage_backend = if backends.len() == 1 {
+        backends[0]
+    } else {
+        loop {
+            let names: Vec<_> = backends.iter().map(|b| b.name()).collect();
+            let question = format!("Storage backend ({})", names.join("/"));
+            match ask(input, output, &question, names[0])?.parse::<StorageBackend>() {
+                Ok(backend) if backends.contains(&backend) => break backend,
+                Ok(backend) => writeln!(output, "  {} isn't built in", backend.name())?,
+                Err(e) => writeln!(output, "  {}", e)?,
+            }
+        }
+    };
+    let postgres_url = match storage_backend {
+        StorageBackend::Postgres => Some(ask(
+            input,
+            output,
+            "PostgreSQL URL",
+            "postgres://localhost/journo",
+        )?),
+        _ => None,
+    };
+
+    let answers = InitAnswers {
+        journal_dir,
+        editor,
+        week_start,
+        theme,
+        storage_backend,
+        postgres_url,
+    };
+    let app = create_journal(&answers)?;
+    writeln!(output, "Wrote {}", config_path.display())?;
+
+    let notes = ask(
+        input,
+        output,
+        "Import existing daily notes (folder of YYYY-MM-DD.md files, blank to skip)",
+        "",
+    )?;
+    if !notes.is_empty() {
+        let mut app = app;
+        app.import_entries(&import_obsidian(notes.as_ref())?, false)?;
+    }
+
+    if answers.journal_dir != default_dir {
+        writeln!(
+            output,
+            "Add `export JOURNAL_DIR={}` to your shell profile so journo finds this journal",
+            answers.journal_dir.display()
+        )?;
+    }
+    writeln!(output, "All set! Run `journo` to start writing.")?;
+    Ok(())
+}
+
+/// Write the config file and create the directories and database it describes
+pub fn create_journal(answers: &InitAnswers) -> anyhow::Result<JournalApp> {
+    fs::create_dir_all(&answers.journal_dir).with_context(|| {
+        format!(
+            "Failed to create journal directory: {}",
+            answers.journal_dir.display()
+        )
+    })?;
+    let config_path = answers.journal_dir.join("config.toml");
+    fs::write(&config_path, answers.config_toml())
+        .with_context(|| format!("Failed to write {}", config_path.display()))?;
+
+    let config = Config::load(answers.journal_dir.clone());
+    fs::create_dir_all(&config.data_dir).with_context(|| {
+        format!(
+            "Failed to create data directory: {}",
+            config.data_dir.display()
+        )
+    })?;
+    let storage = config.open_storage()?;
+    Ok(JournalApp::with_storage(storage, config))
+}
+
+/// Print `question [default]: ` and read the answer, or the default when the
+/// answer is blank or input has ended
+fn ask(
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+    question: &str,
+    default: &str,
+) -> anyhow::Result<String> {
+    if default.is_empty() {
+        write!(output, "{}: ", question)?;
+    } else {
+        write!(output, "{} [{}]: ", question, default)?;
+    }
+    output.flush()?;
+
+    let mut answer = String::new();
+    input.read_line(&mut answer)?;
+    let answer = answer.trim();
+    Ok(if answer.is_empty() {
+        default.to_string()
+    } else {
+        answer.to_string()
+    })
+}
+
+#[cfg(all(test, feature = "duckdb"))]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_wizard_writes_config_and_database() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let journal_dir = temp_dir.path().join("journal");
+        let notes_dir = temp_dir.path().join("notes");
+        fs::create_dir_all(&notes_dir).unwrap();
+        fs::write(notes_dir.join("2024-03-15.md"), "## Tasks\n- [ ] Unpack\n").unwrap();
+
+        let backend_answer = if StorageBackend::available().len() > 1 {
+            "duckdb\n"
+        } else {
+            ""
+        };
+        let answers = format!(
+            "{}\nvim\nmonday\nsolarized\nlight\n{}{}\n",
+            journal_dir.display(),
+            backend_answer,
+            notes_dir.display()
+        );
+        let mut output = Vec::new();
+        run_wizard(&mut Cursor::new(answers), &mut output).unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("Unknown theme 'solarized'"));
+        let config = Config::load(journal_dir.clone());
+        assert_eq!(config.editor, "vim");
+        assert_eq!(config.week_start, Weekday::Mon);
+        assert_eq!(config.theme, "light");
+        assert_eq!(config.storage_backend, StorageBackend::DuckDb);
+        assert!(config.database_path().exists());
+
+        let date = chrono::NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let storage = config.open_storage().unwrap();
+        assert!(storage.load_entry(date).unwrap().is_some());
+    }
+}
@@ -0,0 +1,65 @@
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+
+/// Reflective prompts handed out by `journo prompt` and `journo new --prompt`;
+/// `[prompts] extra` in config.toml adds to them
+pub const BUILTIN_PROMPTS: &[&str] = &[
+    "What took more energy today than it should have?",
+    "What are you looking forward to this week?",
+    "Who made your day better, and did you tell them?",
+    "What would you do differently if you had today again?",
+    "What did you learn today that surprised you?",
+    "What are you avoiding, and why?",
+    "What small win deserves more credit than you gave it?",
+    "Where did your time go today, and was that where you wanted it to go?",
+    "What is worrying you that you can let go of?",
+    "What would make tomorrow a good day?",
+    "What are you grateful for right now?",
+    "Which conversation is still on your mind?",
+    "What did you say yes to that you wish you'd declined?",
+    "When did you feel most like yourself today?",
+    "What habit is serving you well lately, and which one isn't?",
+    "What question have you been meaning to ask someone?",
+    "What would you tell yourself from a year ago?",
+    "What drained you today, and what recharged you?",
+    "What are you curious about at the moment?",
+    "What is one thing you can make simpler?",
+];
+
+/// Days before a prompt that was handed out may come up again
+pub const PROMPT_REPEAT_DAYS: i64 = 30;
+
+/// A random prompt among those not in `recent`, or among all of them once
+/// every one was shown recently
+pub fn pick_prompt<'a>(prompts: &'a [String], recent: &[String]) -> Option<&'a String> {
+    let fresh: Vec<&String> = prompts.iter().filter(|p| !recent.contains(p)).collect();
+    let pool = if fresh.is_empty() {
+        prompts.iter().collect()
+    } else {
+        fresh
+    };
+    if pool.is_empty() {
+        return None;
+    }
+    // RandomState is seeded anew for each process, random enough to vary the
+    // pick without another dependency
+    let roll = RandomState::new().hash_one(pool.len()) as usize;
+    Some(pool[roll % pool.len()])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pick_prompt_skips_recent_ones() {
+        let prompts: Vec<String> = ["one", "two", "three"].map(String::from).to_vec();
+        let recent: Vec<String> = ["one", "three"].map(String::from).to_vec();
+        for _ in 0..10 {
+            assert_eq!(pick_prompt(&prompts, &recent).unwrap(), "two");
+        }
+
+        assert!(pick_prompt(&prompts, &prompts).is_some());
+        assert!(pick_prompt(&[], &recent).is_none());
+    }
+}
@@ -1,7 +1,26 @@
+pub mod api;
 pub mod app;
+pub mod builder;
 pub mod config;
+pub mod daemon;
+#[cfg(feature = "tui")]
+pub mod doctor;
+pub mod init;
+pub mod journaling_prompts;
+pub mod output;
+pub mod pipe;
+pub mod plan;
+pub mod rest;
+pub mod serve;
+pub mod template;
+pub mod trends;
+#[cfg(feature = "tui")]
 pub mod tui;
+pub mod watch;
 
 pub use app::*;
+pub use builder::*;
 pub use config::*;
+pub use output::*;
+#[cfg(feature = "tui")]
 pub use tui::*;
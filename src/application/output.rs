@@ -0,0 +1,255 @@
+use crate::entities::{Bullet, BulletType, CrossReference, Entry, PeriodStats, TaskState};
+use std::io::IsTerminal;
+use std::str::FromStr;
+
+/// When printed output may use ANSI colors and styles (`--color`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorMode {
+    /// Style output only when stdout is a terminal and `NO_COLOR` is unset
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorMode {
+    /// Whether output written to stdout should be styled
+    pub fn use_color(self) -> bool {
+        match self {
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+            ColorMode::Auto => {
+                std::io::stdout().is_terminal()
+                    && std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+            }
+        }
+    }
+}
+
+impl FromStr for ColorMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "auto" => Ok(ColorMode::Auto),
+            "always" => Ok(ColorMode::Always),
+            "never" => Ok(ColorMode::Never),
+            other => Err(format!(
+                "unknown color mode '{}' (expected auto, always or never)",
+                other
+            )),
+        }
+    }
+}
+
+/// Wrap text in an ANSI SGR sequence, e.g. `paint("1;33", text)` for bold yellow
+pub fn paint(codes: &str, text: &str) -> String {
+    format!("\x1b[{}m{}\x1b[0m", codes, text)
+}
+
+/// One-line summary of a day for shell prompts, e.g. "3 tasks (1 done), 2 events"
+pub fn brief_summary(stats: &PeriodStats) -> String {
+    if stats.days_with_entries == 0 {
+        return "nothing logged".to_string();
+    }
+    let tasks = stats.tasks_completed + stats.tasks_pending;
+    format!(
+        "{} {} ({} done), {} {}",
+        tasks,
+        if tasks == 1 { "task" } else { "tasks" },
+        stats.tasks_completed,
+        stats.events,
+        if stats.events == 1 { "event" } else { "events" }
+    )
+}
+
+/// The brief summary with tmux `#[fg=...]` styles, for `status-right`
+pub fn tmux_summary(stats: &PeriodStats) -> String {
+    if stats.days_with_entries == 0 {
+        return "#[fg=colour244]nothing logged#[default]".to_string();
+    }
+    format!(
+        "#[fg=yellow]{} open#[default] #[fg=green]{} done#[default] #[fg=cyan]{} {}#[default]",
+        stats.tasks_pending,
+        stats.tasks_completed,
+        stats.events,
+        if stats.events == 1 { "event" } else { "events" }
+    )
+}
+
+/// A day's entry as plain sentences for screen readers: no symbols or box
+/// drawing, one line per bullet, task states spelled out ("Completed task:")
+/// and recorded follow-ups named after the bullet
+pub fn read_aloud(entry: &Entry, follow_ups: &[CrossReference]) -> String {
+    let date = entry.date.format("%A, %B %-d, %Y");
+    if entry.is_empty() {
+        return format!("No entry for {}.\n", date);
+    }
+
+    let mut lines = vec![format!("{}.", date)];
+    let sessions = entry.sessions();
+    let parts = std::iter::once(None).chain(sessions.into_iter().map(Some));
+    for session in parts {
+        let part = entry.session(session);
+        if part.is_empty() {
+            continue;
+        }
+        if let Some(start) = session {
+            lines.push(format!("Session starting {}.", start.format("%H:%M")));
+        }
+        for bullet_type in BulletType::ALL {
+            let bullets = part.get_bullets(&bullet_type);
+            if bullets.is_empty() {
+                continue;
+            }
+            lines.push(format!(
+                "{}, {} {}.",
+                bullet_type.section_name(),
+                bullets.len(),
+                if bullets.len() == 1 { "item" } else { "items" }
+            ));
+            for bullet in bullets {
+                read_bullet(bullet, 0, follow_ups, &mut lines);
+            }
+        }
+    }
+
+    if let Some(body) = &entry.body {
+        lines.push("Free writing.".to_string());
+        lines.extend(
+            body.lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(speakable),
+        );
+    }
+
+    let mut text = lines.join("\n");
+    text.push('\n');
+    text
+}
+
+fn read_bullet(
+    bullet: &Bullet,
+    depth: usize,
+    follow_ups: &[CrossReference],
+    lines: &mut Vec<String>,
+) {
+    let kind = match (bullet.bullet_type, bullet.task_state) {
+        (BulletType::Task, Some(TaskState::Pending)) => "open task".to_string(),
+        (BulletType::Task, Some(state)) => format!("{} task", state),
+        (bullet_type, _) => bullet_type.to_string(),
+    };
+    let mut label = if depth > 0 {
+        format!("Sub-item, {}", kind)
+    } else {
+        let mut chars = kind.chars();
+        chars
+            .next()
+            .map(|c| c.to_uppercase().chain(chars).collect())
+            .unwrap_or_default()
+    };
+    match (bullet.time, bullet.end_time) {
+        (Some(start), Some(end)) => label.push_str(&format!(
+            " from {} to {}",
+            start.format("%H:%M"),
+            end.format("%H:%M")
+        )),
+        (Some(start), None) => label.push_str(&format!(" at {}", start.format("%H:%M"))),
+        _ => {}
+    }
+
+    let mut sentence = speakable(&bullet.content);
+    if !sentence.ends_with(['.', '!', '?']) {
+        sentence.push('.');
+    }
+    if let Some(reference) = follow_ups.iter().find(|r| r.is_from(bullet)) {
+        sentence.push_str(&format!(
+            " Follow-up of {}.",
+            reference.target_date.format("%A, %B %-d")
+        ));
+    }
+    lines.push(format!("{}: {}", label, sentence));
+    for child in &bullet.children {
+        read_bullet(child, depth + 1, follow_ups, lines);
+    }
+}
+
+/// Bullet content without markup a screen reader would spell out: tag and
+/// mention sigils, emphasis markers and link brackets
+fn speakable(content: &str) -> String {
+    content
+        .split_whitespace()
+        .map(|word| {
+            let bare = word.trim_start_matches(['#', '@']);
+            let word = if bare.starts_with(char::is_alphanumeric) {
+                bare
+            } else {
+                word
+            };
+            word.replace(['*', '`', '[', ']'], "")
+        })
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_brief_summary() {
+        let stats = PeriodStats {
+            days_with_entries: 1,
+            tasks_completed: 1,
+            tasks_pending: 2,
+            events: 2,
+            words: 40,
+        };
+        assert_eq!(brief_summary(&stats), "3 tasks (1 done), 2 events");
+        assert_eq!(
+            tmux_summary(&stats),
+            "#[fg=yellow]2 open#[default] #[fg=green]1 done#[default] #[fg=cyan]2 events#[default]"
+        );
+        assert_eq!(brief_summary(&PeriodStats::default()), "nothing logged");
+    }
+
+    #[test]
+    fn test_read_aloud() {
+        let date = chrono::NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let mut entry = Entry::new(date);
+        entry.add_bullet(
+            Bullet::with_task_state(
+                "Ship the **release** #work",
+                BulletType::Task,
+                TaskState::Completed,
+            )
+            .with_child(Bullet::new("Call @alice", BulletType::Note)),
+        );
+        let mut standup = Bullet::new("Standup!", BulletType::Event);
+        standup.time = chrono::NaiveTime::from_hms_opt(9, 30, 0);
+        let last_standup = Bullet::new("Standup", BulletType::Event);
+        let follow_ups = [CrossReference::new(
+            date,
+            &standup,
+            date - chrono::Duration::days(1),
+            &last_standup,
+        )];
+        entry.add_bullet(standup);
+
+        assert_eq!(
+            read_aloud(&entry, &follow_ups),
+            "Friday, March 15, 2024.\n\
+             Tasks, 1 item.\n\
+             Completed task: Ship the release work.\n\
+             Sub-item, note: Call alice.\n\
+             Events, 1 item.\n\
+             Event at 09:30: Standup! Follow-up of Thursday, March 14.\n"
+        );
+        assert_eq!(
+            read_aloud(&Entry::new(date), &[]),
+            "No entry for Friday, March 15, 2024.\n"
+        );
+    }
+}
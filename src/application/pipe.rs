@@ -0,0 +1,70 @@
+use crate::infrastructure::MarkdownParser;
+use anyhow::{Context, Result, bail};
+use chrono::NaiveDate;
+
+/// What `journo parse` and `journo format` turn markdown read from stdin into
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PipeOutput {
+    /// The parsed entry as pretty-printed JSON, keys sorted so diffs are stable
+    Json,
+    /// The entry written back out the way journo saves it
+    Markdown,
+}
+
+/// Parse `input` as the entry for `date` and render it as `output`. Dropped
+/// lines are reported on stderr, or fail the run in strict mode.
+pub fn transform_entry(
+    parser: &MarkdownParser,
+    date: NaiveDate,
+    input: &str,
+    strict: bool,
+    output: PipeOutput,
+) -> Result<String> {
+    let (entry, warnings) = parser.parse_with_report(date, input)?;
+    for warning in &warnings {
+        eprintln!("warning: {}", warning);
+    }
+    if strict && !warnings.is_empty() {
+        bail!("strict mode refuses to drop content");
+    }
+
+    match output {
+        // Going through Value sorts the section map, which is a HashMap
+        PipeOutput::Json => {
+            let value = serde_json::to_value(&entry).context("Failed to serialize entry")?;
+            Ok(format!("{}\n", serde_json::to_string_pretty(&value)?))
+        }
+        PipeOutput::Markdown => parser.serialize(&entry),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    #[test]
+    fn test_transform_entry() {
+        let parser = MarkdownParser::new();
+        let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let input = "# Notes\n- Second note\n\n# Tasks\n- [ ] Call the bank\n";
+
+        let markdown = transform_entry(&parser, date, input, false, PipeOutput::Markdown).unwrap();
+        assert_eq!(
+            transform_entry(&parser, date, &markdown, false, PipeOutput::Markdown).unwrap(),
+            markdown
+        );
+
+        let json = transform_entry(&parser, date, input, false, PipeOutput::Json).unwrap();
+        assert_eq!(
+            json,
+            transform_entry(&parser, date, &markdown, false, PipeOutput::Json).unwrap()
+        );
+        let value: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["date"], "2024-03-15");
+
+        let stray = "stray line\n# Notes\n- Kept\n";
+        assert!(transform_entry(&parser, date, stray, false, PipeOutput::Markdown).is_ok());
+        assert!(transform_entry(&parser, date, stray, true, PipeOutput::Markdown).is_err());
+    }
+}
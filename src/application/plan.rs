@@ -0,0 +1,467 @@
+use crate::entities::{Bullet, BulletType, DateRange, Entry, TaskState};
+use chrono::{Datelike, Duration, NaiveDate, NaiveTime, Timelike, Weekday};
+
+/// Hours the timeline spans unless blocks fall outside them
+const DAY_START_HOUR: u32 = 8;
+const DAY_END_HOUR: u32 = 18;
+/// Gaps shorter than this aren't worth listing as free time
+const MIN_FREE_MINUTES: i64 = 15;
+
+/// A bullet written as `HH:MM-HH:MM`
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeBlock {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+    pub bullet: Bullet,
+}
+
+/// A day's bullets laid out on a timeline, with clashes and gaps worked out
+#[derive(Debug, Clone, PartialEq)]
+pub struct DayPlan {
+    /// First hour of the timeline
+    pub start: NaiveTime,
+    /// End of the timeline, on the hour unless a block runs to midnight
+    pub end: NaiveTime,
+    /// Time blocks, ordered by start time
+    pub blocks: Vec<TimeBlock>,
+    /// Bullets with a time but no end, ordered by time
+    pub markers: Vec<Bullet>,
+    /// Pairs of indexes into `blocks` that overlap
+    pub overlaps: Vec<(usize, usize)>,
+    /// Gaps between blocks within the timeline
+    pub free: Vec<(NaiveTime, NaiveTime)>,
+}
+
+impl DayPlan {
+    /// Plan for a day's entry; nested bullets count as well
+    pub fn new(entry: Option<&Entry>) -> Self {
+        let mut blocks = Vec::new();
+        let mut markers = Vec::new();
+        for bullet in entry.into_iter().flat_map(|e| e.all_bullets()) {
+            match (bullet.time, bullet.end_time) {
+                (Some(start), Some(end)) => blocks.push(TimeBlock {
+                    start,
+                    end,
+                    bullet: bullet.clone(),
+                }),
+                (Some(_), None) => markers.push(bullet.clone()),
+                _ => {}
+            }
+        }
+        blocks.sort_by_key(|b| (b.start, b.end));
+        markers.sort_by_key(|b| b.time);
+
+        let hour = |h| NaiveTime::from_hms_opt(h, 0, 0).expect("valid hour");
+        let times = blocks
+            .iter()
+            .flat_map(|b| [b.start, b.end])
+            .chain(markers.iter().filter_map(|b| b.time));
+        let (mut start, mut end) = (hour(DAY_START_HOUR), hour(DAY_END_HOUR));
+        for time in times {
+            start = start.min(hour(time.hour()));
+            end = end.max(ceil_hour(time));
+        }
+
+        let mut overlaps = Vec::new();
+        for (i, block) in blocks.iter().enumerate() {
+            for (j, later) in blocks.iter().enumerate().skip(i + 1) {
+                // Sorted by start, so once one starts after this block ends, all later ones do
+                if later.start >= block.end {
+                    break;
+                }
+                overlaps.push((i, j));
+            }
+        }
+
+        let mut free = Vec::new();
+        let mut cursor = start;
+        for block in &blocks {
+            if block.start - cursor >= Duration::minutes(MIN_FREE_MINUTES) {
+                free.push((cursor, block.start));
+            }
+            cursor = cursor.max(block.end);
+        }
+        if end - cursor >= Duration::minutes(MIN_FREE_MINUTES) {
+            free.push((cursor, end));
+        }
+
+        Self {
+            start,
+            end,
+            blocks,
+            markers,
+            overlaps,
+            free,
+        }
+    }
+
+    /// Whether the block at `index` clashes with another
+    pub fn is_overlapping(&self, index: usize) -> bool {
+        self.overlaps.iter().any(|&(a, b)| a == index || b == index)
+    }
+
+    /// Total time taken up by blocks, counting overlapping time once
+    pub fn scheduled(&self) -> Duration {
+        let mut total = Duration::zero();
+        let mut covered_until: Option<NaiveTime> = None;
+        for block in &self.blocks {
+            let start = covered_until.map_or(block.start, |until| until.max(block.start));
+            if block.end > start {
+                total += block.end - start;
+            }
+            covered_until = Some(covered_until.map_or(block.end, |until| until.max(block.end)));
+        }
+        total
+    }
+}
+
+/// A pending task or misstep left over from the week under review
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReviewItem {
+    pub date: NaiveDate,
+    pub bullet: Bullet,
+}
+
+/// A line of the weekly plan, with the review item it was carried over from
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlanLine {
+    pub text: String,
+    pub origin: Option<ReviewItem>,
+}
+
+/// Last week's loose ends beside the plan for the week ahead.
+///
+/// Plan lines starting with a weekday (`wed Call the bank`) become tasks on
+/// that day; the rest become priorities on the first day of the week.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeekPlan {
+    /// The week before `week`
+    pub review: DateRange,
+    /// The week being planned
+    pub week: DateRange,
+    /// Pending tasks and missteps of the review week not carried over yet
+    pub leftovers: Vec<ReviewItem>,
+    pub lines: Vec<PlanLine>,
+}
+
+impl WeekPlan {
+    /// Plan for `week`, given the review week's entries and the entry for the
+    /// week's first day, whose pending priorities start off the plan
+    pub fn new(week: DateRange, review_entries: &[Entry], first_day: Option<&Entry>) -> Self {
+        let review = DateRange::between(
+            week.start() - Duration::days(7),
+            week.start() - Duration::days(1),
+        );
+        let leftovers = review_entries
+            .iter()
+            .filter(|entry| review.contains(entry.date))
+            .flat_map(|entry| {
+                bullets_in_order(entry)
+                    .filter(|bullet| {
+                        bullet.task_state == Some(TaskState::Pending)
+                            || bullet.bullet_type == BulletType::Misstep
+                    })
+                    .map(|bullet| ReviewItem {
+                        date: entry.date,
+                        bullet: bullet.clone(),
+                    })
+            })
+            .collect();
+        let lines = first_day
+            .into_iter()
+            .flat_map(|entry| entry.get_bullets(&BulletType::Priority))
+            .filter(|bullet| bullet.task_state == Some(TaskState::Pending))
+            .map(|bullet| PlanLine {
+                text: bullet.content.clone(),
+                origin: None,
+            })
+            .collect();
+
+        Self {
+            review,
+            week,
+            leftovers,
+            lines,
+        }
+    }
+
+    /// Move a leftover to the end of the plan, returning the new line's index
+    pub fn carry(&mut self, index: usize) -> Option<usize> {
+        if index >= self.leftovers.len() {
+            return None;
+        }
+        let item = self.leftovers.remove(index);
+        self.lines.push(PlanLine {
+            text: item.bullet.content.clone(),
+            origin: Some(item),
+        });
+        Some(self.lines.len() - 1)
+    }
+
+    /// Drop a plan line; one carried over goes back among the leftovers
+    pub fn remove_line(&mut self, index: usize) {
+        if index >= self.lines.len() {
+            return;
+        }
+        if let Some(item) = self.lines.remove(index).origin {
+            let at = self
+                .leftovers
+                .partition_point(|other| other.date <= item.date);
+            self.leftovers.insert(at, item);
+        }
+    }
+
+    /// Carried-over tasks, to be marked migrated where they were written
+    pub fn carried_tasks(&self) -> impl Iterator<Item = &ReviewItem> {
+        self.lines
+            .iter()
+            .filter_map(|line| line.origin.as_ref())
+            .filter(|item| item.bullet.task_state == Some(TaskState::Pending))
+    }
+
+    /// The bullet each non-blank line becomes and the day it goes on
+    pub fn planned_bullets(&self) -> Vec<(NaiveDate, Bullet)> {
+        let mut bullets = Vec::new();
+        for line in &self.lines {
+            let text = line.text.trim();
+            if text.is_empty() {
+                continue;
+            }
+            let on_day = text.split_once(' ').and_then(|(day, rest)| {
+                let weekday = day.parse::<Weekday>().ok()?;
+                let date = self.week.days().find(|date| date.weekday() == weekday)?;
+                Some((date, rest.trim()))
+            });
+            bullets.push(match on_day {
+                Some((date, content)) => (date, Bullet::new(content, BulletType::Task)),
+                None => (self.week.start(), Bullet::new(text, BulletType::Priority)),
+            });
+        }
+        bullets
+    }
+}
+
+/// What the week rollover review does with one of last week's pending tasks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MigrationChoice {
+    /// Leave it pending where it is
+    Keep,
+    /// Mark it migrated and add a pending copy to today's entry
+    Migrate,
+    /// Mark it scheduled and add a pending copy to the entry for the date
+    Schedule(NaiveDate),
+    /// Remove it, with its sub-bullets, from its entry
+    Drop,
+}
+
+/// Last week's pending tasks and priorities with a choice for each, offered
+/// on the first launch of a new week
+#[derive(Debug, Clone, PartialEq)]
+pub struct MigrationReview {
+    /// The week the tasks were written in
+    pub week: DateRange,
+    pub items: Vec<(ReviewItem, MigrationChoice)>,
+}
+
+impl MigrationReview {
+    /// Review of the pending tasks and priorities among `entries` written in
+    /// `week`, all kept until decided otherwise
+    pub fn new(week: DateRange, entries: &[Entry]) -> Self {
+        let items = entries
+            .iter()
+            .filter(|entry| week.contains(entry.date))
+            .flat_map(|entry| {
+                bullets_in_order(entry)
+                    .filter(|bullet| bullet.task_state == Some(TaskState::Pending))
+                    .map(|bullet| {
+                        let item = ReviewItem {
+                            date: entry.date,
+                            bullet: bullet.clone(),
+                        };
+                        (item, MigrationChoice::Keep)
+                    })
+            })
+            .collect();
+        Self { week, items }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Items something was decided for
+    pub fn decided(&self) -> impl Iterator<Item = &(ReviewItem, MigrationChoice)> {
+        self.items
+            .iter()
+            .filter(|(_, choice)| *choice != MigrationChoice::Keep)
+    }
+}
+
+/// Date typed at the schedule prompt of the migration review: `YYYY-MM-DD`,
+/// a weekday (`fri`, the next one after `today`) or a number of days (`+3`)
+pub fn parse_schedule_date(input: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let input = input.trim();
+    if let Some(days) = input.strip_prefix('+') {
+        return today.checked_add_signed(Duration::days(days.parse().ok()?));
+    }
+    if let Ok(weekday) = input.parse::<Weekday>() {
+        let ahead =
+            (weekday.num_days_from_monday() + 7 - today.weekday().num_days_from_monday()) % 7;
+        return Some(today + Duration::days(if ahead == 0 { 7 } else { ahead.into() }));
+    }
+    NaiveDate::parse_from_str(input, "%Y-%m-%d").ok()
+}
+
+/// First day of the week to plan: `date` itself when a week starts on it,
+/// otherwise the start of the following week
+pub fn next_week_start(date: NaiveDate, first_day: Weekday) -> NaiveDate {
+    let week = DateRange::week_containing(date, first_day);
+    if week.start() == date {
+        date
+    } else {
+        week.start() + Duration::days(7)
+    }
+}
+
+/// An entry's bullets, nested ones included, in canonical section order
+fn bullets_in_order(entry: &Entry) -> impl Iterator<Item = &Bullet> {
+    BulletType::ALL.into_iter().flat_map(move |bullet_type| {
+        entry
+            .get_bullets(&bullet_type)
+            .iter()
+            .flat_map(|b| b.flatten())
+    })
+}
+
+/// The hour at or after `time`, staying within the day
+fn ceil_hour(time: NaiveTime) -> NaiveTime {
+    if time.minute() == 0 && time.second() == 0 || time.hour() == 23 {
+        time
+    } else {
+        NaiveTime::from_hms_opt(time.hour() + 1, 0, 0).expect("valid hour")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(h: u32, m: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, 0).unwrap()
+    }
+
+    #[test]
+    fn test_overlaps_and_free_slots() {
+        let mut entry = Entry::new(NaiveDate::from_ymd_opt(2024, 3, 15).unwrap());
+        entry
+            .add_bullet(Bullet::new("Deep work", BulletType::Event).between(at(10, 0), at(11, 30)));
+        entry.add_bullet(Bullet::new("Standup", BulletType::Event).between(at(11, 0), at(11, 15)));
+        entry.add_bullet(
+            Bullet::new("Late call", BulletType::Event).between(at(18, 30), at(19, 10)),
+        );
+        entry.add_bullet(Bullet::new("Pick up parcel", BulletType::Task).at(at(7, 45)));
+
+        let plan = DayPlan::new(Some(&entry));
+        assert_eq!((plan.start, plan.end), (at(7, 0), at(20, 0)));
+        assert_eq!(plan.blocks[0].bullet.content, "Deep work");
+        assert_eq!(plan.overlaps, vec![(0, 1)]);
+        assert!(plan.is_overlapping(1));
+        assert!(!plan.is_overlapping(2));
+        assert_eq!(
+            plan.free,
+            vec![
+                (at(7, 0), at(10, 0)),
+                (at(11, 30), at(18, 30)),
+                (at(19, 10), at(20, 0)),
+            ]
+        );
+        assert_eq!(plan.markers.len(), 1);
+        assert_eq!(plan.scheduled(), Duration::minutes(130));
+    }
+
+    #[test]
+    fn test_week_plan_carries_leftovers() {
+        let day = |d| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+        let week = DateRange::week(next_week_start(day(16), Weekday::Mon));
+        assert_eq!(week.start(), day(18));
+
+        let mut review = Entry::new(day(12));
+        review.add_bullet(Bullet::new("Send invoice", BulletType::Task));
+        review.add_bullet(Bullet::new("Done already", BulletType::Task).complete());
+        review.add_bullet(Bullet::new("Skipped the review", BulletType::Misstep));
+        let mut earlier = Entry::new(day(11));
+        earlier.add_bullet(Bullet::new("Book flights", BulletType::Priority));
+        let mut first_day = Entry::new(day(18));
+        first_day.add_bullet(Bullet::new("Ship release", BulletType::Priority));
+
+        let mut plan = WeekPlan::new(week, &[earlier, review], Some(&first_day));
+        let leftovers: Vec<&str> = plan
+            .leftovers
+            .iter()
+            .map(|item| item.bullet.content.as_str())
+            .collect();
+        assert_eq!(
+            leftovers,
+            vec!["Book flights", "Send invoice", "Skipped the review"]
+        );
+
+        assert_eq!(plan.carry(1), Some(1));
+        assert_eq!(plan.carry(1), Some(2));
+        plan.lines[1].text = "wed Send invoice".to_string();
+        assert_eq!(plan.carried_tasks().count(), 1);
+        assert_eq!(
+            plan.planned_bullets(),
+            vec![
+                (day(18), Bullet::new("Ship release", BulletType::Priority)),
+                (day(20), Bullet::new("Send invoice", BulletType::Task)),
+                (
+                    day(18),
+                    Bullet::new("Skipped the review", BulletType::Priority)
+                ),
+            ]
+        );
+
+        plan.remove_line(1);
+        assert_eq!(plan.leftovers.len(), 2);
+        assert_eq!(plan.leftovers[1].bullet.content, "Send invoice");
+        assert_eq!(plan.carried_tasks().count(), 0);
+    }
+
+    #[test]
+    fn test_migration_review_and_schedule_dates() {
+        let day = |d| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+        let week = DateRange::week(day(4));
+        let mut monday = Entry::new(day(4));
+        monday.add_bullet(Bullet::new("Call the bank", BulletType::Task));
+        monday.add_bullet(Bullet::with_task_state(
+            "Paid rent",
+            BulletType::Task,
+            TaskState::Completed,
+        ));
+        monday.add_bullet(Bullet::new("Launch", BulletType::Priority));
+        let mut later = Entry::new(day(11));
+        later.add_bullet(Bullet::new("Not last week", BulletType::Task));
+
+        let mut review = MigrationReview::new(week, &[monday, later]);
+        let contents: Vec<&str> = review
+            .items
+            .iter()
+            .map(|(item, _)| item.bullet.content.as_str())
+            .collect();
+        assert_eq!(contents, vec!["Call the bank", "Launch"]);
+        assert_eq!(review.decided().count(), 0);
+        review.items[1].1 = MigrationChoice::Drop;
+        assert_eq!(review.decided().count(), 1);
+
+        // 2024-03-11 is a Monday
+        let today = day(11);
+        assert_eq!(parse_schedule_date("+3", today), Some(day(14)));
+        assert_eq!(parse_schedule_date("fri", today), Some(day(15)));
+        assert_eq!(parse_schedule_date("monday", today), Some(day(18)));
+        assert_eq!(
+            parse_schedule_date("2024-04-01", today),
+            NaiveDate::from_ymd_opt(2024, 4, 1)
+        );
+        assert_eq!(parse_schedule_date("soon", today), None);
+    }
+}
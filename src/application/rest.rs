@@ -0,0 +1,300 @@
+use crate::application::JournalApp;
+use crate::application::daemon::parse_command;
+use crate::application::serve::{HttpRequest, HttpResponse};
+use crate::entities::DateRange;
+use anyhow::{Result, anyhow};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+
+#[derive(Deserialize)]
+struct PutEntryBody {
+    markdown: String,
+    /// Save tasks completed before their blockers anyway
+    #[serde(default)]
+    force: bool,
+}
+
+#[derive(Deserialize)]
+struct AddBulletBody {
+    /// Bullet type name, e.g. `task`
+    #[serde(rename = "type")]
+    bullet_type: String,
+    /// Bullet text, optionally starting with an `HH:MM` time
+    content: String,
+}
+
+/// An error answered as `{"error": message}`
+struct ApiError {
+    status: u16,
+    message: String,
+}
+
+impl ApiError {
+    fn new(status: u16, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<anyhow::Error> for ApiError {
+    fn from(e: anyhow::Error) -> Self {
+        Self::new(500, format!("{:#}", e))
+    }
+}
+
+/// Route a request of `journo serve --api-only`. Every request must carry
+/// `Authorization: Bearer <token>`.
+///
+/// Endpoints (dates are `YYYY-MM-DD` or `today`):
+/// - `GET /entries/{date}` → `{date, exists, markdown, entry, created_at, updated_at}`
+/// - `PUT /entries/{date}` with `{markdown, force?}` → `{date, word_count, word_delta, warnings}`
+/// - `POST /entries/{date}/bullets` with `{type, content}` → `201 {date, bullet}`
+/// - `GET /search?q=...&start=...&end=...` → list of `{date, bullet}`
+/// - `GET /stats?start=...&end=...` → task, event and word totals
+pub fn handle_request(app: &mut JournalApp, request: &HttpRequest, token: &str) -> HttpResponse {
+    let authorized = request
+        .headers
+        .get("authorization")
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|given| constant_time_eq(given.trim().as_bytes(), token.as_bytes()));
+    if !authorized {
+        let mut response = HttpResponse::json(401, &json!({ "error": "invalid or missing token" }));
+        response
+            .headers
+            .push(("WWW-Authenticate", "Bearer".to_string()));
+        return response;
+    }
+
+    match route(app, request) {
+        Ok((status, value)) => HttpResponse::json(status, &value),
+        Err(e) => HttpResponse::json(e.status, &json!({ "error": e.message })),
+    }
+}
+
+fn route(app: &mut JournalApp, request: &HttpRequest) -> Result<(u16, Value), ApiError> {
+    let segments: Vec<&str> = request.path.trim_matches('/').split('/').collect();
+    match (request.method.as_str(), segments.as_slice()) {
+        ("GET", ["entries", date]) => {
+            let date = parse_date(app, date)?;
+            let entry = app.storage().load_entry(date)?;
+            let markdown = match &entry {
+                Some(entry) => app.parser().serialize_for_editing(entry)?,
+                None => app.parser().empty_template(),
+            };
+            let timestamps = app.storage().entry_timestamps(date)?;
+            Ok((
+                200,
+                json!({
+                    "date": date,
+                    "exists": entry.is_some(),
+                    "markdown": markdown,
+                    "entry": entry,
+                    "created_at": timestamps.map(|t| t.created_at),
+                    "updated_at": timestamps.map(|t| t.updated_at),
+                }),
+            ))
+        }
+        ("PUT", ["entries", date]) => {
+            let date = parse_date(app, date)?;
+            let body: PutEntryBody = parse_body(request)?;
+            let (word_count, word_delta) = app
+                .save_markdown(date, &body.markdown, body.force)
+                .map_err(|e| ApiError::new(422, format!("{:#}", e)))?;
+            Ok((
+                200,
+                json!({
+                    "date": date,
+                    "word_count": word_count,
+                    "word_delta": word_delta,
+                    "warnings": app.save_warnings(),
+                }),
+            ))
+        }
+        ("POST", ["entries", date, "bullets"]) => {
+            let body: AddBulletBody = parse_body(request)?;
+            // Same rules as a daemon capture: known type, optional leading time
+            let line = format!("ADD {} {} {}", date, body.bullet_type, body.content);
+            let capture =
+                parse_command(&line, app.today()).map_err(|e| ApiError::new(400, e.to_string()))?;
+            app.append_bullet(capture.date, capture.bullet.clone())?;
+            Ok((
+                201,
+                json!({ "date": capture.date, "bullet": capture.bullet }),
+            ))
+        }
+        ("GET", ["search"]) => {
+            let query = request.query.get("q").map(String::as_str).unwrap_or("");
+            let range = query_range(app, request)?;
+            let hits = app.storage().search_bullets(query, &[], &[], range)?;
+            Ok((200, json!(hits)))
+        }
+        ("GET", ["stats"]) => {
+            let stats = match query_range(app, request)? {
+                Some(range) => app.storage().period_stats(range)?,
+                None => Default::default(),
+            };
+            Ok((200, json!(stats)))
+        }
+        (_, ["entries", _] | ["entries", _, "bullets"] | ["search"] | ["stats"]) => {
+            Err(ApiError::new(405, "method not allowed"))
+        }
+        _ => Err(ApiError::new(404, "not found")),
+    }
+}
+
+fn parse_date(app: &JournalApp, date: &str) -> Result<NaiveDate, ApiError> {
+    if date.eq_ignore_ascii_case("today") {
+        return Ok(app.today());
+    }
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|_| ApiError::new(400, format!("invalid date: {}", date)))
+}
+
+fn parse_body<T: DeserializeOwned>(request: &HttpRequest) -> Result<T, ApiError> {
+    serde_json::from_str(&request.body).map_err(|e| ApiError::new(400, e.to_string()))
+}
+
+/// `start` and `end` query parameters, missing bounds falling back to the
+/// first and last entry; `None` when the journal is empty
+fn query_range(app: &JournalApp, request: &HttpRequest) -> Result<Option<DateRange>, ApiError> {
+    let bound = |name: &str| {
+        request
+            .query
+            .get(name)
+            .map(|date| parse_date(app, date))
+            .transpose()
+    };
+    let (start, end) = (bound("start")?, bound("end")?);
+    let Some(span) = app.resolve_range(None)? else {
+        return Ok(None);
+    };
+    Ok(Some(DateRange::between(
+        start.unwrap_or(span.start),
+        end.unwrap_or(span.end),
+    )))
+}
+
+/// Compare without returning early, so response times don't leak how much of
+/// the token was right
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// The configured token, refusing to serve the API without one
+pub fn require_token(token: Option<&str>) -> Result<String> {
+    token
+        .filter(|t| !t.trim().is_empty())
+        .map(str::to_string)
+        .ok_or_else(|| anyhow!("set api_token under [server] in config.toml to use --api-only"))
+}
+
+#[cfg(all(test, feature = "duckdb"))]
+mod tests {
+    use super::*;
+    use crate::application::Config;
+    use crate::infrastructure::DuckDbStorage;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    const TOKEN: &str = "s3cret";
+
+    fn call(app: &mut JournalApp, method: &str, target: &str, body: &str) -> HttpResponse {
+        let raw = format!(
+            "{} {} HTTP/1.1\r\nAuthorization: Bearer {}\r\nContent-Length: {}\r\n\r\n{}",
+            method,
+            target,
+            TOKEN,
+            body.len(),
+            body
+        );
+        let request = HttpRequest::read_from(&mut raw.as_bytes()).unwrap();
+        handle_request(app, &request, TOKEN)
+    }
+
+    fn body(response: &HttpResponse) -> Value {
+        serde_json::from_str(&response.body).unwrap()
+    }
+
+    #[test]
+    fn test_rest_api_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut app = JournalApp::with_storage(
+            Arc::new(DuckDbStorage::in_memory().unwrap()),
+            Config::load(temp_dir.path().into()),
+        );
+        app.set_nudges(false);
+
+        let put = call(
+            &mut app,
+            "PUT",
+            "/entries/2024-03-15",
+            r##"{"markdown": "# Tasks\nShip the API\n"}"##,
+        );
+        assert_eq!(put.status, 200);
+        assert_eq!(body(&put)["word_count"], 3);
+
+        // Saves the app refuses, here completing a blocked task, are 422s
+        let refused = call(
+            &mut app,
+            "PUT",
+            "/entries/2024-03-16",
+            r##"{"markdown": "# Tasks\nProofread\n[x] Edit !after:proofread\n"}"##,
+        );
+        let mut written = Vec::new();
+        refused.write_to(&mut written).unwrap();
+        assert!(
+            String::from_utf8(written)
+                .unwrap()
+                .starts_with("HTTP/1.1 422 Unprocessable Content\r\n")
+        );
+
+        let added = call(
+            &mut app,
+            "POST",
+            "/entries/2024-03-15/bullets",
+            r#"{"type": "event", "content": "09:30 Standup"}"#,
+        );
+        assert_eq!(added.status, 201);
+        assert_eq!(body(&added)["bullet"]["content"], "Standup");
+
+        let entry = body(&call(&mut app, "GET", "/entries/2024-03-15", ""));
+        assert_eq!(entry["exists"], true);
+        assert!(entry["updated_at"].is_string());
+        assert!(
+            entry["markdown"]
+                .as_str()
+                .unwrap()
+                .contains("09:30 Standup")
+        );
+
+        let hits = body(&call(&mut app, "GET", "/search?q=ship", ""));
+        assert_eq!(hits.as_array().unwrap().len(), 1);
+
+        let stats = body(&call(&mut app, "GET", "/stats", ""));
+        assert_eq!(stats["tasks_pending"], 1);
+        assert_eq!(stats["events"], 1);
+
+        assert_eq!(call(&mut app, "GET", "/entries/2024-13-01", "").status, 400);
+        assert_eq!(call(&mut app, "DELETE", "/stats", "").status, 405);
+    }
+
+    #[test]
+    fn test_rest_api_requires_token() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut app = JournalApp::with_storage(
+            Arc::new(DuckDbStorage::in_memory().unwrap()),
+            Config::load(temp_dir.path().into()),
+        );
+        let raw = "GET /stats HTTP/1.1\r\nAuthorization: Bearer wrong\r\n\r\n";
+        let request = HttpRequest::read_from(&mut raw.as_bytes()).unwrap();
+        assert_eq!(handle_request(&mut app, &request, TOKEN).status, 401);
+
+        assert!(require_token(None).is_err());
+        assert!(require_token(Some(" ")).is_err());
+        assert_eq!(require_token(Some(TOKEN)).unwrap(), TOKEN);
+    }
+}
@@ -0,0 +1,778 @@
+use crate::application::{JournalApp, rest};
+use crate::entities::DateRange;
+use crate::infrastructure::exporters::html::{escape, page, render_bullet};
+use anyhow::{Context, Result, bail};
+use chrono::{Datelike, Duration, Local, Months, NaiveDate};
+use std::collections::hash_map::RandomState;
+use std::collections::{BTreeSet, HashMap};
+use std::hash::{BuildHasher, Hasher};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{IpAddr, TcpListener, TcpStream};
+
+/// Largest request body accepted, enough for any day's markdown
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+/// How long a connection may stall before it's dropped, since requests are
+/// served one at a time
+const READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+/// Cookie holding the access token once a browser signed in with `?token=`
+const TOKEN_COOKIE: &str = "journo_token";
+
+/// A parsed HTTP request
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HttpRequest {
+    pub method: String,
+    /// Path without the query string
+    pub path: String,
+    pub query: HashMap<String, String>,
+    /// Header names are lowercased
+    pub headers: HashMap<String, String>,
+    pub body: String,
+}
+
+impl HttpRequest {
+    /// Read a request line, headers and a `Content-Length` body
+    pub fn read_from(reader: &mut impl BufRead) -> Result<Self> {
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let mut parts = line.split_whitespace();
+        let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+            bail!("malformed request line: {}", line.trim());
+        };
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+        let mut headers = HashMap::new();
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                headers.insert(name.trim().to_lowercase(), value.trim().to_string());
+            }
+        }
+
+        let length: usize = headers
+            .get("content-length")
+            .map(|v| v.parse())
+            .transpose()
+            .context("invalid Content-Length")?
+            .unwrap_or(0);
+        if length > MAX_BODY_BYTES {
+            bail!("request body too large ({} bytes)", length);
+        }
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body)?;
+
+        Ok(Self {
+            method: method.to_uppercase(),
+            path: url_decode(path),
+            query: parse_form(query),
+            headers,
+            body: String::from_utf8(body).context("request body is not UTF-8")?,
+        })
+    }
+
+    /// Fields of an `application/x-www-form-urlencoded` body
+    pub fn form(&self) -> HashMap<String, String> {
+        parse_form(&self.body)
+    }
+}
+
+/// A response ready to be written to the connection
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub headers: Vec<(&'static str, String)>,
+    pub body: String,
+}
+
+impl HttpResponse {
+    pub fn html(status: u16, body: String) -> Self {
+        Self {
+            status,
+            content_type: "text/html; charset=utf-8",
+            headers: Vec::new(),
+            body,
+        }
+    }
+
+    pub fn json(status: u16, value: &serde_json::Value) -> Self {
+        Self {
+            status,
+            content_type: "application/json",
+            headers: Vec::new(),
+            body: value.to_string(),
+        }
+    }
+
+    /// `303 See Other`, so a form submission ends on a page that can be reloaded
+    pub fn redirect(location: &str) -> Self {
+        let mut response = Self::html(303, String::new());
+        response.headers.push(("Location", location.to_string()));
+        response
+    }
+
+    pub fn error(status: u16, message: &str) -> Self {
+        let body = format!(
+            "<h1>{}</h1>\n<p>{}</p>\n<nav><a href=\"/\">Calendar</a></nav>",
+            status,
+            escape(message)
+        );
+        Self::html(status, page("Error", &body))
+    }
+
+    pub fn write_to(&self, writer: &mut impl Write) -> std::io::Result<()> {
+        write!(
+            writer,
+            "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+            self.status,
+            reason(self.status),
+            self.content_type,
+            self.body.len()
+        )?;
+        for (name, value) in &self.headers {
+            write!(writer, "{}: {}\r\n", name, value)?;
+        }
+        write!(writer, "\r\n{}", self.body)?;
+        writer.flush()
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        303 => "See Other",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        422 => "Unprocessable Content",
+        _ => "Internal Server Error",
+    }
+}
+
+/// Decode `%XX` escapes and `+` as a space, keeping malformed escapes as-is
+pub fn url_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' if i + 2 < bytes.len()
+                && bytes[i + 1].is_ascii_hexdigit()
+                && bytes[i + 2].is_ascii_hexdigit() =>
+            {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).expect("ASCII hex digits");
+                decoded.push(u8::from_str_radix(hex, 16).expect("valid hex"));
+                i += 2;
+            }
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn parse_form(text: &str) -> HashMap<String, String> {
+    text.split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (url_decode(name), url_decode(value))
+        })
+        .collect()
+}
+
+/// What `journo serve` exposes
+#[derive(Debug, Clone, PartialEq)]
+pub enum ServeMode {
+    /// The web interface, guarded as `WebAccess` says
+    Web(WebAccess),
+    /// Only the JSON API of `rest`, every request carrying this bearer token
+    Api { token: String },
+}
+
+/// Who the web interface answers. Every form carries `csrf_token` and saves
+/// without it are refused, as are requests from another `Origin`. Without an
+/// `access_token` the server only listens on loopback and only for `Host`
+/// names of this machine, so other sites can't reach it through DNS
+/// rebinding; with one, browsers sign in once with `/?token=<access_token>`
+/// and send it back as a cookie.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebAccess {
+    /// Random per server start, required on every POST
+    pub csrf_token: String,
+    /// Password required from every request, when set
+    pub access_token: Option<String>,
+    /// `Host` header values accepted without an access token
+    pub hosts: Vec<String>,
+}
+
+impl WebAccess {
+    /// Access to a server on `addr` (`host:port`), refusing addresses beyond
+    /// loopback unless an access token is given
+    pub fn new(addr: &str, access_token: Option<&str>) -> Result<Self> {
+        let access_token = access_token
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .map(str::to_string);
+        let (host, port) = addr
+            .rsplit_once(':')
+            .with_context(|| format!("expected host:port, got {}", addr))?;
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        let loopback = host.eq_ignore_ascii_case("localhost")
+            || host.parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback());
+        if !loopback && access_token.is_none() {
+            bail!(
+                "the web interface has no password of its own: set api_token under [server] in \
+                 config.toml to serve it on {}, or keep the default 127.0.0.1",
+                host
+            );
+        }
+
+        let mut hosts: Vec<String> = ["localhost", "127.0.0.1", "[::1]"]
+            .iter()
+            .map(|name| format!("{}:{}", name, port))
+            .collect();
+        hosts.push(addr.to_string());
+        Ok(Self {
+            csrf_token: random_token(),
+            access_token,
+            hosts,
+        })
+    }
+
+    /// Why `request` may not be served, as the response to send instead
+    fn refuse(&self, request: &HttpRequest) -> Option<HttpResponse> {
+        let host = request.headers.get("host").map(String::as_str);
+        if let Some(origin) = request.headers.get("origin")
+            && host.is_none_or(|host| *origin != format!("http://{}", host))
+        {
+            return Some(HttpResponse::error(403, "Cross-origin request refused"));
+        }
+
+        let Some(token) = &self.access_token else {
+            return match host {
+                Some(host) if self.hosts.iter().any(|h| h.eq_ignore_ascii_case(host)) => None,
+                _ => Some(HttpResponse::error(403, "Unknown Host header")),
+            };
+        };
+        let cookie = request.headers.get("cookie").and_then(|cookies| {
+            cookies
+                .split(';')
+                .filter_map(|cookie| cookie.trim().split_once('='))
+                .find(|(name, _)| *name == TOKEN_COOKIE)
+                .map(|(_, value)| value)
+        });
+        if cookie.is_some_and(|given| rest::constant_time_eq(given.as_bytes(), token.as_bytes())) {
+            return None;
+        }
+        match request.query.get("token") {
+            // Keep the token out of the address bar and the history
+            Some(given) if rest::constant_time_eq(given.as_bytes(), token.as_bytes()) => {
+                let mut response = HttpResponse::redirect(&request.path);
+                response.headers.push((
+                    "Set-Cookie",
+                    format!(
+                        "{}={}; Path=/; HttpOnly; SameSite=Strict",
+                        TOKEN_COOKIE, token
+                    ),
+                ));
+                Some(response)
+            }
+            _ => Some(HttpResponse::error(
+                401,
+                "Sign in by opening /?token=<api_token> from config.toml",
+            )),
+        }
+    }
+}
+
+/// 128 random bits as hex, read from the OS where it has `/dev/urandom` and
+/// taken from std's randomly keyed hasher elsewhere
+fn random_token() -> String {
+    let mut bytes = [0u8; 16];
+    let from_os = std::fs::File::open("/dev/urandom")
+        .and_then(|mut urandom| urandom.read_exact(&mut bytes))
+        .is_ok();
+    if !from_os {
+        for (i, chunk) in bytes.chunks_mut(8).enumerate() {
+            let mut hasher = RandomState::new().build_hasher();
+            hasher.write_usize(i);
+            chunk.copy_from_slice(&hasher.finish().to_le_bytes());
+        }
+    }
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Serve the journal on `addr` until interrupted. Connections are handled
+/// one at a time, like the capture daemon, so saves never race.
+pub fn run(app: &mut JournalApp, addr: &str, mode: &ServeMode) -> Result<()> {
+    let listener = TcpListener::bind(addr).with_context(|| format!("Failed to bind {}", addr))?;
+    match mode {
+        ServeMode::Web(access) if access.access_token.is_some() => println!(
+            "Serving the journal on http://{} (Ctrl+C to stop); sign in once with \
+             /?token=<api_token>",
+            addr
+        ),
+        ServeMode::Web(_) => println!("Serving the journal on http://{} (Ctrl+C to stop)", addr),
+        ServeMode::Api { .. } => {
+            println!("Serving the JSON API on http://{} (Ctrl+C to stop)", addr)
+        }
+    }
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                tracing::warn!("Failed to accept connection: {}", e);
+                continue;
+            }
+        };
+        if let Err(e) = serve_connection(app, stream, mode) {
+            tracing::warn!("Failed to serve request: {:#}", e);
+        }
+    }
+
+    Ok(())
+}
+
+fn serve_connection(app: &mut JournalApp, stream: TcpStream, mode: &ServeMode) -> Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut writer = stream.try_clone()?;
+    let response = match (HttpRequest::read_from(&mut BufReader::new(stream)), mode) {
+        (Ok(request), ServeMode::Web(access)) => handle_request(app, &request, access),
+        (Ok(request), ServeMode::Api { token }) => rest::handle_request(app, &request, token),
+        (Err(e), ServeMode::Web(_)) => HttpResponse::error(400, &format!("{:#}", e)),
+        (Err(e), ServeMode::Api { .. }) => {
+            HttpResponse::json(400, &serde_json::json!({ "error": format!("{:#}", e) }))
+        }
+    };
+    response.write_to(&mut writer)?;
+    Ok(())
+}
+
+/// Route a request to its page:
+///
+/// - `GET /` → month calendar (`?month=YYYY-MM`) with a search box
+/// - `GET /day/YYYY-MM-DD` → the entry with an editing form
+/// - `POST /day/YYYY-MM-DD` → save the form's `markdown`
+/// - `GET /search?q=...` → matching bullets
+///
+/// Requests `access` refuses get its answer instead.
+pub fn handle_request(
+    app: &mut JournalApp,
+    request: &HttpRequest,
+    access: &WebAccess,
+) -> HttpResponse {
+    if let Some(refusal) = access.refuse(request) {
+        return refusal;
+    }
+
+    // The first and last days chrono can represent have no neighbour to link to
+    let day = request
+        .path
+        .strip_prefix("/day/")
+        .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+        .filter(|date| date.pred_opt().is_some() && date.succ_opt().is_some());
+
+    let result = match (request.method.as_str(), request.path.as_str(), day) {
+        ("GET", "/", _) => calendar_page(app, request.query.get("month")),
+        ("GET", "/search", _) => search_page(app, request.query.get("q")),
+        ("GET", _, Some(date)) => day_page(app, date, None, &access.csrf_token),
+        ("POST", _, Some(date)) => save_day(app, date, request, &access.csrf_token),
+        (_, "/" | "/search", _) | (_, _, Some(_)) => {
+            Ok(HttpResponse::error(405, "Method not allowed"))
+        }
+        _ => Ok(HttpResponse::error(404, "Page not found")),
+    };
+    result.unwrap_or_else(|e| HttpResponse::error(500, &format!("{:#}", e)))
+}
+
+fn day_link(date: NaiveDate, text: &str) -> String {
+    format!("<a href=\"/day/{}\">{}</a>", date.format("%Y-%m-%d"), text)
+}
+
+/// `link` to `date`, or nothing past the range of dates chrono can represent
+fn optional_link(
+    date: Option<NaiveDate>,
+    text: &str,
+    link: impl Fn(NaiveDate, &str) -> String,
+) -> String {
+    date.map(|date| link(date, text)).unwrap_or_default()
+}
+
+fn calendar_page(app: &JournalApp, month: Option<&String>) -> Result<HttpResponse> {
+    let today = app.today();
+    let first = month
+        .and_then(|m| NaiveDate::parse_from_str(&format!("{}-01", m), "%Y-%m-%d").ok())
+        .unwrap_or_else(|| today.with_day(1).expect("day 1 exists"));
+    // The last month chrono can represent has no first day after it to end on
+    if first.checked_add_months(Months::new(1)).is_none() {
+        return Ok(HttpResponse::error(404, "Page not found"));
+    }
+    let range = DateRange::month(first.year(), first.month());
+    let dates: BTreeSet<NaiveDate> = app
+        .storage()
+        .list_dates(range.clone())?
+        .into_iter()
+        .collect();
+
+    let month_link = |date: NaiveDate, text: &str| {
+        format!("<a href=\"/?month={}\">{}</a>", date.format("%Y-%m"), text)
+    };
+    let mut body = format!(
+        "<h1>{}</h1>\n<nav>{} {} {}</nav>\n",
+        first.format("%B %Y"),
+        optional_link(first.pred_opt(), "&larr;", month_link),
+        day_link(today, "Today"),
+        optional_link(range.end.succ_opt(), "&rarr;", month_link)
+    );
+    body.push_str(
+        "<form action=\"/search\"><input name=\"q\" type=\"search\" placeholder=\"Search...\"></form>\n",
+    );
+
+    let week_start = app.config().week_start;
+    body.push_str("<table class=\"calendar\">\n<tr>");
+    for offset in 0..7 {
+        let weekday = NaiveDate::from_isoywd_opt(2024, 1, week_start)
+            .expect("valid week")
+            .checked_add_signed(Duration::days(offset))
+            .expect("valid date");
+        body.push_str(&format!("<th>{}</th>", weekday.format("%a")));
+    }
+    body.push_str("</tr>\n<tr>");
+    let leading_blanks =
+        (first.weekday().num_days_from_monday() + 7 - week_start.num_days_from_monday()) % 7;
+    body.push_str(&"<td></td>".repeat(leading_blanks as usize));
+    let mut date = first;
+    while date <= range.end {
+        if date.weekday() == week_start && date != first {
+            body.push_str("</tr>\n<tr>");
+        }
+        let label = if date == today {
+            format!("<u>{}</u>", date.day())
+        } else {
+            date.day().to_string()
+        };
+        let class = if dates.contains(&date) {
+            " class=\"has-entry\""
+        } else {
+            ""
+        };
+        body.push_str(&format!("<td{}>{}</td>", class, day_link(date, &label)));
+        date += Duration::days(1);
+    }
+    body.push_str("</tr>\n</table>\n");
+
+    Ok(HttpResponse::html(200, page("Journal", &body)))
+}
+
+fn day_page(
+    app: &JournalApp,
+    date: NaiveDate,
+    error: Option<&str>,
+    csrf_token: &str,
+) -> Result<HttpResponse> {
+    let entry = app.storage().load_entry(date)?;
+    let mut body = format!(
+        "<h1>{}</h1>\n<nav>{} <a href=\"/?month={}\">Calendar</a> {}</nav>\n",
+        escape(&date.format(&app.config().date_formats.long).to_string()),
+        optional_link(date.pred_opt(), "&larr;", day_link),
+        date.format("%Y-%m"),
+        optional_link(date.succ_opt(), "&rarr;", day_link)
+    );
+    if entry.is_some()
+        && let Some(timestamps) = app.storage().entry_timestamps(date)?
+    {
+        body.push_str(&format!(
+            "<p><small>{}</small></p>\n",
+            timestamps.last_edited(Local::now().naive_local())
+        ));
+    }
+    if let Some(error) = error {
+        body.push_str(&format!("<p><strong>{}</strong></p>\n", escape(error)));
+    }
+
+    let markdown = match &entry {
+        Some(entry) => {
+            for &bullet_type in app.parser().section_order() {
+                if entry.get_bullets(&bullet_type).is_empty() {
+                    continue;
+                }
+                body.push_str(&format!(
+                    "<h2>{}</h2>\n<ul class=\"bullets\">\n",
+                    bullet_type.section_name()
+                ));
+                for bullet in entry.bullets_chronological(&bullet_type) {
+                    body.push_str(&render_bullet(bullet, &app.config().symbols));
+                }
+                body.push_str("</ul>\n");
+            }
+            app.parser().serialize_for_editing(entry)?
+        }
+        None => {
+            body.push_str("<p>Nothing written yet.</p>\n");
+            app.parser().empty_template()
+        }
+    };
+
+    body.push_str(&format!(
+        "<form method=\"post\" action=\"/day/{}\">\n<input type=\"hidden\" name=\"csrf\" value=\"{}\">\n<textarea name=\"markdown\" rows=\"20\" style=\"width:100%\">{}</textarea>\n<button type=\"submit\">Save</button>\n</form>\n",
+        date.format("%Y-%m-%d"),
+        csrf_token,
+        escape(&markdown)
+    ));
+
+    Ok(HttpResponse::html(
+        200,
+        page(&date.format("%Y-%m-%d").to_string(), &body),
+    ))
+}
+
+fn save_day(
+    app: &mut JournalApp,
+    date: NaiveDate,
+    request: &HttpRequest,
+    csrf_token: &str,
+) -> Result<HttpResponse> {
+    let form = request.form();
+    // Forms from other sites can't know the token of this server run
+    let csrf_ok = form
+        .get("csrf")
+        .is_some_and(|given| rest::constant_time_eq(given.as_bytes(), csrf_token.as_bytes()));
+    if !csrf_ok {
+        return Ok(HttpResponse::error(
+            403,
+            "Form expired or not from this server; reload the page and save again",
+        ));
+    }
+    let Some(markdown) = form.get("markdown") else {
+        return Ok(HttpResponse::error(400, "Missing markdown field"));
+    };
+    // Browsers submit textareas with CRLF line endings
+    match app.save_markdown(date, &markdown.replace("\r\n", "\n"), false) {
+        // Show what the pre-write checks flagged instead of redirecting it away
+        Ok(_) if !app.save_warnings().is_empty() => {
+            let warnings = app.save_warnings().join("; ");
+            day_page(
+                app,
+                date,
+                Some(&format!("Saved, but: {}", warnings)),
+                csrf_token,
+            )
+        }
+        Ok(_) => Ok(HttpResponse::redirect(&format!(
+            "/day/{}",
+            date.format("%Y-%m-%d")
+        ))),
+        Err(e) => {
+            let mut response =
+                day_page(app, date, Some(&format!("Not saved: {:#}", e)), csrf_token)?;
+            response.status = 400;
+            Ok(response)
+        }
+    }
+}
+
+fn search_page(app: &JournalApp, query: Option<&String>) -> Result<HttpResponse> {
+    let query = query.map(|q| q.trim()).unwrap_or_default();
+    let mut body = format!(
+        "<h1>Search</h1>\n<nav><a href=\"/\">Calendar</a></nav>\n<form action=\"/search\"><input name=\"q\" type=\"search\" value=\"{}\"></form>\n",
+        escape(query)
+    );
+    if !query.is_empty() {
+        let hits = app.storage().search_bullets(query, &[], &[], None)?;
+        body.push_str(&format!(
+            "<p>{} matching bullets</p>\n<ul class=\"bullets\">\n",
+            hits.len()
+        ));
+        for hit in &hits {
+            body.push_str(&format!(
+                "<li>{} {}</li>\n",
+                day_link(hit.date, &hit.date.format("%Y-%m-%d").to_string()),
+                escape(&hit.bullet.content)
+            ));
+        }
+        body.push_str("</ul>\n");
+    }
+    Ok(HttpResponse::html(200, page("Search", &body)))
+}
+
+#[cfg(all(test, feature = "duckdb"))]
+mod tests {
+    use super::*;
+    use crate::application::Config;
+    use crate::infrastructure::DuckDbStorage;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    const ADDR: &str = "127.0.0.1:8080";
+
+    fn request(method: &str, target: &str, body: &str) -> HttpRequest {
+        request_with(method, target, body, "")
+    }
+
+    /// A request from a browser on this machine, plus `headers` (each ending
+    /// in CRLF)
+    fn request_with(method: &str, target: &str, body: &str, headers: &str) -> HttpRequest {
+        let raw = format!(
+            "{} {} HTTP/1.1\r\nHost: localhost:8080\r\n{}Content-Length: {}\r\n\r\n{}",
+            method,
+            target,
+            headers,
+            body.len(),
+            body
+        );
+        HttpRequest::read_from(&mut raw.as_bytes()).unwrap()
+    }
+
+    fn test_app() -> (JournalApp, TempDir) {
+        let temp_dir = TempDir::new().unwrap();
+        let mut app = JournalApp::with_storage(
+            Arc::new(DuckDbStorage::in_memory().unwrap()),
+            Config::load(temp_dir.path().into()),
+        );
+        app.set_nudges(false);
+        (app, temp_dir)
+    }
+
+    #[test]
+    fn test_url_decode() {
+        assert_eq!(url_decode("%23+Tasks%0ACall%20mom"), "# Tasks\nCall mom");
+        assert_eq!(url_decode("100%"), "100%");
+        assert_eq!(url_decode("%zz"), "%zz");
+    }
+
+    #[test]
+    fn test_web_ui_edit_and_search() {
+        let (mut app, _temp_dir) = test_app();
+        let access = WebAccess::new(ADDR, None).unwrap();
+
+        let saved = handle_request(
+            &mut app,
+            &request(
+                "POST",
+                "/day/2024-03-15",
+                &format!(
+                    "csrf={}&markdown=%23+Tasks%0D%0ABook+%3Cflights%3E%0D%0A",
+                    access.csrf_token
+                ),
+            ),
+            &access,
+        );
+        assert_eq!(saved.status, 303);
+        assert_eq!(
+            saved.headers,
+            vec![("Location", "/day/2024-03-15".to_string())]
+        );
+
+        let day = handle_request(&mut app, &request("GET", "/day/2024-03-15", ""), &access);
+        assert_eq!(day.status, 200);
+        assert!(day.body.contains("Book &lt;flights&gt;"));
+        assert!(day.body.contains("<textarea name=\"markdown\""));
+
+        let calendar = handle_request(&mut app, &request("GET", "/?month=2024-03", ""), &access);
+        assert!(calendar.body.contains("<h1>March 2024</h1>"));
+        assert!(
+            calendar
+                .body
+                .contains("<td class=\"has-entry\"><a href=\"/day/2024-03-15\">15</a></td>")
+        );
+
+        let search = handle_request(&mut app, &request("GET", "/search?q=flights", ""), &access);
+        assert!(search.body.contains("1 matching bullets"));
+
+        assert_eq!(
+            handle_request(&mut app, &request("GET", "/day/2024-02-30", ""), &access).status,
+            404
+        );
+        // Days and months at the ends of chrono's range have nothing to link past
+        for target in [
+            "/day/-262143-01-01",
+            "/day/%2B262142-12-31",
+            "/?month=%2B262142-12",
+        ] {
+            assert_eq!(
+                handle_request(&mut app, &request("GET", target, ""), &access).status,
+                404,
+                "{}",
+                target
+            );
+        }
+        assert_eq!(
+            handle_request(&mut app, &request("DELETE", "/day/2024-03-15", ""), &access).status,
+            405
+        );
+    }
+
+    #[test]
+    fn test_web_ui_refuses_forged_and_foreign_requests() {
+        let (mut app, _temp_dir) = test_app();
+        let access = WebAccess::new(ADDR, None).unwrap();
+        let form = format!(
+            "csrf={}&markdown=%23+Notes%0D%0Ahi%0D%0A",
+            access.csrf_token
+        );
+
+        let forged = request("POST", "/day/2024-03-15", "csrf=guess&markdown=x");
+        assert_eq!(handle_request(&mut app, &forged, &access).status, 403);
+        let cross_origin = request_with(
+            "POST",
+            "/day/2024-03-15",
+            &form,
+            "Origin: http://evil.example\r\n",
+        );
+        assert_eq!(handle_request(&mut app, &cross_origin, &access).status, 403);
+        let rebound = HttpRequest::read_from(
+            &mut "GET / HTTP/1.1\r\nHost: evil.example:8080\r\n\r\n".as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(handle_request(&mut app, &rebound, &access).status, 403);
+        assert!(app.storage().load_entry(date(15)).unwrap().is_none());
+
+        let same_origin = request_with(
+            "POST",
+            "/day/2024-03-15",
+            &form,
+            "Origin: http://localhost:8080\r\n",
+        );
+        assert_eq!(handle_request(&mut app, &same_origin, &access).status, 303);
+    }
+
+    #[test]
+    fn test_web_ui_beyond_loopback_needs_token() {
+        let (mut app, _temp_dir) = test_app();
+        assert!(WebAccess::new("0.0.0.0:8080", None).is_err());
+        assert!(WebAccess::new("[::1]:8080", None).is_ok());
+        let access = WebAccess::new("0.0.0.0:8080", Some("s3cret")).unwrap();
+
+        let anonymous = request("GET", "/", "");
+        assert_eq!(handle_request(&mut app, &anonymous, &access).status, 401);
+
+        let sign_in = request("GET", "/day/2024-03-15?token=s3cret", "");
+        let signed_in = handle_request(&mut app, &sign_in, &access);
+        assert_eq!(signed_in.status, 303);
+        assert_eq!(
+            signed_in.headers,
+            vec![
+                ("Location", "/day/2024-03-15".to_string()),
+                (
+                    "Set-Cookie",
+                    "journo_token=s3cret; Path=/; HttpOnly; SameSite=Strict".to_string()
+                ),
+            ]
+        );
+
+        let with_cookie = request_with("GET", "/", "", "Cookie: a=b; journo_token=s3cret\r\n");
+        assert_eq!(handle_request(&mut app, &with_cookie, &access).status, 200);
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, day).unwrap()
+    }
+}
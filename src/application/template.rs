@@ -0,0 +1,127 @@
+use anyhow::{Context, bail};
+use chrono::NaiveDate;
+use std::io::Read;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+/// How long a `{{cmd:...}}` placeholder may run before it's killed
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Fill in the placeholders of an `entry_template` for `date`:
+///
+/// - `date`, `weekday`: the day as YYYY-MM-DD and its name
+/// - `cmd:<command>`: what a shell command run in `journal_dir` prints, e.g.
+///   `{{cmd:curl -s 'wttr.in?format=3'}}`; empty when `run_commands` is off
+///   or the command fails or runs longer than five seconds
+///
+/// Unknown placeholders are left untouched.
+pub fn render_entry_template(
+    template: &str,
+    date: NaiveDate,
+    journal_dir: &Path,
+    run_commands: bool,
+) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let Some(length) = rest[start..].find("}}") else {
+            break;
+        };
+        let placeholder = &rest[start..start + length + 2];
+        output.push_str(&rest[..start]);
+        match placeholder_value(&placeholder[2..length], date, journal_dir, run_commands) {
+            Some(value) => output.push_str(&value),
+            None => output.push_str(placeholder),
+        }
+        rest = &rest[start + length + 2..];
+    }
+    output.push_str(rest);
+    output
+}
+
+fn placeholder_value(
+    name: &str,
+    date: NaiveDate,
+    journal_dir: &Path,
+    run_commands: bool,
+) -> Option<String> {
+    match name.trim() {
+        "date" => Some(date.format("%Y-%m-%d").to_string()),
+        "weekday" => Some(date.format("%A").to_string()),
+        name => {
+            let command = name.strip_prefix("cmd:")?.trim();
+            if !run_commands {
+                warn!("template_commands is off, not running: {}", command);
+                return Some(String::new());
+            }
+            Some(run_command(command, journal_dir).unwrap_or_else(|e| {
+                warn!("template command failed: {:#}", e);
+                String::new()
+            }))
+        }
+    }
+}
+
+/// Standard output of `command` run with `sh -c`, minus the final newline
+fn run_command(command: &str, dir: &Path) -> anyhow::Result<String> {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .current_dir(dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("Failed to run {}", command))?;
+
+    // Read on another thread so a chatty command can't fill the pipe and stall
+    let mut stdout = child.stdout.take().expect("stdout is piped");
+    let reader = std::thread::spawn(move || {
+        let mut output = String::new();
+        stdout.read_to_string(&mut output).map(|_| output)
+    });
+
+    let started = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if started.elapsed() > COMMAND_TIMEOUT {
+            let _ = child.kill();
+            let _ = child.wait();
+            bail!("{} took longer than {:?}", command, COMMAND_TIMEOUT);
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    };
+    let output = reader
+        .join()
+        .expect("output reader panicked")
+        .with_context(|| format!("Failed to read the output of {}", command))?;
+    if !status.success() {
+        bail!("{} exited with {}", command, status);
+    }
+    Ok(output.trim_end().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_entry_template() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let template = "# {{weekday}} {{date}}\n{{cmd:echo sunny}}\n{{cmd:exit 1}}|{{mood}}";
+
+        assert_eq!(
+            render_entry_template(template, date, dir.path(), true),
+            "# Friday 2024-03-15\nsunny\n|{{mood}}"
+        );
+        assert_eq!(
+            render_entry_template(template, date, dir.path(), false),
+            "# Friday 2024-03-15\n\n|{{mood}}"
+        );
+    }
+}
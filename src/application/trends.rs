@@ -0,0 +1,870 @@
+use crate::entities::{CrossReference, DateRange, Entry, MetricValue, MoodCheckIn};
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
+
+/// Words counted as positive by `sentiment_score`
+const POSITIVE_WORDS: &[&str] = &[
+    "accomplished",
+    "amazing",
+    "appreciate",
+    "awesome",
+    "beautiful",
+    "best",
+    "better",
+    "calm",
+    "celebrated",
+    "cheerful",
+    "confident",
+    "delighted",
+    "done",
+    "energized",
+    "enjoyed",
+    "excited",
+    "fantastic",
+    "finished",
+    "fun",
+    "glad",
+    "good",
+    "grateful",
+    "great",
+    "happy",
+    "hopeful",
+    "inspired",
+    "joy",
+    "laughed",
+    "love",
+    "loved",
+    "nice",
+    "peaceful",
+    "productive",
+    "proud",
+    "relaxed",
+    "relieved",
+    "rested",
+    "shipped",
+    "success",
+    "thankful",
+    "win",
+    "wonderful",
+];
+
+/// Words counted as negative by `sentiment_score`
+const NEGATIVE_WORDS: &[&str] = &[
+    "afraid",
+    "angry",
+    "annoyed",
+    "anxiety",
+    "anxious",
+    "awful",
+    "bad",
+    "blocked",
+    "bored",
+    "broken",
+    "burnout",
+    "confused",
+    "depressed",
+    "disappointed",
+    "exhausted",
+    "failed",
+    "frustrated",
+    "guilty",
+    "hate",
+    "hurt",
+    "lonely",
+    "lost",
+    "miserable",
+    "missed",
+    "nervous",
+    "overwhelmed",
+    "painful",
+    "sad",
+    "sick",
+    "stress",
+    "stressed",
+    "stuck",
+    "terrible",
+    "tired",
+    "upset",
+    "worried",
+    "worse",
+    "worst",
+];
+
+/// Words that flip the sentiment of the word right after them
+const NEGATIONS: &[&str] = &["not", "no", "never", "don't", "didn't", "isn't", "wasn't"];
+
+/// Frequent words that say nothing about what a day was about, left out when
+/// comparing days by their terms
+const STOP_WORDS: &[&str] = &[
+    "about",
+    "after",
+    "again",
+    "also",
+    "back",
+    "been",
+    "before",
+    "being",
+    "could",
+    "does",
+    "from",
+    "have",
+    "into",
+    "just",
+    "like",
+    "made",
+    "make",
+    "more",
+    "much",
+    "need",
+    "only",
+    "over",
+    "really",
+    "should",
+    "some",
+    "still",
+    "than",
+    "that",
+    "their",
+    "them",
+    "then",
+    "there",
+    "these",
+    "they",
+    "this",
+    "today",
+    "tomorrow",
+    "very",
+    "want",
+    "went",
+    "were",
+    "what",
+    "when",
+    "which",
+    "will",
+    "with",
+    "would",
+    "yesterday",
+    "your",
+];
+
+/// Similarity a day needs to be suggested as related to another
+const MIN_RELATED_SCORE: f64 = 0.2;
+
+/// Length of the periods a trend is bucketed into
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TrendPeriod {
+    #[default]
+    Week,
+    Month,
+}
+
+impl FromStr for TrendPeriod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "week" | "weekly" => Ok(TrendPeriod::Week),
+            "month" | "monthly" => Ok(TrendPeriod::Month),
+            _ => Err(format!("unknown period: {} (expected week or month)", s)),
+        }
+    }
+}
+
+impl TrendPeriod {
+    /// Start of the period containing `date`
+    pub fn start_of(&self, date: NaiveDate, week_start: Weekday) -> NaiveDate {
+        match self {
+            TrendPeriod::Week => DateRange::week_containing(date, week_start).start,
+            TrendPeriod::Month => date.with_day(1).expect("day 1 exists"),
+        }
+    }
+
+    /// Start of the period `count` periods after the one starting on `start`
+    fn advance(&self, start: NaiveDate, count: i32) -> NaiveDate {
+        match self {
+            TrendPeriod::Week => start + Duration::weeks(count as i64),
+            TrendPeriod::Month => {
+                let months = start.year() * 12 + start.month0() as i32 + count;
+                NaiveDate::from_ymd_opt(months.div_euclid(12), months.rem_euclid(12) as u32 + 1, 1)
+                    .expect("first of month exists")
+            }
+        }
+    }
+
+    /// The last `count` periods, oldest first, ending with the one containing `today`
+    pub fn last(&self, count: usize, today: NaiveDate, week_start: Weekday) -> DateRange {
+        let current = self.start_of(today, week_start);
+        let first = self.advance(current, 1 - count.max(1) as i32);
+        let end = self.advance(current, 1) - Duration::days(1);
+        DateRange::between(first, end)
+    }
+}
+
+/// Totals for one week or month of a trend
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrendPoint {
+    pub start: NaiveDate,
+    pub bullets: usize,
+    /// Bullets mentioning the term, when one was given
+    pub term_bullets: usize,
+    /// Average sentiment per bullet; positive numbers mean more upbeat writing
+    pub sentiment: f64,
+}
+
+/// Lexicon-based sentiment of a text: positive words count +1, negative
+/// words -1, and a negation right before a word flips it
+pub fn sentiment_score(text: &str) -> i32 {
+    let mut score = 0;
+    let mut negated = false;
+    for word in text.split_whitespace() {
+        let word = word
+            .trim_matches(|c: char| !c.is_alphanumeric() && c != '\'')
+            .to_lowercase();
+        let value = if POSITIVE_WORDS.contains(&word.as_str()) {
+            1
+        } else if NEGATIVE_WORDS.contains(&word.as_str()) {
+            -1
+        } else {
+            0
+        };
+        score += if negated { -value } else { value };
+        negated = NEGATIONS.contains(&word.as_str());
+    }
+    score
+}
+
+/// Bucket the bullets of `entries` into the periods covering `range`, counting
+/// bullets, bullets containing `term` (ignoring case) and average sentiment.
+/// Periods without entries are included with zero counts.
+pub fn compute_trends(
+    entries: &[Entry],
+    range: DateRange,
+    period: TrendPeriod,
+    term: Option<&str>,
+    week_start: Weekday,
+) -> Vec<TrendPoint> {
+    let mut counter = TrendCounter::new(range, period, term, week_start);
+    for entry in entries {
+        counter.add(entry);
+    }
+    counter.finish()
+}
+
+/// `compute_trends` one entry at a time, for entries streamed from storage
+pub struct TrendCounter {
+    period: TrendPeriod,
+    week_start: Weekday,
+    term: Option<String>,
+    points: Vec<TrendPoint>,
+    scores: Vec<i64>,
+}
+
+impl TrendCounter {
+    pub fn new(
+        range: DateRange,
+        period: TrendPeriod,
+        term: Option<&str>,
+        week_start: Weekday,
+    ) -> Self {
+        let mut points = Vec::new();
+        let mut start = period.start_of(range.start, week_start);
+        while start <= range.end {
+            points.push(TrendPoint {
+                start,
+                bullets: 0,
+                term_bullets: 0,
+                sentiment: 0.0,
+            });
+            start = period.advance(start, 1);
+        }
+
+        Self {
+            period,
+            week_start,
+            term: term.map(str::to_lowercase),
+            scores: vec![0; points.len()],
+            points,
+        }
+    }
+
+    pub fn add(&mut self, entry: &Entry) {
+        let start = self.period.start_of(entry.date, self.week_start);
+        let Some(index) = self.points.iter().position(|p| p.start == start) else {
+            return;
+        };
+        for bullet in entry.all_bullets() {
+            self.points[index].bullets += 1;
+            self.scores[index] += sentiment_score(&bullet.content) as i64;
+            if self
+                .term
+                .as_ref()
+                .is_some_and(|term| bullet.content.to_lowercase().contains(term))
+            {
+                self.points[index].term_bullets += 1;
+            }
+        }
+    }
+
+    pub fn finish(mut self) -> Vec<TrendPoint> {
+        for (point, score) in self.points.iter_mut().zip(self.scores) {
+            if point.bullets > 0 {
+                point.sentiment = score as f64 / point.bullets as f64;
+            }
+        }
+        self.points
+    }
+}
+
+/// One block character per value, scaled between the smallest and largest
+pub fn sparkline(values: &[f64]) -> String {
+    const BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    values
+        .iter()
+        .map(|value| {
+            if (max - min).abs() < f64::EPSILON {
+                BLOCKS[0]
+            } else {
+                let level = ((value - min) / (max - min) * 7.0).round() as usize;
+                BLOCKS[level.min(7)]
+            }
+        })
+        .collect()
+}
+
+/// Bar of `width` cells filled in proportion to `value` out of `goal`, full
+/// once the goal is reached
+pub fn progress_bar(value: usize, goal: usize, width: usize) -> String {
+    let filled = match goal {
+        0 => width,
+        goal => (value * width / goal).min(width),
+    };
+    format!("{}{}", "█".repeat(filled), "░".repeat(width - filled))
+}
+
+/// Braille sparkline of the average mood check-in on each day of `range`,
+/// two days per character so a month fits a narrow panel. Bars use a fixed
+/// 1-5 scale so months stay comparable; days without a check-in are empty.
+pub fn mood_sparkline(moods: &[MoodCheckIn], range: DateRange) -> String {
+    // Braille dots of the left and right columns, bottom row first
+    const LEFT: [u32; 4] = [0x40, 0x04, 0x02, 0x01];
+    const RIGHT: [u32; 4] = [0x80, 0x20, 0x10, 0x08];
+
+    let heights: Vec<usize> = range
+        .days()
+        .map(|date| {
+            let scores: Vec<f64> = moods
+                .iter()
+                .filter(|m| m.date == date)
+                .map(|m| m.score as f64)
+                .collect();
+            if scores.is_empty() {
+                return 0;
+            }
+            let average = scores.iter().sum::<f64>() / scores.len() as f64;
+            1 + ((average - 1.0) / 4.0 * 3.0).round() as usize
+        })
+        .collect();
+
+    heights
+        .chunks(2)
+        .map(|pair| {
+            let left: u32 = LEFT.iter().take(pair[0]).sum();
+            let right: u32 = pair
+                .get(1)
+                .map_or(0, |&height| RIGHT.iter().take(height).sum());
+            char::from_u32(0x2800 + left + right).unwrap_or(' ')
+        })
+        .collect()
+}
+
+/// A day suggested as related to another
+#[derive(Debug, Clone, PartialEq)]
+pub struct RelatedDay {
+    pub date: NaiveDate,
+    /// Whether a bullet of the day is a recorded follow-up of this one,
+    /// rather than the two only sharing terms
+    pub follow_up: bool,
+    /// Cosine similarity of the days' tf-idf weighted terms, 1 for follow-ups
+    pub score: f64,
+}
+
+/// Words of at least four letters in an entry's bullets, lowercased and
+/// counted, without stop words and numbers
+fn term_counts(entry: &Entry) -> HashMap<String, usize> {
+    let mut counts = HashMap::new();
+    for bullet in entry.all_bullets() {
+        for word in bullet.content.split_whitespace() {
+            let word = word
+                .trim_matches(|c: char| !c.is_alphanumeric())
+                .to_lowercase();
+            if word.chars().count() >= 4
+                && !word.chars().all(|c| c.is_ascii_digit())
+                && !STOP_WORDS.contains(&word.as_str())
+            {
+                *counts.entry(word).or_insert(0) += 1;
+            }
+        }
+    }
+    counts
+}
+
+/// Days related to `entry`, at most `limit`: the days its recorded follow-ups
+/// point to first, then the days among `others` sharing at least two terms
+/// with it, most similar first. Terms are weighted by tf-idf over `others`, so
+/// words written every day count for little.
+pub fn related_days(
+    entry: &Entry,
+    others: &[Entry],
+    references: &[CrossReference],
+    limit: usize,
+) -> Vec<RelatedDay> {
+    let mut related: Vec<RelatedDay> = Vec::new();
+    for reference in references {
+        if reference.target_date != entry.date
+            && !related.iter().any(|r| r.date == reference.target_date)
+        {
+            related.push(RelatedDay {
+                date: reference.target_date,
+                follow_up: true,
+                score: 1.0,
+            });
+        }
+    }
+
+    let ours = term_counts(entry);
+    let documents: Vec<(NaiveDate, HashMap<String, usize>)> = others
+        .iter()
+        .filter(|other| other.date != entry.date)
+        .map(|other| (other.date, term_counts(other)))
+        .filter(|(_, terms)| !terms.is_empty())
+        .collect();
+    let mut document_frequency: HashMap<&str, usize> = HashMap::new();
+    for terms in documents.iter().map(|(_, terms)| terms).chain([&ours]) {
+        for term in terms.keys() {
+            *document_frequency.entry(term.as_str()).or_insert(0) += 1;
+        }
+    }
+    let total = (documents.len() + 1) as f64;
+    let weights = |terms: &HashMap<String, usize>| -> HashMap<String, f64> {
+        terms
+            .iter()
+            .map(|(term, count)| {
+                let frequency = document_frequency[term.as_str()] as f64;
+                let idf = ((1.0 + total) / (1.0 + frequency)).ln() + 1.0;
+                (term.clone(), *count as f64 * idf)
+            })
+            .collect()
+    };
+    let norm = |weights: &HashMap<String, f64>| weights.values().map(|w| w * w).sum::<f64>().sqrt();
+
+    let our_weights = weights(&ours);
+    let our_norm = norm(&our_weights);
+    let mut similar: Vec<RelatedDay> = Vec::new();
+    if our_norm > 0.0 {
+        for (date, terms) in &documents {
+            // A single shared word is as likely a coincidence as a link
+            if terms.keys().filter(|term| ours.contains_key(*term)).count() < 2 {
+                continue;
+            }
+            let their_weights = weights(terms);
+            let dot: f64 = our_weights
+                .iter()
+                .filter_map(|(term, weight)| their_weights.get(term).map(|w| w * weight))
+                .sum();
+            let score = dot / (our_norm * norm(&their_weights));
+            if score >= MIN_RELATED_SCORE && !related.iter().any(|r| r.date == *date) {
+                similar.push(RelatedDay {
+                    date: *date,
+                    follow_up: false,
+                    score,
+                });
+            }
+        }
+    }
+    // Most similar first; between equals, the day closest in time
+    similar.sort_by(|a, b| {
+        b.score.total_cmp(&a.score).then_with(|| {
+            (a.date - entry.date)
+                .num_days()
+                .abs()
+                .cmp(&(b.date - entry.date).num_days().abs())
+        })
+    });
+
+    related.extend(similar);
+    related.truncate(limit);
+    related
+}
+
+/// Shades of the SVG heatmap, from no writing to the busiest days
+const HEATMAP_SHADES: [&str; 5] = ["#ebedf0", "#9be9a8", "#40c463", "#30a14e", "#216e39"];
+/// Side of a heatmap cell and the gap after it, in pixels
+const HEATMAP_CELL: i64 = 11;
+const HEATMAP_STEP: i64 = 13;
+
+/// Contribution-style SVG heatmap of `year`, one square per day shaded by
+/// its count relative to the busiest day, in columns of weeks starting on
+/// `first_day`. Hovering a day shows its count, labelled with `unit`.
+pub fn year_heatmap_svg(
+    year: i32,
+    counts: &HashMap<NaiveDate, u64>,
+    unit: &str,
+    first_day: Weekday,
+) -> String {
+    const LEFT: i64 = 32;
+    const TOP: i64 = 40;
+
+    let range = DateRange::year(year);
+    let grid_start = DateRange::week_containing(range.start(), first_day).start;
+    let weeks = (range.end() - grid_start).num_days() / 7 + 1;
+    let max = range
+        .days()
+        .filter_map(|date| counts.get(&date))
+        .copied()
+        .max()
+        .unwrap_or(0);
+    let total: u64 = range.days().filter_map(|date| counts.get(&date)).sum();
+    let days = range
+        .days()
+        .filter(|date| counts.get(date).is_some_and(|c| *c > 0))
+        .count();
+
+    let width = LEFT + weeks * HEATMAP_STEP + 8;
+    let height = TOP + 7 * HEATMAP_STEP + 8;
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+         viewBox=\"0 0 {width} {height}\" font-family=\"sans-serif\" font-size=\"10\">\n\
+         <rect width=\"100%\" height=\"100%\" fill=\"#ffffff\"/>\n\
+         <text x=\"{LEFT}\" y=\"14\" font-size=\"12\" fill=\"#24292f\">{year}: {total} {unit} on {days} days</text>\n"
+    );
+
+    // Month names above the week each month starts in
+    for month in 1..=12 {
+        let first = NaiveDate::from_ymd_opt(year, month, 1).expect("valid month");
+        let week = (first - grid_start).num_days() / 7;
+        svg.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\" fill=\"#57606a\">{}</text>\n",
+            LEFT + week * HEATMAP_STEP,
+            TOP - 6,
+            first.format("%b")
+        ));
+    }
+    for row in [1, 3, 5] {
+        let weekday = grid_start + Duration::days(row);
+        svg.push_str(&format!(
+            "<text x=\"0\" y=\"{}\" fill=\"#57606a\">{}</text>\n",
+            TOP + row * HEATMAP_STEP + HEATMAP_CELL - 2,
+            weekday.format("%a")
+        ));
+    }
+
+    for date in range.days() {
+        let offset = (date - grid_start).num_days();
+        let count = counts.get(&date).copied().unwrap_or(0);
+        let level = if count == 0 || max == 0 {
+            0
+        } else {
+            (1 + (count - 1) * 4 / max) as usize
+        };
+        svg.push_str(&format!(
+            "<rect x=\"{}\" y=\"{}\" width=\"{HEATMAP_CELL}\" height=\"{HEATMAP_CELL}\" rx=\"2\" \
+             fill=\"{}\"><title>{}: {} {}</title></rect>\n",
+            LEFT + offset / 7 * HEATMAP_STEP,
+            TOP + offset % 7 * HEATMAP_STEP,
+            HEATMAP_SHADES[level],
+            date.format("%Y-%m-%d"),
+            count,
+            unit
+        ));
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Rasterize an SVG such as `year_heatmap_svg`'s to PNG at its own size,
+/// drawing text with the system's fonts
+pub fn svg_to_png(svg: &str) -> anyhow::Result<Vec<u8>> {
+    use resvg::usvg::fontdb::{Family, Query};
+    use resvg::{tiny_skia, usvg};
+
+    let mut options = usvg::Options::default();
+    let fonts = options.fontdb_mut();
+    fonts.load_system_fonts();
+    // sans-serif means Arial to fontdb, which most Linux systems don't have
+    let sans_serif = Query {
+        families: &[Family::SansSerif],
+        ..Query::default()
+    };
+    if fonts.query(&sans_serif).is_none() {
+        let fallback = fonts
+            .faces()
+            .flat_map(|face| &face.families)
+            .map(|(name, _)| name)
+            .find(|name| name.contains("Sans") && !name.contains("Mono"))
+            .cloned();
+        if let Some(family) = fallback {
+            fonts.set_sans_serif_family(family);
+        }
+    }
+    let tree = usvg::Tree::from_str(svg, &options)?;
+    let size = tree.size().to_int_size();
+    let mut pixmap = tiny_skia::Pixmap::new(size.width(), size.height())
+        .ok_or_else(|| anyhow::anyhow!("SVG has no area to draw"))?;
+    resvg::render(&tree, tiny_skia::Transform::default(), &mut pixmap.as_mut());
+    Ok(pixmap.encode_png()?)
+}
+
+/// How `journo metric` shows the readings of a metric
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricOutput {
+    /// One line per reading with a summary
+    List,
+    /// Bar chart of the daily averages
+    Chart,
+    /// `date,value` rows for spreadsheets
+    Csv,
+}
+
+/// Bar chart of the daily averages of metric readings, at most `width`
+/// columns wide (consecutive days are averaged together to fit) and `height`
+/// rows tall. Bars start from the smallest value so small changes show.
+pub fn metric_chart(readings: &[MetricValue], width: usize, height: usize) -> Vec<String> {
+    const BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+    let mut days: BTreeMap<NaiveDate, (f64, usize)> = BTreeMap::new();
+    for reading in readings {
+        let day = days.entry(reading.date).or_default();
+        day.0 += reading.value;
+        day.1 += 1;
+    }
+    let daily: Vec<(NaiveDate, f64)> = days
+        .into_iter()
+        .map(|(date, (sum, count))| (date, sum / count as f64))
+        .collect();
+    let (Some(first), Some(last)) = (daily.first(), daily.last()) else {
+        return Vec::new();
+    };
+
+    let per_column = daily.len().div_ceil(width.max(1));
+    let columns: Vec<f64> = daily
+        .chunks(per_column)
+        .map(|chunk| chunk.iter().map(|(_, v)| v).sum::<f64>() / chunk.len() as f64)
+        .collect();
+    let min = columns.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = columns.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+
+    // Bar heights in eighths of a row, at least one so every column shows
+    let height = height.max(1);
+    let steps = height * 8;
+    let bars: Vec<usize> = columns
+        .iter()
+        .map(|value| {
+            if (max - min).abs() < f64::EPSILON {
+                steps / 2
+            } else {
+                1 + ((value - min) / (max - min) * (steps - 1) as f64).round() as usize
+            }
+        })
+        .collect();
+
+    let (top, bottom) = (format!("{:.1}", max), format!("{:.1}", min));
+    let label_width = top.len().max(bottom.len());
+    let mut lines = Vec::new();
+    for row in (0..height).rev() {
+        let label = match row {
+            r if r == height - 1 => format!("{:>label_width$} ┤", top),
+            0 => format!("{:>label_width$} ┤", bottom),
+            _ => format!("{:label_width$} │", ""),
+        };
+        let cells: String = bars
+            .iter()
+            .map(|bar| match bar.saturating_sub(row * 8).min(8) {
+                0 => ' ',
+                fill => BLOCKS[fill - 1],
+            })
+            .collect();
+        lines.push(format!("{}{}", label, cells));
+    }
+    lines.push(format!("{:label_width$} └{}", "", "─".repeat(bars.len())));
+
+    let (from, to) = (
+        first.0.format("%b %d").to_string(),
+        last.0.format("%b %d").to_string(),
+    );
+    let dates = if bars.len() > from.len() + to.len() {
+        format!("{}{:>gap$}", from, to, gap = bars.len() - from.len())
+    } else {
+        format!("{} – {}", from, to)
+    };
+    lines.push(format!("{:label_width$}  {}", "", dates));
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{Bullet, BulletType};
+
+    #[test]
+    fn test_year_heatmap_svg() {
+        let day = |m, d| NaiveDate::from_ymd_opt(2024, m, d).unwrap();
+        let counts = HashMap::from([(day(3, 15), 4), (day(3, 16), 1)]);
+        let svg = year_heatmap_svg(2024, &counts, "bullets", Weekday::Sun);
+
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains(">2024: 5 bullets on 2 days</text>"));
+        assert_eq!(svg.matches("<rect x=").count(), 366);
+        assert!(svg.contains("fill=\"#216e39\"><title>2024-03-15: 4 bullets</title>"));
+        assert!(svg.contains("fill=\"#9be9a8\"><title>2024-03-16: 1 bullets</title>"));
+
+        let png = svg_to_png(&svg).unwrap();
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+        // Width and height from the IHDR chunk match the SVG's
+        assert!(svg.contains("width=\"729\" height=\"139\""));
+        let dimension = |at: usize| u32::from_be_bytes(png[at..at + 4].try_into().unwrap());
+        assert_eq!((dimension(16), dimension(20)), (729, 139));
+    }
+
+    #[test]
+    fn test_progress_bar() {
+        assert_eq!(progress_bar(0, 200, 4), "░░░░");
+        assert_eq!(progress_bar(100, 200, 4), "██░░");
+        assert_eq!(progress_bar(350, 200, 4), "████");
+    }
+
+    #[test]
+    fn test_sentiment_score() {
+        assert_eq!(sentiment_score("Great run, felt happy"), 2);
+        assert_eq!(sentiment_score("Tired and stressed."), -2);
+        assert_eq!(sentiment_score("not happy with the demo"), -1);
+        assert_eq!(sentiment_score("Dentist at 3"), 0);
+    }
+
+    #[test]
+    fn test_compute_trends_by_week() {
+        let day = |d| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+        let mut first = Entry::new(day(4));
+        first.add_bullet(Bullet::new("Anxiety before the talk", BulletType::Note));
+        first.add_bullet(Bullet::new("Great talk", BulletType::Event));
+        let mut second = Entry::new(day(20));
+        second.add_bullet(Bullet::new("Some anxiety, tired", BulletType::Note));
+
+        let range = TrendPeriod::Week.last(3, day(20), Weekday::Mon);
+        assert_eq!(range.start, day(4));
+        let points = compute_trends(
+            &[first, second],
+            range,
+            TrendPeriod::Week,
+            Some("ANXIETY"),
+            Weekday::Mon,
+        );
+        assert_eq!(points.len(), 3);
+        assert_eq!((points[0].bullets, points[0].term_bullets), (2, 1));
+        assert_eq!(points[0].sentiment, 0.0);
+        assert_eq!(points[1].bullets, 0);
+        assert_eq!(points[2].sentiment, -2.0);
+        assert_eq!(
+            sparkline(&points.iter().map(|p| p.sentiment).collect::<Vec<_>>()),
+            "██▁"
+        );
+    }
+
+    #[test]
+    fn test_mood_sparkline_pairs_days() {
+        use crate::entities::MoodPeriod;
+        let day = |d| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+        let mood = |d, period, score| MoodCheckIn {
+            date: day(d),
+            period,
+            score,
+        };
+        let moods = [
+            mood(1, MoodPeriod::Morning, 1),
+            mood(3, MoodPeriod::Morning, 2),
+            mood(3, MoodPeriod::Evening, 4),
+            mood(4, MoodPeriod::Evening, 5),
+        ];
+        assert_eq!(
+            mood_sparkline(&moods, DateRange::between(day(1), day(4))),
+            "⡀⣾"
+        );
+    }
+
+    #[test]
+    fn test_metric_chart_averages_days() {
+        let day = |d| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+        let reading = |d, value| MetricValue {
+            date: day(d),
+            value,
+        };
+        let readings = [
+            reading(1, 80.0),
+            reading(2, 81.0),
+            reading(3, 81.0),
+            reading(3, 83.0),
+            reading(4, 84.0),
+        ];
+        assert_eq!(
+            metric_chart(&readings, 10, 2),
+            vec![
+                "84.0 ┤  ▁█",
+                "80.0 ┤▁▅██",
+                "     └────",
+                "      Mar 01 – Mar 04",
+            ]
+        );
+
+        // Squeezed into two columns, pairs of days are averaged
+        let squeezed = metric_chart(&readings, 2, 1);
+        assert_eq!(squeezed[0], "83.0 ┤▁█");
+        assert!(metric_chart(&[], 10, 2).is_empty());
+    }
+
+    #[test]
+    fn test_related_days_by_shared_terms() {
+        let day = |d| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+        let entry = |d, contents: &[&str]| {
+            let mut entry = Entry::new(day(d));
+            for content in contents {
+                entry.add_bullet(Bullet::new(*content, BulletType::Note));
+            }
+            entry
+        };
+        let selected = entry(
+            15,
+            &["Kitchen renovation quote from the plumber", "Standup"],
+        );
+        let others = [
+            entry(1, &["Plumber came about the kitchen renovation", "Standup"]),
+            entry(5, &["Standup", "Lunch with Sam"]),
+            entry(8, &["Standup", "Long run in the park"]),
+            entry(12, &["Standup", "Renovation budget spreadsheet"]),
+            entry(15, &["Kitchen renovation quote from the plumber"]),
+        ];
+        let sync = Bullet::new("Sync", BulletType::Note);
+        let references = [CrossReference::new(day(15), &sync, day(5), &sync)];
+
+        let related = related_days(&selected, &others, &references, 3);
+        let dates: Vec<_> = related.iter().map(|r| (r.date, r.follow_up)).collect();
+        assert_eq!(
+            dates,
+            vec![(day(5), true), (day(1), false), (day(12), false)]
+        );
+        assert!(related[1].score > related[2].score);
+
+        // Sharing a single word isn't enough
+        assert!(related_days(&entry(15, &["Standup"]), &others, &[], 3).is_empty());
+        assert!(related_days(&entry(15, &[]), &others, &[], 3).is_empty());
+    }
+
+    #[test]
+    fn test_months_wrap_across_years() {
+        let today = NaiveDate::from_ymd_opt(2024, 2, 10).unwrap();
+        let range = TrendPeriod::Month.last(3, today, Weekday::Sun);
+        assert_eq!(range.start, NaiveDate::from_ymd_opt(2023, 12, 1).unwrap());
+        assert_eq!(range.end, NaiveDate::from_ymd_opt(2024, 2, 29).unwrap());
+    }
+}
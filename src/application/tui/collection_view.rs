@@ -0,0 +1,203 @@
+use super::theme::Theme;
+use crate::entities::{BulletHit, BulletType};
+use chrono::NaiveDate;
+use crossterm::ExecutableCommand;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::terminal::{
+    EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
+};
+use crossterm::tty::IsTty;
+use ratatui::{
+    Terminal,
+    backend::CrosstermBackend,
+    layout::{Constraint, Direction, Layout},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+};
+use std::io::{self, Stdout, stdout};
+
+/// Rows moved by PageUp/PageDown
+const PAGE_ROWS: usize = 10;
+
+/// What the user chose when leaving the collection browser
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CollectionViewResult {
+    Exit,
+    EditRequested(NaiveDate),
+}
+
+/// Every bullet of one type across a range in one chronological list, a
+/// BuJo "collection" such as all insights of the year
+pub struct CollectionView {
+    bullet_type: BulletType,
+    hits: Vec<BulletHit>,
+    terminal: Terminal<CrosstermBackend<Stdout>>,
+    theme: Theme,
+    state: ListState,
+    result: Option<CollectionViewResult>,
+}
+
+impl CollectionView {
+    pub fn new(bullet_type: BulletType, hits: Vec<BulletHit>) -> io::Result<Self> {
+        if !IsTty::is_tty(&std::io::stdout()) {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "Not running in a TTY, cannot initialize terminal interface",
+            ));
+        }
+
+        enable_raw_mode()?;
+        stdout().execute(EnterAlternateScreen).inspect_err(|_| {
+            let _ = disable_raw_mode();
+        })?;
+        let terminal = Terminal::new(CrosstermBackend::new(stdout())).inspect_err(|_| {
+            let _ = disable_raw_mode();
+            let _ = stdout().execute(LeaveAlternateScreen);
+        })?;
+
+        let mut state = ListState::default();
+        state.select((!hits.is_empty()).then_some(0));
+        Ok(Self {
+            bullet_type,
+            hits,
+            terminal,
+            theme: Theme::default(),
+            state,
+            result: None,
+        })
+    }
+
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Start on the first bullet written on `date`, e.g. the day just edited
+    pub fn with_selected_date(mut self, date: NaiveDate) -> Self {
+        if let Some(index) = self.hits.iter().position(|hit| hit.date >= date) {
+            self.state.select(Some(index));
+        }
+        self
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        let last = self.hits.len().saturating_sub(1);
+        if let Some(selected) = self.state.selected() {
+            let moved = selected.saturating_add_signed(delta).min(last);
+            self.state.select(Some(moved));
+        }
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) {
+        match (key.code, key.modifiers) {
+            (KeyCode::Char('q'), _) | (KeyCode::Esc, _) => {
+                self.result = Some(CollectionViewResult::Exit);
+            }
+            (KeyCode::Char('c'), KeyModifiers::CONTROL) => {
+                self.result = Some(CollectionViewResult::Exit);
+            }
+            (KeyCode::Enter, _) => {
+                if let Some(hit) = self.state.selected().and_then(|i| self.hits.get(i)) {
+                    self.result = Some(CollectionViewResult::EditRequested(hit.date));
+                }
+            }
+            (KeyCode::Down, _) | (KeyCode::Char('j'), _) => self.move_selection(1),
+            (KeyCode::Up, _) | (KeyCode::Char('k'), _) => self.move_selection(-1),
+            (KeyCode::PageDown, _) => self.move_selection(PAGE_ROWS as isize),
+            (KeyCode::PageUp, _) => self.move_selection(-(PAGE_ROWS as isize)),
+            (KeyCode::Home, _) | (KeyCode::Char('g'), _) => self.move_selection(isize::MIN),
+            (KeyCode::End, _) | (KeyCode::Char('G'), _) => self.move_selection(isize::MAX),
+            _ => {}
+        }
+    }
+
+    fn draw(&mut self) -> io::Result<()> {
+        let theme = &self.theme;
+
+        // The date is only repeated when it changes, so days read as groups
+        let mut previous: Option<NaiveDate> = None;
+        let items: Vec<ListItem> = self
+            .hits
+            .iter()
+            .map(|hit| {
+                let date = if previous == Some(hit.date) {
+                    " ".repeat(11)
+                } else {
+                    hit.date.format("%Y-%m-%d ").to_string()
+                };
+                previous = Some(hit.date);
+                ListItem::new(Line::from(vec![
+                    Span::styled(date, Style::default().fg(theme.colors.dimmed)),
+                    Span::styled(
+                        format!("{} ", theme.symbols.symbol(&hit.bullet)),
+                        Style::default().fg(theme.colors.bullets.color(&hit.bullet)),
+                    ),
+                    Span::raw(hit.bullet.content.clone()),
+                ]))
+            })
+            .collect();
+
+        let title = match (self.hits.first(), self.hits.last()) {
+            (Some(first), Some(last)) => format!(
+                " {}: {} from {} – {} ",
+                self.bullet_type.section_name(),
+                self.hits.len(),
+                first.date.format("%b %d %Y"),
+                last.date.format("%b %d %Y")
+            ),
+            _ => format!(" {}: none in this range ", self.bullet_type.section_name()),
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.colors.border))
+            .title(title)
+            .title_style(Style::default().fg(theme.colors.header));
+        let list = List::new(items).block(block).highlight_style(
+            Style::default()
+                .fg(theme.colors.selected)
+                .add_modifier(Modifier::BOLD | Modifier::REVERSED),
+        );
+        let footer = Line::from(Span::styled(
+            "↑↓/jk=Move • PgUp/PgDn=Page • g/G=First/Last • Enter=Edit day • q=Quit",
+            Style::default().fg(theme.colors.help_text),
+        ));
+        let state = &mut self.state;
+
+        self.terminal.draw(|frame| {
+            let rows = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(0), Constraint::Length(1)])
+                .split(frame.area());
+            frame.render_stateful_widget(list, rows[0], state);
+            frame.render_widget(Paragraph::new(footer), rows[1]);
+        })?;
+        Ok(())
+    }
+
+    pub fn run(&mut self) -> io::Result<CollectionViewResult> {
+        loop {
+            if let Some(result) = self.result.take() {
+                self.cleanup()?;
+                return Ok(result);
+            }
+
+            self.draw()?;
+            if let Event::Key(key) = event::read()? {
+                self.handle_key_event(key);
+            }
+        }
+    }
+
+    fn cleanup(&mut self) -> io::Result<()> {
+        disable_raw_mode()?;
+        self.terminal.backend_mut().execute(LeaveAlternateScreen)?;
+        Ok(())
+    }
+}
+
+impl Drop for CollectionView {
+    fn drop(&mut self) {
+        let _ = self.cleanup();
+    }
+}
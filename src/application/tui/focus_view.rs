@@ -0,0 +1,246 @@
+use super::theme::Theme;
+use crossterm::ExecutableCommand;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers, poll};
+use crossterm::terminal::{
+    EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
+};
+use crossterm::tty::IsTty;
+use ratatui::{
+    Terminal,
+    backend::CrosstermBackend,
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Gauge, Paragraph},
+};
+use std::io::{self, Stdout, stdout};
+use std::time::{Duration, Instant};
+
+/// Minutes added to the timer by the extend key
+const EXTEND_MINUTES: u64 = 5;
+
+#[derive(Debug, Clone)]
+pub struct FocusResult {
+    /// Time actually spent focusing, excluding pauses
+    pub focused: Duration,
+    /// Whether the timer ran out rather than being abandoned
+    pub finished: bool,
+    /// Whether the user confirmed the task is done
+    pub completed: bool,
+}
+
+/// Full-screen countdown timer for a single task
+pub struct FocusView {
+    task: String,
+    terminal: Terminal<CrosstermBackend<Stdout>>,
+    theme: Theme,
+    /// Total length of the session, including extensions
+    total: Duration,
+    /// Focused time accumulated before the current run started
+    banked: Duration,
+    /// When the timer was last started or resumed; `None` while paused
+    running_since: Option<Instant>,
+    /// Timer ran out and we're asking whether the task is done
+    confirming: bool,
+    result: Option<FocusResult>,
+}
+
+impl FocusView {
+    pub fn new(task: impl Into<String>, length: Duration) -> io::Result<Self> {
+        if !IsTty::is_tty(&std::io::stdout()) {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "Not running in a TTY, cannot initialize terminal interface",
+            ));
+        }
+
+        enable_raw_mode()?;
+        stdout().execute(EnterAlternateScreen).inspect_err(|_| {
+            let _ = disable_raw_mode();
+        })?;
+        let terminal = Terminal::new(CrosstermBackend::new(stdout())).inspect_err(|_| {
+            let _ = disable_raw_mode();
+            let _ = stdout().execute(LeaveAlternateScreen);
+        })?;
+
+        Ok(Self {
+            task: task.into(),
+            terminal,
+            theme: Theme::default(),
+            total: length,
+            banked: Duration::ZERO,
+            running_since: Some(Instant::now()),
+            confirming: false,
+            result: None,
+        })
+    }
+
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    fn focused(&self) -> Duration {
+        self.banked + self.running_since.map_or(Duration::ZERO, |t| t.elapsed())
+    }
+
+    fn remaining(&self) -> Duration {
+        self.total.saturating_sub(self.focused())
+    }
+
+    fn toggle_pause(&mut self) {
+        match self.running_since.take() {
+            Some(started) => self.banked += started.elapsed(),
+            None => self.running_since = Some(Instant::now()),
+        }
+    }
+
+    fn finish(&mut self, finished: bool, completed: bool) {
+        self.result = Some(FocusResult {
+            focused: self.focused().min(self.total),
+            finished,
+            completed,
+        });
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) {
+        if self.confirming {
+            match key.code {
+                KeyCode::Char('y') => self.finish(true, true),
+                KeyCode::Char('n') | KeyCode::Esc | KeyCode::Char('q') => self.finish(true, false),
+                _ => {}
+            }
+            return;
+        }
+
+        match (key.code, key.modifiers) {
+            (KeyCode::Char('q'), _) | (KeyCode::Esc, _) => self.finish(false, false),
+            (KeyCode::Char('c'), KeyModifiers::CONTROL) => self.finish(false, false),
+            (KeyCode::Char(' '), _) | (KeyCode::Char('p'), _) => self.toggle_pause(),
+            (KeyCode::Char('+'), _) | (KeyCode::Char('e'), _) => {
+                self.total += Duration::from_secs(EXTEND_MINUTES * 60);
+            }
+            // Finish early
+            (KeyCode::Enter, _) => {
+                self.toggle_pause_if_running();
+                self.confirming = true;
+            }
+            _ => {}
+        }
+    }
+
+    fn toggle_pause_if_running(&mut self) {
+        if self.running_since.is_some() {
+            self.toggle_pause();
+        }
+    }
+
+    fn draw(&mut self) -> io::Result<()> {
+        let remaining = self.remaining();
+        let total = self.total.as_secs().max(1);
+        let ratio = 1.0 - remaining.as_secs() as f64 / total as f64;
+        let paused = self.running_since.is_none() && !self.confirming;
+        let theme = &self.theme;
+        let task = &self.task;
+
+        let status = if self.confirming {
+            "Done! Mark the task complete? [y/n]".to_string()
+        } else if paused {
+            "Paused — space to resume".to_string()
+        } else {
+            format!(
+                "space pause · + extend {}m · enter finish · q abandon",
+                EXTEND_MINUTES
+            )
+        };
+
+        self.terminal.draw(|frame| {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Percentage(30),
+                    Constraint::Length(2),
+                    Constraint::Length(3),
+                    Constraint::Length(3),
+                    Constraint::Length(2),
+                    Constraint::Min(0),
+                ])
+                .split(frame.area());
+
+            let title = Paragraph::new(Line::from(Span::styled(
+                task.clone(),
+                Style::default()
+                    .fg(theme.colors.header)
+                    .add_modifier(Modifier::BOLD),
+            )))
+            .alignment(Alignment::Center);
+            frame.render_widget(title, chunks[1]);
+
+            let secs = remaining.as_secs();
+            let clock = Paragraph::new(Line::from(Span::styled(
+                format!("{:02}:{:02}", secs / 60, secs % 60),
+                Style::default()
+                    .fg(if paused {
+                        theme.colors.dimmed
+                    } else {
+                        theme.colors.primary
+                    })
+                    .add_modifier(Modifier::BOLD),
+            )))
+            .alignment(Alignment::Center);
+            frame.render_widget(clock, chunks[2]);
+
+            let gauge = Gauge::default()
+                .block(Block::default().borders(Borders::NONE))
+                .gauge_style(Style::default().fg(theme.colors.accent))
+                .ratio(ratio.clamp(0.0, 1.0))
+                .label("");
+            frame.render_widget(gauge, chunks[3]);
+
+            let help = Paragraph::new(Line::from(Span::styled(
+                status,
+                Style::default().fg(theme.colors.help_text),
+            )))
+            .alignment(Alignment::Center);
+            frame.render_widget(help, chunks[4]);
+        })?;
+        Ok(())
+    }
+
+    pub fn run(&mut self) -> io::Result<FocusResult> {
+        loop {
+            if let Some(result) = self.result.take() {
+                self.cleanup()?;
+                return Ok(result);
+            }
+
+            if !self.confirming && self.remaining().is_zero() {
+                self.toggle_pause_if_running();
+                self.confirming = true;
+                // Ring the terminal bell so the end isn't missed
+                print!("\x07");
+            }
+
+            // Redraw every tick so the countdown stays current
+            self.draw()?;
+
+            if poll(Duration::from_millis(250))?
+                && let Event::Key(key) = event::read()?
+            {
+                self.handle_key_event(key);
+            }
+        }
+    }
+
+    fn cleanup(&mut self) -> io::Result<()> {
+        disable_raw_mode()?;
+        self.terminal.backend_mut().execute(LeaveAlternateScreen)?;
+        Ok(())
+    }
+}
+
+impl Drop for FocusView {
+    fn drop(&mut self) {
+        let _ = self.cleanup();
+    }
+}
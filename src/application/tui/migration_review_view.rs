@@ -0,0 +1,263 @@
+use super::theme::Theme;
+use crate::application::plan::{MigrationChoice, MigrationReview, parse_schedule_date};
+use chrono::NaiveDate;
+use crossterm::ExecutableCommand;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::terminal::{
+    EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
+};
+use crossterm::tty::IsTty;
+use ratatui::{
+    Terminal,
+    backend::CrosstermBackend,
+    layout::{Constraint, Direction, Layout},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+};
+use std::io::{self, Stdout, stdout};
+
+/// What the user chose when leaving the migration review
+#[derive(Debug, Clone, PartialEq)]
+pub enum MigrationReviewResult {
+    Apply(MigrationReview),
+    /// Leave every task where it is
+    Skip,
+}
+
+/// Checklist of last week's pending tasks, each marked to migrate to today,
+/// schedule for a date or drop
+pub struct MigrationReviewView {
+    review: MigrationReview,
+    today: NaiveDate,
+    terminal: Terminal<CrosstermBackend<Stdout>>,
+    theme: Theme,
+    state: ListState,
+    /// Date being typed to schedule the selected task for
+    date_input: Option<String>,
+    /// Why the last date typed was rejected
+    message: Option<String>,
+    result: Option<MigrationReviewResult>,
+}
+
+impl MigrationReviewView {
+    pub fn new(review: MigrationReview, today: NaiveDate) -> io::Result<Self> {
+        if !IsTty::is_tty(&std::io::stdout()) {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "Not running in a TTY, cannot initialize terminal interface",
+            ));
+        }
+
+        enable_raw_mode()?;
+        stdout().execute(EnterAlternateScreen).inspect_err(|_| {
+            let _ = disable_raw_mode();
+        })?;
+        let terminal = Terminal::new(CrosstermBackend::new(stdout())).inspect_err(|_| {
+            let _ = disable_raw_mode();
+            let _ = stdout().execute(LeaveAlternateScreen);
+        })?;
+
+        let mut state = ListState::default();
+        state.select((!review.is_empty()).then_some(0));
+        Ok(Self {
+            review,
+            today,
+            terminal,
+            theme: Theme::default(),
+            state,
+            date_input: None,
+            message: None,
+            result: None,
+        })
+    }
+
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    fn move_selection(&mut self, down: bool) {
+        let len = self.review.items.len();
+        if let Some(selected) = self.state.selected() {
+            self.state.select(Some(if down {
+                (selected + 1).min(len.saturating_sub(1))
+            } else {
+                selected.saturating_sub(1)
+            }));
+        }
+    }
+
+    /// Set the selected task's choice and move on to the next one
+    fn choose(&mut self, choice: MigrationChoice) {
+        if let Some(i) = self.state.selected() {
+            self.review.items[i].1 = choice;
+            self.move_selection(true);
+        }
+    }
+
+    fn handle_date_key(&mut self, key: KeyEvent) {
+        let Some(input) = self.date_input.as_mut() else {
+            return;
+        };
+        match key.code {
+            KeyCode::Enter => match parse_schedule_date(input, self.today) {
+                Some(date) => {
+                    self.date_input = None;
+                    self.message = None;
+                    self.choose(MigrationChoice::Schedule(date));
+                }
+                None => {
+                    self.message = Some(format!("can't read '{}'", input.trim()));
+                }
+            },
+            KeyCode::Esc => {
+                self.date_input = None;
+                self.message = None;
+            }
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                input.push(c);
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) {
+        if self.date_input.is_some() {
+            self.handle_date_key(key);
+            return;
+        }
+
+        match (key.code, key.modifiers) {
+            (KeyCode::Char('q'), _) | (KeyCode::Esc, _) => {
+                self.result = Some(MigrationReviewResult::Skip);
+            }
+            (KeyCode::Char('c'), KeyModifiers::CONTROL) => {
+                self.result = Some(MigrationReviewResult::Skip);
+            }
+            (KeyCode::Enter, _) => {
+                self.result = Some(MigrationReviewResult::Apply(self.review.clone()));
+            }
+            (KeyCode::Down, _) | (KeyCode::Char('j'), _) => self.move_selection(true),
+            (KeyCode::Up, _) | (KeyCode::Char('k'), _) => self.move_selection(false),
+            (KeyCode::Char('m'), _) => self.choose(MigrationChoice::Migrate),
+            (KeyCode::Char('d'), _) => self.choose(MigrationChoice::Drop),
+            (KeyCode::Char(' '), _) | (KeyCode::Char('u'), _) => self.choose(MigrationChoice::Keep),
+            (KeyCode::Char('s'), _) if self.state.selected().is_some() => {
+                self.date_input = Some(String::new());
+            }
+            _ => {}
+        }
+    }
+
+    fn draw(&mut self) -> io::Result<()> {
+        let theme = &self.theme;
+        let review = &self.review;
+
+        let items: Vec<ListItem> = review
+            .items
+            .iter()
+            .map(|(item, choice)| {
+                let (label, color) = match choice {
+                    MigrationChoice::Keep => ("keep".to_string(), theme.colors.dimmed),
+                    MigrationChoice::Migrate => ("→ today".to_string(), theme.colors.accent),
+                    MigrationChoice::Schedule(date) => (
+                        format!("→ {}", date.format("%a %b %d")),
+                        theme.colors.secondary,
+                    ),
+                    MigrationChoice::Drop => ("✗ drop".to_string(), theme.colors.error_text),
+                };
+                ListItem::new(Line::from(vec![
+                    Span::styled(format!("{:<14}", label), Style::default().fg(color)),
+                    Span::styled(
+                        item.date.format("%a %d ").to_string(),
+                        Style::default().fg(theme.colors.dimmed),
+                    ),
+                    Span::styled(
+                        format!("{} ", theme.symbols.symbol(&item.bullet)),
+                        Style::default().fg(theme.colors.bullets.color(&item.bullet)),
+                    ),
+                    Span::raw(item.bullet.content.clone()),
+                ]))
+            })
+            .collect();
+
+        let title = format!(
+            " New week: pending from {} – {} ",
+            review.week.start().format("%b %d"),
+            review.week.end().format("%b %d")
+        );
+        let footer = match &self.date_input {
+            Some(input) => {
+                let mut spans = vec![
+                    Span::styled(
+                        "Schedule for (YYYY-MM-DD, weekday or +days): ",
+                        Style::default().fg(theme.colors.header),
+                    ),
+                    Span::raw(format!("{}█", input)),
+                ];
+                if let Some(message) = &self.message {
+                    spans.push(Span::styled(
+                        format!("  {}", message),
+                        Style::default().fg(theme.colors.error_text),
+                    ));
+                }
+                Line::from(spans)
+            }
+            None => Line::from(Span::styled(
+                "↑↓/jk=Move • m=Migrate to today • s=Schedule • d=Drop • Space=Keep • Enter=Apply • q=Skip",
+                Style::default().fg(theme.colors.help_text),
+            )),
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.colors.border))
+            .title(title)
+            .title_style(Style::default().fg(theme.colors.header));
+        let list = List::new(items).block(block).highlight_style(
+            Style::default()
+                .fg(theme.colors.selected)
+                .add_modifier(Modifier::BOLD | Modifier::REVERSED),
+        );
+        let state = &mut self.state;
+
+        self.terminal.draw(|frame| {
+            let rows = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(0), Constraint::Length(1)])
+                .split(frame.area());
+            frame.render_stateful_widget(list, rows[0], state);
+            frame.render_widget(Paragraph::new(footer), rows[1]);
+        })?;
+        Ok(())
+    }
+
+    pub fn run(&mut self) -> io::Result<MigrationReviewResult> {
+        loop {
+            if let Some(result) = self.result.take() {
+                self.cleanup()?;
+                return Ok(result);
+            }
+
+            self.draw()?;
+            if let Event::Key(key) = event::read()? {
+                self.handle_key_event(key);
+            }
+        }
+    }
+
+    fn cleanup(&mut self) -> io::Result<()> {
+        disable_raw_mode()?;
+        self.terminal.backend_mut().execute(LeaveAlternateScreen)?;
+        Ok(())
+    }
+}
+
+impl Drop for MigrationReviewView {
+    fn drop(&mut self) {
+        let _ = self.cleanup();
+    }
+}
@@ -1,5 +1,19 @@
+pub mod collection_view;
+pub mod focus_view;
+pub mod migration_review_view;
+pub mod mood_picker;
+pub mod plan_view;
 pub mod theme;
+pub mod week_plan_view;
 pub mod week_view;
+pub mod year_view;
 
+pub use collection_view::*;
+pub use focus_view::*;
+pub use migration_review_view::*;
+pub use mood_picker::*;
+pub use plan_view::*;
 pub use theme::*;
+pub use week_plan_view::*;
 pub use week_view::*;
+pub use year_view::*;
@@ -0,0 +1,149 @@
+use super::theme::Theme;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
+use crossterm::tty::IsTty;
+use ratatui::{
+    Terminal, TerminalOptions, Viewport,
+    backend::CrosstermBackend,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+};
+use std::io::{self, Stdout, stdout};
+
+/// Labels shown under the scores, lowest first
+const MOOD_LABELS: [&str; 5] = ["awful", "low", "okay", "good", "great"];
+
+/// Quick 1-5 picker drawn inline below the shell prompt, so it doesn't take
+/// over the screen on the way into or out of the editor
+pub struct MoodPicker {
+    question: String,
+    terminal: Terminal<CrosstermBackend<Stdout>>,
+    theme: Theme,
+    /// Highlighted score, 1-5
+    selected: u8,
+    result: Option<Option<u8>>,
+}
+
+impl MoodPicker {
+    pub fn new(question: impl Into<String>) -> io::Result<Self> {
+        if !IsTty::is_tty(&std::io::stdout()) {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "Not running in a TTY, cannot initialize terminal interface",
+            ));
+        }
+
+        enable_raw_mode()?;
+        let terminal = Terminal::with_options(
+            CrosstermBackend::new(stdout()),
+            TerminalOptions {
+                viewport: Viewport::Inline(3),
+            },
+        )
+        .inspect_err(|_| {
+            let _ = disable_raw_mode();
+        })?;
+
+        Ok(Self {
+            question: question.into(),
+            terminal,
+            theme: Theme::default(),
+            selected: 3,
+            result: None,
+        })
+    }
+
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) {
+        match (key.code, key.modifiers) {
+            (KeyCode::Char(c @ '1'..='5'), _) => {
+                self.result = Some(Some(c as u8 - b'0'));
+            }
+            (KeyCode::Left, _) | (KeyCode::Char('h'), _) => {
+                self.selected = self.selected.saturating_sub(1).max(1);
+            }
+            (KeyCode::Right, _) | (KeyCode::Char('l'), _) => {
+                self.selected = (self.selected + 1).min(5);
+            }
+            (KeyCode::Enter, _) | (KeyCode::Char(' '), _) => {
+                self.result = Some(Some(self.selected));
+            }
+            (KeyCode::Esc, _) | (KeyCode::Char('q'), _) => self.result = Some(None),
+            (KeyCode::Char('c'), KeyModifiers::CONTROL) => self.result = Some(None),
+            _ => {}
+        }
+    }
+
+    fn draw(&mut self) -> io::Result<()> {
+        let theme = &self.theme;
+        let question = &self.question;
+        let selected = self.selected;
+
+        self.terminal.draw(|frame| {
+            let mut scores = Vec::new();
+            for score in 1..=5u8 {
+                let style = if score == selected {
+                    Style::default()
+                        .fg(theme.colors.selected)
+                        .add_modifier(Modifier::BOLD | Modifier::REVERSED)
+                } else {
+                    Style::default().fg(theme.colors.normal_text)
+                };
+                scores.push(Span::styled(
+                    format!(" {} {} ", score, MOOD_LABELS[score as usize - 1]),
+                    style,
+                ));
+                scores.push(Span::raw(" "));
+            }
+
+            let lines = vec![
+                Line::from(Span::styled(
+                    question.clone(),
+                    Style::default()
+                        .fg(theme.colors.header)
+                        .add_modifier(Modifier::BOLD),
+                )),
+                Line::from(scores),
+                Line::from(Span::styled(
+                    "1-5 or ←/→ and enter · esc skip",
+                    Style::default().fg(theme.colors.help_text),
+                )),
+            ];
+            frame.render_widget(Paragraph::new(lines), frame.area());
+        })?;
+        Ok(())
+    }
+
+    /// Returns the chosen score, or `None` if the prompt was skipped
+    pub fn run(&mut self) -> io::Result<Option<u8>> {
+        loop {
+            if let Some(result) = self.result.take() {
+                self.cleanup()?;
+                return Ok(result);
+            }
+
+            self.draw()?;
+
+            if let Event::Key(key) = event::read()? {
+                self.handle_key_event(key);
+            }
+        }
+    }
+
+    fn cleanup(&mut self) -> io::Result<()> {
+        self.terminal.clear()?;
+        disable_raw_mode()?;
+        Ok(())
+    }
+}
+
+impl Drop for MoodPicker {
+    fn drop(&mut self) {
+        let _ = disable_raw_mode();
+    }
+}
@@ -0,0 +1,351 @@
+use super::theme::Theme;
+use crate::application::DateFormats;
+use crate::application::plan::DayPlan;
+use crate::entities::Journal;
+use chrono::{Duration, NaiveDate, Timelike};
+use crossterm::ExecutableCommand;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::terminal::{
+    EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
+};
+use crossterm::tty::IsTty;
+use ratatui::{
+    Terminal,
+    backend::CrosstermBackend,
+    layout::{Constraint, Direction, Layout},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+};
+use std::io::{self, Stdout, stdout};
+
+/// Minutes covered by one row of the timeline
+const SLOT_MINUTES: i64 = 30;
+/// Width of the overlaps and free time panel
+const SUMMARY_WIDTH: u16 = 30;
+
+/// What the user chose when leaving the plan view
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlanViewResult {
+    Exit,
+    EditRequested(NaiveDate),
+}
+
+/// A day as a vertical timeline of its `HH:MM-HH:MM` time blocks, with
+/// overlapping blocks highlighted and free slots listed beside it
+pub struct PlanView<'a> {
+    date: NaiveDate,
+    today: NaiveDate,
+    journal: &'a mut Journal,
+    terminal: Terminal<CrosstermBackend<Stdout>>,
+    theme: Theme,
+    date_formats: DateFormats,
+    plan: DayPlan,
+    /// Number of timeline rows scrolled past
+    scroll: u16,
+    /// Last storage failure, shown instead of the summary until a reload works
+    error: Option<String>,
+    result: Option<PlanViewResult>,
+}
+
+impl<'a> PlanView<'a> {
+    pub fn new(date: NaiveDate, today: NaiveDate, journal: &'a mut Journal) -> io::Result<Self> {
+        if !IsTty::is_tty(&std::io::stdout()) {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "Not running in a TTY, cannot initialize terminal interface",
+            ));
+        }
+
+        enable_raw_mode()?;
+        stdout().execute(EnterAlternateScreen).inspect_err(|_| {
+            let _ = disable_raw_mode();
+        })?;
+        let terminal = Terminal::new(CrosstermBackend::new(stdout())).inspect_err(|_| {
+            let _ = disable_raw_mode();
+            let _ = stdout().execute(LeaveAlternateScreen);
+        })?;
+
+        let mut view = Self {
+            date,
+            today,
+            journal,
+            terminal,
+            theme: Theme::default(),
+            date_formats: DateFormats::default(),
+            plan: DayPlan::new(None),
+            scroll: 0,
+            error: None,
+            result: None,
+        };
+        view.load();
+        Ok(view)
+    }
+
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Use custom date formats for the title
+    pub fn with_date_formats(mut self, date_formats: DateFormats) -> Self {
+        self.date_formats = date_formats;
+        self
+    }
+
+    /// Rebuild the plan for the current date, keeping the last good one on failure
+    fn load(&mut self) {
+        match self.journal.get_entry(self.date) {
+            Ok(entry) => {
+                self.plan = DayPlan::new(entry);
+                self.error = None;
+            }
+            Err(e) => self.error = Some(format!("{:#}", e)),
+        }
+        self.scroll = 0;
+    }
+
+    fn go_to(&mut self, date: NaiveDate) {
+        self.date = date;
+        self.load();
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) {
+        match (key.code, key.modifiers) {
+            (KeyCode::Char('q'), _) | (KeyCode::Esc, _) => {
+                self.result = Some(PlanViewResult::Exit);
+            }
+            (KeyCode::Char('c'), KeyModifiers::CONTROL) => {
+                self.result = Some(PlanViewResult::Exit);
+            }
+            (KeyCode::Enter, _) | (KeyCode::Char('e'), _) => {
+                self.result = Some(PlanViewResult::EditRequested(self.date));
+            }
+            (KeyCode::Left, _) | (KeyCode::Char('h'), _) => {
+                self.go_to(self.date - Duration::days(1));
+            }
+            (KeyCode::Right, _) | (KeyCode::Char('l'), _) => {
+                self.go_to(self.date + Duration::days(1));
+            }
+            (KeyCode::Char('t'), _) => self.go_to(self.today),
+            (KeyCode::Down, _) | (KeyCode::Char('j'), _) => {
+                self.scroll = self.scroll.saturating_add(1);
+            }
+            (KeyCode::Up, _) | (KeyCode::Char('k'), _) => {
+                self.scroll = self.scroll.saturating_sub(1);
+            }
+            _ => {}
+        }
+    }
+
+    /// One line per slot of the timeline
+    fn timeline_lines(plan: &DayPlan, theme: &Theme) -> Vec<Line<'static>> {
+        let slot = Duration::minutes(SLOT_MINUTES);
+        let mut lines = Vec::new();
+        let mut time = plan.start;
+        while time < plan.end {
+            // A slot running past midnight stops at the end of the timeline
+            let (next, wrapped) = time.overflowing_add_signed(slot);
+            let slot_end = if wrapped != 0 {
+                plan.end
+            } else {
+                next.min(plan.end)
+            };
+
+            let label = if time.minute() == 0 {
+                time.format("%H:%M").to_string()
+            } else {
+                "     ".to_string()
+            };
+            let mut spans = vec![
+                Span::styled(label, Style::default().fg(theme.colors.dimmed)),
+                Span::styled(" │ ", Style::default().fg(theme.colors.dimmed)),
+            ];
+
+            for (index, block) in plan.blocks.iter().enumerate() {
+                if block.start >= slot_end || block.end <= time {
+                    continue;
+                }
+                let color = if plan.is_overlapping(index) {
+                    theme.colors.error_text
+                } else {
+                    theme.colors.accent
+                };
+                let starts_here = block.start >= time;
+                let text = if starts_here {
+                    format!(
+                        "█ {}-{} {}  ",
+                        block.start.format("%H:%M"),
+                        block.end.format("%H:%M"),
+                        block.bullet.content
+                    )
+                } else {
+                    "█  ".to_string()
+                };
+                let mut style = Style::default().fg(color);
+                if starts_here {
+                    style = style.add_modifier(Modifier::BOLD);
+                }
+                spans.push(Span::styled(text, style));
+            }
+
+            for (_, to) in plan
+                .free
+                .iter()
+                .filter(|(from, _)| *from >= time && *from < slot_end)
+            {
+                spans.push(Span::styled(
+                    format!("· free until {}  ", to.format("%H:%M")),
+                    Style::default().fg(theme.colors.dimmed),
+                ));
+            }
+
+            for marker in &plan.markers {
+                if let Some(at) = marker.time.filter(|at| *at >= time && *at < slot_end) {
+                    spans.push(Span::styled(
+                        format!("◆ {} {}  ", at.format("%H:%M"), marker.content),
+                        Style::default().fg(theme.colors.secondary),
+                    ));
+                }
+            }
+
+            lines.push(Line::from(spans));
+            time = slot_end;
+        }
+        lines
+    }
+
+    /// Totals, clashes and free slots shown beside the timeline
+    fn summary_lines(plan: &DayPlan, theme: &Theme) -> Vec<Line<'static>> {
+        let duration = |d: Duration| format!("{}h{:02}", d.num_hours(), d.num_minutes() % 60);
+        let heading = |text: &str| {
+            Line::from(Span::styled(
+                text.to_string(),
+                Style::default()
+                    .fg(theme.colors.header)
+                    .add_modifier(Modifier::BOLD),
+            ))
+        };
+
+        let mut lines = vec![Line::from(format!(
+            "{} scheduled in {} blocks",
+            duration(plan.scheduled()),
+            plan.blocks.len()
+        ))];
+
+        lines.push(Line::from(""));
+        lines.push(heading("Overlaps"));
+        if plan.overlaps.is_empty() {
+            lines.push(Line::from(Span::styled(
+                "None",
+                Style::default().fg(theme.colors.dimmed),
+            )));
+        }
+        for &(a, b) in &plan.overlaps {
+            lines.push(Line::from(Span::styled(
+                format!(
+                    "{} / {}",
+                    plan.blocks[a].bullet.content, plan.blocks[b].bullet.content
+                ),
+                Style::default().fg(theme.colors.error_text),
+            )));
+        }
+
+        lines.push(Line::from(""));
+        lines.push(heading("Free"));
+        if plan.free.is_empty() {
+            lines.push(Line::from(Span::styled(
+                "None",
+                Style::default().fg(theme.colors.dimmed),
+            )));
+        }
+        for &(from, to) in &plan.free {
+            lines.push(Line::from(format!(
+                "{}-{} ({})",
+                from.format("%H:%M"),
+                to.format("%H:%M"),
+                duration(to - from)
+            )));
+        }
+        lines
+    }
+
+    fn draw(&mut self) -> io::Result<()> {
+        let theme = &self.theme;
+        let timeline = Self::timeline_lines(&self.plan, theme);
+        let summary = match &self.error {
+            Some(error) => vec![Line::from(Span::styled(
+                error.clone(),
+                Style::default().fg(theme.colors.error_text),
+            ))],
+            None => Self::summary_lines(&self.plan, theme),
+        };
+        let title = format!(" {} ", self.date.format(&self.date_formats.long));
+        let scroll = self.scroll.min(timeline.len().saturating_sub(1) as u16);
+        self.scroll = scroll;
+
+        self.terminal.draw(|frame| {
+            let rows = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(0), Constraint::Length(1)])
+                .split(frame.area());
+            let columns = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Min(0), Constraint::Length(SUMMARY_WIDTH)])
+                .split(rows[0]);
+
+            let timeline = Paragraph::new(timeline)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_style(Style::default().fg(theme.colors.border))
+                        .title(title)
+                        .title_style(Style::default().fg(theme.colors.header)),
+                )
+                .scroll((scroll, 0));
+            frame.render_widget(timeline, columns[0]);
+
+            let summary = Paragraph::new(summary)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_style(Style::default().fg(theme.colors.border)),
+                )
+                .wrap(Wrap { trim: true });
+            frame.render_widget(summary, columns[1]);
+
+            let help = Paragraph::new(Line::from(Span::styled(
+                "←→/hl=Day • t=Today • ↑↓/jk=Scroll • Enter/e=Edit • q=Quit",
+                Style::default().fg(theme.colors.help_text),
+            )));
+            frame.render_widget(help, rows[1]);
+        })?;
+        Ok(())
+    }
+
+    pub fn run(&mut self) -> io::Result<PlanViewResult> {
+        loop {
+            if let Some(result) = self.result.take() {
+                self.cleanup()?;
+                return Ok(result);
+            }
+
+            self.draw()?;
+            if let Event::Key(key) = event::read()? {
+                self.handle_key_event(key);
+            }
+        }
+    }
+
+    fn cleanup(&mut self) -> io::Result<()> {
+        disable_raw_mode()?;
+        self.terminal.backend_mut().execute(LeaveAlternateScreen)?;
+        Ok(())
+    }
+}
+
+impl<'a> Drop for PlanView<'a> {
+    fn drop(&mut self) {
+        let _ = self.cleanup();
+    }
+}
@@ -1,8 +1,13 @@
+use crate::application::Config;
+use crate::entities::{Bullet, BulletSymbols, BulletType, TaskState};
 use ratatui::style::Color;
+use std::collections::HashMap;
+use tracing::warn;
 
 #[derive(Debug, Clone)]
 pub struct Theme {
     pub colors: ThemeColors,
+    pub symbols: BulletSymbols,
 }
 
 #[derive(Debug, Clone)]
@@ -17,18 +22,109 @@ pub struct ThemeColors {
     pub today: Color,
     pub focused: Color,
     pub focused_week_bg: Color,
+    /// Background of the selected day when it isn't the cursor
+    pub selection_bg: Color,
     pub dimmed: Color,
 
     // UI elements
     pub border: Color,
     pub header: Color,
     pub weekend: Color,
+    /// Public holidays from `[holidays]`
+    pub holiday: Color,
     pub month_indicator: Color,
 
     // Text
     pub normal_text: Color,
     pub help_text: Color,
     pub error_text: Color,
+
+    pub bullets: BulletColors,
+}
+
+/// Names of the bullet types and task states `[colors]` in config.toml sets
+pub const BULLET_COLOR_KEYS: [&str; 10] = [
+    "task",
+    "event",
+    "note",
+    "priority",
+    "inspiration",
+    "insight",
+    "misstep",
+    "completed",
+    "migrated",
+    "scheduled",
+];
+
+/// Colors of bullets by type, and of finished tasks and priorities by state;
+/// a state without a color keeps the color of the bullet's type
+#[derive(Debug, Clone, PartialEq)]
+pub struct BulletColors {
+    pub task: Color,
+    pub event: Color,
+    pub note: Color,
+    pub priority: Color,
+    pub inspiration: Color,
+    pub insight: Color,
+    pub misstep: Color,
+    pub completed: Option<Color>,
+    pub migrated: Option<Color>,
+    pub scheduled: Option<Color>,
+}
+
+impl BulletColors {
+    pub fn for_type(&self, bullet_type: &BulletType) -> Color {
+        match bullet_type {
+            BulletType::Task => self.task,
+            BulletType::Event => self.event,
+            BulletType::Note => self.note,
+            BulletType::Priority => self.priority,
+            BulletType::Inspiration => self.inspiration,
+            BulletType::Insight => self.insight,
+            BulletType::Misstep => self.misstep,
+        }
+    }
+
+    /// Display color for a bullet, the same way `BulletSymbols::symbol`
+    /// picks its glyph
+    pub fn color(&self, bullet: &Bullet) -> Color {
+        let state = match bullet.task_state {
+            _ if !matches!(bullet.bullet_type, BulletType::Task | BulletType::Priority) => None,
+            Some(TaskState::Completed) => self.completed,
+            Some(TaskState::Migrated) => self.migrated,
+            Some(TaskState::Scheduled) => self.scheduled,
+            Some(TaskState::Pending) | None => None,
+        };
+        state.unwrap_or_else(|| self.for_type(&bullet.bullet_type))
+    }
+
+    /// Replace the colors named in `overrides`, keyed by `BULLET_COLOR_KEYS`
+    /// with values such as `light-red`, `#ffaa00` or a 0-255 palette index
+    pub fn apply(&mut self, overrides: &HashMap<String, String>) {
+        for (key, value) in overrides {
+            let Ok(color) = value.parse::<Color>() else {
+                warn!("ignoring colors.{} = \"{}\" (not a color)", key, value);
+                continue;
+            };
+            match key.as_str() {
+                "task" => self.task = color,
+                "event" => self.event = color,
+                "note" => self.note = color,
+                "priority" => self.priority = color,
+                "inspiration" => self.inspiration = color,
+                "insight" => self.insight = color,
+                "misstep" => self.misstep = color,
+                "completed" => self.completed = Some(color),
+                "migrated" => self.migrated = Some(color),
+                "scheduled" => self.scheduled = Some(color),
+                _ => warn!(
+                    "ignoring colors.{} (expected one of {})",
+                    key,
+                    BULLET_COLOR_KEYS.join(", ")
+                ),
+            }
+        }
+    }
 }
 
 impl Default for Theme {
@@ -43,17 +139,137 @@ impl Default for Theme {
                 today: Color::Yellow,
                 focused: Color::White,
                 focused_week_bg: Color::Rgb(28, 28, 28),
+                selection_bg: Color::Rgb(40, 40, 40),
                 dimmed: Color::DarkGray,
 
                 border: Color::Cyan,
                 header: Color::Cyan,
                 weekend: Color::Rgb(150, 150, 150),
+                holiday: Color::LightRed,
                 month_indicator: Color::Green,
 
                 normal_text: Color::White,
                 help_text: Color::Cyan,
                 error_text: Color::Red,
+
+                bullets: BulletColors {
+                    task: Color::White,
+                    event: Color::White,
+                    note: Color::White,
+                    priority: Color::Yellow,
+                    inspiration: Color::Cyan,
+                    insight: Color::Magenta,
+                    misstep: Color::Red,
+                    completed: Some(Color::Green),
+                    migrated: None,
+                    scheduled: None,
+                },
+            },
+            symbols: BulletSymbols::default(),
+        }
+    }
+}
+
+impl Theme {
+    /// Built-in theme by name, falling back to the dark default
+    pub fn named(name: &str) -> Self {
+        match name {
+            "light" => Self::light(),
+            "high-contrast" => Self::high_contrast(),
+            _ => Self::default(),
+        }
+    }
+
+    /// The configured theme with its `[colors]` overrides and `[symbols]`
+    pub fn from_config(config: &Config) -> Self {
+        let mut theme = Self::named(&config.theme);
+        theme.colors.bullets.apply(&config.bullet_colors);
+        theme.symbols = config.symbols.clone();
+        theme
+    }
+
+    /// Colors for terminals with a light background
+    pub fn light() -> Self {
+        Self {
+            colors: ThemeColors {
+                primary: Color::Black,
+                secondary: Color::DarkGray,
+                accent: Color::Blue,
+
+                selected: Color::Blue,
+                today: Color::Magenta,
+                focused: Color::Black,
+                focused_week_bg: Color::Rgb(230, 230, 230),
+                selection_bg: Color::Rgb(40, 40, 40),
+                dimmed: Color::Gray,
+
+                border: Color::Blue,
+                header: Color::Blue,
+                weekend: Color::Rgb(110, 110, 110),
+                holiday: Color::Red,
+                month_indicator: Color::Rgb(0, 128, 0),
+
+                normal_text: Color::Black,
+                help_text: Color::Blue,
+                error_text: Color::Red,
+
+                bullets: BulletColors {
+                    task: Color::Black,
+                    event: Color::Black,
+                    note: Color::Black,
+                    priority: Color::Rgb(176, 112, 0),
+                    inspiration: Color::Rgb(0, 128, 128),
+                    insight: Color::Magenta,
+                    misstep: Color::Red,
+                    completed: Some(Color::Rgb(0, 128, 0)),
+                    migrated: None,
+                    scheduled: None,
+                },
+            },
+            symbols: BulletSymbols::default(),
+        }
+    }
+
+    /// Bright text on the terminal's own dark background, no dark grays or
+    /// tinted backgrounds for low vision
+    pub fn high_contrast() -> Self {
+        Self {
+            colors: ThemeColors {
+                primary: Color::White,
+                secondary: Color::White,
+                accent: Color::Yellow,
+
+                selected: Color::Blue,
+                today: Color::Yellow,
+                focused: Color::White,
+                focused_week_bg: Color::Reset,
+                selection_bg: Color::Blue,
+                dimmed: Color::Gray,
+
+                border: Color::White,
+                header: Color::Yellow,
+                weekend: Color::White,
+                holiday: Color::LightMagenta,
+                month_indicator: Color::LightGreen,
+
+                normal_text: Color::White,
+                help_text: Color::White,
+                error_text: Color::LightRed,
+
+                bullets: BulletColors {
+                    task: Color::White,
+                    event: Color::White,
+                    note: Color::White,
+                    priority: Color::Yellow,
+                    inspiration: Color::LightCyan,
+                    insight: Color::LightMagenta,
+                    misstep: Color::LightRed,
+                    completed: Some(Color::LightGreen),
+                    migrated: None,
+                    scheduled: None,
+                },
             },
+            symbols: BulletSymbols::default(),
         }
     }
 }
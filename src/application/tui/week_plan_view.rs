@@ -0,0 +1,356 @@
+use super::theme::Theme;
+use crate::application::plan::{PlanLine, WeekPlan};
+use crossterm::ExecutableCommand;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::terminal::{
+    EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
+};
+use crossterm::tty::IsTty;
+use ratatui::{
+    Terminal,
+    backend::CrosstermBackend,
+    layout::{Constraint, Direction, Layout},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+};
+use std::io::{self, Stdout, stdout};
+
+/// What the user chose when leaving the weekly planner
+#[derive(Debug, Clone, PartialEq)]
+pub enum WeekPlanViewResult {
+    Save(WeekPlan),
+    Exit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Pane {
+    Review,
+    Plan,
+}
+
+/// Last week's unfinished tasks and missteps on the left, next week's plan
+/// as an editable list of lines on the right
+pub struct WeekPlanView {
+    plan: WeekPlan,
+    terminal: Terminal<CrosstermBackend<Stdout>>,
+    theme: Theme,
+    pane: Pane,
+    review_state: ListState,
+    plan_state: ListState,
+    /// Text of the selected plan line while it's being edited
+    editing: Option<String>,
+    result: Option<WeekPlanViewResult>,
+}
+
+impl WeekPlanView {
+    pub fn new(plan: WeekPlan) -> io::Result<Self> {
+        if !IsTty::is_tty(&std::io::stdout()) {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "Not running in a TTY, cannot initialize terminal interface",
+            ));
+        }
+
+        enable_raw_mode()?;
+        stdout().execute(EnterAlternateScreen).inspect_err(|_| {
+            let _ = disable_raw_mode();
+        })?;
+        let terminal = Terminal::new(CrosstermBackend::new(stdout())).inspect_err(|_| {
+            let _ = disable_raw_mode();
+            let _ = stdout().execute(LeaveAlternateScreen);
+        })?;
+
+        let mut view = Self {
+            plan,
+            terminal,
+            theme: Theme::default(),
+            pane: Pane::Review,
+            review_state: ListState::default(),
+            plan_state: ListState::default(),
+            editing: None,
+            result: None,
+        };
+        view.clamp_selection();
+        Ok(view)
+    }
+
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Keep each pane's selection on an existing item
+    fn clamp_selection(&mut self) {
+        let clamp = |state: &mut ListState, len: usize| {
+            state.select(match len {
+                0 => None,
+                len => Some(state.selected().unwrap_or(0).min(len - 1)),
+            });
+        };
+        clamp(&mut self.review_state, self.plan.leftovers.len());
+        clamp(&mut self.plan_state, self.plan.lines.len());
+    }
+
+    fn move_selection(&mut self, down: bool) {
+        let (state, len) = match self.pane {
+            Pane::Review => (&mut self.review_state, self.plan.leftovers.len()),
+            Pane::Plan => (&mut self.plan_state, self.plan.lines.len()),
+        };
+        if let Some(selected) = state.selected() {
+            state.select(Some(if down {
+                (selected + 1).min(len.saturating_sub(1))
+            } else {
+                selected.saturating_sub(1)
+            }));
+        }
+    }
+
+    /// Start editing a new blank line below the selected one
+    fn open_line(&mut self) {
+        let at = self.plan_state.selected().map_or(0, |i| i + 1);
+        self.plan.lines.insert(
+            at,
+            PlanLine {
+                text: String::new(),
+                origin: None,
+            },
+        );
+        self.pane = Pane::Plan;
+        self.plan_state.select(Some(at));
+        self.editing = Some(String::new());
+    }
+
+    fn handle_edit_key(&mut self, key: KeyEvent) {
+        let Some(text) = self.editing.as_mut() else {
+            return;
+        };
+        match key.code {
+            KeyCode::Enter => {
+                if let Some(i) = self.plan_state.selected() {
+                    self.plan.lines[i].text = text.trim().to_string();
+                }
+                self.editing = None;
+            }
+            KeyCode::Esc => {
+                self.editing = None;
+                // A line opened and abandoned without typing goes away again
+                if let Some(i) = self.plan_state.selected()
+                    && self.plan.lines[i].text.is_empty()
+                {
+                    self.plan.remove_line(i);
+                    self.clamp_selection();
+                }
+            }
+            KeyCode::Backspace => {
+                text.pop();
+            }
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                text.push(c);
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) {
+        if self.editing.is_some() {
+            self.handle_edit_key(key);
+            return;
+        }
+
+        match (key.code, key.modifiers) {
+            (KeyCode::Char('q'), _) | (KeyCode::Esc, _) => {
+                self.result = Some(WeekPlanViewResult::Exit);
+            }
+            (KeyCode::Char('c'), KeyModifiers::CONTROL) => {
+                self.result = Some(WeekPlanViewResult::Exit);
+            }
+            (KeyCode::Char('s'), _) => {
+                self.result = Some(WeekPlanViewResult::Save(self.plan.clone()));
+            }
+            (KeyCode::Tab, _) | (KeyCode::BackTab, _) => {
+                self.pane = match self.pane {
+                    Pane::Review => Pane::Plan,
+                    Pane::Plan => Pane::Review,
+                };
+            }
+            (KeyCode::Down, _) | (KeyCode::Char('j'), _) => self.move_selection(true),
+            (KeyCode::Up, _) | (KeyCode::Char('k'), _) => self.move_selection(false),
+            (KeyCode::Char('o'), _) => self.open_line(),
+
+            // Carry the selected leftover over to the plan
+            (KeyCode::Right, _) | (KeyCode::Char('l'), _) | (KeyCode::Char('m'), _)
+                if self.pane == Pane::Review =>
+            {
+                if let Some(i) = self.review_state.selected()
+                    && let Some(line) = self.plan.carry(i)
+                {
+                    self.plan_state.select(Some(line));
+                    self.clamp_selection();
+                }
+            }
+            // Send a carried line back to the leftovers
+            (KeyCode::Left, _) | (KeyCode::Char('h'), _) | (KeyCode::Char('m'), _)
+                if self.pane == Pane::Plan =>
+            {
+                if let Some(i) = self.plan_state.selected()
+                    && self.plan.lines[i].origin.is_some()
+                {
+                    self.plan.remove_line(i);
+                    self.clamp_selection();
+                }
+            }
+            (KeyCode::Enter, _) | (KeyCode::Char('i'), _) if self.pane == Pane::Plan => {
+                if let Some(i) = self.plan_state.selected() {
+                    self.editing = Some(self.plan.lines[i].text.clone());
+                }
+            }
+            (KeyCode::Char('d'), _) if self.pane == Pane::Plan => {
+                if let Some(i) = self.plan_state.selected() {
+                    self.plan.remove_line(i);
+                    self.clamp_selection();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn draw(&mut self) -> io::Result<()> {
+        let theme = &self.theme;
+        let plan = &self.plan;
+        let pane = self.pane;
+        let editing = self.editing.as_deref();
+        let plan_selected = self.plan_state.selected();
+
+        let leftovers: Vec<ListItem> = plan
+            .leftovers
+            .iter()
+            .map(|item| {
+                ListItem::new(Line::from(vec![
+                    Span::styled(
+                        item.date.format("%a %d ").to_string(),
+                        Style::default().fg(theme.colors.dimmed),
+                    ),
+                    Span::styled(
+                        format!("{} ", theme.symbols.symbol(&item.bullet)),
+                        Style::default().fg(theme.colors.bullets.color(&item.bullet)),
+                    ),
+                    Span::raw(item.bullet.content.clone()),
+                ]))
+            })
+            .collect();
+        let lines: Vec<ListItem> = plan
+            .lines
+            .iter()
+            .enumerate()
+            .map(|(i, line)| {
+                let text = match editing {
+                    Some(input) if plan_selected == Some(i) => format!("{}█", input),
+                    _ => line.text.clone(),
+                };
+                let mut spans = vec![Span::raw(text)];
+                if let Some(origin) = &line.origin {
+                    spans.push(Span::styled(
+                        format!("  ← {}", origin.date.format("%a %d")),
+                        Style::default().fg(theme.colors.dimmed),
+                    ));
+                }
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+
+        let titled = |title: String, focused: bool| {
+            let border = if focused {
+                theme.colors.selected
+            } else {
+                theme.colors.border
+            };
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(border))
+                .title(title)
+                .title_style(Style::default().fg(theme.colors.header))
+        };
+        let highlight = Style::default()
+            .fg(theme.colors.selected)
+            .add_modifier(Modifier::BOLD | Modifier::REVERSED);
+        let review_title = format!(
+            " Left over {} – {} ",
+            plan.review.start().format("%b %d"),
+            plan.review.end().format("%b %d")
+        );
+        let plan_title = format!(
+            " Plan for the week of {} ",
+            plan.week.start().format("%b %d")
+        );
+        let help = if editing.is_some() {
+            "Enter=Done • Esc=Cancel • start with a weekday (wed …) to plan a task for that day"
+        } else {
+            "Tab=Switch pane • ↑↓/jk=Move • →/m=Carry over • ←=Send back • o=New line • Enter/i=Edit • d=Delete • s=Save • q=Quit"
+        };
+        let review_state = &mut self.review_state;
+        let plan_state = &mut self.plan_state;
+
+        self.terminal.draw(|frame| {
+            let rows = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(0), Constraint::Length(1)])
+                .split(frame.area());
+            let columns = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .split(rows[0]);
+
+            let leftovers = List::new(leftovers)
+                .block(titled(review_title, pane == Pane::Review))
+                .highlight_style(if pane == Pane::Review {
+                    highlight
+                } else {
+                    Style::default()
+                });
+            frame.render_stateful_widget(leftovers, columns[0], review_state);
+
+            let lines = List::new(lines)
+                .block(titled(plan_title, pane == Pane::Plan))
+                .highlight_style(if pane == Pane::Plan {
+                    highlight
+                } else {
+                    Style::default()
+                });
+            frame.render_stateful_widget(lines, columns[1], plan_state);
+
+            let help = Paragraph::new(Line::from(Span::styled(
+                help,
+                Style::default().fg(theme.colors.help_text),
+            )));
+            frame.render_widget(help, rows[1]);
+        })?;
+        Ok(())
+    }
+
+    pub fn run(&mut self) -> io::Result<WeekPlanViewResult> {
+        loop {
+            if let Some(result) = self.result.take() {
+                self.cleanup()?;
+                return Ok(result);
+            }
+
+            self.draw()?;
+            if let Event::Key(key) = event::read()? {
+                self.handle_key_event(key);
+            }
+        }
+    }
+
+    fn cleanup(&mut self) -> io::Result<()> {
+        disable_raw_mode()?;
+        self.terminal.backend_mut().execute(LeaveAlternateScreen)?;
+        Ok(())
+    }
+}
+
+impl Drop for WeekPlanView {
+    fn drop(&mut self) {
+        let _ = self.cleanup();
+    }
+}
@@ -1,8 +1,19 @@
 use super::theme::Theme;
-use crate::entities::{BulletType, Journal, TaskState};
-use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use super::year_view::YearView;
+use crate::application::trends::{self, RelatedDay, TrendPeriod};
+use crate::application::{DateFormats, MonthJump, journal_date};
+use crate::entities::{
+    Bullet, BulletSymbols, BulletType, CrossReference, DateRange, Entry, EntryTimestamps,
+    ExternalEvent, Journal, PeriodStats, TaskState,
+};
+use crate::infrastructure::storage::JournalStorage;
+use crate::infrastructure::{Holidays, Query, open_url};
+use chrono::{Datelike, Duration, Local, NaiveDate, Weekday};
 use crossterm::ExecutableCommand;
-use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers, poll};
+use crossterm::event::{
+    self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyModifiers,
+    MouseButton, MouseEvent, MouseEventKind, poll,
+};
 use crossterm::terminal::{
     EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
 };
@@ -10,12 +21,38 @@ use crossterm::tty::IsTty;
 use ratatui::{
     Terminal,
     backend::CrosstermBackend,
-    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    layout::{Alignment, Constraint, Direction, Layout, Position, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Cell, Paragraph, Row, Table},
+    widgets::{
+        Block, Borders, Cell, Paragraph, Row, Scrollbar, ScrollbarOrientation, ScrollbarState,
+        Table,
+    },
 };
+use std::collections::{HashMap, HashSet};
 use std::io::{self, Stdout, stdout};
+use std::sync::Arc;
+use std::time::Instant;
+
+// Calculate the total space needed for our UI
+const CALENDAR_HEIGHT: u16 = 18; // 5 weeks * 3 rows each + header + title
+const HELP_HEIGHT: u16 = 3; // Help text (no borders)
+const BULLET_HEIGHT: u16 = 8; // Space for bullet display
+const STATUS_HEIGHT: u16 = 1; // Error status bar, only shown after a failure
+const STATS_WIDTH: u16 = 22; // Stats panel beside the preview, when toggled on
+const FILTER_HEIGHT: u16 = 1; // Filter prompt, while a filter is typed or applied
+const RELATED_HEIGHT: u16 = 1; // Related days under the preview, when there are any
+const RELATED_DAYS: usize = 3; // Related days listed, jumped to with Alt+1..3
+const RELATED_LOOKBACK_DAYS: i64 = 90; // Days either side compared with the selected one
+const RELATED_WINDOW_MARGIN_DAYS: i64 = 60; // Extra days loaded so nearby moves reuse them
+const MOOD_WEEKS: i64 = 8; // Weeks in the stats panel's mood sparkline
+const MOOD_CHECKIN_DAYS: i64 = 30; // Days of mood check-ins in the stats panel
+const WEEK_GUTTER_WIDTH: u16 = 2; // ISO week numbers, when enabled
+const MINIMAP_WIDTH: u16 = 23; // Month mini-map left of the calendar, when toggled on
+const GOAL_BAR_WIDTH: usize = 12; // Word goal progress bar atop the preview
+const DOUBLE_CLICK: std::time::Duration = std::time::Duration::from_millis(400);
+// How often storage is asked whether another process changed an entry
+const CHANGE_POLL: std::time::Duration = std::time::Duration::from_secs(1);
 
 #[derive(Debug, Clone)]
 pub enum WeekViewResult {
@@ -23,11 +60,67 @@ pub enum WeekViewResult {
     Exited(NaiveDate),
     /// User selected a date to edit (pressed Enter)
     EditRequested(NaiveDate),
+    /// User pasted the bullets of a yanked day onto another day
+    PasteRequested { from: NaiveDate, to: NaiveDate },
+    /// User converted a bullet of a day to another type
+    ConvertRequested {
+        date: NaiveDate,
+        id: i64,
+        to: BulletType,
+    },
+}
+
+/// What the calendar marks on a day
+#[derive(Debug, Clone, Copy, Default)]
+struct DayStatus {
+    /// Has an entry (matching the filter, while one is applied)
+    has_entry: bool,
+    /// Pinned as a favorite with `*` or `journo pin`
+    pinned: bool,
+    /// Whether the day's entry reaches the word goal, if there is one of each
+    goal_met: Option<bool>,
+    /// A public holiday from `[holidays]`
+    holiday: bool,
+}
+
+/// Days the calendar marks besides those with entries
+#[derive(Clone, Copy)]
+struct DayMarks {
+    selected: NaiveDate,
+    /// First and last day of a dragged selection
+    selection: Option<(NaiveDate, NaiveDate)>,
+    today: NaiveDate,
+}
+
+/// What storage knows about a day's bullets beyond their text
+#[derive(Clone, Copy)]
+struct BulletNotes<'a> {
+    /// Recorded follow-ups of the day's bullets
+    follow_ups: &'a [CrossReference],
+    /// Contents of the day's tasks that wait for pending tasks
+    blocked: Option<&'a HashSet<String>>,
+}
+
+/// How the preview arranges an entry's bullets
+struct PreviewLayout<'a> {
+    /// Show the day's `## HH:MM` sessions as one list
+    merge_sessions: bool,
+    section_order: &'a [BulletType],
+    /// Sections showing only their header
+    collapsed: &'a HashSet<BulletType>,
 }
 
 pub struct WeekView<'a> {
     /// Current week being focused (middle row)
     current_week_start: NaiveDate,
+    /// Day weeks start on
+    first_day: Weekday,
+    /// Whether to show the ISO week number gutter and quarter boundaries
+    week_numbers: bool,
+    /// Where PageUp/PageDown and `{`/`}` land
+    month_jump: MonthJump,
+    /// Hour before which the previous day is still "today"
+    day_rollover_hour: u32,
     /// Currently selected date
     selected_date: NaiveDate,
     /// Terminal instance
@@ -40,8 +133,114 @@ pub struct WeekView<'a> {
     show_help: bool,
     /// Theme for styling
     theme: Theme,
+    /// Formats for the month title and selected date line
+    date_formats: DateFormats,
     /// Journal reference for checking entries
     journal: &'a mut Journal,
+    /// Cached entry statuses for the visible weeks
+    entry_statuses: HashMap<NaiveDate, DayStatus>,
+    /// Cached entry for the selected date
+    selected_entry: Option<Entry>,
+    /// When the selected entry was created and last edited, if recorded
+    selected_timestamps: Option<EntryTimestamps>,
+    /// Week whose surroundings are currently cached
+    loaded_week_start: Option<NaiveDate>,
+    /// Whether the cached data must be reloaded before the next draw
+    data_stale: bool,
+    /// Whether the screen must be redrawn
+    needs_redraw: bool,
+    /// Storage's change watermark when it was last checked
+    watermark: Option<u64>,
+    /// When the watermark was last checked
+    watermark_checked: Instant,
+    /// Whether a loading label should be shown instead of the preview
+    loading: bool,
+    /// Cached entries for the days before and after the selected date
+    adjacent_entries: [Option<Entry>; 2],
+    /// Number of lines the bullet preview is scrolled down
+    preview_scroll: u16,
+    /// Whether the preview shows yesterday/selected/tomorrow side by side
+    show_split: bool,
+    /// Whether a day's `## HH:MM` sessions are previewed as one list
+    merge_sessions: bool,
+    /// Order of the bullet sections in the preview
+    section_order: Vec<BulletType>,
+    /// Preview sections folded to their header with the number keys
+    collapsed_sections: HashSet<BulletType>,
+    /// Storage for imported calendar events and the year heatmap, if any
+    storage: Option<Arc<dyn JournalStorage>>,
+    /// Cached calendar events for the selected date and the days around it
+    external_events: HashMap<NaiveDate, Vec<ExternalEvent>>,
+    /// Recorded follow-ups of bullets on the selected date and the days around it
+    follow_ups: HashMap<NaiveDate, Vec<CrossReference>>,
+    /// Tasks waiting for pending tasks on the selected date and the days around it
+    blocked: HashMap<NaiveDate, HashSet<String>>,
+    /// Days related to the selected one by follow-ups or shared terms
+    related_days: Vec<RelatedDay>,
+    /// Entries loaded for finding related days, reused while the days
+    /// compared with the selected one stay within their range
+    related_window: Option<(DateRange, Vec<Entry>)>,
+    /// Last storage failure, shown in the status bar until a retry succeeds
+    error: Option<String>,
+    /// Index of the link in the selected entry that `o` opens next
+    link_cursor: usize,
+    /// Day whose referenced dates `g` steps through, and the index of the
+    /// one it last jumped to
+    reference_trail: Option<(NaiveDate, usize)>,
+    /// Whether the year heatmap should be opened
+    show_year: bool,
+    /// Whether the stats panel is shown next to the preview
+    show_stats: bool,
+    /// Whether the month of the selected day is shown left of the calendar
+    show_minimap: bool,
+    /// Words a day should reach; days are colored by whether they do
+    word_goal: Option<usize>,
+    /// Public holidays, colored in the calendar and named in the preview
+    holidays: Holidays,
+    /// Day whose bullets were yanked with `y`, waiting to be pasted
+    yanked: Option<NaiveDate>,
+    /// Yanked day and target day, once `p` was pressed
+    paste_request: Option<(NaiveDate, NaiveDate)>,
+    /// Text being typed at the `c` prompt, `<bullet> <type>`
+    convert_input: Option<String>,
+    /// Why the typed conversion can't be done
+    convert_error: Option<String>,
+    /// Day, bullet id and new type, once a conversion was confirmed
+    convert_request: Option<(NaiveDate, i64, BulletType)>,
+    /// Totals for the focused week, loaded from storage
+    week_stats: Option<PeriodStats>,
+    /// Current writing streak in days, shown in the stats panel
+    streak: Option<u32>,
+    /// Sparkline of the average sentiment of the weeks up to the focused one
+    mood_trend: Option<String>,
+    /// Focused week `mood_trend` was computed for
+    mood_trend_week: Option<NaiveDate>,
+    /// Braille sparkline of the last month's mood check-ins, if there are any
+    mood_checkins: Option<String>,
+    /// Where the calendar was last drawn, for mapping mouse clicks to days
+    calendar_area: Rect,
+    /// Where the month mini-map was last drawn; empty while it's hidden
+    minimap_area: Rect,
+    /// Where the bullet preview was last drawn, so the mouse wheel scrolls it
+    /// instead of the calendar while the pointer is over it
+    preview_area: Rect,
+    /// Day and time of the last click, to recognize double-clicks
+    last_click: Option<(NaiveDate, Instant)>,
+    /// Day a mouse drag started on
+    drag_anchor: Option<NaiveDate>,
+    /// First and last day selected by dragging; the stats panel covers these
+    /// days instead of the focused week
+    selection: Option<(NaiveDate, NaiveDate)>,
+    /// Text being typed at the `f` prompt
+    filter_input: Option<String>,
+    /// Query the preview and calendar dots are narrowed to, as typed
+    filter: Option<String>,
+    /// `filter` parsed; the last valid query while the typed one doesn't parse
+    filter_query: Option<Query>,
+    /// Why the typed filter doesn't parse
+    filter_error: Option<String>,
+    /// Saved queries usable as `view:name` in the filter
+    views: HashMap<String, String>,
 }
 
 impl<'a> WeekView<'a> {
@@ -54,85 +253,240 @@ impl<'a> WeekView<'a> {
             ));
         }
 
-        enable_raw_mode().map_err(|e| {
-            io::Error::new(
-                io::ErrorKind::Other,
-                format!("Failed to enable raw mode: {}", e),
-            )
-        })?;
+        enable_raw_mode()
+            .map_err(|e| io::Error::other(format!("Failed to enable raw mode: {}", e)))?;
 
         stdout().execute(EnterAlternateScreen).map_err(|e| {
             let _ = disable_raw_mode(); // Clean up on failure
-            io::Error::new(
-                io::ErrorKind::Other,
-                format!("Failed to enter alternate screen: {}", e),
-            )
+            io::Error::other(format!("Failed to enter alternate screen: {}", e))
         })?;
+        // Mouse support is a nicety, so a terminal without it is fine
+        let _ = stdout().execute(EnableMouseCapture);
 
         let backend = CrosstermBackend::new(stdout());
         let terminal = Terminal::new(backend).map_err(|e| {
             let _ = disable_raw_mode();
             let _ = stdout().execute(LeaveAlternateScreen);
-            io::Error::new(
-                io::ErrorKind::Other,
-                format!("Failed to create terminal: {}", e),
-            )
+            io::Error::other(format!("Failed to create terminal: {}", e))
         })?;
 
-        let week_start = Self::get_week_start(initial_date);
+        let week_start = DateRange::week_containing(initial_date, Weekday::Sun).start;
 
         Ok(Self {
             current_week_start: week_start,
+            first_day: Weekday::Sun,
+            week_numbers: false,
+            month_jump: MonthJump::default(),
+            day_rollover_hour: 0,
             selected_date: initial_date,
             terminal,
             should_exit: false,
             should_edit: false,
             show_help: false,
             theme: Theme::default(),
+            date_formats: DateFormats::default(),
             journal,
+            entry_statuses: HashMap::new(),
+            selected_entry: None,
+            selected_timestamps: None,
+            loaded_week_start: None,
+            data_stale: true,
+            needs_redraw: true,
+            watermark: None,
+            watermark_checked: Instant::now(),
+            loading: false,
+            adjacent_entries: [None, None],
+            preview_scroll: 0,
+            show_split: false,
+            merge_sessions: false,
+            storage: None,
+            external_events: HashMap::new(),
+            follow_ups: HashMap::new(),
+            blocked: HashMap::new(),
+            related_days: Vec::new(),
+            related_window: None,
+            section_order: BulletType::ALL.to_vec(),
+            collapsed_sections: HashSet::new(),
+            error: None,
+            link_cursor: 0,
+            reference_trail: None,
+            show_year: false,
+            show_stats: false,
+            show_minimap: false,
+            word_goal: None,
+            holidays: Holidays::default(),
+            yanked: None,
+            paste_request: None,
+            convert_input: None,
+            convert_error: None,
+            convert_request: None,
+            week_stats: None,
+            streak: None,
+            mood_trend: None,
+            mood_trend_week: None,
+            mood_checkins: None,
+            calendar_area: Rect::default(),
+            minimap_area: Rect::default(),
+            preview_area: Rect::default(),
+            last_click: None,
+            drag_anchor: None,
+            selection: None,
+            filter_input: None,
+            filter: None,
+            filter_query: None,
+            filter_error: None,
+            views: HashMap::new(),
         })
     }
 
-    /// Get the start of the week (Sunday) for a given date
-    fn get_week_start(date: NaiveDate) -> NaiveDate {
-        let days_since_sunday = match date.weekday() {
-            Weekday::Sun => 0,
-            Weekday::Mon => 1,
-            Weekday::Tue => 2,
-            Weekday::Wed => 3,
-            Weekday::Thu => 4,
-            Weekday::Fri => 5,
-            Weekday::Sat => 6,
-        };
-        date - Duration::days(days_since_sunday as i64)
+    /// Use custom date formats for the title and status line
+    pub fn with_date_formats(mut self, date_formats: DateFormats) -> Self {
+        self.date_formats = date_formats;
+        self
+    }
+
+    /// Show this writing streak in the stats panel
+    pub fn with_streak(mut self, streak: u32) -> Self {
+        self.streak = Some(streak);
+        self
+    }
+
+    /// Use custom glyphs for bullet types and task states
+    pub fn with_symbols(mut self, symbols: BulletSymbols) -> Self {
+        self.theme.symbols = symbols;
+        self
+    }
+
+    /// Read imported calendar events and per-day bullet counts from this storage
+    pub fn with_storage(mut self, storage: Arc<dyn JournalStorage>) -> Self {
+        self.storage = Some(storage);
+        self
+    }
+
+    /// Saved queries the filter can pull in with `view:name`
+    pub fn with_views(mut self, views: HashMap<String, String>) -> Self {
+        self.views = views;
+        self
+    }
+
+    /// Use custom colors (glyphs still come from `with_symbols`)
+    /// Preview sections in this order instead of the canonical one
+    pub fn with_section_order(mut self, order: Vec<BulletType>) -> Self {
+        self.section_order = order;
+        self
+    }
+
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme.colors = theme.colors;
+        self
+    }
+
+    /// Start weeks on this day instead of Sunday
+    pub fn with_week_start(mut self, first_day: Weekday) -> Self {
+        self.first_day = first_day;
+        self.current_week_start = self.get_week_start(self.selected_date);
+        self
     }
 
-    /// Generate dates for a week starting from the given Sunday
+    /// Show ISO week numbers in a gutter column and underline the days
+    /// right before a quarter boundary
+    pub fn with_week_numbers(mut self, week_numbers: bool) -> Self {
+        self.week_numbers = week_numbers;
+        self
+    }
+
+    /// Keep treating the previous day as today until `hour`
+    pub fn with_day_rollover_hour(mut self, hour: u32) -> Self {
+        self.day_rollover_hour = hour;
+        self
+    }
+
+    /// Land on the same weekday of the month instead of the same day when
+    /// moving by months or years
+    pub fn with_month_jump(mut self, month_jump: MonthJump) -> Self {
+        self.month_jump = month_jump;
+        self
+    }
+
+    /// Start with the month mini-map shown beside the calendar
+    pub fn with_month_minimap(mut self, show_minimap: bool) -> Self {
+        self.show_minimap = show_minimap;
+        self
+    }
+
+    /// Show progress towards this many words a day and color days by it
+    pub fn with_word_goal(mut self, word_goal: Option<usize>) -> Self {
+        self.word_goal = word_goal;
+        self
+    }
+
+    /// Highlight these public holidays
+    pub fn with_holidays(mut self, holidays: Holidays) -> Self {
+        self.holidays = holidays;
+        self
+    }
+
+    /// Get the start of the week containing the given date
+    fn get_week_start(&self, date: NaiveDate) -> NaiveDate {
+        DateRange::week_containing(date, self.first_day).start
+    }
+
+    /// Generate dates for a week starting from the given day
     fn get_week_dates(week_start: NaiveDate) -> Vec<NaiveDate> {
         (0..7).map(|i| week_start + Duration::days(i)).collect()
     }
 
     /// Check if a date has an entry in the journal
-    fn has_entry(&mut self, date: NaiveDate) -> bool {
-        self.journal.get_entry(date).unwrap_or(None).is_some()
+    /// Whether a day gets an entry dot: it has an entry with, while a filter
+    /// is applied, a bullet matching it
+    fn has_entry(&mut self, date: NaiveDate) -> anyhow::Result<bool> {
+        let filter = self.filter_query.as_ref();
+        Ok(self.journal.get_entry(date)?.is_some_and(|entry| {
+            filter.is_none_or(|filter| {
+                entry
+                    .all_bullets()
+                    .any(|bullet| Self::bullet_matches(date, bullet, filter))
+            })
+        }))
+    }
+
+    /// Whether a bullet or one of its sub-bullets matches the filter query
+    fn bullet_matches(date: NaiveDate, bullet: &Bullet, filter: &Query) -> bool {
+        filter.matches(date, bullet)
+            || bullet
+                .children
+                .iter()
+                .any(|child| Self::bullet_matches(date, child, filter))
     }
 
     /// Get entry status for all dates in the given range
     fn get_entry_statuses(
         &mut self,
         weeks: &[NaiveDate],
-    ) -> std::collections::HashMap<NaiveDate, bool> {
-        let mut statuses = std::collections::HashMap::new();
+    ) -> anyhow::Result<HashMap<NaiveDate, DayStatus>> {
+        let mut statuses = HashMap::new();
+        let pins: HashSet<NaiveDate> = match &self.storage {
+            Some(storage) => storage.pinned_dates()?.into_iter().collect(),
+            None => HashSet::new(),
+        };
 
         for &week_start in weeks {
             let dates = Self::get_week_dates(week_start);
             for date in dates {
-                let has_entry = self.journal.get_entry(date).unwrap_or(None).is_some();
-                statuses.insert(date, has_entry);
+                let status = DayStatus {
+                    has_entry: self.has_entry(date)?,
+                    pinned: pins.contains(&date),
+                    goal_met: match (self.word_goal, self.journal.get_entry(date)?) {
+                        (Some(goal), Some(entry)) => Some(entry.word_count() >= goal),
+                        _ => None,
+                    },
+                    holiday: self.holidays.contains(date),
+                };
+                statuses.insert(date, status);
             }
         }
 
-        statuses
+        Ok(statuses)
     }
 
     /// Calculate centered area with both horizontal and vertical centering
@@ -164,16 +518,25 @@ impl<'a> WeekView<'a> {
     fn get_date_style_static(
         date: NaiveDate,
         is_focused_week: bool,
-        selected_date: NaiveDate,
+        marks: DayMarks,
         theme: &Theme,
     ) -> Style {
-        let is_selected = date == selected_date;
-        let is_today = date == chrono::Local::now().date_naive();
+        if marks
+            .selection
+            .is_some_and(|(first, last)| (first..=last).contains(&date))
+        {
+            return Style::default()
+                .fg(theme.colors.focused)
+                .bg(theme.colors.selected);
+        }
+
+        let is_selected = date == marks.selected;
+        let is_today = date == marks.today;
         let is_weekend = matches!(date.weekday(), Weekday::Sat | Weekday::Sun);
 
         if is_selected {
             // Subtle selection - slightly lighter background
-            let light_bg = Color::Rgb(40, 40, 40);
+            let light_bg = theme.colors.selection_bg;
             if is_weekend {
                 Style::default().fg(theme.colors.weekend).bg(light_bg)
             } else if is_focused_week {
@@ -198,38 +561,65 @@ impl<'a> WeekView<'a> {
     fn create_week_row_static(
         week_start: NaiveDate,
         is_focused: bool,
-        selected_date: NaiveDate,
+        marks: DayMarks,
         theme: &Theme,
-        entry_statuses: &std::collections::HashMap<NaiveDate, bool>,
+        entry_statuses: &HashMap<NaiveDate, DayStatus>,
+        week_numbers: bool,
     ) -> Row<'static> {
         let dates = Self::get_week_dates(week_start);
-        let cells: Vec<Cell> = dates
-            .iter()
-            .map(|&date| {
-                let day = date.day();
-                let has_entry = *entry_statuses.get(&date).unwrap_or(&false);
-                let _is_today = date == chrono::Local::now().date_naive();
-
-                // Get base style (row style will handle background)
-                let style = Self::get_date_style_static(date, is_focused, selected_date, theme);
-
-                // Show month indicator on the 1st of each month
-                let day_text = if day == 1 {
-                    format!("{} {}", date.format("%b"), day)
-                } else {
-                    day.to_string()
-                };
+        let mut cells: Vec<Cell> = Vec::new();
+        if week_numbers {
+            // A week belongs to the ISO week of its Monday, whichever day it starts on
+            let monday = dates
+                .iter()
+                .find(|date| date.weekday() == Weekday::Mon)
+                .copied()
+                .unwrap_or(week_start);
+            cells.push(
+                Cell::from(format!("{:>2}", monday.iso_week().week()))
+                    .style(Style::default().fg(theme.colors.dimmed)),
+            );
+        }
+        cells.extend(dates.iter().map(|&date| {
+            let day = date.day();
+            let status = entry_statuses.get(&date).copied().unwrap_or_default();
+
+            // Get base style (row style will handle background)
+            let mut style = Self::get_date_style_static(date, is_focused, marks, theme);
+            // Holidays stand out; written days show whether they reached
+            // the word goal
+            if date != marks.selected {
+                match status.goal_met {
+                    _ if status.holiday => style = style.fg(theme.colors.holiday),
+                    Some(true) => style = style.fg(theme.colors.accent),
+                    Some(false) => style = style.fg(theme.colors.secondary),
+                    None => {}
+                }
+            }
+            // The underline runs along the last days of a quarter, above
+            // the first days of the next
+            if week_numbers && quarter(date) != quarter(date + Duration::weeks(1)) {
+                style = style.add_modifier(Modifier::UNDERLINED);
+            }
 
-                // Add entry indicator (small dot)
-                let content = if has_entry {
-                    format!("{}•", day_text)
-                } else {
-                    format!("{} ", day_text) // space to keep alignment
-                };
+            // Show month indicator on the 1st of each month
+            let day_text = if day == 1 {
+                format!("{} {}", date.format("%b"), day)
+            } else {
+                day.to_string()
+            };
+
+            // Add pin or entry indicator (star or small dot)
+            let content = if status.pinned {
+                format!("{}★", day_text)
+            } else if status.has_entry {
+                format!("{}•", day_text)
+            } else {
+                format!("{} ", day_text) // space to keep alignment
+            };
 
-                Cell::from(content).style(style)
-            })
-            .collect();
+            Cell::from(content).style(style)
+        }));
 
         let mut row = Row::new(cells);
 
@@ -244,9 +634,11 @@ impl<'a> WeekView<'a> {
     /// Create the week view table (static version for drawing)
     fn create_week_table_static(
         current_week_start: NaiveDate,
-        selected_date: NaiveDate,
+        marks: DayMarks,
         theme: &Theme,
-        entry_statuses: &std::collections::HashMap<NaiveDate, bool>,
+        date_formats: &DateFormats,
+        entry_statuses: &HashMap<NaiveDate, DayStatus>,
+        week_numbers: bool,
     ) -> Table<'static> {
         let focused_week = current_week_start;
 
@@ -255,16 +647,19 @@ impl<'a> WeekView<'a> {
             .map(|offset| focused_week + Duration::weeks(offset))
             .collect();
 
-        let header = Row::new(vec![
-            Cell::from("Sun").style(Style::default().fg(theme.colors.weekend)),
-            Cell::from("Mon").style(Style::default().fg(theme.colors.header)),
-            Cell::from("Tue").style(Style::default().fg(theme.colors.header)),
-            Cell::from("Wed").style(Style::default().fg(theme.colors.header)),
-            Cell::from("Thu").style(Style::default().fg(theme.colors.header)),
-            Cell::from("Fri").style(Style::default().fg(theme.colors.header)),
-            Cell::from("Sat").style(Style::default().fg(theme.colors.weekend)),
-        ])
-        .height(1);
+        let mut header_cells = Vec::new();
+        if week_numbers {
+            header_cells.push(Cell::from("Wk").style(Style::default().fg(theme.colors.dimmed)));
+        }
+        header_cells.extend(Self::get_week_dates(focused_week).into_iter().map(|date| {
+            let color = if matches!(date.weekday(), Weekday::Sat | Weekday::Sun) {
+                theme.colors.weekend
+            } else {
+                theme.colors.header
+            };
+            Cell::from(date.format("%a").to_string()).style(Style::default().fg(color))
+        }));
+        let header = Row::new(header_cells).height(1);
 
         let rows: Vec<Row> = weeks
             .iter()
@@ -274,16 +669,20 @@ impl<'a> WeekView<'a> {
                 Self::create_week_row_static(
                     week_start,
                     is_focused,
-                    selected_date,
+                    marks,
                     theme,
                     entry_statuses,
+                    week_numbers,
                 )
             })
             .collect();
 
-        Table::new(
-            rows,
-            [
+        let widths = if week_numbers {
+            let mut widths = vec![Constraint::Length(WEEK_GUTTER_WIDTH)];
+            widths.extend([Constraint::Ratio(1, 7); 7]);
+            widths
+        } else {
+            vec![
                 Constraint::Percentage(14), // ~14.3% each for 7 columns
                 Constraint::Percentage(14),
                 Constraint::Percentage(14),
@@ -291,22 +690,43 @@ impl<'a> WeekView<'a> {
                 Constraint::Percentage(14),
                 Constraint::Percentage(15),
                 Constraint::Percentage(15),
-            ],
-        )
-        .header(header)
-        .block(
-            Block::default()
-                .borders(Borders::NONE)
-                .title(format!("{}  ", selected_date.format("%B %Y")))
-                .title_style(Style::default().fg(theme.colors.header))
-                .title_alignment(Alignment::Center),
-        )
-        .column_spacing(1)
+            ]
+        };
+
+        Table::new(rows, widths)
+            .header(header)
+            .block(
+                Block::default()
+                    .borders(Borders::NONE)
+                    .title(format!("{}  ", marks.selected.format(&date_formats.month)))
+                    .title_style(Style::default().fg(theme.colors.header))
+                    .title_alignment(Alignment::Center),
+            )
+            .column_spacing(1)
     }
 
     /// Handle keyboard input
     fn handle_key_event(&mut self, key: KeyEvent) {
+        if self.filter_input.is_some() {
+            self.handle_filter_key(key);
+            return;
+        }
+        if self.convert_input.is_some() {
+            self.handle_convert_key(key);
+            return;
+        }
+
         match (key.code, key.modifiers) {
+            // Clear a dragged selection before leaving
+            (KeyCode::Esc, _) if self.selection.is_some() => {
+                self.clear_selection();
+            }
+
+            // Then an applied filter
+            (KeyCode::Esc, _) if self.filter.is_some() => {
+                self.set_filter(None);
+            }
+
             // Exit
             (KeyCode::Char('q'), _) | (KeyCode::Esc, _) => {
                 self.should_exit = true;
@@ -320,56 +740,575 @@ impl<'a> WeekView<'a> {
 
             // Navigation - Arrow keys
             (KeyCode::Left, _) | (KeyCode::Char('h'), _) => {
-                self.selected_date = self.selected_date - Duration::days(1);
+                self.selected_date -= Duration::days(1);
                 self.update_current_week();
             }
             (KeyCode::Right, _) | (KeyCode::Char('l'), _) => {
-                self.selected_date = self.selected_date + Duration::days(1);
+                self.selected_date += Duration::days(1);
                 self.update_current_week();
             }
             (KeyCode::Up, _) | (KeyCode::Char('k'), _) => {
-                self.selected_date = self.selected_date - Duration::weeks(1);
+                self.selected_date -= Duration::weeks(1);
                 self.update_current_week();
             }
             (KeyCode::Down, _) | (KeyCode::Char('j'), _) => {
-                self.selected_date = self.selected_date + Duration::weeks(1);
+                self.selected_date += Duration::weeks(1);
                 self.update_current_week();
             }
 
             // Jump to today
             (KeyCode::Char('t'), _) => {
-                self.selected_date = chrono::Local::now().date_naive();
+                self.selected_date = self.today();
                 self.update_current_week();
             }
 
+            // Jump to the previous or next day with an entry
+            (KeyCode::Char('['), _) => {
+                self.jump_to_entry(false);
+            }
+            (KeyCode::Char(']'), _) => {
+                self.jump_to_entry(true);
+            }
+
             // Enter to edit selected date
             (KeyCode::Enter, _) => {
                 self.should_edit = true;
                 self.should_exit = true;
             }
 
+            // Retry loading after a storage error
+            (KeyCode::Char('r'), _) => {
+                self.data_stale = true;
+            }
+
+            // Open links of the selected entry in the browser, one per press
+            (KeyCode::Char('o'), _) => {
+                self.open_next_link();
+            }
+
+            // Go to the dates the selected entry refers to, one per press
+            (KeyCode::Char('g'), _) => {
+                self.jump_to_reference();
+            }
+
+            // Filter the preview and calendar by a query
+            (KeyCode::Char('f'), KeyModifiers::NONE) => {
+                self.filter_input = Some(self.filter.clone().unwrap_or_default());
+            }
+
+            // Convert a bullet of the selected day to another type
+            (KeyCode::Char('c'), KeyModifiers::NONE) => {
+                self.convert_input = Some(String::new());
+                self.convert_error = None;
+            }
+
+            // Cycle the filter through the @@contexts in use
+            (KeyCode::Char('@'), _) => {
+                self.cycle_context();
+            }
+
+            // Year heatmap
+            (KeyCode::Char('Y'), _) => {
+                self.show_year = true;
+            }
+
+            // Yank the selected day's bullets
+            (KeyCode::Char('y'), KeyModifiers::NONE) => {
+                self.yanked = Some(self.selected_date);
+            }
+
+            // Paste the yanked bullets onto the selected day
+            (KeyCode::Char('p'), _) => {
+                if let Some(from) = self.yanked.filter(|from| *from != self.selected_date) {
+                    self.paste_request = Some((from, self.selected_date));
+                    self.should_exit = true;
+                }
+            }
+
+            // Pin or unpin the selected day
+            (KeyCode::Char('*'), _) => {
+                self.toggle_pin();
+            }
+
+            // Toggle the week stats panel
+            (KeyCode::Char('s'), _) => {
+                self.show_stats = !self.show_stats;
+                // The mood trends are only loaded while the panel is open
+                self.data_stale |= self.show_stats;
+            }
+
+            // Toggle the month mini-map
+            (KeyCode::Char('m'), KeyModifiers::NONE) => {
+                self.show_minimap = !self.show_minimap;
+                // Days of the month outside the five weeks need their dots
+                self.data_stale |= self.show_minimap;
+            }
+
             // Toggle help
             (KeyCode::Char('?'), _) => {
                 self.show_help = !self.show_help;
             }
 
-            // Jump by month
+            // Scroll the bullet preview by a line or a page; the calendar
+            // keeps its selection
+            (KeyCode::Char('e'), KeyModifiers::CONTROL) | (KeyCode::Char('J'), _) => {
+                self.preview_scroll = self.preview_scroll.saturating_add(1);
+            }
+            (KeyCode::Char('y'), KeyModifiers::CONTROL) | (KeyCode::Char('K'), _) => {
+                self.preview_scroll = self.preview_scroll.saturating_sub(1);
+            }
+            (KeyCode::Char('f'), KeyModifiers::CONTROL) => {
+                self.preview_scroll = self.preview_scroll.saturating_add(BULLET_HEIGHT - 1);
+            }
+            (KeyCode::Char('b'), KeyModifiers::CONTROL) => {
+                self.preview_scroll = self.preview_scroll.saturating_sub(BULLET_HEIGHT - 1);
+            }
+
+            // Toggle yesterday/today/tomorrow split preview
+            (KeyCode::Char('v'), _) => {
+                self.show_split = !self.show_split;
+            }
+
+            // Show a day's sessions under their own headings or merged
+            (KeyCode::Char('S'), _) => {
+                self.merge_sessions = !self.merge_sessions;
+            }
+
+            // Jump to the n-th related day listed under the preview
+            (KeyCode::Char(c @ '1'..='9'), KeyModifiers::ALT) => {
+                let index = c as usize - '1' as usize;
+                if let Some(related) = self.related_days.get(index) {
+                    self.selected_date = related.date;
+                    self.update_current_week();
+                }
+            }
+
+            // Fold or unfold the n-th preview section
+            (KeyCode::Char(c @ '1'..='9'), _) => {
+                let index = c as usize - '1' as usize;
+                if let Some(&bullet_type) = self.section_order.get(index)
+                    && !self.collapsed_sections.remove(&bullet_type)
+                {
+                    self.collapsed_sections.insert(bullet_type);
+                }
+            }
+
+            // Jump by month or year
             (KeyCode::PageUp, _) => {
-                self.selected_date = self.selected_date - Duration::days(30);
+                self.selected_date = self.month_jump.shift(self.selected_date, -1);
                 self.update_current_week();
             }
             (KeyCode::PageDown, _) => {
-                self.selected_date = self.selected_date + Duration::days(30);
+                self.selected_date = self.month_jump.shift(self.selected_date, 1);
                 self.update_current_week();
             }
+            (KeyCode::Char('{'), _) => {
+                self.selected_date = self.month_jump.shift(self.selected_date, -12);
+                self.update_current_week();
+            }
+            (KeyCode::Char('}'), _) => {
+                self.selected_date = self.month_jump.shift(self.selected_date, 12);
+                self.update_current_week();
+            }
+
+            _ => {}
+        }
+    }
 
+    /// Typing at the filter prompt narrows the view as each key is pressed;
+    /// Enter keeps the filter and Esc drops it
+    fn handle_filter_key(&mut self, key: KeyEvent) {
+        let Some(input) = self.filter_input.as_mut() else {
+            return;
+        };
+        match key.code {
+            // A query that doesn't parse stays open for fixing
+            KeyCode::Enter if self.filter_error.is_some() => return,
+            KeyCode::Enter => {
+                self.filter_input = None;
+                return;
+            }
+            KeyCode::Esc => {
+                self.filter_input = None;
+                self.set_filter(None);
+                return;
+            }
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                input.push(c);
+            }
+            _ => return,
+        }
+        let filter = Some(input.clone()).filter(|f| !f.is_empty());
+        self.set_filter(filter);
+    }
+
+    /// Typing at the convert prompt; Enter converts once the bullet and
+    /// type check out, Esc gives up
+    fn handle_convert_key(&mut self, key: KeyEvent) {
+        let Some(input) = self.convert_input.as_mut() else {
+            return;
+        };
+        match key.code {
+            KeyCode::Enter => {
+                let input = input.clone();
+                match self.parse_conversion(&input) {
+                    Ok((id, to)) => {
+                        self.convert_input = None;
+                        self.convert_request = Some((self.selected_date, id, to));
+                        self.should_exit = true;
+                    }
+                    Err(e) => self.convert_error = Some(format!("{:#}", e)),
+                }
+            }
+            KeyCode::Esc => {
+                self.convert_input = None;
+                self.convert_error = None;
+            }
+            KeyCode::Backspace => {
+                input.pop();
+                self.convert_error = None;
+            }
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                input.push(c);
+                self.convert_error = None;
+            }
+            _ => {}
+        }
+    }
+
+    /// Bullet id and new type from `<bullet> <type>`, where the bullet is an
+    /// id or `<type>:<n>` on the selected day
+    fn parse_conversion(&self, input: &str) -> anyhow::Result<(i64, BulletType)> {
+        let Some(storage) = &self.storage else {
+            anyhow::bail!("No storage to convert in");
+        };
+        let Some((reference, to)) = input.trim().split_once(char::is_whitespace) else {
+            anyhow::bail!("Use <bullet> <type>, e.g. task:2 note");
+        };
+        let to: BulletType = to.trim().parse().map_err(anyhow::Error::msg)?;
+        let id = storage.resolve_bullet(self.selected_date, reference)?;
+        if let Some((_, bullet)) = storage.load_bullet(id)?
+            && bullet.bullet_type == to
+        {
+            anyhow::bail!("\"{}\" is already a {}", bullet.content, to);
+        }
+        Ok((id, to))
+    }
+
+    /// Filter by the next `@@context` in use, then go back to no filter
+    fn cycle_context(&mut self) {
+        let Some(storage) = &self.storage else {
+            return;
+        };
+        let contexts = match storage.list_contexts() {
+            Ok(contexts) => contexts,
+            Err(e) => {
+                self.error = Some(format!("{:#}", e));
+                return;
+            }
+        };
+        let current = self
+            .filter
+            .as_deref()
+            .and_then(|filter| filter.strip_prefix("@@"))
+            .and_then(|context| contexts.iter().position(|c| c == context));
+        let next = match current {
+            Some(index) => contexts.get(index + 1),
+            None => contexts.first(),
+        };
+        self.set_filter(next.map(|context| format!("@@{}", context)));
+    }
+
+    fn set_filter(&mut self, filter: Option<String>) {
+        if self.filter == filter {
+            return;
+        }
+        let parsed = filter
+            .as_deref()
+            .map(|filter| Query::parse_with_views(filter, &self.views))
+            .transpose();
+        self.filter = filter;
+        match parsed {
+            Ok(query) => {
+                self.filter_error = None;
+                self.filter_query = query;
+                self.preview_scroll = 0;
+                // Entry dots depend on the filter
+                self.data_stale = true;
+            }
+            // Half-typed queries like `after:2024-0` keep the last valid one
+            Err(e) => self.filter_error = Some(e.to_string()),
+        }
+    }
+
+    /// Click selects a day, double-click edits it, dragging selects a range
+    /// of days and the wheel moves by weeks
+    fn handle_mouse_event(&mut self, mouse: MouseEvent) {
+        let mut calendar_area = self.calendar_area;
+        if self.week_numbers {
+            // Skip the week number gutter and the spacing after it
+            let gutter = (WEEK_GUTTER_WIDTH + 1).min(calendar_area.width);
+            calendar_area.x += gutter;
+            calendar_area.width -= gutter;
+        }
+        let date = Self::date_at(
+            calendar_area,
+            self.current_week_start,
+            mouse.column,
+            mouse.row,
+        );
+        let over_preview = self
+            .preview_area
+            .contains(Position::new(mouse.column, mouse.row));
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                let minimap_date = Self::minimap_date_at(
+                    self.minimap_area,
+                    self.selected_date,
+                    self.first_day,
+                    mouse.column,
+                    mouse.row,
+                );
+                let Some(date) = date.or(minimap_date) else {
+                    return;
+                };
+                let double_click = self
+                    .last_click
+                    .is_some_and(|(last, at)| last == date && at.elapsed() < DOUBLE_CLICK);
+                if double_click {
+                    self.should_edit = true;
+                    self.should_exit = true;
+                    return;
+                }
+                self.last_click = Some((date, Instant::now()));
+                self.drag_anchor = Some(date);
+                self.selected_date = date;
+                self.update_current_week();
+            }
+            MouseEventKind::Drag(MouseButton::Left) => {
+                let (Some(anchor), Some(date)) = (self.drag_anchor, date) else {
+                    return;
+                };
+                if date == self.selected_date {
+                    return;
+                }
+                self.selection = (date != anchor).then(|| (anchor.min(date), anchor.max(date)));
+                // Not through update_current_week, which would scroll the
+                // calendar under the pointer and drop the selection
+                self.selected_date = date;
+                self.data_stale = true;
+            }
+            MouseEventKind::Up(MouseButton::Left) => {
+                self.drag_anchor = None;
+            }
+            MouseEventKind::ScrollUp if over_preview => {
+                self.preview_scroll = self.preview_scroll.saturating_sub(1);
+            }
+            MouseEventKind::ScrollDown if over_preview => {
+                self.preview_scroll = self.preview_scroll.saturating_add(1);
+            }
+            MouseEventKind::ScrollUp => {
+                self.selected_date -= Duration::weeks(1);
+                self.update_current_week();
+            }
+            MouseEventKind::ScrollDown => {
+                self.selected_date += Duration::weeks(1);
+                self.update_current_week();
+            }
             _ => {}
         }
     }
 
+    fn clear_selection(&mut self) {
+        if self.selection.take().is_some() {
+            self.data_stale = true;
+        }
+    }
+
+    /// Day under a terminal cell of the calendar drawn in `area`, whose middle
+    /// row is the week starting on `week_start`
+    fn date_at(area: Rect, week_start: NaiveDate, column: u16, row: u16) -> Option<NaiveDate> {
+        // The month title and weekday header sit above the five week rows
+        let week = row.checked_sub(area.y + 2)? as i64;
+        if week >= 5 || area.width == 0 || column < area.x || column >= area.x + area.width {
+            return None;
+        }
+        let day = ((column - area.x) as u32 * 7 / area.width as u32) as i64;
+        Some(week_start + Duration::weeks(week - 2) + Duration::days(day))
+    }
+
+    /// Day under a terminal cell of the mini-map drawn in `area` for the
+    /// month of `selected_date`
+    fn minimap_date_at(
+        area: Rect,
+        selected_date: NaiveDate,
+        first_day: Weekday,
+        column: u16,
+        row: u16,
+    ) -> Option<NaiveDate> {
+        // The month title and weekday header sit above the week rows, and
+        // each day takes three columns
+        let week = row.checked_sub(area.y + 2)? as usize;
+        let day = column.checked_sub(area.x)? / 3;
+        if column >= area.x + area.width || day >= 7 {
+            return None;
+        }
+        let week_start = *month_weeks(selected_date, first_day).get(week)?;
+        let date = week_start + Duration::days(day as i64);
+        (date.month() == selected_date.month()).then_some(date)
+    }
+
+    /// Pin the selected day, or unpin it if it already is
+    fn toggle_pin(&mut self) {
+        let Some(storage) = &self.storage else {
+            return;
+        };
+        let date = self.selected_date;
+        let pinned = self
+            .entry_statuses
+            .get(&date)
+            .is_some_and(|status| status.pinned);
+        match storage.set_pinned(date, !pinned) {
+            Ok(()) => self.entry_statuses.entry(date).or_default().pinned = !pinned,
+            Err(e) => self.error = Some(format!("{:#}", e)),
+        }
+    }
+
+    /// Select the nearest earlier or later day with an entry, however many
+    /// empty weeks lie between
+    fn jump_to_entry(&mut self, forward: bool) {
+        let Some(storage) = &self.storage else {
+            return;
+        };
+        match storage.adjacent_entry_date(self.selected_date, forward) {
+            Ok(Some(date)) => {
+                self.selected_date = date;
+                self.update_current_week();
+            }
+            Ok(None) => {}
+            Err(e) => self.error = Some(format!("{:#}", e)),
+        }
+    }
+
+    /// Days an entry refers to: `[[YYYY-MM-DD]]` and `@YYYY-MM-DD` in its
+    /// bullets, then the days its recorded follow-ups continue
+    fn referenced_dates(&mut self, date: NaiveDate) -> anyhow::Result<Vec<NaiveDate>> {
+        let mut dates: Vec<NaiveDate> = match self.journal.get_entry(date)? {
+            Some(entry) => entry
+                .all_bullets()
+                .flat_map(|bullet| bullet.date_references())
+                .map(|(_, referenced)| referenced)
+                .collect(),
+            None => Vec::new(),
+        };
+        if let Some(storage) = &self.storage {
+            dates.extend(
+                storage
+                    .load_cross_references(date)?
+                    .into_iter()
+                    .map(|reference| reference.target_date),
+            );
+        }
+        let mut seen = HashSet::new();
+        dates.retain(|referenced| *referenced != date && seen.insert(*referenced));
+        Ok(dates)
+    }
+
+    /// The referencing day, index and date of the reference `g` goes to next:
+    /// the first one of the selected entry, or right after a jump the one
+    /// following it in the entry jumped from
+    fn next_reference(&mut self) -> anyhow::Result<Option<(NaiveDate, usize, NaiveDate)>> {
+        if let Some((origin, index)) = self.reference_trail {
+            let dates = self.referenced_dates(origin)?;
+            if dates.get(index) == Some(&self.selected_date) {
+                let next = (index + 1) % dates.len();
+                return Ok(Some((origin, next, dates[next])));
+            }
+        }
+        let origin = self.selected_date;
+        Ok(self
+            .referenced_dates(origin)?
+            .first()
+            .map(|&date| (origin, 0, date)))
+    }
+
+    /// Select the next day the entry refers to
+    fn jump_to_reference(&mut self) {
+        match self.next_reference() {
+            Ok(Some((origin, index, date))) => {
+                self.reference_trail = Some((origin, index));
+                self.selected_date = date;
+                self.update_current_week();
+            }
+            Ok(None) => {}
+            Err(e) => self.error = Some(format!("{:#}", e)),
+        }
+    }
+
+    /// Reload what's shown when an entry was saved or deleted since the last
+    /// check, e.g. by the capture daemon or the file watcher. Nothing else
+    /// can write to a DuckDB journal while it's open here, so that backend
+    /// isn't polled.
+    fn check_for_changes(&mut self) {
+        let Some(storage) = self
+            .storage
+            .as_ref()
+            .filter(|storage| storage.allows_concurrent_writers())
+        else {
+            return;
+        };
+        self.watermark_checked = Instant::now();
+        match storage.change_watermark() {
+            Ok(watermark) => {
+                if self.watermark.is_some_and(|last| last != watermark) {
+                    // The journal caches entries too, so those go as well
+                    self.journal.entries.clear();
+                    self.related_window = None;
+                    self.mood_trend_week = None;
+                    self.data_stale = true;
+                }
+                self.watermark = Some(watermark);
+            }
+            Err(e) => self.error = Some(format!("{:#}", e)),
+        }
+    }
+
+    /// Open the next URL found in the selected entry's bullets, cycling back
+    /// to the first after the last
+    fn open_next_link(&mut self) {
+        let Some(entry) = &self.selected_entry else {
+            return;
+        };
+        let urls: Vec<String> = entry.all_bullets().flat_map(|b| b.urls()).collect();
+        if urls.is_empty() {
+            return;
+        }
+
+        let url = &urls[self.link_cursor % urls.len()];
+        if let Err(e) = open_url(url) {
+            // Logging to stderr would garble the alternate screen
+            self.error = Some(format!("{:#}", e));
+        }
+        self.link_cursor = (self.link_cursor + 1) % urls.len();
+    }
+
+    /// Journal day it is now, which starts at the rollover hour
+    fn today(&self) -> NaiveDate {
+        journal_date(Local::now().naive_local(), self.day_rollover_hour)
+    }
+
     /// Update the current week focus based on selected date
     fn update_current_week(&mut self) {
-        let selected_week_start = Self::get_week_start(self.selected_date);
+        let selected_week_start = self.get_week_start(self.selected_date);
+
+        // The selection changed, so the previewed entry must be reloaded
+        self.data_stale = true;
+        self.selection = None;
+        self.preview_scroll = 0;
+        self.link_cursor = 0;
 
         // Only update if we've moved to a different week
         if selected_week_start != self.current_week_start {
@@ -377,15 +1316,81 @@ impl<'a> WeekView<'a> {
         }
     }
 
+    /// Create the mini-calendar of the selected day's month, marking the
+    /// days with entries and the week focused in the calendar
+    fn create_minimap_static(
+        current_week_start: NaiveDate,
+        selected_date: NaiveDate,
+        today: NaiveDate,
+        first_day: Weekday,
+        theme: &Theme,
+        entry_statuses: &HashMap<NaiveDate, DayStatus>,
+    ) -> Paragraph<'static> {
+        let weeks = month_weeks(selected_date, first_day);
+
+        let mut lines = vec![Line::from(Span::styled(
+            (0..7)
+                .map(|offset| weeks[0] + Duration::days(offset))
+                .map(|date| format!("{:<3}", &date.format("%a").to_string()[..2]))
+                .collect::<String>(),
+            Style::default().fg(theme.colors.header),
+        ))];
+        for &week_start in &weeks {
+            let spans = (0..7)
+                .map(|offset| {
+                    let date = week_start + Duration::days(offset);
+                    if date.month() != selected_date.month() {
+                        return Span::raw("   ");
+                    }
+                    let status = entry_statuses.get(&date).copied().unwrap_or_default();
+                    let mut style = if date == today {
+                        Style::default()
+                            .fg(theme.colors.today)
+                            .add_modifier(Modifier::BOLD)
+                    } else if status.holiday {
+                        Style::default().fg(theme.colors.holiday)
+                    } else if status.has_entry || status.pinned {
+                        Style::default().fg(theme.colors.focused)
+                    } else {
+                        Style::default().fg(theme.colors.dimmed)
+                    };
+                    if status.has_entry || status.pinned {
+                        style = style.add_modifier(Modifier::UNDERLINED);
+                    }
+                    if date == selected_date {
+                        style = style.bg(theme.colors.selection_bg);
+                    } else if week_start == current_week_start {
+                        style = style.bg(theme.colors.focused_week_bg);
+                    }
+                    Span::styled(format!("{:>2} ", date.day()), style)
+                })
+                .collect::<Vec<_>>();
+            lines.push(Line::from(spans));
+        }
+
+        Paragraph::new(lines).block(
+            Block::default()
+                .borders(Borders::RIGHT)
+                .border_style(Style::default().fg(theme.colors.border))
+                .title(selected_date.format("%B").to_string())
+                .title_style(Style::default().fg(theme.colors.header))
+                .title_alignment(Alignment::Center),
+        )
+    }
+
     /// Create help text (static version)
-    fn create_help_text_static(selected_date: NaiveDate, theme: &Theme) -> Paragraph<'static> {
+    fn create_help_text_static(
+        selected_date: NaiveDate,
+        theme: &Theme,
+        date_formats: &DateFormats,
+    ) -> Paragraph<'static> {
         let help_text = vec![
             Line::from(vec![Span::styled(
-                "↑↓/jk=Week • ←→/hl=Day • PgUp/PgDn=Month • t=Today • Enter=Edit • ?=Help • q=Quit",
+                "↑↓/jk=Week • ←→/hl=Day • PgUp/PgDn=Month • {/}=Year • t=Today • [/]=Prev/next entry • Enter/double-click=Edit • Drag=Select days • f=Filter • @=Context • c=Convert bullet • o=Open link • g=Go to referenced date • Y=Year • s=Stats • m=Month map • y/p=Copy day • *=Pin • J/K C-f/C-b=Scroll preview • v=Split • S=Merge sessions • 1-7=Fold section • M-1..3=Related day • r=Retry • ?=Help • q=Quit",
                 Style::default().fg(theme.colors.dimmed),
             )]),
             Line::from(vec![Span::styled(
-                format!("{}", selected_date.format("%A, %B %d, %Y")),
+                selected_date.format(&date_formats.long).to_string(),
                 Style::default().fg(theme.colors.focused),
             )]),
         ];
@@ -395,199 +1400,894 @@ impl<'a> WeekView<'a> {
             .alignment(Alignment::Center)
     }
 
-    /// Create bullet display widget for the selected date
-    fn create_bullet_display(
-        entry: Option<&crate::entities::Entry>,
+    /// Progress bar towards the word goal, atop a day's preview
+    fn create_goal_line(words: usize, goal: usize, theme: &Theme) -> Line<'static> {
+        let color = if words >= goal {
+            theme.colors.accent
+        } else {
+            theme.colors.secondary
+        };
+        Line::from(vec![
+            Span::styled(
+                trends::progress_bar(words, goal, GOAL_BAR_WIDTH),
+                Style::default().fg(color),
+            ),
+            Span::styled(
+                format!(" {}/{} words", words, goal),
+                Style::default().fg(theme.colors.dimmed),
+            ),
+        ])
+    }
+
+    /// Line under the preview listing the related days with their jump keys
+    fn create_related_line(related_days: &[RelatedDay], theme: &Theme) -> Paragraph<'static> {
+        let mut spans = vec![Span::styled(
+            "Related:",
+            Style::default().fg(theme.colors.dimmed),
+        )];
+        for (i, related) in related_days.iter().enumerate() {
+            spans.push(Span::styled(
+                format!("  M-{} ", i + 1),
+                Style::default().fg(theme.colors.accent),
+            ));
+            spans.push(Span::raw(related.date.format("%a %b %d").to_string()));
+            if related.follow_up {
+                spans.push(Span::styled(
+                    " (follow-up)",
+                    Style::default().fg(theme.colors.dimmed),
+                ));
+            }
+        }
+        Paragraph::new(Line::from(spans))
+    }
+
+    /// Add a line for a bullet and, indented beneath it, its sub-bullets
+    fn push_bullet_lines(
+        lines: &mut Vec<Line<'static>>,
+        entry: &Entry,
+        bullet: &Bullet,
+        depth: usize,
+        notes: BulletNotes,
+        filter: Option<&Query>,
+        theme: &Theme,
+    ) {
+        if filter.is_some_and(|filter| !Self::bullet_matches(entry.date, bullet, filter)) {
+            return;
+        }
+
+        let bullet_style = Style::default().fg(theme.colors.bullets.color(bullet));
+
+        let mut spans = vec![Span::styled(
+            format!("{}{} ", "  ".repeat(depth), theme.symbols.symbol(bullet)),
+            bullet_style,
+        )];
+        if bullet.task_state == Some(TaskState::Pending)
+            && notes
+                .blocked
+                .is_some_and(|blocked| blocked.contains(&bullet.content))
+        {
+            spans.push(Span::styled("⛓ ", Style::default().fg(theme.colors.dimmed)));
+        }
+        if let Some(time) = bullet.time_label() {
+            spans.push(Span::styled(
+                format!("{} ", time),
+                Style::default().fg(theme.colors.accent),
+            ));
+        }
+        // Date references are underlined; `g` jumps to them
+        let text_style = Style::default().fg(theme.colors.focused);
+        let mut written = 0;
+        for (range, _) in bullet.date_references() {
+            if range.start > written {
+                spans.push(Span::styled(
+                    bullet.content[written..range.start].to_string(),
+                    text_style,
+                ));
+            }
+            spans.push(Span::styled(
+                bullet.content[range.clone()].to_string(),
+                Style::default()
+                    .fg(theme.colors.accent)
+                    .add_modifier(Modifier::UNDERLINED),
+            ));
+            written = range.end;
+        }
+        if written < bullet.content.len() || written == 0 {
+            spans.push(Span::styled(
+                bullet.content[written..].to_string(),
+                text_style,
+            ));
+        }
+        if let Some(reference) = notes.follow_ups.iter().find(|r| r.is_from(bullet)) {
+            spans.push(Span::styled(
+                format!(
+                    "  ↩ follow-up of {}",
+                    reference.target_date.format("%Y-%m-%d")
+                ),
+                Style::default().fg(theme.colors.dimmed),
+            ));
+        }
+        lines.push(Line::from(spans));
+
+        for child in &bullet.children {
+            Self::push_bullet_lines(lines, entry, child, depth + 1, notes, filter, theme);
+        }
+    }
+
+    /// Create the stats panel for the focused week or the dragged selection
+    fn create_stats_panel(
+        stats: Option<&PeriodStats>,
+        selection: Option<(NaiveDate, NaiveDate)>,
+        streak: Option<u32>,
+        mood_trend: Option<&str>,
+        mood_checkins: Option<&str>,
+        goal_days: Option<usize>,
         theme: &Theme,
     ) -> Paragraph<'static> {
+        let (title, days) = match selection {
+            Some((first, last)) => {
+                let days = (last - first).num_days() + 1;
+                (format!(" {} days ", days), days)
+            }
+            None => (" Week ".to_string(), 7),
+        };
+        let row = |label: &str, value: String| {
+            Line::from(vec![
+                Span::styled(
+                    format!("{:<9}", label),
+                    Style::default().fg(theme.colors.dimmed),
+                ),
+                Span::styled(value, Style::default().fg(theme.colors.focused)),
+            ])
+        };
+
+        let mut lines = match stats {
+            Some(stats) => vec![
+                row(
+                    "Tasks",
+                    format!(
+                        "{}/{}",
+                        stats.tasks_completed,
+                        stats.tasks_completed + stats.tasks_pending
+                    ),
+                ),
+                row("Pending", stats.tasks_pending.to_string()),
+                row("Events", stats.events.to_string()),
+                row("Words", stats.words.to_string()),
+                row("Days", format!("{}/{}", stats.days_with_entries, days)),
+            ],
+            None => vec![Line::from(Span::styled(
+                "No stats available",
+                Style::default().fg(theme.colors.dimmed),
+            ))],
+        };
+        if let Some(goal_days) = goal_days {
+            lines.push(row("Goal met", format!("{}/{}", goal_days, days)));
+        }
+        if let Some(streak) = streak {
+            lines.push(row("Streak", format!("{} days", streak)));
+        }
+        if let Some(trend) = mood_trend {
+            lines.push(row("Mood", trend.to_string()));
+        }
+        if let Some(checkins) = mood_checkins {
+            lines.push(row("Mood 30d", String::new()));
+            lines.push(Line::from(Span::styled(
+                checkins.to_string(),
+                Style::default().fg(theme.colors.accent),
+            )));
+        }
+
+        Paragraph::new(lines).block(
+            Block::default()
+                .borders(Borders::LEFT)
+                .border_style(Style::default().fg(theme.colors.border))
+                .title(title),
+        )
+    }
+
+    /// Color of a preview section's header: the everyday sections use the
+    /// header color, the others their bullets' color
+    fn section_color(bullet_type: BulletType, theme: &Theme) -> Color {
+        match bullet_type {
+            BulletType::Task | BulletType::Event | BulletType::Note => theme.colors.header,
+            _ => theme.colors.bullets.for_type(&bullet_type),
+        }
+    }
+
+    /// Create the preview lines for an entry's bullets, grouped under a
+    /// header per non-empty section such as "▾ Tasks (2/3)"
+    fn create_bullet_lines(
+        entry: Option<&Entry>,
+        events: &[ExternalEvent],
+        notes: BulletNotes,
+        filter: Option<&Query>,
+        layout: &PreviewLayout,
+        theme: &Theme,
+    ) -> Vec<Line<'static>> {
+        // Calendar events come first so the day's schedule is visible at a glance
+        let mut lines: Vec<Line<'static>> = events
+            .iter()
+            .filter(|event| {
+                filter.is_none_or(|filter| {
+                    filter.is_text_only()
+                        && filter.includes_date(event.date)
+                        && filter.matches_text(&event.summary)
+                })
+            })
+            .map(|event| {
+                Line::from(vec![
+                    Span::styled("◇ ", Style::default().fg(theme.colors.dimmed)),
+                    Span::styled(
+                        format!("{} ", event.time_label()),
+                        Style::default().fg(theme.colors.accent),
+                    ),
+                    Span::styled(
+                        event.summary.clone(),
+                        Style::default().fg(theme.colors.dimmed),
+                    ),
+                ])
+            })
+            .collect();
+
         let entry = match entry {
             Some(entry) => entry,
             None => {
-                return Paragraph::new(vec![Line::from(vec![Span::styled(
+                lines.push(Line::from(vec![Span::styled(
                     "No entry for this date".to_string(),
                     Style::default().fg(theme.colors.dimmed),
-                )])])
-                .block(Block::default().borders(Borders::NONE))
-                .alignment(Alignment::Left);
+                )]));
+                return lines;
             }
         };
+        let event_lines = lines.len();
 
-        let mut lines = Vec::new();
-
-        let bullet_types = [
-            BulletType::Task,
-            BulletType::Event,
-            BulletType::Note,
-            BulletType::Priority,
-            BulletType::Inspiration,
-            BulletType::Insight,
-            BulletType::Misstep,
-        ];
-
-        for bullet_type in bullet_types {
-            let bullets = entry.get_bullets(&bullet_type);
-            for bullet in bullets {
-                let symbol = bullet.symbol();
+        // Bullets outside sessions first, then each `## HH:MM` session under
+        // its own heading unless they're merged
+        let mut sessions = vec![None];
+        if !layout.merge_sessions {
+            sessions.extend(entry.sessions().into_iter().map(Some));
+        }
+        for session in sessions {
+            let start = lines.len();
+            for bullet_type in layout.section_order {
+                let bullets: Vec<&Bullet> = entry
+                    .bullets_chronological(bullet_type)
+                    .into_iter()
+                    .filter(|bullet| layout.merge_sessions || bullet.session == session)
+                    .filter(|bullet| {
+                        filter.is_none_or(|filter| Self::bullet_matches(entry.date, bullet, filter))
+                    })
+                    .collect();
+                if bullets.is_empty() {
+                    continue;
+                }
 
-                let bullet_style = match bullet_type {
-                    BulletType::Priority => Style::default().fg(Color::Yellow),
-                    BulletType::Task if bullet.task_state == Some(TaskState::Completed) => {
-                        Style::default().fg(Color::Green)
-                    }
-                    BulletType::Inspiration => Style::default().fg(Color::Cyan),
-                    BulletType::Insight => Style::default().fg(Color::Magenta),
-                    BulletType::Misstep => Style::default().fg(Color::Red),
-                    _ => Style::default().fg(theme.colors.focused),
+                let collapsed = layout.collapsed.contains(bullet_type);
+                let count = if bullets.iter().any(|b| b.task_state.is_some()) {
+                    let done = bullets
+                        .iter()
+                        .filter(|b| b.task_state == Some(TaskState::Completed))
+                        .count();
+                    format!("{}/{}", done, bullets.len())
+                } else {
+                    bullets.len().to_string()
                 };
-
-                lines.push(Line::from(vec![
-                    Span::styled(format!("{} ", symbol), bullet_style),
-                    Span::styled(
-                        bullet.content.clone(),
-                        Style::default().fg(theme.colors.focused),
+                lines.push(Line::from(Span::styled(
+                    format!(
+                        "{} {} ({})",
+                        if collapsed { "▸" } else { "▾" },
+                        bullet_type.section_name(),
+                        count
                     ),
-                ]));
+                    Style::default()
+                        .fg(Self::section_color(*bullet_type, theme))
+                        .add_modifier(Modifier::BOLD),
+                )));
+                if collapsed {
+                    continue;
+                }
+                for bullet in bullets {
+                    Self::push_bullet_lines(&mut lines, entry, bullet, 0, notes, filter, theme);
+                }
+            }
+            if let Some(session) = session
+                && lines.len() > start
+            {
+                lines.insert(
+                    start,
+                    Line::from(Span::styled(
+                        format!("── {} ──", session.format("%H:%M")),
+                        Style::default().fg(theme.colors.header),
+                    )),
+                );
             }
         }
 
-        if lines.is_empty() {
+        // Free writing goes below every section, as in the markdown
+        if let Some(body) = entry.body.as_deref().filter(|body| {
+            filter.is_none_or(|filter| {
+                filter.is_text_only()
+                    && filter.includes_date(entry.date)
+                    && filter.matches_text(body)
+            })
+        }) {
+            lines.push(Line::from(Span::styled(
+                "Freewrite",
+                Style::default()
+                    .fg(theme.colors.header)
+                    .add_modifier(Modifier::BOLD),
+            )));
+            lines.extend(body.lines().map(|line| Line::from(line.to_string())));
+        }
+
+        if lines.len() == event_lines {
+            let message = match filter {
+                Some(_) => "No bullets matching the filter".to_string(),
+                None => "No bullets for this date".to_string(),
+            };
             lines.push(Line::from(vec![Span::styled(
-                "No bullets for this date".to_string(),
+                message,
                 Style::default().fg(theme.colors.dimmed),
             )]));
         }
 
-        Paragraph::new(lines)
-            .block(Block::default().borders(Borders::NONE))
-            .alignment(Alignment::Left)
+        lines
     }
 
-    /// Run the week view TUI loop
-    pub fn run(&mut self) -> io::Result<WeekViewResult> {
-        loop {
-            // Check for exit condition before drawing
-            if self.should_exit {
-                break;
-            }
+    /// Create the bullet preview widget for a pane `height` rows tall,
+    /// scrolled down by `scroll` lines
+    fn create_bullet_display(
+        lines: Vec<Line<'static>>,
+        scroll: u16,
+        height: u16,
+        title: Option<String>,
+        theme: &Theme,
+    ) -> Paragraph<'static> {
+        let mut block = Block::default().borders(Borders::NONE);
+        let mut rows = height as usize;
+        if let Some(title) = title {
+            block = block
+                .title(title)
+                .title_style(Style::default().fg(theme.colors.header));
+            rows = rows.saturating_sub(1);
+        }
 
-            // Generate weeks we need to check for entry statuses
-            let weeks: Vec<NaiveDate> = (-2..=2)
-                .map(|offset| self.current_week_start + Duration::weeks(offset))
-                .collect();
+        Paragraph::new(Self::visible_lines(lines, scroll as usize, rows, theme))
+            .block(block)
+            .alignment(Alignment::Left)
+    }
 
-            // Get entry statuses before drawing (requires mutable access to journal)
-            let entry_statuses = self.get_entry_statuses(&weeks);
+    /// The lines shown in `rows` rows after skipping `scroll`, only these
+    /// being handed to the widget so days with hundreds of bullets draw as
+    /// fast as short ones. When the rest doesn't fit, the last row says how
+    /// many lines are cut off instead.
+    fn visible_lines(
+        lines: Vec<Line<'static>>,
+        scroll: usize,
+        rows: usize,
+        theme: &Theme,
+    ) -> Vec<Line<'static>> {
+        let remaining = lines.len().saturating_sub(scroll);
+        let shown = if remaining > rows {
+            rows.saturating_sub(1)
+        } else {
+            remaining
+        };
+        let mut visible: Vec<Line<'static>> = lines.into_iter().skip(scroll).take(shown).collect();
+        if remaining > shown {
+            visible.push(Line::from(vec![Span::styled(
+                format!("+{} more", remaining - shown),
+                Style::default().fg(theme.colors.dimmed),
+            )]));
+        }
+        visible
+    }
 
-            // Get the selected date's entry for bullet display
-            let selected_entry = self
+    /// Reload entry statuses and the selected entry from the journal
+    fn refresh_data(&mut self) -> anyhow::Result<()> {
+        // Generate weeks we need to check for entry statuses
+        let mut weeks: Vec<NaiveDate> = (-2..=2)
+            .map(|offset| self.current_week_start + Duration::weeks(offset))
+            .collect();
+        if self.show_minimap {
+            // The month can reach beyond the five weeks of the calendar
+            for week_start in month_weeks(self.selected_date, self.first_day) {
+                if !weeks.contains(&week_start) {
+                    weeks.push(week_start);
+                }
+            }
+        }
+
+        self.entry_statuses = self.get_entry_statuses(&weeks)?;
+        self.selected_entry = self.journal.get_entry(self.selected_date)?.cloned();
+        let mut adjacent_entries = [None, None];
+        for (slot, offset) in adjacent_entries.iter_mut().zip([-1, 1]) {
+            *slot = self
                 .journal
-                .get_entry(self.selected_date)
-                .unwrap_or(None)
+                .get_entry(self.selected_date + Duration::days(offset))?
                 .cloned();
+        }
+        self.adjacent_entries = adjacent_entries;
 
-            // Capture the state we need for drawing (after mutable borrow is complete)
-            let current_week_start = self.current_week_start;
-            let selected_date = self.selected_date;
-            let show_help = self.show_help;
-            let theme = &self.theme;
+        let stats_range = match self.selection {
+            Some((first, last)) => DateRange::between(first, last),
+            None => DateRange::week(self.current_week_start),
+        };
+        self.week_stats = match &self.storage {
+            Some(storage) => Some(storage.period_stats(stats_range)?),
+            None => None,
+        };
 
-            self.terminal.draw(|frame| {
-                let size = frame.area();
+        self.mood_trend = match &self.storage {
+            // Its eight weeks of entries only change with the focused week
+            Some(_) if self.show_stats && self.mood_trend_week == Some(self.current_week_start) => {
+                self.mood_trend.take()
+            }
+            Some(storage) if self.show_stats => {
+                let range = DateRange::between(
+                    self.current_week_start - Duration::weeks(MOOD_WEEKS - 1),
+                    self.current_week_start + Duration::days(6),
+                );
+                let entries = storage.load_entries(range.clone())?;
+                let points = trends::compute_trends(
+                    &entries,
+                    range,
+                    TrendPeriod::Week,
+                    None,
+                    self.first_day,
+                );
+                let sentiment: Vec<f64> = points.iter().map(|p| p.sentiment).collect();
+                Some(trends::sparkline(&sentiment))
+            }
+            _ => None,
+        };
+        self.mood_trend_week = self.mood_trend.is_some().then_some(self.current_week_start);
+
+        self.mood_checkins = match &self.storage {
+            Some(storage) if self.show_stats => {
+                let today = self.today();
+                let range =
+                    DateRange::between(today - Duration::days(MOOD_CHECKIN_DAYS - 1), today);
+                let moods = storage.load_moods(range.clone())?;
+                (!moods.is_empty()).then(|| trends::mood_sparkline(&moods, range))
+            }
+            _ => None,
+        };
 
-                // Calculate the total space needed for our UI
-                const CALENDAR_HEIGHT: u16 = 18; // 5 weeks * 3 rows each + header + title
-                const HELP_HEIGHT: u16 = 3; // Help text (no borders)
-                const BULLET_HEIGHT: u16 = 8; // Space for bullet display
+        self.selected_timestamps = match &self.storage {
+            Some(storage) if self.selected_entry.is_some() => {
+                storage.entry_timestamps(self.selected_date)?
+            }
+            _ => None,
+        };
 
-                const MIN_CALENDAR_WIDTH: u16 = 78;
-                const MAX_CALENDAR_WIDTH: u16 = 100;
-                const PREFERRED_CALENDAR_WIDTH: u16 = 86;
+        self.external_events = HashMap::new();
+        self.follow_ups = HashMap::new();
+        self.blocked = HashMap::new();
+        if let Some(storage) = &self.storage {
+            for offset in -1..=1 {
+                let date = self.selected_date + Duration::days(offset);
+                self.external_events
+                    .insert(date, storage.load_external_events(date)?);
+                self.follow_ups
+                    .insert(date, storage.load_cross_references(date)?);
+                self.blocked
+                    .insert(date, storage.pending_blockers(date)?.into_keys().collect());
+            }
+        }
 
-                let needed_width = if size.width >= MAX_CALENDAR_WIDTH + 10 {
-                    PREFERRED_CALENDAR_WIDTH
-                } else if size.width >= MIN_CALENDAR_WIDTH + 4 {
-                    std::cmp::min(size.width.saturating_sub(4), MAX_CALENDAR_WIDTH)
-                } else {
-                    std::cmp::min(size.width, MIN_CALENDAR_WIDTH)
-                };
+        self.related_days = match (&self.storage, &self.selected_entry) {
+            (Some(storage), Some(entry)) => {
+                let first = self.selected_date - Duration::days(RELATED_LOOKBACK_DAYS);
+                let last = self.selected_date + Duration::days(RELATED_LOOKBACK_DAYS);
+                let loaded = self
+                    .related_window
+                    .as_ref()
+                    .is_some_and(|(range, _)| range.contains(first) && range.contains(last));
+                if !loaded {
+                    let range = DateRange::between(
+                        first - Duration::days(RELATED_WINDOW_MARGIN_DAYS),
+                        last + Duration::days(RELATED_WINDOW_MARGIN_DAYS),
+                    );
+                    let mut entries = storage.load_entries(range.clone())?;
+                    entries.sort_by_key(|e| e.date);
+                    self.related_window = Some((range, entries));
+                }
+                let entries = self
+                    .related_window
+                    .as_ref()
+                    .map(|(_, entries)| entries.as_slice())
+                    .unwrap_or_default();
+                let start = entries.partition_point(|e| e.date < first);
+                let end = entries.partition_point(|e| e.date <= last);
+                let references = self
+                    .follow_ups
+                    .get(&self.selected_date)
+                    .map(Vec::as_slice)
+                    .unwrap_or_default();
+                trends::related_days(entry, &entries[start..end], references, RELATED_DAYS)
+            }
+            _ => Vec::new(),
+        };
+        self.loaded_week_start = Some(self.current_week_start);
+        Ok(())
+    }
 
-                let total_height = if show_help {
-                    CALENDAR_HEIGHT + BULLET_HEIGHT + HELP_HEIGHT
-                } else {
-                    CALENDAR_HEIGHT + BULLET_HEIGHT
-                };
+    /// Draw the current state to the terminal
+    fn draw(&mut self) -> io::Result<()> {
+        let current_week_start = self.current_week_start;
+        let selected_date = self.selected_date;
+        let marks = DayMarks {
+            selected: selected_date,
+            selection: self.selection,
+            today: self.today(),
+        };
+        let week_numbers = self.week_numbers;
+        let first_day = self.first_day;
+        let show_minimap = self.show_minimap;
+        let word_goal = self.word_goal;
+        let mut calendar_area = self.calendar_area;
+        let mut minimap_area = Rect::default();
+        let mut drawn_preview_area = self.preview_area;
+        let show_help = self.show_help;
+        let error = self.error.as_deref();
+        let theme = &self.theme;
+        let date_formats = &self.date_formats;
+        let entry_statuses = &self.entry_statuses;
+        let show_split = self.show_split;
+        let layout = PreviewLayout {
+            merge_sessions: self.merge_sessions,
+            section_order: &self.section_order,
+            collapsed: &self.collapsed_sections,
+        };
+        let filter = self.filter_query.as_ref();
+        let filter_prompt = match (&self.filter_input, &self.filter, &self.filter_error) {
+            (Some(input), _, Some(error)) => Some(format!("Filter: {}█  ({})", input, error)),
+            (Some(input), _, None) => Some(format!("Filter: {}█", input)),
+            (None, Some(filter), _) => {
+                Some(format!("Filter: {} (f to change, Esc to clear)", filter))
+            }
+            (None, None, _) => None,
+        };
+        // The convert prompt takes the filter's line while it's open
+        let filter_prompt = match (&self.convert_input, &self.convert_error) {
+            (Some(input), Some(error)) => Some(format!("Convert: {}█  ({})", input, error)),
+            (Some(input), None) => Some(format!(
+                "Convert: {}█  (<bullet> <type>, e.g. task:2 note)",
+                input
+            )),
+            (None, _) => filter_prompt,
+        };
+        let loading = self.loading;
+        let related_line = (!self.related_days.is_empty())
+            .then(|| Self::create_related_line(&self.related_days, theme));
+        let goal_days = self.word_goal.map(|_| {
+            let (first, last) = self
+                .selection
+                .unwrap_or((current_week_start, current_week_start + Duration::days(6)));
+            first
+                .iter_days()
+                .take_while(|date| *date <= last)
+                .filter(|date| entry_statuses.get(date).and_then(|s| s.goal_met) == Some(true))
+                .count()
+        });
+        let stats_panel = self.show_stats.then(|| {
+            Self::create_stats_panel(
+                self.week_stats.as_ref(),
+                self.selection,
+                self.streak,
+                self.mood_trend.as_deref(),
+                self.mood_checkins.as_deref(),
+                goal_days,
+                theme,
+            )
+        });
+
+        let last_edited = self
+            .selected_timestamps
+            .map(|t| t.last_edited(Local::now().naive_local()));
+
+        // Preview content for yesterday/selected/tomorrow, or just the selected date
+        let preview_lines = |date: NaiveDate, entry: Option<&Entry>| {
+            let events = self
+                .external_events
+                .get(&date)
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            let notes = BulletNotes {
+                follow_ups: self
+                    .follow_ups
+                    .get(&date)
+                    .map(Vec::as_slice)
+                    .unwrap_or_default(),
+                blocked: self.blocked.get(&date),
+            };
+            let mut lines: Vec<Line<'static>> = self
+                .holidays
+                .on(date)
+                .into_iter()
+                .map(|name| {
+                    Line::from(Span::styled(
+                        format!("✦ {}", name),
+                        Style::default().fg(theme.colors.holiday),
+                    ))
+                })
+                .collect();
+            lines.extend(Self::create_bullet_lines(
+                entry, events, notes, filter, &layout, theme,
+            ));
+            if let (Some(goal), Some(entry)) = (word_goal, entry) {
+                lines.insert(0, Self::create_goal_line(entry.word_count(), goal, theme));
+            }
+            lines
+        };
+        let previews: Vec<(NaiveDate, Vec<Line<'static>>)> = if show_split {
+            let yesterday = selected_date - Duration::days(1);
+            let tomorrow = selected_date + Duration::days(1);
+            vec![
+                (
+                    yesterday,
+                    preview_lines(yesterday, self.adjacent_entries[0].as_ref()),
+                ),
+                (
+                    selected_date,
+                    preview_lines(selected_date, self.selected_entry.as_ref()),
+                ),
+                (
+                    tomorrow,
+                    preview_lines(tomorrow, self.adjacent_entries[1].as_ref()),
+                ),
+            ]
+        } else {
+            vec![(
+                selected_date,
+                preview_lines(selected_date, self.selected_entry.as_ref()),
+            )]
+        };
+
+        // Clamp scrolling so the last line stays at the bottom of the pane
+        let content_height = previews
+            .iter()
+            .map(|(_, lines)| lines.len())
+            .max()
+            .unwrap_or(0)
+            + usize::from(show_split || last_edited.is_some()); // Titles take a row
+        let max_scroll = content_height.saturating_sub(BULLET_HEIGHT as usize) as u16;
+        self.preview_scroll = self.preview_scroll.min(max_scroll);
+        let scroll = self.preview_scroll;
+
+        self.terminal.draw(|frame| {
+            let size = frame.area();
+
+            // Calculate the total space needed for our UI
+            const MIN_CALENDAR_WIDTH: u16 = 78;
+            const MAX_CALENDAR_WIDTH: u16 = 100;
+            const PREFERRED_CALENDAR_WIDTH: u16 = 86;
+
+            let mut needed_width = if size.width >= MAX_CALENDAR_WIDTH + 10 {
+                PREFERRED_CALENDAR_WIDTH
+            } else if size.width >= MIN_CALENDAR_WIDTH + 4 {
+                std::cmp::min(size.width.saturating_sub(4), MAX_CALENDAR_WIDTH)
+            } else {
+                std::cmp::min(size.width, MIN_CALENDAR_WIDTH)
+            };
+            if show_minimap {
+                needed_width = std::cmp::min(size.width, needed_width + MINIMAP_WIDTH);
+            }
 
-                // Calculate centered area for the entire UI
-                let centered_area = Self::calculate_centered_area(size, needed_width, total_height);
+            let mut total_height = if show_help {
+                CALENDAR_HEIGHT + BULLET_HEIGHT + HELP_HEIGHT
+            } else {
+                CALENDAR_HEIGHT + BULLET_HEIGHT
+            };
+            if related_line.is_some() {
+                total_height += RELATED_HEIGHT;
+            }
+            if filter_prompt.is_some() {
+                total_height += FILTER_HEIGHT;
+            }
+            if error.is_some() {
+                total_height += STATUS_HEIGHT;
+            }
 
-                // Create and draw week table
-                let table = Self::create_week_table_static(
+            // Calculate centered area for the entire UI
+            let centered_area = Self::calculate_centered_area(size, needed_width, total_height);
+
+            // Create vertical layout within the centered area
+            let mut constraints = vec![
+                Constraint::Length(CALENDAR_HEIGHT), // Main week view
+                Constraint::Length(BULLET_HEIGHT),   // Bullet display
+            ];
+            if related_line.is_some() {
+                constraints.push(Constraint::Length(RELATED_HEIGHT)); // Related days
+            }
+            if filter_prompt.is_some() {
+                constraints.push(Constraint::Length(FILTER_HEIGHT)); // Filter prompt
+            }
+            if show_help {
+                constraints.push(Constraint::Length(HELP_HEIGHT)); // Help text
+            }
+            if error.is_some() {
+                constraints.push(Constraint::Length(STATUS_HEIGHT)); // Error status bar
+            }
+            let main_chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints(constraints)
+                .split(centered_area);
+
+            // Create and draw week table
+            let table = Self::create_week_table_static(
+                current_week_start,
+                marks,
+                theme,
+                date_formats,
+                entry_statuses,
+                week_numbers,
+            );
+            // The mini-map takes a column on the left of the calendar
+            calendar_area = main_chunks[0];
+            if show_minimap {
+                let split = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Length(MINIMAP_WIDTH), Constraint::Min(0)])
+                    .split(main_chunks[0]);
+                let minimap = Self::create_minimap_static(
                     current_week_start,
                     selected_date,
+                    marks.today,
+                    first_day,
                     theme,
-                    &entry_statuses,
+                    entry_statuses,
                 );
+                frame.render_widget(minimap, split[0]);
+                minimap_area = split[0];
+                calendar_area = split[1];
+            }
+            frame.render_widget(table, calendar_area);
+            drawn_preview_area = main_chunks[1];
+
+            // Create and draw bullet display, or a loading label while storage
+            // is queried; the query blocks this thread, so the label can't animate
+            if loading {
+                let indicator = Paragraph::new(Line::from(vec![Span::styled(
+                    "Loading…",
+                    Style::default().fg(theme.colors.dimmed),
+                )]));
+                frame.render_widget(indicator, main_chunks[1]);
+            } else {
+                // Reserve a column on the right for the stats panel when it's open
+                let preview_area = match stats_panel {
+                    Some(panel) => {
+                        let split = Layout::default()
+                            .direction(Direction::Horizontal)
+                            .constraints([Constraint::Min(0), Constraint::Length(STATS_WIDTH)])
+                            .split(main_chunks[1]);
+                        frame.render_widget(panel, split[1]);
+                        split[0]
+                    }
+                    None => main_chunks[1],
+                };
 
-                if show_help {
-                    // Create vertical layout within the centered area
-                    let main_chunks = Layout::default()
-                        .direction(Direction::Vertical)
-                        .constraints([
-                            Constraint::Length(CALENDAR_HEIGHT), // Main week view
-                            Constraint::Length(BULLET_HEIGHT),   // Bullet display
-                            Constraint::Length(HELP_HEIGHT),     // Help text
-                        ])
-                        .split(centered_area);
-
-                    frame.render_widget(table, main_chunks[0]);
-
-                    // Create and draw bullet display
-                    let bullet_display =
-                        Self::create_bullet_display(selected_entry.as_ref(), theme);
-                    frame.render_widget(bullet_display, main_chunks[1]);
-
-                    // Create and draw help
-                    let help = Self::create_help_text_static(selected_date, theme);
-                    frame.render_widget(help, main_chunks[2]);
-                } else {
-                    // Create vertical layout for calendar and bullets
-                    let main_chunks = Layout::default()
-                        .direction(Direction::Vertical)
-                        .constraints([
-                            Constraint::Length(CALENDAR_HEIGHT), // Main week view
-                            Constraint::Length(BULLET_HEIGHT),   // Bullet display
-                        ])
-                        .split(centered_area);
-
-                    frame.render_widget(table, main_chunks[0]);
-
-                    // Create and draw bullet display
-                    let bullet_display =
-                        Self::create_bullet_display(selected_entry.as_ref(), theme);
-                    frame.render_widget(bullet_display, main_chunks[1]);
+                let columns = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints(vec![
+                        Constraint::Ratio(1, previews.len() as u32);
+                        previews.len()
+                    ])
+                    .split(preview_area);
+
+                for ((date, lines), area) in previews.into_iter().zip(columns.iter()) {
+                    let title = if show_split {
+                        Some(date.format("%a %b %d").to_string())
+                    } else {
+                        last_edited.clone()
+                    };
+                    let display =
+                        Self::create_bullet_display(lines, scroll, area.height, title, theme);
+                    frame.render_widget(display, *area);
+                }
+
+                if max_scroll > 0 {
+                    let mut scrollbar_state =
+                        ScrollbarState::new(max_scroll as usize + 1).position(scroll as usize);
+                    frame.render_stateful_widget(
+                        Scrollbar::new(ScrollbarOrientation::VerticalRight)
+                            .style(Style::default().fg(theme.colors.dimmed)),
+                        preview_area,
+                        &mut scrollbar_state,
+                    );
+                }
+            }
+
+            let mut next_chunk = 2;
+            if let Some(related) = related_line {
+                frame.render_widget(related, main_chunks[next_chunk]);
+                next_chunk += 1;
+            }
+            if let Some(prompt) = filter_prompt {
+                let prompt = Paragraph::new(Line::from(vec![Span::styled(
+                    prompt,
+                    Style::default().fg(theme.colors.accent),
+                )]));
+                frame.render_widget(prompt, main_chunks[next_chunk]);
+                next_chunk += 1;
+            }
+
+            if show_help {
+                let help = Self::create_help_text_static(selected_date, theme, date_formats);
+                frame.render_widget(help, main_chunks[next_chunk]);
+            }
+
+            if let Some(error) = error {
+                let status = Paragraph::new(Line::from(vec![Span::styled(
+                    format!("Storage error: {} (r to retry)", error),
+                    Style::default().fg(theme.colors.error_text),
+                )]));
+                frame.render_widget(status, main_chunks[main_chunks.len() - 1]);
+            }
+        })?;
+        self.calendar_area = calendar_area;
+        self.minimap_area = minimap_area;
+        self.preview_area = drawn_preview_area;
+
+        Ok(())
+    }
+
+    /// Run the week view TUI loop
+    pub fn run(&mut self) -> io::Result<WeekViewResult> {
+        loop {
+            // Check for exit condition before drawing
+            if self.should_exit {
+                break;
+            }
+
+            if self.watermark.is_none() || self.watermark_checked.elapsed() >= CHANGE_POLL {
+                self.check_for_changes();
+            }
+
+            if self.data_stale {
+                // Moving to another week means querying a new set of dates, which
+                // can be slow on large journals: show a loading label meanwhile
+                if self.loaded_week_start != Some(self.current_week_start) {
+                    self.loading = true;
+                    self.draw()?;
+                    self.loading = false;
                 }
-            })?;
+                // Keep showing the last good data, with the failure in the status bar
+                self.error = self.refresh_data().err().map(|e| format!("{:#}", e));
+                self.data_stale = false;
+                self.needs_redraw = true;
+            }
+
+            // Only redraw when something actually changed
+            if self.needs_redraw {
+                self.draw()?;
+                self.needs_redraw = false;
+            }
 
             // Handle events with timeout to prevent blocking indefinitely
-            match poll(std::time::Duration::from_millis(100))? {
-                true => {
-                    match event::read()? {
-                        Event::Key(key) => {
-                            self.handle_key_event(key);
-                        }
-                        Event::Resize(_, _) => {
-                            // Terminal was resized, continue to redraw
-                            continue;
-                        }
-                        _ => {
-                            // Other events (mouse, focus, etc.) - ignore
-                            continue;
+            if poll(std::time::Duration::from_millis(100))? {
+                match event::read()? {
+                    Event::Key(key) => {
+                        self.handle_key_event(key);
+                        if self.show_year {
+                            self.show_year = false;
+                            self.run_year_view()?;
                         }
+                        self.needs_redraw = true;
+                    }
+                    Event::Mouse(mouse) => {
+                        self.handle_mouse_event(mouse);
+                        self.needs_redraw = true;
+                    }
+                    Event::Resize(_, _) => {
+                        // Terminal was resized, redraw at the new size
+                        self.needs_redraw = true;
+                    }
+                    _ => {
+                        // Other events (focus, paste) - ignore
                     }
-                }
-                false => {
-                    // No event received, continue loop
-                    continue;
                 }
             }
         }
@@ -596,21 +2296,73 @@ impl<'a> WeekView<'a> {
         self.cleanup()?;
 
         // Return result based on user action
-        if self.should_edit {
+        if let Some((from, to)) = self.paste_request {
+            Ok(WeekViewResult::PasteRequested { from, to })
+        } else if let Some((date, id, to)) = self.convert_request {
+            Ok(WeekViewResult::ConvertRequested { date, id, to })
+        } else if self.should_edit {
             Ok(WeekViewResult::EditRequested(self.selected_date))
         } else {
             Ok(WeekViewResult::Exited(self.selected_date))
         }
     }
 
+    /// Show the year heatmap on this terminal and jump to the day picked there
+    fn run_year_view(&mut self) -> io::Result<()> {
+        let storage = self.storage.clone();
+        let journal = &*self.journal;
+        let load_counts = |year: i32| -> anyhow::Result<HashMap<NaiveDate, u64>> {
+            let range = DateRange::year(year);
+            match &storage {
+                Some(storage) => storage.daily_bullet_counts(range),
+                // Without storage only presence is known
+                None => Ok(journal
+                    .list_dates_in_range(range)?
+                    .into_iter()
+                    .map(|date| (date, 1))
+                    .collect()),
+            }
+        };
+
+        let counts = load_counts(self.selected_date.year()).map_err(io::Error::other)?;
+        let mut year_view = YearView::new(self.selected_date, counts)
+            .with_theme(self.theme.clone())
+            .with_date_formats(self.date_formats.clone());
+        if let Some(date) = year_view.run(&mut self.terminal, load_counts)? {
+            self.selected_date = date;
+            self.update_current_week();
+        }
+        self.terminal.clear()?;
+        Ok(())
+    }
+
     /// Explicit cleanup method
     fn cleanup(&mut self) -> io::Result<()> {
+        let _ = self.terminal.backend_mut().execute(DisableMouseCapture);
         disable_raw_mode()?;
         self.terminal.backend_mut().execute(LeaveAlternateScreen)?;
         Ok(())
     }
 }
 
+/// Quarter of the year `date` falls in, as `(year, 0..=3)`
+fn quarter(date: NaiveDate) -> (i32, u32) {
+    (date.year(), date.month0() / 3)
+}
+
+/// Starts of the weeks overlapping the month of `date`
+fn month_weeks(date: NaiveDate, first_day: Weekday) -> Vec<NaiveDate> {
+    let first = date.with_day(1).expect("every month has a 1st");
+    let mut weeks = vec![DateRange::week_containing(first, first_day).start];
+    loop {
+        let next = *weeks.last().expect("starts with a week") + Duration::weeks(1);
+        if next.month() != date.month() {
+            return weeks;
+        }
+        weeks.push(next);
+    }
+}
+
 impl<'a> Drop for WeekView<'a> {
     fn drop(&mut self) {
         // Fallback cleanup if explicit cleanup wasn't called
@@ -0,0 +1,236 @@
+use super::theme::Theme;
+use crate::application::DateFormats;
+use chrono::{Datelike, Duration, NaiveDate};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    Terminal,
+    backend::CrosstermBackend,
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+};
+use std::collections::HashMap;
+use std::io::{self, Stdout};
+
+const WEEKDAY_LABELS: [&str; 7] = ["Sun", "", "Tue", "", "Thu", "", "Sat"];
+
+/// Heatmap shades from no entry to the busiest days
+const HEAT_COLORS: [Color; 5] = [
+    Color::Rgb(40, 40, 40),
+    Color::Rgb(14, 68, 41),
+    Color::Rgb(0, 109, 50),
+    Color::Rgb(38, 166, 65),
+    Color::Rgb(57, 211, 83),
+];
+
+/// GitHub-style heatmap of a year, one cell per day shaded by the number of
+/// bullets written. Runs on a terminal owned by the calling view.
+pub struct YearView {
+    year: i32,
+    cursor: NaiveDate,
+    /// Bullet counts per day of `year`
+    counts: HashMap<NaiveDate, u64>,
+    theme: Theme,
+    date_formats: DateFormats,
+    /// Set when the user picks a day or leaves
+    outcome: Option<Option<NaiveDate>>,
+    /// Set when the cursor moves to another year and its counts must be loaded
+    year_changed: bool,
+}
+
+impl YearView {
+    pub fn new(cursor: NaiveDate, counts: HashMap<NaiveDate, u64>) -> Self {
+        Self {
+            year: cursor.year(),
+            cursor,
+            counts,
+            theme: Theme::default(),
+            date_formats: DateFormats::default(),
+            outcome: None,
+            year_changed: false,
+        }
+    }
+
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Use custom date formats for the status line
+    pub fn with_date_formats(mut self, date_formats: DateFormats) -> Self {
+        self.date_formats = date_formats;
+        self
+    }
+
+    /// Show the heatmap until a day is chosen with Enter (returned) or the view
+    /// is left with q/Esc. `load_counts` is called whenever the cursor moves
+    /// into another year.
+    pub fn run(
+        &mut self,
+        terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+        mut load_counts: impl FnMut(i32) -> anyhow::Result<HashMap<NaiveDate, u64>>,
+    ) -> io::Result<Option<NaiveDate>> {
+        loop {
+            if self.year_changed {
+                self.year = self.cursor.year();
+                self.counts = load_counts(self.year).map_err(io::Error::other)?;
+                self.year_changed = false;
+            }
+
+            self.draw(terminal)?;
+
+            if let Event::Key(key) = event::read()? {
+                self.handle_key_event(key);
+            }
+            if let Some(outcome) = self.outcome {
+                return Ok(outcome);
+            }
+        }
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) {
+        let moved = match (key.code, key.modifiers) {
+            (KeyCode::Char('q'), _) | (KeyCode::Esc, _) | (KeyCode::Char('Y'), _) => {
+                self.outcome = Some(None);
+                return;
+            }
+            (KeyCode::Char('c'), KeyModifiers::CONTROL) => {
+                self.outcome = Some(None);
+                return;
+            }
+            (KeyCode::Enter, _) => {
+                self.outcome = Some(Some(self.cursor));
+                return;
+            }
+            // Columns are weeks and rows are weekdays
+            (KeyCode::Left, _) | (KeyCode::Char('h'), _) => self.cursor - Duration::weeks(1),
+            (KeyCode::Right, _) | (KeyCode::Char('l'), _) => self.cursor + Duration::weeks(1),
+            (KeyCode::Up, _) | (KeyCode::Char('k'), _) => self.cursor - Duration::days(1),
+            (KeyCode::Down, _) | (KeyCode::Char('j'), _) => self.cursor + Duration::days(1),
+            (KeyCode::PageUp, _) => self.cursor.with_year(self.year - 1).unwrap_or(self.cursor),
+            (KeyCode::PageDown, _) => self.cursor.with_year(self.year + 1).unwrap_or(self.cursor),
+            _ => return,
+        };
+
+        self.cursor = moved;
+        self.year_changed = moved.year() != self.year;
+    }
+
+    /// Shade index for a day relative to the busiest day of the year
+    fn heat_level(&self, count: u64, max: u64) -> usize {
+        if count == 0 || max == 0 {
+            return 0;
+        }
+        (1 + (count - 1) * 4 / max) as usize
+    }
+
+    fn heatmap_lines(&self) -> Vec<Line<'static>> {
+        let first = NaiveDate::from_ymd_opt(self.year, 1, 1).expect("Invalid year");
+        let last = NaiveDate::from_ymd_opt(self.year, 12, 31).expect("Invalid year");
+        let grid_start = first - Duration::days(first.weekday().num_days_from_sunday() as i64);
+        let weeks = ((last - grid_start).num_days() / 7 + 1) as usize;
+        let max = self.counts.values().copied().max().unwrap_or(0);
+
+        // Month names above the week in which each month starts, padded so
+        // every line has the same width and stays aligned when centered
+        let mut header = " ".repeat(4);
+        for week in 0..weeks {
+            if header.chars().count() > 4 + week {
+                continue; // Still covered by the previous month name
+            }
+            let week_start = grid_start + Duration::weeks(week as i64);
+            match (0..7)
+                .map(|d| week_start + Duration::days(d))
+                .find(|d| d.year() == self.year && d.day() == 1)
+            {
+                Some(month_start) => header.push_str(&month_start.format("%b").to_string()),
+                None => header.push(' '),
+            }
+        }
+        let header: String = format!("{:<width$}", header, width = 4 + weeks)
+            .chars()
+            .take(4 + weeks)
+            .collect();
+        let mut lines = vec![Line::from(Span::styled(
+            header,
+            Style::default().fg(self.theme.colors.header),
+        ))];
+
+        for (weekday, label) in WEEKDAY_LABELS.iter().enumerate() {
+            let mut spans = vec![Span::styled(
+                format!("{:<4}", label),
+                Style::default().fg(self.theme.colors.dimmed),
+            )];
+            for week in 0..weeks {
+                let date =
+                    grid_start + Duration::weeks(week as i64) + Duration::days(weekday as i64);
+                if date.year() != self.year {
+                    spans.push(Span::raw(" "));
+                    continue;
+                }
+                let count = self.counts.get(&date).copied().unwrap_or(0);
+                let mut style = Style::default().fg(HEAT_COLORS[self.heat_level(count, max)]);
+                if date == self.cursor {
+                    style = style
+                        .bg(self.theme.colors.selected)
+                        .add_modifier(Modifier::BOLD);
+                }
+                spans.push(Span::styled("■", style));
+            }
+            lines.push(Line::from(spans));
+        }
+        lines
+    }
+
+    fn draw(&self, terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> io::Result<()> {
+        let heatmap = self.heatmap_lines();
+        let count = self.counts.get(&self.cursor).copied().unwrap_or(0);
+        let entries = self.counts.values().filter(|c| **c > 0).count();
+        let status = vec![
+            Line::from(Span::styled(
+                format!(
+                    "{}: {} bullets",
+                    self.cursor.format(&self.date_formats.long),
+                    count
+                ),
+                Style::default().fg(self.theme.colors.focused),
+            )),
+            Line::from(Span::styled(
+                format!(
+                    "{} days with entries • ←→/hl=Week • ↑↓/jk=Day • PgUp/PgDn=Year • Enter=Open • q=Back",
+                    entries
+                ),
+                Style::default().fg(self.theme.colors.dimmed),
+            )),
+        ];
+
+        terminal.draw(|frame| {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Min(0),
+                    Constraint::Length(10),
+                    Constraint::Length(2),
+                    Constraint::Min(0),
+                ])
+                .split(frame.area());
+
+            let block = Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(self.theme.colors.border))
+                .title(format!(" {} ", self.year));
+            frame.render_widget(
+                Paragraph::new(heatmap)
+                    .block(block)
+                    .alignment(Alignment::Center),
+                chunks[1],
+            );
+            frame.render_widget(
+                Paragraph::new(status).alignment(Alignment::Center),
+                chunks[2],
+            );
+        })?;
+        Ok(())
+    }
+}
@@ -0,0 +1,207 @@
+use crate::application::JournalApp;
+use crate::infrastructure::{EntryRepository, FileSystemRepository, Reconciler, merge_entries};
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use notify::{RecursiveMode, Watcher};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::mpsc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Quiet time after a change before files are read, so an editor's
+/// write-rename-chmod sequence (or a Syncthing transfer) is handled once
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// What syncing one changed file did
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchOutcome {
+    /// File and database already agreed, e.g. after our own write
+    Unchanged,
+    /// The database was updated from the file
+    Saved,
+    /// Both sides changed since the last sync; the file's bullets were merged
+    /// into the database copy and written back to the file
+    Merged,
+    /// The file was removed; the database copy is kept
+    Removed,
+}
+
+/// Keeps the database in step with edits to the markdown files, remembering
+/// the content both sides last agreed on to tell external edits from conflicts
+pub struct FileSync {
+    files: FileSystemRepository,
+    /// Content hash per date as of the last time file and database matched
+    synced: HashMap<NaiveDate, u64>,
+}
+
+impl FileSync {
+    /// Start from the entries whose file and database copies currently match
+    pub fn new(app: &JournalApp) -> Result<Self> {
+        let files = app.config().file_repository();
+        let mut synced = HashMap::new();
+        {
+            let reconciler = Reconciler::new(&files, app.storage().as_ref());
+            for date in files.all_dates()? {
+                let (Some(file), Some(db)) = (files.load(date)?, app.storage().load_entry(date)?)
+                else {
+                    continue;
+                };
+                let hash = reconciler.content_hash(&file)?;
+                if hash == reconciler.content_hash(&db)? {
+                    synced.insert(date, hash);
+                }
+            }
+        }
+        Ok(Self { files, synced })
+    }
+
+    pub fn files(&self) -> &FileSystemRepository {
+        &self.files
+    }
+
+    /// Bring the database up to date with the file for `date`, saving through
+    /// the app so write hooks run as for an editor save
+    pub fn sync_date(&mut self, app: &mut JournalApp, date: NaiveDate) -> Result<WatchOutcome> {
+        let Some(file) = self.files.load(date)? else {
+            self.synced.remove(&date);
+            return Ok(WatchOutcome::Removed);
+        };
+        let storage = app.storage().clone();
+        let db = storage.load_entry(date)?;
+
+        let reconciler = Reconciler::new(&self.files, storage.as_ref());
+        let file_hash = reconciler.content_hash(&file)?;
+        let db_hash = db
+            .as_ref()
+            .map(|e| reconciler.content_hash(e))
+            .transpose()?;
+        if db_hash == Some(file_hash) {
+            self.synced.insert(date, file_hash);
+            return Ok(WatchOutcome::Unchanged);
+        }
+
+        // The database moved on since the last sync too (or was never in sync)
+        let conflict = db_hash.is_some() && db_hash != self.synced.get(&date).copied();
+        let (resolved, outcome) = match db {
+            Some(db) if conflict => (merge_entries(&db, &file), WatchOutcome::Merged),
+            _ => (file, WatchOutcome::Saved),
+        };
+
+        let markdown = app.parser().serialize(&resolved)?;
+        app.save_markdown(date, &markdown, false)?;
+        if outcome == WatchOutcome::Merged {
+            // Our own write comes back as an event, which then finds both sides equal
+            self.files.save(resolved.clone())?;
+        }
+        self.synced
+            .insert(date, reconciler.content_hash(&resolved)?);
+        Ok(outcome)
+    }
+}
+
+/// Watch the data directory and sync every changed entry file into the
+/// database until interrupted
+pub fn run(app: &mut JournalApp) -> Result<()> {
+    let mut sync = FileSync::new(app)?;
+    let data_dir = sync.files().data_dir().clone();
+    std::fs::create_dir_all(&data_dir)
+        .with_context(|| format!("Failed to create {}", data_dir.display()))?;
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx)?;
+    watcher
+        .watch(&data_dir, RecursiveMode::Recursive)
+        .with_context(|| format!("Failed to watch {}", data_dir.display()))?;
+    println!("Watching {} (Ctrl+C to stop)", data_dir.display());
+
+    while let Ok(event) = rx.recv() {
+        let mut events = vec![event];
+        while let Ok(event) = rx.recv_timeout(DEBOUNCE) {
+            events.push(event);
+        }
+
+        let mut dates = BTreeSet::new();
+        for event in events {
+            match event {
+                Ok(event) => dates.extend(
+                    event
+                        .paths
+                        .iter()
+                        .filter_map(|path| sync.files().date_for_path(path)),
+                ),
+                Err(e) => warn!("File watch error: {}", e),
+            }
+        }
+
+        for date in dates {
+            match sync.sync_date(app, date) {
+                Ok(WatchOutcome::Unchanged) => {}
+                Ok(WatchOutcome::Saved) => info!("{}: saved changes from file", date),
+                Ok(WatchOutcome::Merged) => warn!(
+                    "{}: conflict, changed in both the file and the database; merged the file's bullets in",
+                    date
+                ),
+                Ok(WatchOutcome::Removed) => {
+                    info!("{}: file removed, database copy kept", date)
+                }
+                Err(e) => warn!("{}: failed to sync file: {:#}", date, e),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(all(test, feature = "duckdb"))]
+mod tests {
+    use super::*;
+    use crate::application::Config;
+    use crate::entities::{Bullet, BulletType, Entry};
+    use crate::infrastructure::DuckDbStorage;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_sync_saves_edits_and_merges_conflicts() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut app = JournalApp::with_storage(
+            Arc::new(DuckDbStorage::in_memory().unwrap()),
+            Config::load(temp_dir.path().into()),
+        );
+        let files = app.config().file_repository();
+        let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+
+        let mut entry = Entry::new(date);
+        entry.add_bullet(Bullet::new("Dentist at 3", BulletType::Event));
+        files.save(entry.clone()).unwrap();
+        app.storage().save_entry(&entry).unwrap();
+        let mut sync = FileSync::new(&app).unwrap();
+        assert_eq!(
+            sync.sync_date(&mut app, date).unwrap(),
+            WatchOutcome::Unchanged
+        );
+
+        // Edited only in the file
+        entry.add_bullet(Bullet::new("Synced from laptop", BulletType::Note));
+        files.save(entry.clone()).unwrap();
+        assert_eq!(sync.sync_date(&mut app, date).unwrap(), WatchOutcome::Saved);
+        let db = app.storage().load_entry(date).unwrap().unwrap();
+        assert_eq!(db.total_bullets(), 2);
+
+        // Edited on both sides
+        app.append_bullet(date, Bullet::new("Added in journo", BulletType::Task))
+            .unwrap();
+        entry.add_bullet(Bullet::new("Added in vim", BulletType::Task));
+        files.save(entry).unwrap();
+        assert_eq!(
+            sync.sync_date(&mut app, date).unwrap(),
+            WatchOutcome::Merged
+        );
+        let db = app.storage().load_entry(date).unwrap().unwrap();
+        assert_eq!(db.total_bullets(), 4);
+        assert_eq!(
+            sync.sync_date(&mut app, date).unwrap(),
+            WatchOutcome::Unchanged
+        );
+    }
+}
@@ -1,10 +1,33 @@
-mod application;
-mod entities;
-mod infrastructure;
+use journo::{application, entities, infrastructure};
 
-use application::{JournalApp, WeekView, WeekViewResult};
-use chrono::{Local, NaiveDate};
-use clap::{Parser, Subcommand};
+use anyhow::Context;
+use application::{
+    BulletAction, CollectionView, CollectionViewResult, ColorMode, Config,
+    DEFAULT_REFLECTION_TEMPLATE, JournalApp, PlanView, PlanViewResult, Theme, WeekPlanView,
+    WeekPlanViewResult, WeekView, WeekViewResult, api, brief_summary, daemon, doctor, init,
+    pipe::{PipeOutput, transform_entry},
+    plan::next_week_start,
+    rest, serve, tmux_summary,
+    trends::{MetricOutput, TrendPeriod},
+    watch,
+};
+use chrono::{Datelike, NaiveDate, Weekday};
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use clap_complete::CompleteEnv;
+use clap_complete::engine::{ArgValueCandidates, ArgValueCompleter, CompletionCandidate};
+use clap_complete::env::{self, EnvCompleter};
+use entities::{BulletType, DateRange, TaskState};
+use infrastructure::storage::JournalStorage;
+use infrastructure::{
+    ExporterRegistry, MarkdownParser, MergePolicy, TableFormat, import_dayone, import_jrnl,
+    import_obsidian, init_logging,
+};
+use std::collections::BTreeSet;
+use std::ffi::OsStr;
+use std::io::Read;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Instant;
 
 #[derive(Parser)]
 #[command(name = "journo")]
@@ -13,6 +36,18 @@ use clap::{Parser, Subcommand};
 pub struct Cli {
     #[command(subcommand)]
     pub command: Option<Commands>,
+    /// Refuse to save edits with lines outside the known section headers
+    #[arg(long, global = true)]
+    pub strict: bool,
+    /// Log more to stderr: -v for info, -vv for debug
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    pub verbose: u8,
+    /// Don't point out days going over the `[limits]` in config.toml
+    #[arg(long, global = true)]
+    pub no_nudge: bool,
+    /// Color printed output: auto (terminals without NO_COLOR), always or never
+    #[arg(long, global = true, default_value = "auto")]
+    pub color: ColorMode,
 }
 
 #[derive(Subcommand)]
@@ -20,7 +55,31 @@ pub enum Commands {
     /// Create/edit today's entry (opens $EDITOR)
     New {
         /// Specific date (YYYY-MM-DD format, defaults to today)
+        #[arg(short, long, add = ArgValueCandidates::new(entry_dates))]
+        date: Option<String>,
+        /// Start a new `## HH:MM` session at the current time, kept apart
+        /// from the day's earlier writing
+        #[arg(short, long)]
+        session: bool,
+        /// Insert a journaling prompt as a comment under Notes
         #[arg(short, long)]
+        prompt: bool,
+        /// Free-write on an empty buffer without headers, kept below the
+        /// day's bullets
+        #[arg(short, long, conflicts_with_all = ["session", "prompt"])]
+        free: bool,
+    },
+    /// Reopen the editor on the draft kept after a failed edit
+    Recover {
+        /// Date of the draft (YYYY-MM-DD format, defaults to today)
+        #[arg(add = ArgValueCandidates::new(entry_dates))]
+        date: Option<String>,
+    },
+    /// Print the markdown file of a day under `data/`, writing it first if
+    /// missing, so other editors can open it (run `journo watch` to save edits)
+    Path {
+        /// Date of the entry (YYYY-MM-DD format, defaults to today)
+        #[arg(add = ArgValueCandidates::new(entry_dates))]
         date: Option<String>,
     },
     /// Start the interactive TUI
@@ -28,75 +87,917 @@ pub enum Commands {
     /// Start the week view TUI
     Week {
         /// Specific date to focus on (YYYY-MM-DD format, defaults to today)
+        #[arg(short, long, add = ArgValueCandidates::new(entry_dates))]
+        date: Option<String>,
+    },
+    /// Plan a day on a timeline of its time blocks (`10:00-11:30 Deep work`)
+    Plan {
+        /// Day to plan (YYYY-MM-DD format, defaults to today)
+        #[arg(short, long, add = ArgValueCandidates::new(entry_dates))]
+        date: Option<String>,
+    },
+    /// Review last week's unfinished tasks and missteps beside a plan for the
+    /// week ahead
+    PlanWeek {
+        /// Plan the week starting on or after this date (YYYY-MM-DD format,
+        /// defaults to today)
         #[arg(short, long)]
         date: Option<String>,
     },
+    /// Show writing statistics
+    Stats {
+        /// Write a year heatmap of the days written to this .svg or .png file instead
+        #[arg(long, value_name = "FILE")]
+        heatmap: Option<PathBuf>,
+        /// Year of the heatmap (defaults to the current year)
+        #[arg(long, requires = "heatmap")]
+        year: Option<i32>,
+        /// Shade the heatmap by words written instead of bullets
+        #[arg(long, requires = "heatmap")]
+        words: bool,
+    },
+    /// Remind you to write if days have gone by without an entry, e.g. from
+    /// cron or a shell profile
+    Nag {
+        /// Send the reminder through `notify_command` instead of printing it
+        #[arg(long)]
+        notify: bool,
+    },
+    /// Show the journal's backend, size, date span, schema level, directories and plugins
+    Info,
+    /// Show how often a term comes up and the mood of your writing over time
+    Trends {
+        /// Count bullets containing this word or phrase (ignoring case)
+        #[arg(short, long)]
+        term: Option<String>,
+        /// Bucket by week or month
+        #[arg(long, default_value = "week")]
+        by: TrendPeriod,
+        /// Number of weeks or months to show, ending with the current one
+        #[arg(short = 'n', long, default_value_t = 12)]
+        periods: usize,
+    },
+    /// Search bullets by content, type and task state
+    Search {
+        /// Query, e.g. `type:task state:pending "invoice" after:2024-01-01 tag:work`;
+        /// plain words are matched in bullet content, ignoring case
+        #[arg(default_value = "", add = ArgValueCompleter::new(tags))]
+        query: String,
+        /// Only include bullets of these types (repeatable)
+        #[arg(short, long = "type")]
+        types: Vec<BulletType>,
+        /// Only include tasks in these states (repeatable)
+        #[arg(short, long = "state")]
+        states: Vec<TaskState>,
+        /// Restrict results to the range around today
+        #[arg(short, long, value_enum, default_value_t = RangeArg::All)]
+        range: RangeArg,
+        /// Show this many neighboring bullets around each match
+        #[arg(short = 'C', long, default_value_t = 0)]
+        context: usize,
+    },
+    /// Export the journal to another format
+    Export {
+        /// Output format: html, obsidian, csv or parquet (raw tables, DuckDB
+        /// only) or a registered exporter such as json, markdown or pdf
+        #[arg(short, long, default_value = "html", add = ArgValueCandidates::new(export_formats))]
+        format: String,
+        /// Output directory (e.g. `<vault>/daily/` for Obsidian), or the file
+        /// to write for single-file formats
+        #[arg(short, long)]
+        out: PathBuf,
+        /// Restrict the export to the range around today
+        #[arg(short, long, value_enum, default_value_t = RangeArg::All)]
+        range: RangeArg,
+    },
+    /// Import entries from another journaling tool
+    Import {
+        /// Format of the source journal
+        #[arg(short, long, value_enum)]
+        format: ImportFormat,
+        /// jrnl file, Day One JSON export (file or directory) or Obsidian vault folder
+        path: PathBuf,
+        /// Report what would be imported without saving anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Summarize completed tasks, events and insights with the digest template
+    Digest {
+        /// Range around today to summarize
+        #[arg(short, long, value_enum, default_value_t = RangeArg::Week)]
+        range: RangeArg,
+        /// Shorthand for `--range week`
+        #[arg(long, conflicts_with = "range")]
+        week: bool,
+        /// Write the digest to this file instead of stdout
+        #[arg(short, long)]
+        out: Option<PathBuf>,
+        /// Email the digest using the [digest] settings in config.toml
+        #[arg(long)]
+        email: bool,
+    },
+    /// Reconcile markdown files in the data directory with the database
+    SyncFiles {
+        /// Resolve every conflict with this policy (file, db, newer or merge) instead of asking
+        #[arg(short, long)]
+        policy: Option<MergePolicy>,
+        /// Only report differences without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Watch the data directory and save external edits to markdown files
+    /// into the database as they happen
+    Watch,
+    /// Print the shell completion script (e.g. `source <(journo completions bash)`)
+    Completions {
+        #[arg(value_enum)]
+        shell: CompletionShell,
+    },
+    /// Interactively set up a journal directory, config file and database
+    Init,
+    /// Diagnose problems with the journal setup
+    Doctor,
+    /// Read an entry's markdown from stdin and print it as JSON
+    Parse {
+        /// Date of the entry (YYYY-MM-DD format, defaults to today)
+        date: Option<String>,
+    },
+    /// Read an entry's markdown from stdin and print it the way journo saves it
+    Format {
+        /// Date of the entry (YYYY-MM-DD format, defaults to today)
+        date: Option<String>,
+    },
+    /// List people mentioned with @name and how often
+    People,
+    /// Show every bullet mentioning a person
+    Person {
+        /// Person to look up, with or without the leading @
+        #[arg(add = ArgValueCandidates::new(people))]
+        name: String,
+    },
+    /// Gather every bullet of a type into one chronological list, e.g.
+    /// `collection insights --range year`
+    Collection {
+        /// Bullet type or section name, e.g. insight or insights
+        #[arg(value_parser = BulletType::from_name)]
+        bullet_type: BulletType,
+        /// Restrict the collection to the range around today
+        #[arg(short, long, value_enum, default_value_t = RangeArg::All)]
+        range: RangeArg,
+        /// Browse the collection in a terminal view instead of printing it
+        #[arg(long)]
+        browse: bool,
+    },
+    /// List links found in bullets
+    Links {
+        /// Restrict the list to the range around today
+        #[arg(short, long, value_enum, default_value_t = RangeArg::All)]
+        range: RangeArg,
+        /// Fetch page titles for links that don't have one yet
+        #[arg(long)]
+        fetch_titles: bool,
+    },
+    /// Pin a day as a favorite, e.g. a milestone worth finding again
+    Pin {
+        /// Date to pin (YYYY-MM-DD format, defaults to today)
+        #[arg(add = ArgValueCandidates::new(entry_dates))]
+        date: Option<String>,
+        /// Unpin the day instead
+        #[arg(long)]
+        remove: bool,
+    },
+    /// List pinned days
+    Pins,
+    /// Print a journaling prompt, avoiding ones shown in the last 30 days
+    Prompt,
+    /// Print a day's entry
+    Show {
+        /// Specific date (YYYY-MM-DD format, defaults to today)
+        #[arg(short, long, add = ArgValueCandidates::new(entry_dates))]
+        date: Option<String>,
+        /// Plain sentences for screen readers, with task states spelled out
+        /// instead of symbols
+        #[arg(long)]
+        screenreader: bool,
+    },
+    /// Show a time-ordered schedule for a day
+    Agenda {
+        /// Specific date (YYYY-MM-DD format, defaults to today)
+        #[arg(short, long, add = ArgValueCandidates::new(entry_dates))]
+        date: Option<String>,
+        /// Only list bullets marked with this @@context
+        #[arg(short, long, add = ArgValueCandidates::new(contexts))]
+        context: Option<String>,
+    },
+    /// Today's agenda, or with --brief/--tmux a one-line summary for shell
+    /// prompts and status bars
+    Today {
+        /// Print only a summary like "3 tasks (1 done), 2 events"
+        #[arg(long)]
+        brief: bool,
+        /// Print the summary with tmux status-line styles
+        #[arg(long, conflicts_with = "brief")]
+        tmux: bool,
+    },
+    /// Listen on a Unix socket for `ADD <date> <type> <content>` quick captures
+    Daemon {
+        /// Socket path (defaults to journo.sock in the journal directory)
+        #[arg(short, long)]
+        socket: Option<PathBuf>,
+    },
+    /// Serve JSON-RPC requests on stdin/stdout for editor integrations
+    Api,
+    /// Serve a small web interface (calendar, entries, search) over HTTP
+    Serve {
+        /// Port to listen on
+        #[arg(short, long, default_value_t = 8080)]
+        port: u16,
+        /// Address to bind; use 0.0.0.0 to reach it from other devices on the
+        /// network, which needs `api_token` from `[server]` to sign in with
+        #[arg(long, default_value = "127.0.0.1")]
+        host: String,
+        /// Serve only the JSON API, authenticated with `api_token` from `[server]`
+        #[arg(long)]
+        api_only: bool,
+    },
+    /// Edit an entry answering the questions of a prompt template
+    Reflect {
+        /// Specific date (YYYY-MM-DD format, defaults to today)
+        #[arg(short, long, add = ArgValueCandidates::new(entry_dates))]
+        date: Option<String>,
+        /// Template name from config.toml
+        #[arg(short, long, default_value = DEFAULT_REFLECTION_TEMPLATE)]
+        template: String,
+    },
+    /// Run a focus timer on one of today's tasks, creating it if needed
+    Focus {
+        /// Text of the task to work on
+        task: String,
+        /// Length of the session in minutes
+        #[arg(short, long, default_value_t = 25)]
+        minutes: u64,
+    },
+    /// Move markdown entry files to a new path layout (e.g. "%Y-%m-%d.md")
+    Relayout {
+        /// strftime pattern relative to the data directory
+        layout: String,
+        /// Only show which files would be moved
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Change a single bullet, for scripts and editor keybindings
+    Bullet {
+        #[command(subcommand)]
+        command: BulletCommand,
+    },
+    /// Copy bullets from one entry to another
+    Copy {
+        /// Date to copy from (YYYY-MM-DD)
+        #[arg(add = ArgValueCandidates::new(entry_dates))]
+        from: String,
+        /// Date to copy to (YYYY-MM-DD)
+        #[arg(add = ArgValueCandidates::new(entry_dates))]
+        to: String,
+        /// Only copy bullets of this type
+        #[arg(short, long = "type")]
+        bullet_type: Option<BulletType>,
+        /// Only copy tasks that are still pending
+        #[arg(long)]
+        pending_only: bool,
+    },
+    /// Combine two entries into one, e.g. a day split by a timezone mishap
+    Merge {
+        /// First entry to merge (YYYY-MM-DD)
+        #[arg(add = ArgValueCandidates::new(entry_dates))]
+        first: String,
+        /// Second entry to merge (YYYY-MM-DD)
+        #[arg(add = ArgValueCandidates::new(entry_dates))]
+        second: String,
+        /// Date of the merged entry; the other entries go to the trash
+        #[arg(long, add = ArgValueCandidates::new(entry_dates))]
+        into: String,
+    },
+    /// Change a bullet's type in place, e.g. a note that turned out to be an insight
+    Convert {
+        /// Date of the entry (YYYY-MM-DD)
+        #[arg(add = ArgValueCandidates::new(entry_dates))]
+        date: String,
+        /// Bullet id, or <type>:<n> for the n-th bullet of a type (e.g. note:2)
+        bullet: String,
+        /// Type to convert the bullet to
+        #[arg(long)]
+        to: BulletType,
+    },
+    /// Show the readings of a metric written as `#name:value` in bullets
+    Metric {
+        /// Metric name, e.g. `weight` for `#weight:82.5`
+        #[arg(add = ArgValueCandidates::new(metrics))]
+        name: String,
+        /// Draw a chart of the daily averages
+        #[arg(long, conflicts_with = "csv")]
+        chart: bool,
+        /// Print `date,value` rows for a spreadsheet
+        #[arg(long)]
+        csv: bool,
+        /// Restrict readings to the range around today
+        #[arg(short, long, value_enum, default_value_t = RangeArg::All)]
+        range: RangeArg,
+    },
+    /// List tasks left pending for a while and decide what to do with each
+    Stale {
+        /// Minimum age in days of the tasks to list
+        #[arg(long, default_value_t = 14)]
+        days: u32,
+    },
+    /// Manage database snapshots in the backups directory
+    Backups {
+        #[command(subcommand)]
+        command: BackupsCommand,
+    },
+    /// Manage events imported from an external calendar
+    Calendar {
+        #[command(subcommand)]
+        command: CalendarCommand,
+    },
+    /// Rename or merge hashtags across the whole journal
+    Tag {
+        #[command(subcommand)]
+        command: TagCommand,
+    },
+    /// Change the state of many tasks at once, e.g.
+    /// `tasks complete --match standup --range week`
+    Tasks {
+        #[command(subcommand)]
+        command: TasksCommand,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum BulletCommand {
+    /// Mark a task completed
+    Complete {
+        /// Date of the entry (YYYY-MM-DD)
+        #[arg(add = ArgValueCandidates::new(entry_dates))]
+        date: String,
+        /// Bullet id, or <type>:<n> for the n-th bullet of a type (e.g. task:2)
+        bullet: String,
+        /// Complete even if tasks it waits for are still pending
+        #[arg(long)]
+        force: bool,
+    },
+    /// Mark a task migrated
+    Migrate {
+        /// Date of the entry (YYYY-MM-DD)
+        #[arg(add = ArgValueCandidates::new(entry_dates))]
+        date: String,
+        /// Bullet id, or <type>:<n> for the n-th bullet of a type (e.g. task:2)
+        bullet: String,
+    },
+    /// Mark a task scheduled
+    Schedule {
+        /// Date of the entry (YYYY-MM-DD)
+        #[arg(add = ArgValueCandidates::new(entry_dates))]
+        date: String,
+        /// Bullet id, or <type>:<n> for the n-th bullet of a type (e.g. task:2)
+        bullet: String,
+    },
+    /// Replace the text of a bullet
+    Edit {
+        /// Date of the entry (YYYY-MM-DD)
+        #[arg(add = ArgValueCandidates::new(entry_dates))]
+        date: String,
+        /// Bullet id, or <type>:<n> for the n-th bullet of a type (e.g. task:2)
+        bullet: String,
+        /// New content
+        content: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TasksCommand {
+    /// Mark matching pending tasks completed
+    Complete {
+        /// Text the tasks contain, ignoring case
+        #[arg(short, long = "match")]
+        pattern: String,
+        /// Range around today to look in
+        #[arg(short, long, value_enum, default_value_t = RangeArg::Week)]
+        range: RangeArg,
+        /// Only show which tasks would change
+        #[arg(long)]
+        dry_run: bool,
+        /// Complete even if tasks they wait for are still pending
+        #[arg(long)]
+        force: bool,
+    },
+    /// Mark matching pending tasks migrated
+    Migrate {
+        /// Text the tasks contain, ignoring case
+        #[arg(short, long = "match")]
+        pattern: String,
+        /// Range around today to look in
+        #[arg(short, long, value_enum, default_value_t = RangeArg::Week)]
+        range: RangeArg,
+        /// Only show which tasks would change
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Mark matching pending tasks scheduled
+    Schedule {
+        /// Text the tasks contain, ignoring case
+        #[arg(short, long = "match")]
+        pattern: String,
+        /// Range around today to look in
+        #[arg(short, long, value_enum, default_value_t = RangeArg::Week)]
+        range: RangeArg,
+        /// Only show which tasks would change
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum BackupsCommand {
+    /// List snapshots, newest first
+    List,
+    /// Delete snapshots outside the retention policy
+    Prune {
+        /// Only show which snapshots would be deleted
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TagCommand {
+    /// Rename a hashtag in every bullet, e.g. `tag rename q3 q3-plan`
+    Rename {
+        /// Tag to rename, with or without the leading `#`
+        old: String,
+        /// New name for the tag
+        new: String,
+        /// Only show which bullets would change
+        #[arg(long)]
+        dry_run: bool,
+        /// Rewrite without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Merge hashtags into one, e.g. `tag merge run jog --into running`
+    Merge {
+        /// Tags to merge, with or without the leading `#`
+        #[arg(required = true)]
+        tags: Vec<String>,
+        /// Tag they all become; may be one of the merged tags
+        #[arg(long)]
+        into: String,
+        /// Only show which bullets would change
+        #[arg(long)]
+        dry_run: bool,
+        /// Rewrite without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum CalendarCommand {
+    /// Import events from an .ics file or URL (defaults to `calendar` in config.toml)
+    Sync {
+        /// Path or http(s)/webcal URL of the calendar
+        source: Option<String>,
+    },
+}
+
+/// Source formats supported by `import`
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum ImportFormat {
+    /// jrnl plain-text journal
+    Jrnl,
+    /// Day One JSON export
+    #[value(name = "dayone")]
+    DayOne,
+    /// Obsidian daily notes named YYYY-MM-DD.md
+    Obsidian,
+}
+
+/// Shells `completions` can register with
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum CompletionShell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+/// Date ranges relative to today, used by commands that filter by date
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum RangeArg {
+    Day,
+    Week,
+    Month,
+    Year,
+    All,
+}
+
+impl RangeArg {
+    pub fn to_range(self, today: NaiveDate, week_start: Weekday) -> Option<DateRange> {
+        match self {
+            RangeArg::Day => Some(DateRange::day(today)),
+            RangeArg::Week => Some(DateRange::week_containing(today, week_start)),
+            RangeArg::Month => Some(DateRange::month(today.year(), today.month())),
+            RangeArg::Year => Some(DateRange::year(today.year())),
+            RangeArg::All => None,
+        }
+    }
 }
 
 impl Cli {
     pub fn run() -> anyhow::Result<()> {
+        let started = Instant::now();
         let cli = Self::parse();
-        let mut app = JournalApp::new();
+        init_logging(
+            &Config::default_journal_dir(),
+            cli.verbose,
+            cli.color.use_color(),
+        );
+
+        // Diagnostics must work even when the database can't be opened
+        if let Some(Commands::Doctor) = cli.command {
+            let checks = doctor::run_diagnostics(&Config::from_env());
+            if !doctor::print_report(&checks, cli.color.use_color()) {
+                std::process::exit(1);
+            }
+            return Ok(());
+        }
+
+        if let Some(Commands::Completions { shell }) = cli.command {
+            let completer: &dyn EnvCompleter = match shell {
+                CompletionShell::Bash => &env::Bash,
+                CompletionShell::Zsh => &env::Zsh,
+                CompletionShell::Fish => &env::Fish,
+            };
+            // The script calls back into this binary with COMPLETE=<shell> set
+            let bin = std::env::args()
+                .next()
+                .unwrap_or_else(|| "journo".to_string());
+            completer.write_registration(
+                "COMPLETE",
+                "journo",
+                "journo",
+                &bin,
+                &mut std::io::stdout(),
+            )?;
+            return Ok(());
+        }
+
+        // Runs before any journal exists, so it must not open storage either
+        if let Some(Commands::Init) = cli.command {
+            let mut stdin = std::io::stdin().lock();
+            return init::run_wizard(&mut stdin, &mut std::io::stdout());
+        }
+
+        // Filters for scripts and editors, which need no storage
+        if let Some(Commands::Parse { date } | Commands::Format { date }) = &cli.command {
+            let output = match cli.command {
+                Some(Commands::Parse { .. }) => PipeOutput::Json,
+                _ => PipeOutput::Markdown,
+            };
+            return print_transformed_stdin(date.clone(), cli.strict, output);
+        }
+
+        // Long-running, so it opens the journal per capture rather than
+        // holding storage (and DuckDB's file lock) while idle
+        if let Some(Commands::Daemon { socket }) = &cli.command {
+            let socket = socket
+                .clone()
+                .unwrap_or_else(|| Config::from_env().journal_dir.join("journo.sock"));
+            return daemon::run(&socket, JournalApp::new);
+        }
+
+        // Runs on every prompt, so skip the app setup and make a single query
+        if let Some(Commands::Today { brief, tmux }) = cli.command
+            && (brief || tmux)
+        {
+            return print_today_summary(tmux, started);
+        }
+
+        // Only commands that get this far open the storage
+        let mut app = JournalApp::new().context("Failed to open journal storage")?;
+        tracing::info!("journal opened in {:.1?}", started.elapsed());
+        app.set_strict_parsing(cli.strict);
+        app.set_color_mode(cli.color);
+        app.set_nudges(!cli.no_nudge);
 
         match cli.command {
-            Some(Commands::New { date }) => {
-                let target_date = if let Some(date_str) = date {
-                    NaiveDate::parse_from_str(&date_str, "%Y-%m-%d")?
+            Some(Commands::New {
+                date,
+                session,
+                prompt,
+                free,
+            }) => {
+                let target_date = resolve_date(date, app.today())?;
+                if free {
+                    app.edit_freewrite(target_date)?;
+                } else if session {
+                    app.edit_new_session(target_date)?;
+                } else if prompt || app.config().prompt_on_new {
+                    app.edit_entry_with_journaling_prompt(target_date)?;
                 } else {
-                    Local::now().naive_local().date()
-                };
-
-                app.edit_entry_for_date(target_date)?;
+                    app.edit_entry_for_date(target_date)?;
+                }
+            }
+            Some(Commands::Recover { date }) => {
+                let target_date = resolve_date(date, app.today())?;
+                app.recover(target_date)?;
+            }
+            Some(Commands::Path { date }) => {
+                let target_date = resolve_date(date, app.today())?;
+                println!("{}", app.entry_file_path(target_date)?.display());
             }
             Some(Commands::Tui) => {
                 app.run_tui()?;
             }
             Some(Commands::Week { date }) => {
-                let target_date = if let Some(date_str) = date {
-                    NaiveDate::parse_from_str(&date_str, "%Y-%m-%d")?
+                let target_date = resolve_date(date, app.today())?;
+                run_week_view(&mut app, target_date)?;
+            }
+            Some(Commands::Plan { date }) => {
+                let target_date = resolve_date(date, app.today())?;
+                run_plan_view(&mut app, target_date)?;
+            }
+            Some(Commands::PlanWeek { date }) => {
+                let target_date = resolve_date(date, app.today())?;
+                run_week_plan_view(&mut app, target_date)?;
+            }
+            Some(Commands::Stats {
+                heatmap,
+                year,
+                words,
+            }) => match heatmap {
+                Some(path) => {
+                    let year = year.unwrap_or_else(|| app.today().year());
+                    app.write_heatmap(&path, year, words)?;
+                }
+                None => app.print_stats()?,
+            },
+            Some(Commands::Nag { notify }) => {
+                app.nag(notify)?;
+            }
+            Some(Commands::Info) => {
+                app.print_info()?;
+            }
+            Some(Commands::Trends { term, by, periods }) => {
+                app.print_trends(term.as_deref(), by, periods)?;
+            }
+            Some(Commands::Search {
+                query,
+                types,
+                states,
+                range,
+                context,
+            }) => {
+                let range = range.to_range(app.today(), app.config().week_start);
+                app.search_bullets(&query, &types, &states, range, context)?;
+            }
+            Some(Commands::Export { format, out, range }) => {
+                let range = range.to_range(app.today(), app.config().week_start);
+                match format.to_lowercase().as_str() {
+                    "html" => app.export_html(&out, range)?,
+                    "obsidian" => app.export_obsidian(&out, range)?,
+                    "csv" => app.export_tables(TableFormat::Csv, &out, range)?,
+                    "parquet" => app.export_tables(TableFormat::Parquet, &out, range)?,
+                    _ => app.export(&format, &out, range)?,
+                }
+            }
+            Some(Commands::Import {
+                format,
+                path,
+                dry_run,
+            }) => {
+                let result = match format {
+                    ImportFormat::Jrnl => import_jrnl(&path)?,
+                    ImportFormat::DayOne => import_dayone(&path)?,
+                    ImportFormat::Obsidian => import_obsidian(&path)?,
+                };
+                app.import_entries(&result, dry_run)?;
+            }
+            Some(Commands::Digest {
+                range,
+                week,
+                out,
+                email,
+            }) => {
+                let range = if week { RangeArg::Week } else { range };
+                let range = range.to_range(app.today(), app.config().week_start);
+                app.digest(range, out.as_deref(), email)?;
+            }
+            Some(Commands::SyncFiles { policy, dry_run }) => {
+                app.sync_files(policy, dry_run)?;
+            }
+            Some(Commands::Watch) => {
+                watch::run(&mut app)?;
+            }
+            Some(
+                Commands::Doctor
+                | Commands::Init
+                | Commands::Completions { .. }
+                | Commands::Parse { .. }
+                | Commands::Format { .. }
+                | Commands::Daemon { .. },
+            ) => {
+                unreachable!("handled before opening storage")
+            }
+            Some(Commands::People) => {
+                app.print_people()?;
+            }
+            Some(Commands::Person { name }) => {
+                app.print_person(&name)?;
+            }
+            Some(Commands::Links {
+                range,
+                fetch_titles,
+            }) => {
+                let range = range.to_range(app.today(), app.config().week_start);
+                app.print_links(range, fetch_titles)?;
+            }
+            Some(Commands::Collection {
+                bullet_type,
+                range,
+                browse,
+            }) => {
+                let range = range.to_range(app.today(), app.config().week_start);
+                if browse {
+                    run_collection_view(&mut app, bullet_type, range)?;
                 } else {
-                    Local::now().naive_local().date()
+                    app.print_collection(bullet_type, range)?;
+                }
+            }
+            Some(Commands::Pin { date, remove }) => {
+                let target_date = resolve_date(date, app.today())?;
+                app.set_pinned(target_date, !remove)?;
+            }
+            Some(Commands::Pins) => {
+                app.print_pins()?;
+            }
+            Some(Commands::Prompt) => {
+                app.print_prompt()?;
+            }
+            Some(Commands::Show { date, screenreader }) => {
+                let target_date = resolve_date(date, app.today())?;
+                app.print_entry(target_date, screenreader)?;
+            }
+            Some(Commands::Agenda { date, context }) => {
+                let target_date = resolve_date(date, app.today())?;
+                app.print_agenda(target_date, context.as_deref())?;
+            }
+            Some(Commands::Today { .. }) => {
+                app.print_agenda(app.today(), None)?;
+            }
+            Some(Commands::Api) => {
+                api::run(&mut app)?;
+            }
+            Some(Commands::Serve {
+                port,
+                host,
+                api_only,
+            }) => {
+                let addr = format!("{}:{}", host, port);
+                let mode = if api_only {
+                    let token = rest::require_token(app.config().api_token.as_deref())?;
+                    serve::ServeMode::Api { token }
+                } else {
+                    let access = serve::WebAccess::new(&addr, app.config().api_token.as_deref())?;
+                    serve::ServeMode::Web(access)
                 };
-
-                loop {
-                    let result = {
-                        let mut week_view = WeekView::new(target_date, &mut app.journal)?;
-                        week_view.run()?
-                    }; // week_view is dropped here, releasing the borrow
-
-                    match result {
-                        WeekViewResult::EditRequested(selected_date) => {
-                            app.edit_entry_for_date(selected_date)?;
-                            // Continue loop to return to WeekView
-                        }
-                        WeekViewResult::Exited(_) => {
-                            // User exited, break the loop
-                            break;
-                        }
+                serve::run(&mut app, &addr, &mode)?;
+            }
+            Some(Commands::Reflect { date, template }) => {
+                let target_date = resolve_date(date, app.today())?;
+                app.reflect(target_date, &template)?;
+            }
+            Some(Commands::Focus { task, minutes }) => {
+                app.focus(&task, minutes)?;
+            }
+            Some(Commands::Relayout { layout, dry_run }) => {
+                app.relayout(&layout, dry_run)?;
+            }
+            Some(Commands::Bullet { command }) => {
+                let (date, bullet, action, force) = match command {
+                    BulletCommand::Complete {
+                        date,
+                        bullet,
+                        force,
+                    } => (date, bullet, BulletAction::Complete, force),
+                    BulletCommand::Migrate { date, bullet } => {
+                        (date, bullet, BulletAction::Migrate, false)
+                    }
+                    BulletCommand::Schedule { date, bullet } => {
+                        (date, bullet, BulletAction::Schedule, false)
                     }
+                    BulletCommand::Edit {
+                        date,
+                        bullet,
+                        content,
+                    } => (date, bullet, BulletAction::Edit(content), false),
+                };
+                let date = resolve_date(Some(date), app.today())?;
+                app.update_bullet(date, &bullet, action, force)?;
+            }
+            Some(Commands::Tasks { command }) => {
+                let (pattern, range, dry_run, action, force) = match command {
+                    TasksCommand::Complete {
+                        pattern,
+                        range,
+                        dry_run,
+                        force,
+                    } => (pattern, range, dry_run, BulletAction::Complete, force),
+                    TasksCommand::Migrate {
+                        pattern,
+                        range,
+                        dry_run,
+                    } => (pattern, range, dry_run, BulletAction::Migrate, false),
+                    TasksCommand::Schedule {
+                        pattern,
+                        range,
+                        dry_run,
+                    } => (pattern, range, dry_run, BulletAction::Schedule, false),
+                };
+                let range = range.to_range(app.today(), app.config().week_start);
+                app.update_tasks(&pattern, range, action, dry_run, force)?;
+            }
+            Some(Commands::Copy {
+                from,
+                to,
+                bullet_type,
+                pending_only,
+            }) => {
+                let from = resolve_date(Some(from), app.today())?;
+                let to = resolve_date(Some(to), app.today())?;
+                let copied = app.copy_bullets(from, to, bullet_type, pending_only)?;
+                println!("Copied {} bullets from {} to {}", copied, from, to);
+            }
+            Some(Commands::Merge {
+                first,
+                second,
+                into,
+            }) => {
+                let first = resolve_date(Some(first), app.today())?;
+                let second = resolve_date(Some(second), app.today())?;
+                let into = resolve_date(Some(into), app.today())?;
+                let trashed = app.merge_days(first, second, into)?;
+                println!("Merged {} and {} into {}", first, second, into);
+                for path in trashed {
+                    println!("Moved the old entry to {}", path.display());
                 }
             }
+            Some(Commands::Convert { date, bullet, to }) => {
+                let date = resolve_date(Some(date), app.today())?;
+                app.convert_bullet(date, &bullet, to)?;
+            }
+            Some(Commands::Metric {
+                name,
+                chart,
+                csv,
+                range,
+            }) => {
+                let range = range.to_range(app.today(), app.config().week_start);
+                let output = if chart {
+                    MetricOutput::Chart
+                } else if csv {
+                    MetricOutput::Csv
+                } else {
+                    MetricOutput::List
+                };
+                app.print_metric(&name, range, output)?;
+            }
+            Some(Commands::Stale { days }) => {
+                app.review_stale_tasks(days)?;
+            }
+            Some(Commands::Backups { command }) => match command {
+                BackupsCommand::List => app.list_backups()?,
+                BackupsCommand::Prune { dry_run } => app.prune_backups(dry_run)?,
+            },
+            Some(Commands::Calendar { command }) => match command {
+                CalendarCommand::Sync { source } => app.sync_calendar(source.as_deref())?,
+            },
+            Some(Commands::Tag { command }) => match command {
+                TagCommand::Rename {
+                    old,
+                    new,
+                    dry_run,
+                    yes,
+                } => app.retag(&[old], &new, dry_run, yes)?,
+                TagCommand::Merge {
+                    tags,
+                    into,
+                    dry_run,
+                    yes,
+                } => app.retag(&tags, &into, dry_run, yes)?,
+            },
             None => {
                 // Default: start week view
-                let target_date = Local::now().naive_local().date();
-
-                loop {
-                    let result = {
-                        let mut week_view = WeekView::new(target_date, &mut app.journal)?;
-                        week_view.run()?
-                    }; // week_view is dropped here, releasing the borrow
-
-                    match result {
-                        WeekViewResult::EditRequested(selected_date) => {
-                            app.edit_entry_for_date(selected_date)?;
-                            // Continue loop to return to WeekView
-                        }
-                        WeekViewResult::Exited(_) => {
-                            // User exited, break the loop
-                            break;
-                        }
-                    }
-                }
+                let target_date = app.today();
+                run_week_view(&mut app, target_date)?;
             }
         }
 
@@ -104,6 +1005,287 @@ impl Cli {
     }
 }
 
+/// Parse an optional YYYY-MM-DD argument, defaulting to today
+fn resolve_date(date: Option<String>, today: NaiveDate) -> anyhow::Result<NaiveDate> {
+    match date {
+        Some(date_str) => Ok(NaiveDate::parse_from_str(&date_str, "%Y-%m-%d")?),
+        None => Ok(today),
+    }
+}
+
+/// Run the week view, opening the editor whenever an entry is selected
+fn run_week_view(app: &mut JournalApp, mut target_date: NaiveDate) -> anyhow::Result<()> {
+    app.review_week_rollover()?;
+    let date_formats = app.config().date_formats.clone();
+    let symbols = app.config().symbols.clone();
+    let theme = Theme::from_config(app.config());
+    let week_start = app.config().week_start;
+    let week_numbers = app.config().week_numbers;
+    let month_minimap = app.config().month_minimap;
+    let word_goal = app.config().word_goal;
+    let holidays = app.config().holidays.load()?;
+    let month_jump = app.config().month_jump;
+    let day_rollover_hour = app.config().day_rollover_hour;
+    let views = app.config().views.clone();
+    let section_order = app.config().section_order.clone();
+    let storage = app.storage().clone();
+
+    loop {
+        let streak = app.current_streak()?;
+        let result = {
+            let mut week_view = WeekView::new(target_date, &mut app.journal)?
+                .with_date_formats(date_formats.clone())
+                .with_theme(theme.clone())
+                .with_symbols(symbols.clone())
+                .with_week_start(week_start)
+                .with_week_numbers(week_numbers)
+                .with_month_minimap(month_minimap)
+                .with_word_goal(word_goal)
+                .with_holidays(holidays.clone())
+                .with_month_jump(month_jump)
+                .with_day_rollover_hour(day_rollover_hour)
+                .with_storage(storage.clone())
+                .with_views(views.clone())
+                .with_section_order(section_order.clone())
+                .with_streak(streak);
+            week_view.run()?
+        }; // week_view is dropped here, releasing the borrow
+
+        match result {
+            WeekViewResult::EditRequested(selected_date) => {
+                app.edit_entry_for_date(selected_date)?;
+                // Continue loop to return to WeekView
+            }
+            WeekViewResult::PasteRequested { from, to } => {
+                app.copy_bullets(from, to, None, false)?;
+                target_date = to;
+            }
+            WeekViewResult::ConvertRequested { date, id, to } => {
+                app.convert_bullet(date, &id.to_string(), to)?;
+                target_date = date;
+            }
+            WeekViewResult::Exited(_) => {
+                // User exited, break the loop
+                break;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Run the day planner, opening the editor whenever the day is to be edited
+fn run_plan_view(app: &mut JournalApp, mut date: NaiveDate) -> anyhow::Result<()> {
+    let theme = Theme::from_config(app.config());
+    let date_formats = app.config().date_formats.clone();
+    let today = app.today();
+    loop {
+        let result = PlanView::new(date, today, &mut app.journal)?
+            .with_theme(theme.clone())
+            .with_date_formats(date_formats.clone())
+            .run()?;
+        match result {
+            PlanViewResult::EditRequested(edited) => {
+                app.edit_entry_for_date(edited)?;
+                date = edited;
+            }
+            PlanViewResult::Exit => break,
+        }
+    }
+    Ok(())
+}
+
+/// Run the collection browser, opening the editor for the day of a bullet and
+/// coming back to the refreshed collection afterwards
+fn run_collection_view(
+    app: &mut JournalApp,
+    bullet_type: BulletType,
+    range: Option<DateRange>,
+) -> anyhow::Result<()> {
+    let theme = Theme::from_config(app.config());
+    let mut selected = None;
+    loop {
+        let hits = app.collection(bullet_type, range.clone())?;
+        let mut view = CollectionView::new(bullet_type, hits)?.with_theme(theme.clone());
+        if let Some(date) = selected {
+            view = view.with_selected_date(date);
+        }
+        let result = view.run()?;
+        drop(view);
+        match result {
+            CollectionViewResult::EditRequested(date) => {
+                app.edit_entry_for_date(date)?;
+                selected = Some(date);
+            }
+            CollectionViewResult::Exit => break,
+        }
+    }
+    Ok(())
+}
+
+/// Run the weekly planner and save the plan if asked to
+fn run_week_plan_view(app: &mut JournalApp, date: NaiveDate) -> anyhow::Result<()> {
+    let start = next_week_start(date, app.config().week_start);
+    let plan = app.week_plan(start)?;
+    let result = WeekPlanView::new(plan)?
+        .with_theme(Theme::from_config(app.config()))
+        .run()?;
+    if let WeekPlanViewResult::Save(plan) = result {
+        let saved = app.save_week_plan(&plan)?;
+        println!(
+            "Saved the plan for the week of {} ({} entries updated)",
+            start, saved
+        );
+    }
+    Ok(())
+}
+
+/// `today --brief`/`--tmux`: one stats query for today. Prompts call this
+/// constantly, so a journal that can't be opened (e.g. while the TUI holds the
+/// DuckDB lock) prints nothing instead of an error on every prompt.
+fn print_today_summary(tmux: bool, started: Instant) -> anyhow::Result<()> {
+    let config = Config::from_env();
+    let stats = match config
+        .open_storage()
+        .and_then(|storage| storage.period_stats(DateRange::day(config.today())))
+    {
+        Ok(stats) => stats,
+        Err(e) => {
+            tracing::debug!("no today summary: {:#}", e);
+            std::process::exit(1);
+        }
+    };
+    if tmux {
+        println!("{}", tmux_summary(&stats));
+    } else {
+        println!("{}", brief_summary(&stats));
+    }
+    tracing::info!("summary printed in {:.1?}", started.elapsed());
+    Ok(())
+}
+
+/// `parse`/`format`: read markdown from stdin, write the result to stdout
+fn print_transformed_stdin(
+    date: Option<String>,
+    strict: bool,
+    output: PipeOutput,
+) -> anyhow::Result<()> {
+    let config = Config::from_env();
+    let date = resolve_date(date, config.today())?;
+    let mut input = String::new();
+    std::io::stdin().read_to_string(&mut input)?;
+    let parser = MarkdownParser::new().with_section_order(&config.section_order);
+    print!(
+        "{}",
+        transform_entry(&parser, date, &input, strict, output)?
+    );
+    Ok(())
+}
+
+/// Storage for completion candidates; completion stays quiet when the
+/// journal can't be opened (e.g. while the TUI holds the DuckDB lock)
+fn completion_storage() -> Option<Arc<dyn JournalStorage>> {
+    Config::from_env().open_storage().ok()
+}
+
+/// Today followed by the dates that have entries, newest first
+fn entry_dates() -> Vec<CompletionCandidate> {
+    let config = Config::from_env();
+    let today = config.today();
+    let mut dates = vec![today];
+    if let Some(storage) = completion_storage()
+        && let Ok(Some((first, last))) = storage.entry_date_span()
+        && let Ok(mut stored) = storage.list_dates(DateRange::between(first, last))
+    {
+        stored.retain(|date| *date != today);
+        stored.reverse();
+        dates.extend(stored);
+    }
+
+    dates
+        .into_iter()
+        .map(|date| {
+            let help = if date == today {
+                "today".to_string()
+            } else {
+                date.format("%A").to_string()
+            };
+            CompletionCandidate::new(date.format("%Y-%m-%d").to_string()).help(Some(help.into()))
+        })
+        .collect()
+}
+
+/// People mentioned in the journal, most frequent first
+fn people() -> Vec<CompletionCandidate> {
+    let Some(people) = completion_storage().and_then(|s| s.list_people().ok()) else {
+        return Vec::new();
+    };
+    people
+        .into_iter()
+        .map(|person| CompletionCandidate::new(person.name))
+        .collect()
+}
+
+/// Contexts used as @@context markers, most used first
+fn contexts() -> Vec<CompletionCandidate> {
+    let Some(contexts) = completion_storage().and_then(|s| s.list_contexts().ok()) else {
+        return Vec::new();
+    };
+    contexts.into_iter().map(CompletionCandidate::new).collect()
+}
+
+/// Metrics recorded as #name:value, most used first
+fn metrics() -> Vec<CompletionCandidate> {
+    let Some(metrics) = completion_storage().and_then(|s| s.list_metrics().ok()) else {
+        return Vec::new();
+    };
+    metrics.into_iter().map(CompletionCandidate::new).collect()
+}
+
+/// The built-in site formats followed by the registered exporters
+fn export_formats() -> Vec<CompletionCandidate> {
+    let mut candidates = vec![
+        CompletionCandidate::new("html").help(Some(
+            "Static HTML site with calendar index, day and tag pages".into(),
+        )),
+        CompletionCandidate::new("obsidian").help(Some(
+            "Obsidian daily notes with YAML frontmatter and wiki-links".into(),
+        )),
+        CompletionCandidate::new("csv").help(Some(
+            "Raw bullet tables as CSV files, with their schema (DuckDB only)".into(),
+        )),
+        CompletionCandidate::new("parquet").help(Some(
+            "Raw bullet tables as Parquet files, with their schema (DuckDB only)".into(),
+        )),
+    ];
+    candidates.extend(
+        ExporterRegistry::with_defaults()
+            .list_exporters()
+            .into_iter()
+            .map(CompletionCandidate::new),
+    );
+    candidates
+}
+
+/// Hashtags used in bullets, offered once the search query starts with `#`
+fn tags(current: &OsStr) -> Vec<CompletionCandidate> {
+    let Some(prefix) = current.to_str().filter(|c| c.starts_with('#')) else {
+        return Vec::new();
+    };
+    let Some(hits) = completion_storage().and_then(|s| s.search_bullets("#", &[], &[], None).ok())
+    else {
+        return Vec::new();
+    };
+
+    let tags: BTreeSet<String> = hits.iter().flat_map(|hit| hit.bullet.tags()).collect();
+    tags.into_iter()
+        .map(|tag| format!("#{}", tag))
+        .filter(|tag| tag.starts_with(prefix))
+        .map(CompletionCandidate::new)
+        .collect()
+}
+
 fn main() -> anyhow::Result<()> {
+    CompleteEnv::with_factory(Cli::command).complete();
     Cli::run()
 }
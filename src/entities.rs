@@ -1,9 +1,11 @@
 use crate::infrastructure::EntryRepository;
 use anyhow::Result;
-use chrono::NaiveDate;
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
 
 // ============================================================================
 // Bullet Journal Domain Types
@@ -20,6 +22,52 @@ pub enum BulletType {
     Misstep,
 }
 
+impl BulletType {
+    /// All bullet types in their canonical section order
+    pub const ALL: [BulletType; 7] = [
+        BulletType::Task,
+        BulletType::Event,
+        BulletType::Note,
+        BulletType::Priority,
+        BulletType::Inspiration,
+        BulletType::Insight,
+        BulletType::Misstep,
+    ];
+
+    /// Every bullet type, those in `preferred` first in the given order and
+    /// the rest after them in canonical order
+    pub fn ordered(preferred: &[BulletType]) -> Vec<BulletType> {
+        let mut order: Vec<BulletType> = Vec::with_capacity(Self::ALL.len());
+        for bullet_type in preferred.iter().chain(Self::ALL.iter()) {
+            if !order.contains(bullet_type) {
+                order.push(*bullet_type);
+            }
+        }
+        order
+    }
+
+    /// Human-readable section name, matching the markdown headers
+    pub fn section_name(&self) -> &'static str {
+        match self {
+            BulletType::Task => "Tasks",
+            BulletType::Event => "Events",
+            BulletType::Note => "Notes",
+            BulletType::Priority => "Priority",
+            BulletType::Inspiration => "Inspiration",
+            BulletType::Insight => "Insights",
+            BulletType::Misstep => "Missteps",
+        }
+    }
+
+    /// Type named by its section header ("Insights") or type name ("insight")
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        Self::ALL
+            .into_iter()
+            .find(|t| t.section_name().eq_ignore_ascii_case(name))
+            .map_or_else(|| name.parse(), Ok)
+    }
+}
+
 impl fmt::Display for BulletType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -34,6 +82,23 @@ impl fmt::Display for BulletType {
     }
 }
 
+impl FromStr for BulletType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "task" => Ok(BulletType::Task),
+            "event" => Ok(BulletType::Event),
+            "note" => Ok(BulletType::Note),
+            "priority" => Ok(BulletType::Priority),
+            "inspiration" => Ok(BulletType::Inspiration),
+            "insight" => Ok(BulletType::Insight),
+            "misstep" => Ok(BulletType::Misstep),
+            _ => Err(format!("unknown bullet type: {}", s)),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Copy)]
 pub enum TaskState {
     Pending,
@@ -53,11 +118,38 @@ impl fmt::Display for TaskState {
     }
 }
 
+impl FromStr for TaskState {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "pending" => Ok(TaskState::Pending),
+            "completed" => Ok(TaskState::Completed),
+            "migrated" => Ok(TaskState::Migrated),
+            "scheduled" => Ok(TaskState::Scheduled),
+            _ => Err(format!("unknown task state: {}", s)),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Bullet {
     pub content: String,
     pub bullet_type: BulletType,
     pub task_state: Option<TaskState>,
+    /// Optional time of day, written as an `HH:MM` prefix in markdown
+    #[serde(default)]
+    pub time: Option<NaiveTime>,
+    /// End of the time block started at `time`, written as `HH:MM-HH:MM`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_time: Option<NaiveTime>,
+    /// Sub-bullets written indented beneath this one
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<Bullet>,
+    /// Start of the session the bullet was written in, from a `## HH:MM`
+    /// header; `None` for bullets outside any session
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session: Option<NaiveTime>,
 }
 
 impl Bullet {
@@ -69,6 +161,10 @@ impl Bullet {
                 BulletType::Task | BulletType::Priority => Some(TaskState::Pending),
                 _ => None,
             },
+            time: None,
+            end_time: None,
+            children: Vec::new(),
+            session: None,
         }
     }
 
@@ -81,6 +177,59 @@ impl Bullet {
             content: content.into(),
             bullet_type,
             task_state: Some(state),
+            time: None,
+            end_time: None,
+            children: Vec::new(),
+            session: None,
+        }
+    }
+
+    pub fn at(mut self, time: NaiveTime) -> Self {
+        self.time = Some(time);
+        self
+    }
+
+    /// Make this a time block running from `start` to `end`
+    pub fn between(mut self, start: NaiveTime, end: NaiveTime) -> Self {
+        self.time = Some(start);
+        self.end_time = Some(end);
+        self
+    }
+
+    /// `HH:MM`, or `HH:MM-HH:MM` for a time block, when the bullet has a time
+    pub fn time_label(&self) -> Option<String> {
+        let start = self.time?.format("%H:%M");
+        Some(match self.end_time {
+            Some(end) => format!("{}-{}", start, end.format("%H:%M")),
+            None => start.to_string(),
+        })
+    }
+
+    /// Put this bullet and its sub-bullets in the session starting at `session`
+    pub fn in_session(mut self, session: NaiveTime) -> Self {
+        self.visit_mut(&mut |bullet| bullet.session = Some(session));
+        self
+    }
+
+    pub fn with_child(mut self, child: Bullet) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    /// This bullet followed by all of its descendants, depth first
+    pub fn flatten(&self) -> Vec<&Bullet> {
+        let mut bullets = vec![self];
+        for child in &self.children {
+            bullets.extend(child.flatten());
+        }
+        bullets
+    }
+
+    /// Apply `f` to this bullet and every descendant
+    pub fn visit_mut(&mut self, f: &mut impl FnMut(&mut Bullet)) {
+        f(self);
+        for child in &mut self.children {
+            child.visit_mut(f);
         }
     }
 
@@ -105,28 +254,559 @@ impl Bullet {
         self
     }
 
-    /// Get the display symbol for this bullet
-    pub fn symbol(&self) -> &'static str {
-        let default_symbol = match self.bullet_type {
-            BulletType::Task => "•",
-            BulletType::Event => "○",
-            BulletType::Note => "—",
-            BulletType::Priority => "★",
-            BulletType::Inspiration => "!",
-            BulletType::Insight => "$",
-            BulletType::Misstep => "v",
+    /// Number of whitespace-separated words in the bullet content
+    pub fn word_count(&self) -> usize {
+        self.content.split_whitespace().count()
+    }
+
+    /// Content compared when looking for duplicates: lowercased, with runs of
+    /// whitespace collapsed
+    pub fn normalized_content(&self) -> String {
+        self.content
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_lowercase()
+    }
+
+    /// Whether this bullet looks like a follow-up of `earlier`, e.g. "Team
+    /// sync: roadmap" after "Team sync": of the same type, with at least three
+    /// quarters of the shorter one's words (of 3+ letters, ignoring case and
+    /// punctuation) found in the other
+    pub fn follows_up(&self, earlier: &Bullet) -> bool {
+        if self.bullet_type != earlier.bullet_type {
+            return false;
+        }
+        let words = |bullet: &Bullet| -> HashSet<String> {
+            bullet
+                .content
+                .split_whitespace()
+                .map(|word| {
+                    word.trim_matches(|c: char| !c.is_alphanumeric())
+                        .to_lowercase()
+                })
+                .filter(|word| word.chars().count() >= 3)
+                .collect()
         };
+        let (ours, theirs) = (words(self), words(earlier));
+        let shorter = ours.len().min(theirs.len());
+        shorter > 0 && ours.intersection(&theirs).count() * 4 >= shorter * 3
+    }
 
-        if matches!(self.bullet_type, BulletType::Task | BulletType::Priority) {
-            match self.task_state {
-                Some(TaskState::Pending) => "•",
-                Some(TaskState::Completed) => "X",
-                Some(TaskState::Migrated) => ">",
-                Some(TaskState::Scheduled) => "<",
-                None => default_symbol,
+    /// Hashtags (`#word`) mentioned in the bullet content, lowercased and deduplicated
+    pub fn tags(&self) -> Vec<String> {
+        let mut tags: Vec<String> = Vec::new();
+        for word in self.content.split_whitespace() {
+            let Some(rest) = word.strip_prefix('#') else {
+                continue;
+            };
+            let tag: String = rest
+                .chars()
+                .take_while(|c| c.is_alphanumeric() || *c == '-' || *c == '_')
+                .collect::<String>()
+                .to_lowercase();
+            if !tag.is_empty() && !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+        tags
+    }
+
+    /// Rewrite the hashtag `#from` (matched ignoring case) to `#to`, here and
+    /// in sub-bullets. Where the bullet already has `#to`, `#from` is dropped
+    /// instead of repeating it. Returns whether anything changed.
+    pub fn rename_tag(&mut self, from: &str, to: &str) -> bool {
+        let is_tag_char = |c: &char| c.is_alphanumeric() || *c == '-' || *c == '_';
+        let already_tagged = self.tags().iter().any(|tag| tag == to);
+
+        let mut renamed = String::with_capacity(self.content.len());
+        let mut changed = false;
+        let mut rest = self.content.as_str();
+        let mut word_start = true;
+        while let Some(c) = rest.chars().next() {
+            if word_start && c == '#' {
+                let name: String = rest[1..].chars().take_while(is_tag_char).collect();
+                if !name.is_empty() && name.to_lowercase() == from {
+                    if !already_tagged {
+                        renamed.push('#');
+                        renamed.push_str(to);
+                    }
+                    changed = true;
+                    rest = &rest[1 + name.len()..];
+                    word_start = false;
+                    continue;
+                }
+            }
+            renamed.push(c);
+            word_start = c.is_whitespace();
+            rest = &rest[c.len_utf8()..];
+        }
+        if changed {
+            self.content = if already_tagged {
+                // Close the gap the dropped tag leaves behind
+                renamed.split_whitespace().collect::<Vec<_>>().join(" ")
+            } else {
+                renamed
+            };
+        }
+
+        for child in &mut self.children {
+            changed |= child.rename_tag(from, to);
+        }
+        changed
+    }
+
+    /// People mentioned as `@name`, lowercased and deduplicated. Email addresses
+    /// and `@@context` markers are not mentions.
+    pub fn mentions(&self) -> Vec<String> {
+        let chars: Vec<char> = self.content.chars().collect();
+        let mut mentions: Vec<String> = Vec::new();
+
+        for (i, &c) in chars.iter().enumerate() {
+            if c != '@' {
+                continue;
+            }
+            let preceded_by_word = i > 0 && {
+                let prev = chars[i - 1];
+                prev.is_alphanumeric() || prev == '_' || prev == '@'
+            };
+            let starts_name = chars.get(i + 1).is_some_and(|n| n.is_alphabetic());
+            if preceded_by_word || !starts_name {
+                continue;
+            }
+
+            let name: String = chars[i + 1..]
+                .iter()
+                .take_while(|c| c.is_alphanumeric() || **c == '-' || **c == '_')
+                .collect::<String>()
+                .to_lowercase();
+            if !mentions.contains(&name) {
+                mentions.push(name);
+            }
+        }
+        mentions
+    }
+
+    /// Days referred to as `[[YYYY-MM-DD]]` or `@YYYY-MM-DD`, with the byte
+    /// range each reference takes in the content
+    pub fn date_references(&self) -> Vec<(Range<usize>, NaiveDate)> {
+        let content = &self.content;
+        let mut references = Vec::new();
+        let mut i = 0;
+        while let Some(rest) = content.get(i..).filter(|rest| !rest.is_empty()) {
+            let (prefix, suffix) = if rest.starts_with("[[") {
+                ("[[", "]]")
+            } else if rest.starts_with('@')
+                && !content[..i].ends_with(|c: char| c.is_alphanumeric() || c == '_')
+            {
+                ("@", "")
+            } else {
+                i += rest.chars().next().map_or(1, char::len_utf8);
+                continue;
+            };
+
+            let date_start = i + prefix.len();
+            let date_end = date_start + "YYYY-MM-DD".len();
+            let date = content
+                .get(date_start..date_end)
+                .and_then(|text| NaiveDate::parse_from_str(text, "%Y-%m-%d").ok());
+            let after = content.get(date_end..).unwrap_or_default();
+            let closed = if suffix.is_empty() {
+                !after.starts_with(|c: char| c.is_alphanumeric())
+            } else {
+                after.starts_with(suffix)
+            };
+            match date {
+                Some(date) if closed => {
+                    let end = date_end + suffix.len();
+                    references.push((i..end, date));
+                    i = end;
+                }
+                _ => i += prefix.len(),
+            }
+        }
+        references
+    }
+
+    /// `http://` and `https://` URLs in the bullet content, without trailing
+    /// punctuation
+    pub fn urls(&self) -> Vec<String> {
+        let mut urls: Vec<String> = Vec::new();
+        for word in self.content.split_whitespace() {
+            let Some(start) = word.find("http://").or_else(|| word.find("https://")) else {
+                continue;
+            };
+            let url = word[start..]
+                .trim_end_matches(|c: char| ".,;:!?)]>\"'".contains(c))
+                .to_string();
+            if !url.ends_with("://") && !urls.contains(&url) {
+                urls.push(url);
+            }
+        }
+        urls
+    }
+
+    /// GTD-style contexts written as `@@work`, lowercased and deduplicated
+    pub fn contexts(&self) -> Vec<String> {
+        let mut contexts: Vec<String> = Vec::new();
+        for word in self.content.split_whitespace() {
+            let Some(rest) = word.strip_prefix("@@") else {
+                continue;
+            };
+            let context: String = rest
+                .chars()
+                .take_while(|c| c.is_alphanumeric() || *c == '-' || *c == '_')
+                .collect::<String>()
+                .to_lowercase();
+            if !context.is_empty() && !contexts.contains(&context) {
+                contexts.push(context);
+            }
+        }
+        contexts
+    }
+
+    /// Numeric measurements written as `#name:value` (e.g. `#weight:82.5`),
+    /// names lowercased; the first value wins when a name repeats
+    pub fn metrics(&self) -> Vec<(String, f64)> {
+        let mut metrics: Vec<(String, f64)> = Vec::new();
+        for word in self.content.split_whitespace() {
+            let Some((name, value)) = word.strip_prefix('#').and_then(|rest| rest.split_once(':'))
+            else {
+                continue;
+            };
+            if name.is_empty()
+                || !name
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+            {
+                continue;
+            }
+            let number: String = value
+                .chars()
+                .take_while(|c| c.is_ascii_digit() || matches!(c, '.' | '-' | '+'))
+                .collect();
+            let Ok(value) = number.trim_end_matches('.').parse::<f64>() else {
+                continue;
+            };
+            let name = name.to_lowercase();
+            if value.is_finite() && !metrics.iter().any(|(n, _)| *n == name) {
+                metrics.push((name, value));
+            }
+        }
+        metrics
+    }
+
+    /// References to the tasks this one waits for, written as `!after:word` or
+    /// `!after:"several words"`, lowercased
+    pub fn blockers(&self) -> Vec<String> {
+        let mut blockers = Vec::new();
+        let mut rest = self.content.as_str();
+        while let Some(start) = rest.find("!after:") {
+            rest = &rest[start + "!after:".len()..];
+            let (blocker, remainder) = match rest.strip_prefix('"') {
+                Some(quoted) => quoted.split_once('"').unwrap_or((quoted, "")),
+                None => rest.split_once(char::is_whitespace).unwrap_or((rest, "")),
+            };
+            let blocker = blocker.trim().to_lowercase();
+            if !blocker.is_empty() && !blockers.contains(&blocker) {
+                blockers.push(blocker);
             }
+            rest = remainder;
+        }
+        blockers
+    }
+}
+
+/// Glyphs used to display bullets, configurable through `[symbols]` in
+/// config.toml
+#[derive(Debug, Clone, PartialEq)]
+pub struct BulletSymbols {
+    pub task: String,
+    pub event: String,
+    pub note: String,
+    pub priority: String,
+    pub inspiration: String,
+    pub insight: String,
+    pub misstep: String,
+    pub completed: String,
+    pub migrated: String,
+    pub scheduled: String,
+}
+
+impl Default for BulletSymbols {
+    fn default() -> Self {
+        Self {
+            task: "•".to_string(),
+            event: "○".to_string(),
+            note: "—".to_string(),
+            priority: "★".to_string(),
+            inspiration: "!".to_string(),
+            insight: "$".to_string(),
+            misstep: "v".to_string(),
+            completed: "X".to_string(),
+            migrated: ">".to_string(),
+            scheduled: "<".to_string(),
+        }
+    }
+}
+
+impl BulletSymbols {
+    /// Display symbol for a bullet: its task state for finished tasks and
+    /// priorities, otherwise its type
+    pub fn symbol(&self, bullet: &Bullet) -> &str {
+        if !matches!(bullet.bullet_type, BulletType::Task | BulletType::Priority) {
+            return self.for_type(&bullet.bullet_type);
+        }
+        match bullet.task_state {
+            Some(TaskState::Completed) => &self.completed,
+            Some(TaskState::Migrated) => &self.migrated,
+            Some(TaskState::Scheduled) => &self.scheduled,
+            Some(TaskState::Pending) | None => self.for_type(&bullet.bullet_type),
+        }
+    }
+
+    pub fn for_type(&self, bullet_type: &BulletType) -> &str {
+        match bullet_type {
+            BulletType::Task => &self.task,
+            BulletType::Event => &self.event,
+            BulletType::Note => &self.note,
+            BulletType::Priority => &self.priority,
+            BulletType::Inspiration => &self.inspiration,
+            BulletType::Insight => &self.insight,
+            BulletType::Misstep => &self.misstep,
+        }
+    }
+}
+
+/// A single bullet matched by a search, along with the date it belongs to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BulletHit {
+    pub date: NaiveDate,
+    pub bullet: Bullet,
+}
+
+/// A pending task or priority an `!after:` reference can name
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingTask {
+    /// Database id; `None` for tasks of an entry that isn't saved yet
+    pub id: Option<i64>,
+    pub date: NaiveDate,
+    pub bullet: Bullet,
+}
+
+impl PendingTask {
+    /// Whether `reference`, as returned by `Bullet::blockers`, names this
+    /// task by its whole content, ignoring case and spacing. Bullet ids
+    /// aren't references: they change whenever the task's day is saved.
+    pub fn is_named_by(&self, reference: &str) -> bool {
+        let reference = reference.split_whitespace().collect::<Vec<_>>().join(" ");
+        self.bullet.normalized_content() == reference
+    }
+
+    /// Tasks among `candidates` that the `references` of a task written on
+    /// `date` resolve to. A text naming several tasks resolves to the one
+    /// written nearest `date`, the earlier one on a tie, so a task repeated
+    /// across days waits for the closest copy.
+    pub fn resolve_blockers<'a>(
+        date: NaiveDate,
+        references: &[String],
+        candidates: &'a [PendingTask],
+    ) -> Vec<&'a PendingTask> {
+        let mut blockers: Vec<&PendingTask> = Vec::new();
+        for reference in references {
+            let nearest = candidates
+                .iter()
+                .filter(|task| task.is_named_by(reference))
+                .min_by_key(|task| ((task.date - date).num_days().abs(), task.date));
+            if let Some(task) = nearest
+                && !blockers.contains(&task)
+            {
+                blockers.push(task);
+            }
+        }
+        blockers
+    }
+}
+
+/// A URL collected from a bullet
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Link {
+    pub date: NaiveDate,
+    pub url: String,
+    /// Page title, if it has been fetched
+    pub title: Option<String>,
+    /// Content of the bullet the link was found in
+    pub content: String,
+}
+
+/// Totals over a date range, e.g. for the week view stats panel
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PeriodStats {
+    pub days_with_entries: u64,
+    /// Completed tasks and priorities
+    pub tasks_completed: u64,
+    /// Pending tasks and priorities
+    pub tasks_pending: u64,
+    pub events: u64,
+    pub words: u64,
+}
+
+/// When an entry was first saved and last changed, in local time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EntryTimestamps {
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl EntryTimestamps {
+    /// How long ago the entry was last changed, e.g. "last edited 2h ago"
+    pub fn last_edited(&self, now: NaiveDateTime) -> String {
+        let elapsed = now - self.updated_at;
+        if elapsed.num_minutes() < 1 {
+            return "last edited just now".to_string();
+        }
+        if elapsed.num_days() >= 30 {
+            return format!("last edited {}", self.updated_at.format("%Y-%m-%d"));
+        }
+        let ago = if elapsed.num_hours() < 1 {
+            format!("{}m", elapsed.num_minutes())
+        } else if elapsed.num_days() < 1 {
+            format!("{}h", elapsed.num_hours())
         } else {
-            default_symbol
+            format!("{}d", elapsed.num_days())
+        };
+        format!("last edited {} ago", ago)
+    }
+}
+
+/// Part of the day a mood check-in belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MoodPeriod {
+    /// Asked when the day's entry is created
+    Morning,
+    /// Asked when the entry is saved in the evening
+    Evening,
+}
+
+impl MoodPeriod {
+    /// Question shown by the mood picker
+    pub fn question(&self) -> &'static str {
+        match self {
+            MoodPeriod::Morning => "How are you starting the day?",
+            MoodPeriod::Evening => "How did the day go?",
+        }
+    }
+}
+
+impl fmt::Display for MoodPeriod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoodPeriod::Morning => write!(f, "morning"),
+            MoodPeriod::Evening => write!(f, "evening"),
+        }
+    }
+}
+
+impl FromStr for MoodPeriod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "morning" => Ok(MoodPeriod::Morning),
+            "evening" => Ok(MoodPeriod::Evening),
+            _ => Err(format!("unknown mood period: {}", s)),
+        }
+    }
+}
+
+/// A 1-5 mood rating given at the start or end of a day
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MoodCheckIn {
+    pub date: NaiveDate,
+    pub period: MoodPeriod,
+    pub score: u8,
+}
+
+/// A bullet recorded as following up on a bullet of an earlier day, e.g. the
+/// notes of a recurring meeting. Both are identified by their normalized
+/// content, as bullet ids change whenever an entry is saved.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CrossReference {
+    pub date: NaiveDate,
+    pub content: String,
+    pub target_date: NaiveDate,
+    pub target_content: String,
+}
+
+impl CrossReference {
+    pub fn new(date: NaiveDate, bullet: &Bullet, target_date: NaiveDate, target: &Bullet) -> Self {
+        Self {
+            date,
+            content: bullet.normalized_content(),
+            target_date,
+            target_content: target.normalized_content(),
+        }
+    }
+
+    /// Whether `bullet` is the follow-up side of this reference
+    pub fn is_from(&self, bullet: &Bullet) -> bool {
+        self.content == bullet.normalized_content()
+    }
+}
+
+/// A person mentioned across the journal
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PersonSummary {
+    pub name: String,
+    /// Number of bullets mentioning the person
+    pub mentions: u64,
+    pub last_seen: NaiveDate,
+}
+
+/// One reading of a `#name:value` metric
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricValue {
+    pub date: NaiveDate,
+    pub value: f64,
+}
+
+/// A read-only event imported from an external calendar
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExternalEvent {
+    pub uid: Option<String>,
+    pub date: NaiveDate,
+    /// Start and end times, `None` for all-day events
+    pub start: Option<NaiveTime>,
+    pub end: Option<NaiveTime>,
+    pub summary: String,
+    pub location: Option<String>,
+}
+
+impl ExternalEvent {
+    /// Time span for display, e.g. `09:00-09:30`, or `all day`
+    pub fn time_label(&self) -> String {
+        let fmt = |t: NaiveTime| t.format("%H:%M").to_string();
+        match (self.start, self.end) {
+            (Some(start), Some(end)) => format!("{}-{}", fmt(start), fmt(end)),
+            (Some(start), None) => fmt(start),
+            _ => "all day".to_string(),
+        }
+    }
+}
+
+/// A question asked when writing from a template; answers become bullets of
+/// the given type
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Prompt {
+    pub question: String,
+    pub bullet_type: BulletType,
+}
+
+impl Prompt {
+    pub fn new(question: impl Into<String>, bullet_type: BulletType) -> Self {
+        Self {
+            question: question.into(),
+            bullet_type,
         }
     }
 }
@@ -139,6 +819,9 @@ impl Bullet {
 pub struct Entry {
     pub date: NaiveDate,
     pub bullets: HashMap<BulletType, Vec<Bullet>>,
+    /// Free-written text kept below the bullets, see `journo new --free`
+    #[serde(default)]
+    pub body: Option<String>,
 }
 
 impl Entry {
@@ -146,13 +829,14 @@ impl Entry {
         Self {
             date,
             bullets: HashMap::new(),
+            body: None,
         }
     }
 
     pub fn add_bullet(&mut self, bullet: Bullet) -> &mut Self {
         self.bullets
-            .entry(bullet.bullet_type.clone())
-            .or_insert_with(Vec::new)
+            .entry(bullet.bullet_type)
+            .or_default()
             .push(bullet);
         self
     }
@@ -163,18 +847,100 @@ impl Entry {
             .map_or(&[], |bullets| bullets.as_slice())
     }
 
+    /// Bullets of a type ordered by time of day; untimed bullets keep their
+    /// original order after the timed ones
+    pub fn bullets_chronological(&self, bullet_type: &BulletType) -> Vec<&Bullet> {
+        let mut bullets: Vec<&Bullet> = self.get_bullets(bullet_type).iter().collect();
+        bullets.sort_by_key(|b| (b.time.is_none(), b.time));
+        bullets
+    }
+
     pub fn get_bullets_mut(&mut self, bullet_type: &BulletType) -> &mut Vec<Bullet> {
-        self.bullets
-            .entry(bullet_type.clone())
-            .or_insert_with(Vec::new)
+        self.bullets.entry(*bullet_type).or_default()
+    }
+
+    /// Rename the hashtag `#from` to `#to` in every bullet, see
+    /// `Bullet::rename_tag`. Returns whether anything changed.
+    pub fn rename_tag(&mut self, from: &str, to: &str) -> bool {
+        let mut changed = false;
+        for bullet in self.bullets.values_mut().flatten() {
+            changed |= bullet.rename_tag(from, to);
+        }
+        changed
     }
 
+    /// Every bullet in the entry, including nested sub-bullets
     pub fn all_bullets(&self) -> impl Iterator<Item = &Bullet> {
-        self.bullets.values().flatten()
+        self.bullets.values().flatten().flat_map(|b| b.flatten())
+    }
+
+    /// Indexes of top-level bullets of `bullet_type` repeating the normalized
+    /// content of an earlier one, leaving out repeats `previous` already had
+    /// so only the ones a save adds are reported
+    pub fn added_duplicates(
+        &self,
+        previous: Option<&Entry>,
+        bullet_type: &BulletType,
+    ) -> Vec<usize> {
+        let mut allowed: HashMap<String, usize> = HashMap::new();
+        for bullet in previous.map_or(&[][..], |p| p.get_bullets(bullet_type)) {
+            *allowed.entry(bullet.normalized_content()).or_default() += 1;
+        }
+
+        let mut seen: HashMap<String, usize> = HashMap::new();
+        let mut duplicates = Vec::new();
+        for (index, bullet) in self.get_bullets(bullet_type).iter().enumerate() {
+            let key = bullet.normalized_content();
+            let count = seen.entry(key.clone()).or_default();
+            *count += 1;
+            if *count > allowed.get(&key).copied().unwrap_or(0).max(1) {
+                duplicates.push(index);
+            }
+        }
+        duplicates
+    }
+
+    /// Pending tasks and priorities of this entry, without ids
+    pub fn pending_tasks(&self) -> Vec<PendingTask> {
+        self.all_bullets()
+            .filter(|b| b.task_state == Some(TaskState::Pending))
+            .map(|bullet| PendingTask {
+                id: None,
+                date: self.date,
+                bullet: Bullet {
+                    children: Vec::new(),
+                    ..bullet.clone()
+                },
+            })
+            .collect()
     }
 
     pub fn is_empty(&self) -> bool {
-        self.bullets.values().all(|bullets| bullets.is_empty())
+        self.bullets.values().all(|bullets| bullets.is_empty()) && self.body.is_none()
+    }
+
+    /// Start times of the entry's `## HH:MM` sessions, earliest first
+    pub fn sessions(&self) -> Vec<NaiveTime> {
+        let mut sessions: Vec<NaiveTime> = self
+            .bullets
+            .values()
+            .flatten()
+            .filter_map(|b| b.session)
+            .collect();
+        sessions.sort();
+        sessions.dedup();
+        sessions
+    }
+
+    /// The bullets of one session, or of none for `None`, as an entry of their own
+    pub fn session(&self, session: Option<NaiveTime>) -> Entry {
+        let mut entry = Entry::new(self.date);
+        for bullet in self.bullets.values().flatten() {
+            if bullet.session == session {
+                entry.add_bullet(bullet.clone());
+            }
+        }
+        entry
     }
 
     pub fn bullet_count(&self, bullet_type: &BulletType) -> usize {
@@ -184,7 +950,18 @@ impl Entry {
     }
 
     pub fn total_bullets(&self) -> usize {
-        self.bullets.values().map(|bullets| bullets.len()).sum()
+        self.all_bullets().count()
+    }
+
+    pub fn word_count(&self) -> usize {
+        let body_words = self
+            .body
+            .as_deref()
+            .map_or(0, |b| b.split_whitespace().count());
+        self.all_bullets()
+            .map(|bullet| bullet.word_count())
+            .sum::<usize>()
+            + body_words
     }
 }
 
@@ -197,6 +974,8 @@ pub enum ViewScope {
     Day(NaiveDate),
     Week(NaiveDate),  // Start of week
     Month(NaiveDate), // Start of month
+    Year(NaiveDate),  // Start of year
+    Span(NaiveDate, NaiveDate),
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -232,6 +1011,13 @@ impl DateRange {
         }
     }
 
+    /// The week containing `date`, for weeks that start on `first_day`
+    pub fn week_containing(date: NaiveDate, first_day: Weekday) -> Self {
+        let offset =
+            (7 + date.weekday().num_days_from_monday() - first_day.num_days_from_monday()) % 7;
+        Self::week(date - chrono::Duration::days(offset as i64))
+    }
+
     pub fn month(year: i32, month: u32) -> Self {
         let start = NaiveDate::from_ymd_opt(year, month, 1).expect("Invalid year/month");
         let end = if month == 12 {
@@ -250,6 +1036,26 @@ impl DateRange {
         }
     }
 
+    pub fn year(year: i32) -> Self {
+        let start = NaiveDate::from_ymd_opt(year, 1, 1).expect("Invalid year");
+        let end = NaiveDate::from_ymd_opt(year, 12, 31).expect("Invalid year");
+
+        Self {
+            start,
+            end,
+            scope: ViewScope::Year(start),
+        }
+    }
+
+    /// Arbitrary inclusive range between two dates
+    pub fn between(start: NaiveDate, end: NaiveDate) -> Self {
+        Self {
+            start,
+            end,
+            scope: ViewScope::Span(start, end),
+        }
+    }
+
     pub fn contains(&self, date: NaiveDate) -> bool {
         date >= self.start && date <= self.end
     }
@@ -279,10 +1085,10 @@ impl Journal {
     }
 
     pub fn get_entry(&mut self, date: NaiveDate) -> Result<Option<&Entry>> {
-        if !self.entries.contains_key(&date) {
-            if let Some(entry) = self.repository.load(date)? {
-                self.entries.insert(date, entry);
-            }
+        if !self.entries.contains_key(&date)
+            && let Some(entry) = self.repository.load(date)?
+        {
+            self.entries.insert(date, entry);
         }
 
         Ok(self.entries.get(&date))
@@ -0,0 +1,184 @@
+use crate::entities::{
+    BulletHit, BulletType, DateRange, Entry, PeriodStats, PersonSummary, TaskState,
+};
+#[cfg(feature = "duckdb")]
+use crate::infrastructure::DuckDbStorage;
+use crate::infrastructure::storage::JournalStorage;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Async counterpart of the entry and query side of `JournalStorage`, for
+/// embedders running on an async runtime (an Axum server, a GUI event loop).
+/// Arguments are owned so calls can be moved onto another thread.
+#[async_trait]
+pub trait AsyncJournalStorage: Send + Sync {
+    /// Load a single entry by date
+    async fn load_entry(&self, date: NaiveDate) -> Result<Option<Entry>>;
+
+    /// Load all entries in the range, sorted by date
+    async fn load_entries(&self, range: DateRange) -> Result<Vec<Entry>>;
+
+    /// Dates with entries in the range
+    async fn list_dates(&self, range: DateRange) -> Result<Vec<NaiveDate>>;
+
+    /// Save an entry, replacing the stored one for its date
+    async fn save_entry(&self, entry: Entry) -> Result<()>;
+
+    /// Delete an entry and its bullets
+    async fn delete_entry(&self, date: NaiveDate) -> Result<()>;
+
+    /// Version of an entry, bumped on every save or delete (0 if never saved)
+    async fn entry_version(&self, date: NaiveDate) -> Result<u64>;
+
+    /// Search entries by text content
+    async fn search_entries(&self, query: String) -> Result<Vec<Entry>>;
+
+    /// Search individual bullets, see `JournalStorage::search_bullets`
+    async fn search_bullets(
+        &self,
+        query: String,
+        types: Vec<BulletType>,
+        states: Vec<TaskState>,
+        range: Option<DateRange>,
+    ) -> Result<Vec<BulletHit>>;
+
+    /// People mentioned with `@name`, most frequently mentioned first
+    async fn list_people(&self) -> Result<Vec<PersonSummary>>;
+
+    /// Number of bullets written on each day of a range that has any
+    async fn daily_bullet_counts(&self, range: DateRange) -> Result<HashMap<NaiveDate, u64>>;
+
+    /// Task, event and word totals for a range
+    async fn period_stats(&self, range: DateRange) -> Result<PeriodStats>;
+
+    /// Dates of the first and last entries, if any exist
+    async fn entry_date_span(&self) -> Result<Option<(NaiveDate, NaiveDate)>>;
+}
+
+/// Runs a blocking `JournalStorage` on Tokio's blocking thread pool through
+/// `spawn_blocking`, so database calls never stall the runtime's workers.
+/// Anything not covered by `AsyncJournalStorage` can go through `run`.
+#[derive(Clone)]
+pub struct BlockingStorage {
+    inner: Arc<dyn JournalStorage>,
+}
+
+impl BlockingStorage {
+    pub fn new(inner: Arc<dyn JournalStorage>) -> Self {
+        Self { inner }
+    }
+
+    /// Open (and migrate) a DuckDB database without blocking the runtime
+    #[cfg(feature = "duckdb")]
+    pub async fn open_duckdb(db_path: impl Into<std::path::PathBuf>) -> Result<Self> {
+        let db_path = db_path.into();
+        let storage = tokio::task::spawn_blocking(move || DuckDbStorage::new(db_path))
+            .await
+            .context("Storage task panicked")??;
+        Ok(Self::new(Arc::new(storage)))
+    }
+
+    /// The wrapped storage, e.g. to hand to a `JournalBuilder`
+    pub fn inner(&self) -> &Arc<dyn JournalStorage> {
+        &self.inner
+    }
+
+    /// Run a closure against the storage on the blocking thread pool
+    pub async fn run<T, F>(&self, f: F) -> Result<T>
+    where
+        F: FnOnce(&dyn JournalStorage) -> Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let inner = self.inner.clone();
+        tokio::task::spawn_blocking(move || f(inner.as_ref()))
+            .await
+            .context("Storage task panicked")?
+    }
+}
+
+#[async_trait]
+impl AsyncJournalStorage for BlockingStorage {
+    async fn load_entry(&self, date: NaiveDate) -> Result<Option<Entry>> {
+        self.run(move |s| s.load_entry(date)).await
+    }
+
+    async fn load_entries(&self, range: DateRange) -> Result<Vec<Entry>> {
+        self.run(move |s| s.load_entries(range)).await
+    }
+
+    async fn list_dates(&self, range: DateRange) -> Result<Vec<NaiveDate>> {
+        self.run(move |s| s.list_dates(range)).await
+    }
+
+    async fn save_entry(&self, entry: Entry) -> Result<()> {
+        self.run(move |s| s.save_entry(&entry)).await
+    }
+
+    async fn delete_entry(&self, date: NaiveDate) -> Result<()> {
+        self.run(move |s| s.delete_entry(date)).await
+    }
+
+    async fn entry_version(&self, date: NaiveDate) -> Result<u64> {
+        self.run(move |s| s.entry_version(date)).await
+    }
+
+    async fn search_entries(&self, query: String) -> Result<Vec<Entry>> {
+        self.run(move |s| s.search_entries(&query)).await
+    }
+
+    async fn search_bullets(
+        &self,
+        query: String,
+        types: Vec<BulletType>,
+        states: Vec<TaskState>,
+        range: Option<DateRange>,
+    ) -> Result<Vec<BulletHit>> {
+        self.run(move |s| s.search_bullets(&query, &types, &states, range))
+            .await
+    }
+
+    async fn list_people(&self) -> Result<Vec<PersonSummary>> {
+        self.run(|s| s.list_people()).await
+    }
+
+    async fn daily_bullet_counts(&self, range: DateRange) -> Result<HashMap<NaiveDate, u64>> {
+        self.run(move |s| s.daily_bullet_counts(range)).await
+    }
+
+    async fn period_stats(&self, range: DateRange) -> Result<PeriodStats> {
+        self.run(move |s| s.period_stats(range)).await
+    }
+
+    async fn entry_date_span(&self) -> Result<Option<(NaiveDate, NaiveDate)>> {
+        self.run(|s| s.entry_date_span()).await
+    }
+}
+
+#[cfg(all(test, feature = "duckdb"))]
+mod tests {
+    use super::*;
+    use crate::entities::Bullet;
+
+    #[tokio::test]
+    async fn test_blocking_storage_round_trip() {
+        let storage = BlockingStorage::new(Arc::new(DuckDbStorage::in_memory().unwrap()));
+        let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let mut entry = Entry::new(date);
+        entry.add_bullet(Bullet::new("Ship the async API", BulletType::Task));
+
+        storage.save_entry(entry).await.unwrap();
+        let loaded = storage.load_entry(date).await.unwrap().unwrap();
+        assert_eq!(loaded.total_bullets(), 1);
+        assert_eq!(storage.entry_version(date).await.unwrap(), 1);
+
+        let hits = storage
+            .search_bullets("async".to_string(), vec![], vec![], None)
+            .await
+            .unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(storage.run(|s| s.count_entries()).await.unwrap(), 1);
+    }
+}
@@ -0,0 +1,197 @@
+use crate::entities::ExternalEvent;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, Utc};
+
+/// Read an iCalendar document from a local path or an http(s)/webcal URL
+pub fn fetch_ics(source: &str) -> Result<String> {
+    let url = match source.strip_prefix("webcal://") {
+        Some(rest) => format!("https://{}", rest),
+        None => source.to_string(),
+    };
+
+    if url.starts_with("http://") || url.starts_with("https://") {
+        ureq::get(&url)
+            .call()
+            .with_context(|| format!("Failed to fetch {}", url))?
+            .body_mut()
+            .read_to_string()
+            .with_context(|| format!("Failed to read calendar from {}", url))
+    } else {
+        std::fs::read_to_string(source).with_context(|| format!("Failed to read {}", source))
+    }
+}
+
+/// Extract events from an iCalendar (`.ics`) document.
+///
+/// Only the properties needed for the day preview are read. Times in UTC are
+/// converted to local time, times with a `TZID` are taken as local time, and
+/// recurrence rules are not expanded. All-day events spanning several days
+/// yield one event per day.
+pub fn parse_ics(content: &str) -> Vec<ExternalEvent> {
+    let mut events = Vec::new();
+    let mut current: Option<RawEvent> = None;
+
+    for line in unfold_lines(content) {
+        let Some((name_and_params, value)) = line.split_once(':') else {
+            continue;
+        };
+        let mut parts = name_and_params.split(';');
+        let name = parts.next().unwrap_or_default().to_ascii_uppercase();
+
+        match (name.as_str(), value) {
+            ("BEGIN", "VEVENT") => current = Some(RawEvent::default()),
+            ("END", "VEVENT") => {
+                if let Some(raw) = current.take() {
+                    events.extend(raw.into_events());
+                }
+            }
+            _ => {
+                let Some(raw) = current.as_mut() else {
+                    continue;
+                };
+                match name.as_str() {
+                    "UID" => raw.uid = Some(value.to_string()),
+                    "SUMMARY" => raw.summary = Some(unescape(value)),
+                    "LOCATION" => raw.location = Some(unescape(value)),
+                    "DTSTART" => raw.start = parse_datetime(value),
+                    "DTEND" => raw.end = parse_datetime(value),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    events
+}
+
+/// A date or date-time property value
+#[derive(Debug, Clone, Copy)]
+enum IcsTime {
+    Date(NaiveDate),
+    DateTime(NaiveDateTime),
+}
+
+#[derive(Default)]
+struct RawEvent {
+    uid: Option<String>,
+    summary: Option<String>,
+    location: Option<String>,
+    start: Option<IcsTime>,
+    end: Option<IcsTime>,
+}
+
+impl RawEvent {
+    fn into_events(self) -> Vec<ExternalEvent> {
+        let Some(start) = self.start else {
+            return Vec::new();
+        };
+        let event = |date, start, end| ExternalEvent {
+            uid: self.uid.clone(),
+            date,
+            start,
+            end,
+            summary: self.summary.clone().unwrap_or_default(),
+            location: self.location.clone(),
+        };
+
+        match start {
+            IcsTime::Date(first) => {
+                // DTEND of an all-day event is exclusive
+                let last = match self.end {
+                    Some(IcsTime::Date(end)) if end > first => end - Duration::days(1),
+                    _ => first,
+                };
+                first
+                    .iter_days()
+                    .take_while(|d| *d <= last)
+                    .map(|d| event(d, None, None))
+                    .collect()
+            }
+            IcsTime::DateTime(start) => {
+                // Events running past midnight only show their end on the first day
+                let end = match self.end {
+                    Some(IcsTime::DateTime(end)) if end.date() == start.date() => Some(end.time()),
+                    _ => None,
+                };
+                vec![event(start.date(), Some(start.time()), end)]
+            }
+        }
+    }
+}
+
+/// Join folded continuation lines (those starting with a space or tab)
+fn unfold_lines(content: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in content.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some(last)) => last.push_str(continuation),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+fn parse_datetime(value: &str) -> Option<IcsTime> {
+    if let Some(utc) = value.strip_suffix('Z') {
+        let naive = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        let local = DateTime::<Utc>::from_naive_utc_and_offset(naive, Utc).with_timezone(&Local);
+        return Some(IcsTime::DateTime(local.naive_local()));
+    }
+    if let Ok(naive) = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S") {
+        return Some(IcsTime::DateTime(naive));
+    }
+    NaiveDate::parse_from_str(value, "%Y%m%d")
+        .ok()
+        .map(IcsTime::Date)
+}
+
+fn unescape(value: &str) -> String {
+    value
+        .replace("\\n", " ")
+        .replace("\\N", " ")
+        .replace("\\,", ",")
+        .replace("\\;", ";")
+        .replace("\\\\", "\\")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ics_events() {
+        let ics = "BEGIN:VCALENDAR\r\n\
+BEGIN:VEVENT\r\n\
+UID:standup@example.com\r\n\
+DTSTART;TZID=Europe/Berlin:20240315T093000\r\n\
+DTEND;TZID=Europe/Berlin:20240315T094500\r\n\
+SUMMARY:Team standup\\, daily\r\n\
+LOCATION:Room\r\n  4\r\n\
+END:VEVENT\r\n\
+BEGIN:VEVENT\r\n\
+DTSTART;VALUE=DATE:20240316\r\n\
+DTEND;VALUE=DATE:20240318\r\n\
+SUMMARY:Conference\r\n\
+END:VEVENT\r\n\
+END:VCALENDAR\r\n";
+
+        let events = parse_ics(ics);
+        assert_eq!(events.len(), 3);
+
+        let standup = &events[0];
+        assert_eq!(standup.date, NaiveDate::from_ymd_opt(2024, 3, 15).unwrap());
+        assert_eq!(standup.summary, "Team standup, daily");
+        assert_eq!(standup.location.as_deref(), Some("Room 4"));
+        assert_eq!(standup.time_label(), "09:30-09:45");
+
+        assert_eq!(
+            events[1].date,
+            NaiveDate::from_ymd_opt(2024, 3, 16).unwrap()
+        );
+        assert_eq!(
+            events[2].date,
+            NaiveDate::from_ymd_opt(2024, 3, 17).unwrap()
+        );
+        assert_eq!(events[2].time_label(), "all day");
+    }
+}
@@ -0,0 +1,140 @@
+use crate::entities::{Bullet, BulletType, DateRange, Entry, TaskState};
+use chrono::NaiveDate;
+
+/// Layout used when no `[digest] template` is configured
+pub const DEFAULT_DIGEST_TEMPLATE: &str = "# Digest for {{start}} to {{end}}
+
+{{entry_count}} entries, {{completed_count}} tasks completed.
+
+## Completed tasks
+{{completed_tasks}}
+
+## Events
+{{events}}
+
+## Insights
+{{insights}}
+";
+
+/// Renders a summary of a date range from a template with `{{variable}}`
+/// placeholders:
+///
+/// - `start`, `end`: the range bounds as YYYY-MM-DD
+/// - `entry_count`, `completed_count`: numbers of entries and completed tasks
+/// - `completed_tasks`, `events`, `insights`, `priorities`: markdown lists of
+///   those bullets, each followed by its date
+/// - `freewriting`: the free-written bodies of the entries, each under its date
+///
+/// Unknown placeholders are left untouched.
+pub struct DigestRenderer<'a> {
+    template: &'a str,
+}
+
+impl<'a> DigestRenderer<'a> {
+    pub fn new(template: &'a str) -> Self {
+        Self { template }
+    }
+
+    /// Render the digest for entries within `range`
+    pub fn render(&self, range: &DateRange, entries: &[Entry]) -> String {
+        let completed = collect(entries, |b| {
+            matches!(b.bullet_type, BulletType::Task | BulletType::Priority)
+                && b.task_state == Some(TaskState::Completed)
+        });
+        let events = collect(entries, |b| b.bullet_type == BulletType::Event);
+        let insights = collect(entries, |b| b.bullet_type == BulletType::Insight);
+        let priorities = collect(entries, |b| b.bullet_type == BulletType::Priority);
+
+        let variables = [
+            ("start", range.start().format("%Y-%m-%d").to_string()),
+            ("end", range.end().format("%Y-%m-%d").to_string()),
+            ("entry_count", entries.len().to_string()),
+            ("completed_count", completed.len().to_string()),
+            ("completed_tasks", markdown_list(&completed)),
+            ("events", markdown_list(&events)),
+            ("insights", markdown_list(&insights)),
+            ("priorities", markdown_list(&priorities)),
+            ("freewriting", freewriting(entries)),
+        ];
+
+        let mut output = self.template.to_string();
+        for (name, value) in variables {
+            output = output.replace(&format!("{{{{{}}}}}", name), &value);
+        }
+        output
+    }
+}
+
+/// Bullets of every entry, nested ones included, that match `filter`
+fn collect(entries: &[Entry], filter: impl Fn(&Bullet) -> bool) -> Vec<(NaiveDate, &Bullet)> {
+    entries
+        .iter()
+        .flat_map(|entry| {
+            BulletType::ALL.into_iter().flat_map(move |bullet_type| {
+                entry
+                    .bullets_chronological(&bullet_type)
+                    .into_iter()
+                    .flat_map(|b| b.flatten())
+                    .map(move |b| (entry.date, b))
+            })
+        })
+        .filter(|(_, b)| filter(b))
+        .collect()
+}
+
+fn markdown_list(bullets: &[(NaiveDate, &Bullet)]) -> String {
+    if bullets.is_empty() {
+        return "- none".to_string();
+    }
+    bullets
+        .iter()
+        .map(|(date, bullet)| format!("- {} ({})", bullet.content, date.format("%a %b %d")))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn freewriting(entries: &[Entry]) -> String {
+    let bodies: Vec<String> = entries
+        .iter()
+        .filter_map(|entry| {
+            let body = entry.body.as_ref()?;
+            Some(format!("### {}\n\n{}", entry.date.format("%a %b %d"), body))
+        })
+        .collect();
+    if bodies.is_empty() {
+        return "none".to_string();
+    }
+    bodies.join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_digest() {
+        let monday = NaiveDate::from_ymd_opt(2024, 3, 11).unwrap();
+        let mut entry = Entry::new(monday);
+        entry.add_bullet(Bullet::new("Ship release", BulletType::Task).complete());
+        entry.add_bullet(Bullet::new("Still open", BulletType::Task));
+        entry.add_bullet(Bullet::new("Team offsite", BulletType::Event));
+        entry.body = Some("Long day.".to_string());
+
+        let range = DateRange::week(NaiveDate::from_ymd_opt(2024, 3, 10).unwrap());
+        let digest = DigestRenderer::new(DEFAULT_DIGEST_TEMPLATE).render(&range, &[entry.clone()]);
+        assert!(digest.starts_with("# Digest for 2024-03-10 to 2024-03-16"));
+        assert!(digest.contains("1 entries, 1 tasks completed."));
+        assert!(digest.contains("## Completed tasks\n- Ship release (Mon Mar 11)\n"));
+        assert!(digest.contains("## Events\n- Team offsite (Mon Mar 11)\n"));
+        assert!(digest.contains("## Insights\n- none\n"));
+        assert!(!digest.contains("Still open"));
+        assert!(!digest.contains("Long day."));
+
+        let bodies = DigestRenderer::new("{{freewriting}}").render(&range, &[entry]);
+        assert_eq!(bodies, "### Mon Mar 11\n\nLong day.");
+
+        let custom =
+            DigestRenderer::new("{{completed_count}} done {{unknown}}").render(&range, &[]);
+        assert_eq!(custom, "0 done {{unknown}}");
+    }
+}
@@ -0,0 +1,343 @@
+use crate::entities::{Bullet, BulletSymbols, BulletType, Entry};
+use anyhow::{Context, Result};
+use chrono::{Datelike, Duration, NaiveDate};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const STYLE: &str = "body{font-family:sans-serif;max-width:48rem;margin:2rem auto;padding:0 1rem;color:#222}\
+a{color:#2563eb;text-decoration:none}\
+table.calendar{border-collapse:collapse;margin-bottom:1.5rem}\
+table.calendar td,table.calendar th{width:2.5rem;text-align:center;padding:.25rem}\
+td.has-entry a{font-weight:bold}\
+ul.bullets{list-style:none;padding-left:0}\
+ul.nested{padding-left:1.5rem}\
+span.symbol{display:inline-block;width:1.5rem;color:#666}\
+div.freewrite p{white-space:pre-wrap}\
+nav{margin:1rem 0}";
+
+const SEARCH_SCRIPT: &str = r#"<input id="q" type="search" placeholder="Search...">
+<ul id="results" class="bullets"></ul>
+<script src="search.js"></script>
+<script>
+{
+  const items = window.SEARCH || [];
+  const q = document.getElementById('q');
+  const results = document.getElementById('results');
+  q.addEventListener('input', () => {
+    const term = q.value.toLowerCase();
+    results.innerHTML = '';
+    if (!term) return;
+    items.filter(i => i.content.toLowerCase().includes(term)).slice(0, 100).forEach(i => {
+      const li = document.createElement('li');
+      const a = document.createElement('a');
+      a.href = 'days/' + i.date + '.html';
+      a.textContent = i.date;
+      li.append(a, ' ' + i.content);
+      results.append(li);
+    });
+  });
+}
+</script>"#;
+
+/// Record written to `search.js` for client-side search
+#[derive(Serialize)]
+struct SearchRecord<'a> {
+    date: String,
+    #[serde(rename = "type")]
+    bullet_type: String,
+    content: &'a str,
+}
+
+/// Renders journal entries as a browsable static HTML site:
+///
+/// ```text
+/// out_dir/
+/// ├── index.html          # Calendar index and client-side search
+/// ├── search.js           # Every bullet as `window.SEARCH`, for the search box
+/// ├── days/YYYY-MM-DD.html
+/// └── tags/<tag>.html
+/// ```
+pub struct HtmlSiteExporter {
+    out_dir: PathBuf,
+    symbols: BulletSymbols,
+}
+
+impl HtmlSiteExporter {
+    pub fn new(out_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            out_dir: out_dir.into(),
+            symbols: BulletSymbols::default(),
+        }
+    }
+
+    /// Use custom glyphs for bullet types and task states
+    pub fn with_symbols(mut self, symbols: BulletSymbols) -> Self {
+        self.symbols = symbols;
+        self
+    }
+
+    /// Write the site for the given entries, which should be sorted by date
+    pub fn export(&self, entries: &[Entry]) -> Result<()> {
+        let days_dir = self.out_dir.join("days");
+        let tags_dir = self.out_dir.join("tags");
+        fs::create_dir_all(&days_dir)
+            .with_context(|| format!("Failed to create directory: {}", days_dir.display()))?;
+        fs::create_dir_all(&tags_dir)
+            .with_context(|| format!("Failed to create directory: {}", tags_dir.display()))?;
+
+        let mut tags: BTreeMap<String, Vec<(NaiveDate, String)>> = BTreeMap::new();
+        let mut search_records = Vec::new();
+
+        for (i, entry) in entries.iter().enumerate() {
+            let prev = i.checked_sub(1).map(|p| entries[p].date);
+            let next = entries.get(i + 1).map(|e| e.date);
+            write_file(
+                &days_dir.join(format!("{}.html", entry.date.format("%Y-%m-%d"))),
+                &render_day(entry, prev, next, &self.symbols),
+            )?;
+
+            for bullet_type in BulletType::ALL {
+                for bullet in entry
+                    .get_bullets(&bullet_type)
+                    .iter()
+                    .flat_map(|b| b.flatten())
+                {
+                    for tag in bullet.tags() {
+                        tags.entry(tag)
+                            .or_default()
+                            .push((entry.date, bullet.content.clone()));
+                    }
+                    search_records.push(SearchRecord {
+                        date: entry.date.format("%Y-%m-%d").to_string(),
+                        bullet_type: bullet_type.to_string(),
+                        content: &bullet.content,
+                    });
+                }
+            }
+        }
+
+        for (tag, bullets) in &tags {
+            write_file(
+                &tags_dir.join(format!("{}.html", tag)),
+                &render_tag(tag, bullets),
+            )?;
+        }
+
+        let dates: BTreeSet<NaiveDate> = entries.iter().map(|e| e.date).collect();
+        write_file(
+            &self.out_dir.join("index.html"),
+            &render_index(&dates, tags.keys()),
+        )?;
+        // A script rather than JSON, since browsers refuse to fetch files
+        // next to a page opened from disk
+        write_file(
+            &self.out_dir.join("search.js"),
+            &format!(
+                "window.SEARCH = {};\n",
+                serde_json::to_string(&search_records)?
+            ),
+        )?;
+
+        Ok(())
+    }
+}
+
+fn write_file(path: &Path, content: &str) -> Result<()> {
+    fs::write(path, content).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+pub(crate) fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub(crate) fn page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n{}\n</body>\n</html>\n",
+        escape(title),
+        STYLE,
+        body
+    )
+}
+
+fn render_day(
+    entry: &Entry,
+    prev: Option<NaiveDate>,
+    next: Option<NaiveDate>,
+    symbols: &BulletSymbols,
+) -> String {
+    let mut body = format!("<h1>{}</h1>\n<nav>", entry.date.format("%A, %B %d, %Y"));
+    if let Some(prev) = prev {
+        body.push_str(&format!(
+            "<a href=\"{0}.html\">&larr; {0}</a> ",
+            prev.format("%Y-%m-%d")
+        ));
+    }
+    body.push_str("<a href=\"../index.html\">Index</a>");
+    if let Some(next) = next {
+        body.push_str(&format!(
+            " <a href=\"{0}.html\">{0} &rarr;</a>",
+            next.format("%Y-%m-%d")
+        ));
+    }
+    body.push_str("</nav>\n");
+
+    for bullet_type in BulletType::ALL {
+        let bullets = entry.get_bullets(&bullet_type);
+        if bullets.is_empty() {
+            continue;
+        }
+        body.push_str(&format!(
+            "<h2>{}</h2>\n<ul class=\"bullets\">\n",
+            bullet_type.section_name()
+        ));
+        for bullet in entry.bullets_chronological(&bullet_type) {
+            body.push_str(&render_bullet(bullet, symbols));
+        }
+        body.push_str("</ul>\n");
+    }
+
+    if let Some(text) = &entry.body {
+        body.push_str("<h2>Freewrite</h2>\n<div class=\"freewrite\">\n");
+        for paragraph in text.split("\n\n").map(str::trim).filter(|p| !p.is_empty()) {
+            body.push_str(&format!("<p>{}</p>\n", escape(paragraph)));
+        }
+        body.push_str("</div>\n");
+    }
+
+    page(&entry.date.format("%Y-%m-%d").to_string(), &body)
+}
+
+/// List item for a bullet, with sub-bullets as a nested list
+pub(crate) fn render_bullet(bullet: &Bullet, symbols: &BulletSymbols) -> String {
+    let time = bullet
+        .time_label()
+        .map(|t| format!("<time>{}</time> ", t))
+        .unwrap_or_default();
+    let mut html = format!(
+        "<li><span class=\"symbol\">{}</span>{}{}",
+        escape(symbols.symbol(bullet)),
+        time,
+        escape(&bullet.content)
+    );
+    if !bullet.children.is_empty() {
+        html.push_str("\n<ul class=\"bullets nested\">\n");
+        for child in &bullet.children {
+            html.push_str(&render_bullet(child, symbols));
+        }
+        html.push_str("</ul>\n");
+    }
+    html.push_str("</li>\n");
+    html
+}
+
+fn render_tag(tag: &str, bullets: &[(NaiveDate, String)]) -> String {
+    let mut body = format!(
+        "<h1>#{}</h1>\n<nav><a href=\"../index.html\">Index</a></nav>\n<ul class=\"bullets\">\n",
+        escape(tag)
+    );
+    for (date, content) in bullets {
+        body.push_str(&format!(
+            "<li><a href=\"../days/{0}.html\">{0}</a> {1}</li>\n",
+            date.format("%Y-%m-%d"),
+            escape(content)
+        ));
+    }
+    body.push_str("</ul>\n");
+    page(&format!("#{}", tag), &body)
+}
+
+fn render_index<'a>(dates: &BTreeSet<NaiveDate>, tags: impl Iterator<Item = &'a String>) -> String {
+    let mut body = String::from("<h1>Journal</h1>\n");
+    body.push_str(SEARCH_SCRIPT);
+    body.push('\n');
+
+    let tag_links: Vec<String> = tags
+        .map(|tag| format!("<a href=\"tags/{0}.html\">#{0}</a>", escape(tag)))
+        .collect();
+    if !tag_links.is_empty() {
+        body.push_str(&format!("<h2>Tags</h2>\n<p>{}</p>\n", tag_links.join(" ")));
+    }
+
+    let months: BTreeSet<(i32, u32)> = dates.iter().map(|d| (d.year(), d.month())).collect();
+    for (year, month) in months.into_iter().rev() {
+        body.push_str(&render_month(year, month, dates));
+    }
+
+    page("Journal", &body)
+}
+
+fn render_month(year: i32, month: u32, dates: &BTreeSet<NaiveDate>) -> String {
+    let first = NaiveDate::from_ymd_opt(year, month, 1).expect("Invalid year/month");
+    let mut html = format!(
+        "<h2>{}</h2>\n<table class=\"calendar\">\n<tr><th>Sun</th><th>Mon</th><th>Tue</th><th>Wed</th><th>Thu</th><th>Fri</th><th>Sat</th></tr>\n<tr>",
+        first.format("%B %Y")
+    );
+
+    let leading_blanks = first.weekday().num_days_from_sunday();
+    for _ in 0..leading_blanks {
+        html.push_str("<td></td>");
+    }
+
+    let mut date = first;
+    while date.month() == month {
+        if date.weekday().num_days_from_sunday() == 0 && date != first {
+            html.push_str("</tr>\n<tr>");
+        }
+        if dates.contains(&date) {
+            html.push_str(&format!(
+                "<td class=\"has-entry\"><a href=\"days/{}.html\">{}</a></td>",
+                date.format("%Y-%m-%d"),
+                date.day()
+            ));
+        } else {
+            html.push_str(&format!("<td>{}</td>", date.day()));
+        }
+        date += Duration::days(1);
+    }
+    html.push_str("</tr>\n</table>\n");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_export_writes_site_structure() {
+        let temp_dir = TempDir::new().unwrap();
+        let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let mut entry = Entry::new(date);
+        entry.add_bullet(Bullet::new("Plan <launch> #work", BulletType::Task));
+        entry.add_bullet(Bullet::new("Coffee with Sam #Social", BulletType::Event));
+
+        HtmlSiteExporter::new(temp_dir.path())
+            .export(&[entry])
+            .unwrap();
+
+        let day = fs::read_to_string(temp_dir.path().join("days/2024-03-15.html")).unwrap();
+        assert!(day.contains("<h2>Tasks</h2>"));
+        assert!(day.contains("Plan &lt;launch&gt; #work"));
+
+        let index = fs::read_to_string(temp_dir.path().join("index.html")).unwrap();
+        assert!(index.contains("March 2024"));
+        assert!(index.contains("days/2024-03-15.html"));
+        assert!(index.contains("tags/social.html"));
+
+        assert!(temp_dir.path().join("tags/work.html").exists());
+
+        assert!(index.contains(r#"<script src="search.js">"#));
+        let script = fs::read_to_string(temp_dir.path().join("search.js")).unwrap();
+        let records = script
+            .strip_prefix("window.SEARCH = ")
+            .and_then(|rest| rest.strip_suffix(";\n"))
+            .unwrap();
+        let search: serde_json::Value = serde_json::from_str(records).unwrap();
+        assert_eq!(search.as_array().unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,11 @@
+pub mod digest;
+pub mod html;
+pub mod obsidian;
+pub mod pdf;
+pub mod registry;
+
+pub use digest::*;
+pub use html::*;
+pub use obsidian::*;
+pub use pdf::*;
+pub use registry::*;
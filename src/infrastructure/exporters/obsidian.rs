@@ -0,0 +1,193 @@
+use crate::entities::{Bullet, BulletType, Entry, TaskState};
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::PathBuf;
+
+/// Writes journal entries as Obsidian daily notes, one `YYYY-MM-DD.md` per day:
+///
+/// ```markdown
+/// ---
+/// date: 2024-03-15
+/// tags:
+///   - work
+/// ---
+/// [[2024-03-14]] | [[2024-03-16]]
+///
+/// ## Tasks
+/// - [x] 09:30 Plan launch with [[sam|@sam]] #work
+/// ```
+///
+/// Tasks use the checkbox markers Obsidian understands (`[ ]`, `[x]`, and
+/// `[>]`/`[<]` for migrated and scheduled), `@mentions` and dates written as
+/// `YYYY-MM-DD` become wiki-links, and each note links to its neighbours.
+pub struct ObsidianExporter {
+    out_dir: PathBuf,
+}
+
+impl ObsidianExporter {
+    pub fn new(out_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            out_dir: out_dir.into(),
+        }
+    }
+
+    /// Write a note for each of the given entries, which should be sorted by date
+    pub fn export(&self, entries: &[Entry]) -> Result<()> {
+        self.create_dir()?;
+        for (i, entry) in entries.iter().enumerate() {
+            let prev = i.checked_sub(1).map(|p| entries[p].date);
+            let next = entries.get(i + 1).map(|e| e.date);
+            self.write_note(entry, prev, next)?;
+        }
+        Ok(())
+    }
+
+    pub fn create_dir(&self) -> Result<()> {
+        fs::create_dir_all(&self.out_dir)
+            .with_context(|| format!("Failed to create directory: {}", self.out_dir.display()))
+    }
+
+    /// Write the note for one entry, linking to the days written before and
+    /// after it, so entries can be exported one at a time
+    pub fn write_note(
+        &self,
+        entry: &Entry,
+        prev: Option<NaiveDate>,
+        next: Option<NaiveDate>,
+    ) -> Result<()> {
+        let path = self
+            .out_dir
+            .join(format!("{}.md", entry.date.format("%Y-%m-%d")));
+        fs::write(&path, render_note(entry, prev, next))
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+fn render_note(entry: &Entry, prev: Option<NaiveDate>, next: Option<NaiveDate>) -> String {
+    let tags: BTreeSet<String> = entry.all_bullets().flat_map(|b| b.tags()).collect();
+    let mut note = format!("---\ndate: {}\n", entry.date.format("%Y-%m-%d"));
+    if !tags.is_empty() {
+        note.push_str("tags:\n");
+        for tag in &tags {
+            note.push_str(&format!("  - {}\n", tag));
+        }
+    }
+    note.push_str("---\n");
+
+    let nav: Vec<String> = [prev, next]
+        .into_iter()
+        .flatten()
+        .map(|date| format!("[[{}]]", date.format("%Y-%m-%d")))
+        .collect();
+    if !nav.is_empty() {
+        note.push_str(&nav.join(" | "));
+        note.push('\n');
+    }
+
+    for bullet_type in BulletType::ALL {
+        let bullets = entry.get_bullets(&bullet_type);
+        if bullets.is_empty() {
+            continue;
+        }
+        note.push_str(&format!("\n## {}\n", bullet_type.section_name()));
+        for bullet in entry.bullets_chronological(&bullet_type) {
+            render_bullet(&mut note, bullet, 0);
+        }
+    }
+    if let Some(body) = &entry.body {
+        let lines: Vec<String> = body.lines().map(link_references).collect();
+        note.push_str(&format!("\n## Freewrite\n{}\n", lines.join("\n")));
+    }
+    note
+}
+
+fn render_bullet(note: &mut String, bullet: &Bullet, depth: usize) {
+    let checkbox = match bullet.task_state {
+        Some(TaskState::Pending) => "[ ] ",
+        Some(TaskState::Completed) => "[x] ",
+        Some(TaskState::Migrated) => "[>] ",
+        Some(TaskState::Scheduled) => "[<] ",
+        None => "",
+    };
+    let time = bullet
+        .time_label()
+        .map(|t| format!("{} ", t))
+        .unwrap_or_default();
+    note.push_str(&format!(
+        "{}- {}{}{}\n",
+        "  ".repeat(depth),
+        checkbox,
+        time,
+        link_references(&bullet.content)
+    ));
+    for child in &bullet.children {
+        render_bullet(note, child, depth + 1);
+    }
+}
+
+/// Turn `@name` mentions and `YYYY-MM-DD` dates into wiki-links, keeping any
+/// trailing punctuation outside the link
+fn link_references(content: &str) -> String {
+    content
+        .split(' ')
+        .map(|word| {
+            let end = word
+                .char_indices()
+                .skip(1)
+                .find(|(_, c)| !(c.is_alphanumeric() || *c == '-' || *c == '_'))
+                .map_or(word.len(), |(i, _)| i);
+            let (token, rest) = word.split_at(end);
+            if let Some(name) = token
+                .strip_prefix('@')
+                .filter(|n| n.starts_with(|c: char| c.is_alphabetic()))
+            {
+                format!("[[{}|{}]]{}", name.to_lowercase(), token, rest)
+            } else if NaiveDate::parse_from_str(token, "%Y-%m-%d").is_ok() {
+                format!("[[{}]]{}", token, rest)
+            } else {
+                word.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveTime;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_export_writes_daily_notes() {
+        let temp_dir = TempDir::new().unwrap();
+        let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let mut entry = Entry::new(date);
+        entry.add_bullet(
+            Bullet::with_task_state(
+                "Plan launch with @Sam #work",
+                BulletType::Task,
+                TaskState::Completed,
+            )
+            .at(NaiveTime::from_hms_opt(9, 30, 0).unwrap())
+            .with_child(Bullet::new("Follow up on 2024-03-20.", BulletType::Task)),
+        );
+        entry.add_bullet(Bullet::new("Mail sam@example.com", BulletType::Note));
+        entry.body = Some("Long talk with @Sam.".to_string());
+        let next = Entry::new(NaiveDate::from_ymd_opt(2024, 3, 16).unwrap());
+
+        ObsidianExporter::new(temp_dir.path())
+            .export(&[entry, next])
+            .unwrap();
+
+        let note = fs::read_to_string(temp_dir.path().join("2024-03-15.md")).unwrap();
+        assert!(note.starts_with("---\ndate: 2024-03-15\ntags:\n  - work\n---\n[[2024-03-16]]\n"));
+        assert!(note.contains("## Tasks\n- [x] 09:30 Plan launch with [[sam|@Sam]] #work\n"));
+        assert!(note.contains("  - [ ] Follow up on [[2024-03-20]].\n"));
+        assert!(note.contains("## Notes\n- Mail sam@example.com\n"));
+        assert!(note.ends_with("## Freewrite\nLong talk with [[sam|@Sam]].\n"));
+        assert!(temp_dir.path().join("2024-03-16.md").exists());
+    }
+}
@@ -0,0 +1,436 @@
+use crate::entities::{Bullet, BulletSymbols, BulletType, Entry};
+use crate::infrastructure::Exporter;
+use anyhow::Result;
+use chrono::{Datelike, Duration, NaiveDate};
+use pdf_writer::{Content, Finish, Name, Pdf, Rect, Ref, Str, TextStr};
+use std::collections::BTreeMap;
+
+// A5 portrait, in points
+const PAGE_WIDTH: f32 = 419.5;
+const PAGE_HEIGHT: f32 = 595.3;
+const MARGIN: f32 = 44.0;
+const TEXT_WIDTH: f32 = PAGE_WIDTH - 2.0 * MARGIN;
+const BODY_SIZE: f32 = 10.0;
+const LEADING: f32 = 13.5;
+const INDENT: f32 = 14.0;
+
+const REGULAR: Name = Name(b"F1");
+const BOLD: Name = Name(b"F2");
+
+/// Widths of ASCII 32..=126 in Helvetica, in thousandths of the font size
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556, 1015, 667, 667, 722, 722, 667,
+    611, 778, 722, 278, 500, 667, 556, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+    667, 611, 278, 278, 278, 469, 556, 333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500,
+    222, 833, 556, 556, 556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+
+/// Entries typeset as an A5 booklet for printing: a cover page, then for
+/// every month a calendar marking the days written followed by those days'
+/// entries. Only the PDF base fonts are used, so nothing is embedded and
+/// characters outside Windows-1252 print as `?`.
+pub struct PdfExporter;
+
+impl Exporter for PdfExporter {
+    fn name(&self) -> &str {
+        "pdf"
+    }
+
+    fn extension(&self) -> &str {
+        "pdf"
+    }
+
+    fn export(&self, entries: &[Entry]) -> Result<Vec<u8>> {
+        let mut booklet = Booklet::new();
+        booklet.cover(entries);
+
+        let mut months: BTreeMap<(i32, u32), Vec<&Entry>> = BTreeMap::new();
+        for entry in entries.iter().filter(|e| !e.is_empty()) {
+            months
+                .entry((entry.date.year(), entry.date.month()))
+                .or_default()
+                .push(entry);
+        }
+        for ((year, month), entries) in months {
+            let first = NaiveDate::from_ymd_opt(year, month, 1).expect("valid month");
+            booklet.month_calendar(first, &entries);
+            for entry in entries {
+                booklet.entry(entry);
+            }
+        }
+
+        Ok(booklet.finish())
+    }
+}
+
+/// Pages being laid out, writing into the last one
+struct Booklet {
+    pages: Vec<Content>,
+    /// Baseline of the next line on the current page
+    y: f32,
+    symbols: BulletSymbols,
+}
+
+impl Booklet {
+    fn new() -> Self {
+        Self {
+            pages: Vec::new(),
+            y: 0.0,
+            // Glyphs the base fonts have
+            symbols: BulletSymbols {
+                event: "o".to_string(),
+                note: "–".to_string(),
+                priority: "*".to_string(),
+                ..BulletSymbols::default()
+            },
+        }
+    }
+
+    fn new_page(&mut self) {
+        self.pages.push(Content::new());
+        self.y = PAGE_HEIGHT - MARGIN;
+    }
+
+    fn page(&mut self) -> &mut Content {
+        self.pages.last_mut().expect("a page was started")
+    }
+
+    /// Start a new page unless `height` more points fit on this one
+    fn ensure_space(&mut self, height: f32) {
+        if self.pages.is_empty() || self.y - height < MARGIN {
+            self.new_page();
+        }
+    }
+
+    fn text_at(&mut self, font: Name, size: f32, x: f32, y: f32, text: &str) {
+        let encoded = encode(text);
+        self.page()
+            .begin_text()
+            .set_font(font, size)
+            .next_line(x, y)
+            .show(Str(&encoded))
+            .end_text();
+    }
+
+    fn centered(&mut self, font: Name, size: f32, y: f32, text: &str) {
+        let x = (PAGE_WIDTH - text_width(text, size)) / 2.0;
+        self.text_at(font, size, x, y, text);
+    }
+
+    /// Write wrapped text, its first line starting with `marker` and the
+    /// following ones indented past it
+    fn paragraph(&mut self, indent: f32, marker: &str, text: &str) {
+        let marker_width = if marker.is_empty() {
+            0.0
+        } else {
+            text_width(marker, BODY_SIZE) + 5.0
+        };
+        let x = MARGIN + indent + marker_width;
+        for (i, line) in wrap(text, TEXT_WIDTH - indent - marker_width, BODY_SIZE)
+            .iter()
+            .enumerate()
+        {
+            self.ensure_space(LEADING);
+            self.y -= LEADING;
+            let y = self.y;
+            if i == 0 && !marker.is_empty() {
+                self.text_at(REGULAR, BODY_SIZE, MARGIN + indent, y, marker);
+            }
+            self.text_at(REGULAR, BODY_SIZE, x, y, line);
+        }
+    }
+
+    fn cover(&mut self, entries: &[Entry]) {
+        self.new_page();
+        self.centered(BOLD, 30.0, PAGE_HEIGHT * 0.6, "Journal");
+        if let (Some(first), Some(last)) = (entries.first(), entries.last()) {
+            let span = if (first.date.year(), first.date.month())
+                == (last.date.year(), last.date.month())
+            {
+                first.date.format("%B %Y").to_string()
+            } else {
+                format!(
+                    "{} – {}",
+                    first.date.format("%B %Y"),
+                    last.date.format("%B %Y")
+                )
+            };
+            self.centered(REGULAR, 14.0, PAGE_HEIGHT * 0.6 - 30.0, &span);
+            let count = format!(
+                "{} {}",
+                entries.len(),
+                if entries.len() == 1 {
+                    "entry"
+                } else {
+                    "entries"
+                }
+            );
+            self.centered(REGULAR, 11.0, PAGE_HEIGHT * 0.6 - 50.0, &count);
+        }
+    }
+
+    /// A page with the month's calendar, days with an entry shaded and
+    /// showing their bullet count
+    fn month_calendar(&mut self, first: NaiveDate, entries: &[&Entry]) {
+        self.new_page();
+        let title = first.format("%B %Y").to_string();
+        self.text_at(BOLD, 20.0, MARGIN, PAGE_HEIGHT - MARGIN - 20.0, &title);
+
+        let cell_width = TEXT_WIDTH / 7.0;
+        let cell_height = 44.0;
+        let grid_top = PAGE_HEIGHT - MARGIN - 60.0;
+        for (i, name) in ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"]
+            .iter()
+            .enumerate()
+        {
+            let x = MARGIN + i as f32 * cell_width + 3.0;
+            self.text_at(BOLD, 8.0, x, grid_top + 5.0, name);
+        }
+
+        let offset = first.weekday().num_days_from_monday() as usize;
+        let mut day = first;
+        while day.month() == first.month() {
+            let index = offset + day.day0() as usize;
+            let x = MARGIN + (index % 7) as f32 * cell_width;
+            let y = grid_top - (index / 7 + 1) as f32 * cell_height;
+            let entry = entries.iter().find(|e| e.date == day);
+
+            let page = self.page();
+            if entry.is_some() {
+                page.set_fill_gray(0.88)
+                    .rect(x, y, cell_width, cell_height)
+                    .fill_nonzero()
+                    .set_fill_gray(0.0);
+            }
+            page.set_line_width(0.5)
+                .set_stroke_gray(0.6)
+                .rect(x, y, cell_width, cell_height)
+                .stroke();
+
+            let font = if entry.is_some() { BOLD } else { REGULAR };
+            let number = day.day().to_string();
+            self.text_at(font, 10.0, x + 4.0, y + cell_height - 13.0, &number);
+            if let Some(entry) = entry {
+                let count = entry.total_bullets().to_string();
+                self.text_at(REGULAR, 7.0, x + 4.0, y + 5.0, &count);
+            }
+            day += Duration::days(1);
+        }
+
+        let summary = format!(
+            "{} {} this month",
+            entries.len(),
+            if entries.len() == 1 {
+                "entry"
+            } else {
+                "entries"
+            }
+        );
+        let weeks = (offset + days_in_month(first) as usize).div_ceil(7);
+        let y = grid_top - weeks as f32 * cell_height - 24.0;
+        self.text_at(REGULAR, 9.0, MARGIN, y, &summary);
+        // Entries start on a page of their own
+        self.y = MARGIN;
+    }
+
+    fn entry(&mut self, entry: &Entry) {
+        // Keep the heading together with at least a few lines
+        self.ensure_space(LEADING * 5.0);
+        self.y -= 22.0;
+        let heading = entry.date.format("%A, %B %-d").to_string();
+        let y = self.y;
+        self.text_at(BOLD, 13.0, MARGIN, y, &heading);
+        self.y -= 4.0;
+
+        for bullet_type in BulletType::ALL {
+            let bullets = entry.bullets_chronological(&bullet_type);
+            if bullets.is_empty() {
+                continue;
+            }
+            self.section_heading(bullet_type.section_name());
+            for bullet in bullets {
+                self.bullet(bullet, 0.0);
+            }
+        }
+        if let Some(body) = &entry.body {
+            self.section_heading("Freewrite");
+            for line in body.lines() {
+                self.paragraph(0.0, "", line);
+            }
+        }
+        self.y -= 8.0;
+    }
+
+    /// Small grey capitals above a section of an entry
+    fn section_heading(&mut self, title: &str) {
+        self.ensure_space(LEADING * 2.0);
+        self.y -= LEADING + 2.0;
+        let y = self.y;
+        self.page().set_fill_gray(0.4);
+        self.text_at(BOLD, 8.0, MARGIN, y, &title.to_uppercase());
+        self.page().set_fill_gray(0.0);
+    }
+
+    fn bullet(&mut self, bullet: &Bullet, indent: f32) {
+        let text = match bullet.time_label() {
+            Some(time) => format!("{} {}", time, bullet.content),
+            None => bullet.content.clone(),
+        };
+        let marker = self.symbols.symbol(bullet).to_string();
+        self.paragraph(indent, &marker, &text);
+        for child in &bullet.children {
+            self.bullet(child, indent + INDENT);
+        }
+    }
+
+    /// Number the pages after the cover and write the document
+    fn finish(mut self) -> Vec<u8> {
+        let total = self.pages.len();
+        for (number, page) in self.pages.iter_mut().enumerate().skip(1) {
+            let label = number.to_string();
+            let x = (PAGE_WIDTH - text_width(&label, 8.0)) / 2.0;
+            page.begin_text()
+                .set_font(REGULAR, 8.0)
+                .next_line(x, MARGIN / 2.0)
+                .show(Str(label.as_bytes()))
+                .end_text();
+        }
+
+        let catalog_id = Ref::new(1);
+        let tree_id = Ref::new(2);
+        let info_id = Ref::new(3);
+        let regular_id = Ref::new(4);
+        let bold_id = Ref::new(5);
+        let page_ids: Vec<Ref> = (0..total).map(|i| Ref::new(6 + 2 * i as i32)).collect();
+
+        let mut pdf = Pdf::new();
+        pdf.catalog(catalog_id).pages(tree_id);
+        pdf.pages(tree_id)
+            .kids(page_ids.iter().copied())
+            .count(total as i32);
+        pdf.document_info(info_id).title(TextStr("Journal"));
+        pdf.type1_font(regular_id)
+            .base_font(Name(b"Helvetica"))
+            .encoding_predefined(Name(b"WinAnsiEncoding"));
+        pdf.type1_font(bold_id)
+            .base_font(Name(b"Helvetica-Bold"))
+            .encoding_predefined(Name(b"WinAnsiEncoding"));
+
+        for (content, page_id) in self.pages.into_iter().zip(&page_ids) {
+            let content_id = Ref::new(page_id.get() + 1);
+            let mut page = pdf.page(*page_id);
+            page.parent(tree_id)
+                .media_box(Rect::new(0.0, 0.0, PAGE_WIDTH, PAGE_HEIGHT))
+                .contents(content_id);
+            page.resources()
+                .fonts()
+                .pair(REGULAR, regular_id)
+                .pair(BOLD, bold_id);
+            page.finish();
+            pdf.stream(content_id, &content.finish());
+        }
+        pdf.finish()
+    }
+}
+
+fn days_in_month(first: NaiveDate) -> u32 {
+    let next = if first.month() == 12 {
+        NaiveDate::from_ymd_opt(first.year() + 1, 1, 1)
+    } else {
+        NaiveDate::from_ymd_opt(first.year(), first.month() + 1, 1)
+    };
+    (next.expect("valid month") - first).num_days() as u32
+}
+
+/// Windows-1252 bytes for the base fonts' `WinAnsiEncoding`
+fn encode(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| match c {
+            ' '..='~' => c as u8,
+            '\u{a0}'..='\u{ff}' => c as u32 as u8,
+            '€' => 0x80,
+            '…' => 0x85,
+            '‘' => 0x91,
+            '’' => 0x92,
+            '“' => 0x93,
+            '”' => 0x94,
+            '•' => 0x95,
+            '–' => 0x96,
+            '—' => 0x97,
+            _ => b'?',
+        })
+        .collect()
+}
+
+/// Width of `text` in Helvetica at `size` points; close enough for the bold
+/// face, which is only used for short headings
+fn text_width(text: &str, size: f32) -> f32 {
+    let units: u32 = text
+        .chars()
+        .map(|c| match c {
+            ' '..='~' => HELVETICA_WIDTHS[c as usize - 32] as u32,
+            _ => 556,
+        })
+        .sum();
+    units as f32 * size / 1000.0
+}
+
+/// Break text into lines no wider than `width`, splitting words that are
+/// longer than a line on their own
+fn wrap(text: &str, width: f32, size: f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        let candidate = if line.is_empty() {
+            word.to_string()
+        } else {
+            format!("{} {}", line, word)
+        };
+        if text_width(&candidate, size) <= width {
+            line = candidate;
+            continue;
+        }
+        if !line.is_empty() {
+            lines.push(std::mem::take(&mut line));
+        }
+        for c in word.chars() {
+            if !line.is_empty() && text_width(&format!("{}{}", line, c), size) > width {
+                lines.push(std::mem::take(&mut line));
+            }
+            line.push(c);
+        }
+    }
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pdf_booklet_pages() {
+        let mut march = Entry::new(NaiveDate::from_ymd_opt(2024, 3, 15).unwrap());
+        march.add_bullet(Bullet::new("Dentist at 3", BulletType::Event));
+        march.add_bullet(Bullet::new("Renew passport — soon", BulletType::Task));
+        let mut april = Entry::new(NaiveDate::from_ymd_opt(2024, 4, 2).unwrap());
+        april.add_bullet(Bullet::new("word ".repeat(800), BulletType::Note));
+
+        let pdf = PdfExporter.export(&[march, april]).unwrap();
+        let text = String::from_utf8_lossy(&pdf);
+        assert!(pdf.starts_with(b"%PDF-"));
+        // Cover, two calendars, one page for March and two for the long April note
+        assert!(text.contains("/Count 6"), "{}", text);
+    }
+
+    #[test]
+    fn test_wrap_and_encode() {
+        let lines = wrap("a fairly long sentence to wrap", 60.0, BODY_SIZE);
+        assert!(lines.len() > 1);
+        assert!(lines.iter().all(|l| text_width(l, BODY_SIZE) <= 60.0));
+        assert_eq!(encode("Café – ★"), b"Caf\xe9 \x96 ?");
+    }
+}
@@ -0,0 +1,164 @@
+use crate::entities::Entry;
+use crate::infrastructure::{MarkdownParser, PdfExporter};
+use anyhow::Result;
+
+/// Trait for formats that render entries into a single file
+pub trait Exporter: Send + Sync {
+    /// Name the format is selected by, e.g. `journo export --format <name>`
+    fn name(&self) -> &str;
+
+    /// File extension used when exporting into a directory
+    fn extension(&self) -> &str;
+
+    /// Render the given entries, which are sorted by date
+    fn export(&self, entries: &[Entry]) -> Result<Vec<u8>>;
+}
+
+/// Registry for managing single-file exporters
+pub struct ExporterRegistry {
+    exporters: Vec<Box<dyn Exporter>>,
+}
+
+impl ExporterRegistry {
+    pub fn new() -> Self {
+        Self {
+            exporters: Vec::new(),
+        }
+    }
+
+    /// Registry with the exporters shipped with journo
+    pub fn with_defaults() -> Self {
+        let mut registry = Self::new();
+        registry.register(JsonExporter);
+        registry.register(MarkdownExporter);
+        registry.register(PdfExporter);
+        registry
+    }
+
+    /// Register a new exporter, replacing any registered under the same name
+    pub fn register<E>(&mut self, exporter: E)
+    where
+        E: Exporter + 'static,
+    {
+        self.exporters.retain(|e| e.name() != exporter.name());
+        self.exporters.push(Box::new(exporter));
+    }
+
+    /// Look up an exporter by name, ignoring case
+    pub fn get(&self, name: &str) -> Option<&dyn Exporter> {
+        self.exporters
+            .iter()
+            .find(|e| e.name().eq_ignore_ascii_case(name))
+            .map(|e| e.as_ref())
+    }
+
+    /// List all registered exporters
+    pub fn list_exporters(&self) -> Vec<&str> {
+        self.exporters.iter().map(|e| e.name()).collect()
+    }
+}
+
+impl Default for ExporterRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// All entries as a pretty-printed JSON array
+pub struct JsonExporter;
+
+impl Exporter for JsonExporter {
+    fn name(&self) -> &str {
+        "json"
+    }
+
+    fn extension(&self) -> &str {
+        "json"
+    }
+
+    fn export(&self, entries: &[Entry]) -> Result<Vec<u8>> {
+        Ok(serde_json::to_vec_pretty(entries)?)
+    }
+}
+
+/// All entries in one markdown file, each day under a `## YYYY-MM-DD` heading
+/// with its sections in the same format as the entry files
+pub struct MarkdownExporter;
+
+impl Exporter for MarkdownExporter {
+    fn name(&self) -> &str {
+        "markdown"
+    }
+
+    fn extension(&self) -> &str {
+        "md"
+    }
+
+    fn export(&self, entries: &[Entry]) -> Result<Vec<u8>> {
+        let parser = MarkdownParser::new();
+        let mut out = String::new();
+        for entry in entries {
+            out.push_str(&format!("## {}\n\n", entry.date.format("%Y-%m-%d")));
+            // Nest the entry's sections under the day heading
+            for line in parser.serialize(entry)?.lines() {
+                if line.starts_with('#') {
+                    out.push_str("##");
+                }
+                out.push_str(line);
+                out.push('\n');
+            }
+        }
+        Ok(out.into_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{Bullet, BulletType};
+    use chrono::NaiveDate;
+
+    struct CountExporter;
+
+    impl Exporter for CountExporter {
+        fn name(&self) -> &str {
+            "count"
+        }
+
+        fn extension(&self) -> &str {
+            "txt"
+        }
+
+        fn export(&self, entries: &[Entry]) -> Result<Vec<u8>> {
+            Ok(entries.len().to_string().into_bytes())
+        }
+    }
+
+    #[test]
+    fn test_registry_lookup_and_builtin_formats() {
+        let mut registry = ExporterRegistry::with_defaults();
+        registry.register(CountExporter);
+        assert_eq!(
+            registry.list_exporters(),
+            vec!["json", "markdown", "pdf", "count"]
+        );
+        assert!(registry.get("nope").is_none());
+
+        let mut entry = Entry::new(NaiveDate::from_ymd_opt(2024, 3, 15).unwrap());
+        entry.add_bullet(Bullet::new("Dentist at 3", BulletType::Event));
+        let entries = [entry];
+
+        let count = registry.get("COUNT").unwrap().export(&entries).unwrap();
+        assert_eq!(count, b"1");
+
+        let markdown = registry.get("markdown").unwrap().export(&entries).unwrap();
+        assert_eq!(
+            String::from_utf8(markdown).unwrap(),
+            "## 2024-03-15\n\n### Events\nDentist at 3\n\n"
+        );
+
+        let json = registry.get("json").unwrap().export(&entries).unwrap();
+        let parsed: Vec<Entry> = serde_json::from_slice(&json).unwrap();
+        assert_eq!(parsed.len(), 1);
+    }
+}
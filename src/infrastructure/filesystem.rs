@@ -1,12 +1,18 @@
 use crate::entities::{DateRange, Entry};
 use crate::infrastructure::{EntryRepository, HookRegistry, MarkdownParser, WriteContext};
-use anyhow::Result;
-use chrono::NaiveDate;
-use std::path::PathBuf;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Default path of an entry file relative to the data directory
+pub const DEFAULT_FILE_LAYOUT: &str = "%Y/%m/%d/entry.md";
 
 pub struct FileSystemRepository {
     data_dir: PathBuf,
     journal_dir: PathBuf,
+    /// strftime pattern of entry paths relative to `data_dir`
+    layout: String,
     parser: MarkdownParser,
     hook_registry: HookRegistry,
 }
@@ -16,6 +22,7 @@ impl FileSystemRepository {
         Self {
             data_dir,
             journal_dir,
+            layout: DEFAULT_FILE_LAYOUT.to_string(),
             parser: MarkdownParser::new(),
             hook_registry: HookRegistry::new(),
         }
@@ -29,18 +36,172 @@ impl FileSystemRepository {
         Self {
             data_dir,
             journal_dir,
+            layout: DEFAULT_FILE_LAYOUT.to_string(),
             parser: MarkdownParser::new(),
             hook_registry,
         }
     }
 
-    fn entry_path(&self, date: NaiveDate) -> PathBuf {
-        self.data_dir
-            .join(date.format("%Y").to_string())
-            .join(date.format("%m").to_string())
-            .join(date.format("%d").to_string())
-            .join("entry.md")
+    /// Write entry files with this parser, e.g. one with a custom section order
+    pub fn with_parser(mut self, parser: MarkdownParser) -> Self {
+        self.parser = parser;
+        self
+    }
+
+    /// Use a different file layout, e.g. `%Y-%m-%d.md` for a flat directory
+    pub fn with_layout(mut self, layout: impl Into<String>) -> Self {
+        self.layout = layout.into();
+        self
+    }
+
+    pub fn data_dir(&self) -> &PathBuf {
+        &self.data_dir
+    }
+
+    pub fn layout(&self) -> &str {
+        &self.layout
+    }
+
+    /// Scan the data directory for every date that has an entry file
+    pub fn all_dates(&self) -> Result<Vec<NaiveDate>> {
+        let mut dates = Vec::new();
+        let mut pending = vec![self.data_dir.clone()];
+
+        while let Some(dir) = pending.pop() {
+            for path in read_dir_if_dir(&dir)? {
+                if path.is_dir() {
+                    pending.push(path);
+                } else if let Some(date) = self.date_for_path(&path) {
+                    dates.push(date);
+                }
+            }
+        }
+
+        dates.sort();
+        dates.dedup();
+        Ok(dates)
+    }
+
+    /// Move every entry file to the paths of another layout, returning the
+    /// moves made (or that would be made, for a dry run)
+    pub fn relayout(&self, new_layout: &str, dry_run: bool) -> Result<Vec<(PathBuf, PathBuf)>> {
+        validate_layout(new_layout)?;
+        let target =
+            Self::new(self.data_dir.clone(), self.journal_dir.clone()).with_layout(new_layout);
+
+        let mut moves = Vec::new();
+        for date in self.all_dates()? {
+            let from = self.entry_path(date);
+            let to = target.entry_path(date);
+            if from == to {
+                continue;
+            }
+            if to.exists() {
+                anyhow::bail!(
+                    "Refusing to overwrite {} with {}",
+                    to.display(),
+                    from.display()
+                );
+            }
+            moves.push((from, to));
+        }
+
+        if !dry_run {
+            for (from, to) in &moves {
+                if let Some(parent) = to.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::rename(from, to).with_context(|| {
+                    format!("Failed to move {} to {}", from.display(), to.display())
+                })?;
+                self.remove_empty_parents(from);
+            }
+        }
+
+        Ok(moves)
+    }
+
+    /// Path of the entry file for `date`, whether or not it exists
+    pub fn entry_path(&self, date: NaiveDate) -> PathBuf {
+        self.data_dir.join(date.format(&self.layout).to_string())
+    }
+
+    /// Delete the entry file for `date`, returning whether there was one
+    pub fn remove(&self, date: NaiveDate) -> Result<bool> {
+        let path = self.entry_path(date);
+        if !path.exists() {
+            return Ok(false);
+        }
+        std::fs::remove_file(&path)
+            .with_context(|| format!("Failed to remove {}", path.display()))?;
+        self.remove_empty_parents(&path);
+        Ok(true)
+    }
+
+    /// Local time the entry file for `date` was last modified, if it exists
+    pub fn modified_at(&self, date: NaiveDate) -> Result<Option<NaiveDateTime>> {
+        let path = self.entry_path(date);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let modified = std::fs::metadata(&path)
+            .and_then(|m| m.modified())
+            .with_context(|| format!("Failed to read modification time of {}", path.display()))?;
+        Ok(Some(DateTime::<Local>::from(modified).naive_local()))
+    }
+
+    /// Date of an entry file if its path matches the layout
+    pub fn date_for_path(&self, path: &Path) -> Option<NaiveDate> {
+        let relative = path.strip_prefix(&self.data_dir).ok()?;
+        let relative: Vec<String> = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().into_owned())
+            .collect();
+        let date = NaiveDate::parse_from_str(&relative.join("/"), &self.layout).ok()?;
+        // Parsing ignores some mismatches (e.g. unpadded numbers), so check the round trip
+        (self.entry_path(date) == path).then_some(date)
+    }
+
+    /// Remove directories left empty by moving an entry file out of them
+    fn remove_empty_parents(&self, path: &Path) {
+        let mut dir = path.parent();
+        while let Some(current) = dir {
+            if current == self.data_dir || std::fs::remove_dir(current).is_err() {
+                break;
+            }
+            dir = current.parent();
+        }
+    }
+}
+
+/// Check that a layout maps every date to a distinct path that parses back
+pub fn validate_layout(layout: &str) -> Result<()> {
+    let sample = NaiveDate::from_ymd_opt(2024, 3, 15).expect("valid date");
+    let path = sample.format(layout).to_string();
+    if NaiveDate::parse_from_str(&path, layout).ok() != Some(sample) {
+        anyhow::bail!(
+            "File layout '{}' must contain the full date (e.g. %Y, %m and %d)",
+            layout
+        );
+    }
+    if Path::new(&path).is_absolute() || path.split('/').any(|part| part == "..") {
+        anyhow::bail!(
+            "File layout '{}' must stay inside the data directory",
+            layout
+        );
+    }
+    Ok(())
+}
+
+fn read_dir_if_dir(path: &Path) -> Result<Vec<PathBuf>> {
+    if !path.is_dir() {
+        return Ok(Vec::new());
     }
+    let mut paths = Vec::new();
+    for entry in std::fs::read_dir(path)? {
+        paths.push(entry?.path());
+    }
+    Ok(paths)
 }
 
 impl EntryRepository for FileSystemRepository {
@@ -68,7 +229,7 @@ impl EntryRepository for FileSystemRepository {
         std::fs::create_dir_all(&self.journal_dir)?;
 
         let content = self.parser.serialize(&entry)?;
-        std::fs::write(&path, &content)?;
+        write_atomically(&path, &content)?;
 
         // Call write hooks after successful write
         let context = WriteContext {
@@ -96,3 +257,77 @@ impl EntryRepository for FileSystemRepository {
         Ok(dates)
     }
 }
+
+/// Write to a temporary file beside `path` and rename it into place, so a
+/// crash mid-write leaves the previous version intact
+fn write_atomically(path: &Path, content: &str) -> Result<()> {
+    let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+    temp_name.push(".tmp");
+    let temp_path = path.with_file_name(temp_name);
+
+    let mut file = std::fs::File::create(&temp_path)
+        .with_context(|| format!("Failed to create {}", temp_path.display()))?;
+    file.write_all(content.as_bytes())
+        .and_then(|_| file.sync_all())
+        .with_context(|| format!("Failed to write {}", temp_path.display()))?;
+    std::fs::rename(&temp_path, path)
+        .with_context(|| format!("Failed to replace {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{Bullet, BulletType};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_relayout_moves_files_to_new_scheme() {
+        let temp_dir = TempDir::new().unwrap();
+        let data_dir = temp_dir.path().join("data");
+        let nested = FileSystemRepository::new(data_dir.clone(), temp_dir.path().into());
+        let dates = [
+            NaiveDate::from_ymd_opt(2024, 3, 15).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 4, 1).unwrap(),
+        ];
+        for date in dates {
+            let mut entry = Entry::new(date);
+            entry.add_bullet(Bullet::new("Moved", BulletType::Note));
+            nested.save(entry).unwrap();
+        }
+
+        assert_eq!(nested.relayout("%Y-%m-%d.md", true).unwrap().len(), 2);
+        assert!(data_dir.join("2024/03/15/entry.md").exists());
+
+        nested.relayout("%Y-%m-%d.md", false).unwrap();
+        assert!(data_dir.join("2024-03-15.md").exists());
+        assert!(!data_dir.join("2024").exists());
+
+        let flat =
+            FileSystemRepository::new(data_dir, temp_dir.path().into()).with_layout("%Y-%m-%d.md");
+        assert_eq!(flat.all_dates().unwrap(), dates);
+        assert!(flat.load(dates[0]).unwrap().is_some());
+
+        assert!(validate_layout("%Y/%m.md").is_err());
+    }
+
+    #[test]
+    fn test_save_replaces_file_without_leftovers() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = FileSystemRepository::new(temp_dir.path().join("data"), temp_dir.path().into())
+            .with_layout("%Y-%m-%d.md");
+        let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        for content in ["First", "Second"] {
+            let mut entry = Entry::new(date);
+            entry.add_bullet(Bullet::new(content, BulletType::Note));
+            repo.save(entry).unwrap();
+        }
+
+        let entry = repo.load(date).unwrap().unwrap();
+        assert_eq!(entry.all_bullets().next().unwrap().content, "Second");
+        let files: Vec<_> = std::fs::read_dir(temp_dir.path().join("data"))
+            .unwrap()
+            .map(|e| e.unwrap().file_name())
+            .collect();
+        assert_eq!(files, vec!["2024-03-15.md"]);
+    }
+}
@@ -0,0 +1,250 @@
+use crate::infrastructure::calendar::parse_ics;
+use anyhow::{Context, Result};
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Country codes with a built-in holiday pack
+pub const HOLIDAY_COUNTRIES: [&str; 5] = ["de", "fr", "gb", "pl", "us"];
+
+/// How a public holiday's date is found in a given year
+#[derive(Debug, Clone, Copy)]
+enum Rule {
+    /// Same month and day every year
+    Fixed(u32, u32),
+    /// Days after Easter Sunday (negative for before)
+    Easter(i64),
+    /// n-th weekday of a month, counting from the end for negative n
+    Nth(u32, Weekday, i32),
+}
+
+const US: &[(&str, Rule)] = &[
+    ("New Year's Day", Rule::Fixed(1, 1)),
+    ("Martin Luther King Jr. Day", Rule::Nth(1, Weekday::Mon, 3)),
+    ("Presidents' Day", Rule::Nth(2, Weekday::Mon, 3)),
+    ("Memorial Day", Rule::Nth(5, Weekday::Mon, -1)),
+    ("Juneteenth", Rule::Fixed(6, 19)),
+    ("Independence Day", Rule::Fixed(7, 4)),
+    ("Labor Day", Rule::Nth(9, Weekday::Mon, 1)),
+    ("Columbus Day", Rule::Nth(10, Weekday::Mon, 2)),
+    ("Veterans Day", Rule::Fixed(11, 11)),
+    ("Thanksgiving", Rule::Nth(11, Weekday::Thu, 4)),
+    ("Christmas Day", Rule::Fixed(12, 25)),
+];
+
+const GB: &[(&str, Rule)] = &[
+    ("New Year's Day", Rule::Fixed(1, 1)),
+    ("Good Friday", Rule::Easter(-2)),
+    ("Easter Monday", Rule::Easter(1)),
+    ("Early May bank holiday", Rule::Nth(5, Weekday::Mon, 1)),
+    ("Spring bank holiday", Rule::Nth(5, Weekday::Mon, -1)),
+    ("Summer bank holiday", Rule::Nth(8, Weekday::Mon, -1)),
+    ("Christmas Day", Rule::Fixed(12, 25)),
+    ("Boxing Day", Rule::Fixed(12, 26)),
+];
+
+const DE: &[(&str, Rule)] = &[
+    ("Neujahr", Rule::Fixed(1, 1)),
+    ("Karfreitag", Rule::Easter(-2)),
+    ("Ostermontag", Rule::Easter(1)),
+    ("Tag der Arbeit", Rule::Fixed(5, 1)),
+    ("Christi Himmelfahrt", Rule::Easter(39)),
+    ("Pfingstmontag", Rule::Easter(50)),
+    ("Tag der Deutschen Einheit", Rule::Fixed(10, 3)),
+    ("1. Weihnachtstag", Rule::Fixed(12, 25)),
+    ("2. Weihnachtstag", Rule::Fixed(12, 26)),
+];
+
+const FR: &[(&str, Rule)] = &[
+    ("Jour de l'an", Rule::Fixed(1, 1)),
+    ("Lundi de Pâques", Rule::Easter(1)),
+    ("Fête du Travail", Rule::Fixed(5, 1)),
+    ("Victoire 1945", Rule::Fixed(5, 8)),
+    ("Ascension", Rule::Easter(39)),
+    ("Lundi de Pentecôte", Rule::Easter(50)),
+    ("Fête nationale", Rule::Fixed(7, 14)),
+    ("Assomption", Rule::Fixed(8, 15)),
+    ("Toussaint", Rule::Fixed(11, 1)),
+    ("Armistice 1918", Rule::Fixed(11, 11)),
+    ("Noël", Rule::Fixed(12, 25)),
+];
+
+const PL: &[(&str, Rule)] = &[
+    ("Nowy Rok", Rule::Fixed(1, 1)),
+    ("Trzech Króli", Rule::Fixed(1, 6)),
+    ("Wielkanoc", Rule::Easter(0)),
+    ("Poniedziałek Wielkanocny", Rule::Easter(1)),
+    ("Święto Pracy", Rule::Fixed(5, 1)),
+    ("Święto Konstytucji 3 Maja", Rule::Fixed(5, 3)),
+    ("Zielone Świątki", Rule::Easter(49)),
+    ("Boże Ciało", Rule::Easter(60)),
+    ("Wniebowzięcie NMP", Rule::Fixed(8, 15)),
+    ("Wszystkich Świętych", Rule::Fixed(11, 1)),
+    ("Święto Niepodległości", Rule::Fixed(11, 11)),
+    ("Boże Narodzenie", Rule::Fixed(12, 25)),
+    ("Drugi dzień Bożego Narodzenia", Rule::Fixed(12, 26)),
+];
+
+fn country_pack(country: &str) -> Option<&'static [(&'static str, Rule)]> {
+    match country.to_ascii_lowercase().as_str() {
+        "us" => Some(US),
+        "gb" | "uk" => Some(GB),
+        "de" => Some(DE),
+        "fr" => Some(FR),
+        "pl" => Some(PL),
+        _ => None,
+    }
+}
+
+/// Public holidays of a built-in country pack plus those of a custom
+/// iCalendar file, whose all-day events are each taken as a holiday
+#[derive(Debug, Clone, Default)]
+pub struct Holidays {
+    pack: Option<&'static [(&'static str, Rule)]>,
+    custom: BTreeMap<NaiveDate, Vec<String>>,
+}
+
+impl Holidays {
+    /// Holidays of `country` (a code from `HOLIDAY_COUNTRIES`) and of the
+    /// `.ics` file at `file`; either may be left out
+    pub fn load(country: Option<&str>, file: Option<&Path>) -> Result<Self> {
+        let pack = match country {
+            Some(code) => Some(country_pack(code).with_context(|| {
+                format!(
+                    "No holidays for country '{}' (one of {})",
+                    code,
+                    HOLIDAY_COUNTRIES.join(", ")
+                )
+            })?),
+            None => None,
+        };
+
+        let mut custom: BTreeMap<NaiveDate, Vec<String>> = BTreeMap::new();
+        if let Some(file) = file {
+            let content = std::fs::read_to_string(file)
+                .with_context(|| format!("Failed to read holiday file {}", file.display()))?;
+            for event in parse_ics(&content) {
+                if event.start.is_none() {
+                    custom.entry(event.date).or_default().push(event.summary);
+                }
+            }
+        }
+        Ok(Self { pack, custom })
+    }
+
+    /// Holidays from a country pack only, e.g. for tests
+    pub fn for_country(country: &str) -> Option<Self> {
+        country_pack(country).map(|pack| Self {
+            pack: Some(pack),
+            custom: BTreeMap::new(),
+        })
+    }
+
+    /// Whether any holidays were configured
+    pub fn is_empty(&self) -> bool {
+        self.pack.is_none() && self.custom.is_empty()
+    }
+
+    /// Names of the holidays falling on `date`
+    pub fn on(&self, date: NaiveDate) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .pack
+            .unwrap_or_default()
+            .iter()
+            .filter(|(_, rule)| rule_date(*rule, date.year()) == Some(date))
+            .map(|(name, _)| name.to_string())
+            .collect();
+        if let Some(custom) = self.custom.get(&date) {
+            names.extend(custom.iter().cloned());
+        }
+        names
+    }
+
+    /// Whether `date` is a holiday
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        !self.on(date).is_empty()
+    }
+}
+
+fn rule_date(rule: Rule, year: i32) -> Option<NaiveDate> {
+    match rule {
+        Rule::Fixed(month, day) => NaiveDate::from_ymd_opt(year, month, day),
+        Rule::Easter(offset) => Some(easter_sunday(year)? + Duration::days(offset)),
+        Rule::Nth(month, weekday, n) if n < 0 => {
+            let next_month = match month {
+                12 => NaiveDate::from_ymd_opt(year + 1, 1, 1)?,
+                _ => NaiveDate::from_ymd_opt(year, month + 1, 1)?,
+            };
+            let last = next_month.pred_opt()?;
+            let back =
+                (last.weekday().num_days_from_monday() + 7 - weekday.num_days_from_monday()) % 7;
+            Some(last - Duration::days(back as i64) + Duration::weeks(n as i64 + 1))
+        }
+        Rule::Nth(month, weekday, n) => {
+            NaiveDate::from_weekday_of_month_opt(year, month, weekday, n as u8)
+        }
+    }
+}
+
+/// Easter Sunday of the Gregorian calendar (anonymous Gregorian algorithm)
+fn easter_sunday(year: i32) -> Option<NaiveDate> {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_country_pack_dates() {
+        assert_eq!(easter_sunday(2024), Some(day(2024, 3, 31)));
+        assert_eq!(easter_sunday(2025), Some(day(2025, 4, 20)));
+
+        let us = Holidays::for_country("US").unwrap();
+        assert_eq!(us.on(day(2024, 11, 28)), vec!["Thanksgiving"]);
+        assert_eq!(us.on(day(2024, 5, 27)), vec!["Memorial Day"]);
+        assert!(!us.contains(day(2024, 5, 20)));
+
+        let gb = Holidays::for_country("gb").unwrap();
+        assert_eq!(gb.on(day(2024, 3, 29)), vec!["Good Friday"]);
+        assert_eq!(gb.on(day(2024, 8, 26)), vec!["Summer bank holiday"]);
+
+        assert!(Holidays::for_country("xx").is_none());
+        assert!(Holidays::default().is_empty());
+    }
+
+    #[test]
+    fn test_custom_holiday_file() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("holidays.ics");
+        std::fs::write(
+            &path,
+            "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20240603\r\nSUMMARY:Company day off\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n",
+        )
+        .unwrap();
+
+        let holidays = Holidays::load(Some("us"), Some(&path)).unwrap();
+        assert_eq!(holidays.on(day(2024, 6, 3)), vec!["Company day off"]);
+        assert!(holidays.contains(day(2024, 7, 4)));
+        assert!(Holidays::load(None, Some(&temp_dir.path().join("missing.ics"))).is_err());
+        assert!(Holidays::load(Some("xx"), None).is_err());
+    }
+}
@@ -2,6 +2,7 @@ use crate::entities::Entry;
 use anyhow::Result;
 use chrono::NaiveDate;
 use std::path::PathBuf;
+use tracing::warn;
 
 /// Context provided to write hooks
 #[derive(Debug, Clone)]
@@ -26,14 +27,36 @@ pub trait WriteHook: Send + Sync {
     }
 }
 
+/// Trait for plugins that adjust an entry before it's written
+pub trait PreWriteHook: Send + Sync {
+    /// Called before an entry is saved, returning warnings to show the user
+    fn before_write(&self, entry: &mut Entry) -> Result<Vec<String>>;
+
+    /// Human-readable name for this hook
+    fn name(&self) -> &str;
+}
+
 /// Registry for managing write hooks
 pub struct HookRegistry {
+    pre_write: Vec<Box<dyn PreWriteHook>>,
     hooks: Vec<Box<dyn WriteHook>>,
 }
 
 impl HookRegistry {
     pub fn new() -> Self {
-        Self { hooks: Vec::new() }
+        Self {
+            pre_write: Vec::new(),
+            hooks: Vec::new(),
+        }
+    }
+
+    /// Register a hook that runs before entries are written, after any
+    /// registered earlier
+    pub fn register_pre_write<H>(&mut self, hook: H)
+    where
+        H: PreWriteHook + 'static,
+    {
+        self.pre_write.push(Box::new(hook));
     }
 
     /// Register a new write hook
@@ -48,16 +71,32 @@ impl HookRegistry {
     pub fn execute_write_hooks(&self, context: &WriteContext, entry: &Entry) -> Result<()> {
         for hook in &self.hooks {
             if let Err(e) = hook.on_entry_written(context, entry) {
-                eprintln!("Warning: Hook '{}' failed: {}", hook.name(), e);
+                warn!(hook = hook.name(), "Hook failed: {:#}", e);
                 // Continue with other hooks even if one fails
             }
         }
         Ok(())
     }
 
+    /// Run the pre-write hooks in registration order, collecting their warnings
+    pub fn execute_pre_write_hooks(&self, entry: &mut Entry) -> Vec<String> {
+        let mut warnings = Vec::new();
+        for hook in &self.pre_write {
+            match hook.before_write(entry) {
+                Ok(messages) => warnings.extend(messages),
+                Err(e) => warn!(hook = hook.name(), "Hook failed: {:#}", e),
+            }
+        }
+        warnings
+    }
+
     /// List all registered hooks
     pub fn list_hooks(&self) -> Vec<&str> {
-        self.hooks.iter().map(|h| h.name()).collect()
+        self.pre_write
+            .iter()
+            .map(|h| h.name())
+            .chain(self.hooks.iter().map(|h| h.name()))
+            .collect()
     }
 }
 
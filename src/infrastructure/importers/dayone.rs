@@ -0,0 +1,169 @@
+use super::{ImportResult, bullet_from_markdown};
+use crate::entities::BulletType;
+use crate::infrastructure::MarkdownParser;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use serde::Deserialize;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+/// `Journal.json` from a Day One JSON export
+#[derive(Deserialize)]
+struct DayOneExport {
+    entries: Vec<DayOneEntry>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DayOneEntry {
+    creation_date: String,
+    #[serde(default)]
+    text: String,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    starred: bool,
+    #[serde(default)]
+    photos: Vec<Value>,
+    #[serde(default)]
+    videos: Vec<Value>,
+    #[serde(default)]
+    audios: Vec<Value>,
+    location: Option<Value>,
+    weather: Option<Value>,
+}
+
+/// Import a Day One JSON export, given either the `.json` file or the
+/// unzipped export directory. Entries land on the local date they were
+/// created; markdown list items become bullets (checkboxes as tasks), other
+/// lines notes, with the entry's tags appended to its first bullet as `#tags`.
+/// Media, locations and weather aren't imported.
+pub fn import_dayone(path: &Path) -> Result<ImportResult> {
+    let mut result = ImportResult::default();
+    for file in json_files(path)? {
+        let content = fs::read_to_string(&file)
+            .with_context(|| format!("Failed to read {}", file.display()))?;
+        let export: DayOneExport = serde_json::from_str(&content)
+            .with_context(|| format!("{} is not a Day One JSON export", file.display()))?;
+        for entry in export.entries {
+            convert_entry(&mut result, entry)?;
+        }
+    }
+    Ok(result)
+}
+
+/// The export file itself, or the `.json` files of an export directory
+fn json_files(path: &Path) -> Result<Vec<std::path::PathBuf>> {
+    if !path.is_dir() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+    let mut files: Vec<_> = fs::read_dir(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| p.extension().and_then(|e| e.to_str()) == Some("json"))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+fn convert_entry(result: &mut ImportResult, entry: DayOneEntry) -> Result<()> {
+    let created = DateTime::parse_from_rfc3339(&entry.creation_date)
+        .with_context(|| format!("Invalid creationDate: {}", entry.creation_date))?
+        .with_timezone(&Local)
+        .naive_local();
+    let date = created.date();
+
+    let media = entry.photos.len() + entry.videos.len() + entry.audios.len();
+    if media > 0 {
+        result.skip(date, format!("{} media attachments not imported", media));
+    }
+    if entry.location.is_some() || entry.weather.is_some() {
+        result.skip(date, "location and weather not imported");
+    }
+    if entry.starred {
+        result.skip(date, "star dropped from entry");
+    }
+
+    let mut first = true;
+    for line in entry.text.lines() {
+        let depth = MarkdownParser::indent_depth(line);
+        let text = unescape(line.trim());
+        // Headings become plain bullets, but a leading #tag stays a tag
+        let heading = text.trim_start_matches('#');
+        let line = if heading.len() < text.len() && heading.starts_with(' ') {
+            heading.trim()
+        } else {
+            text.as_str()
+        };
+        if line.is_empty() {
+            continue;
+        }
+        if line.starts_with("![") {
+            result.skip(date, "inline image not imported");
+            continue;
+        }
+
+        let mut bullet = bullet_from_markdown(line, BulletType::Note);
+        if first {
+            bullet = bullet.at(created.time());
+            for tag in &entry.tags {
+                bullet
+                    .content
+                    .push_str(&format!(" #{}", tag.replace(' ', "-")));
+            }
+            first = false;
+        }
+        result.add_bullet(date, depth, bullet);
+    }
+
+    if first {
+        result.skip(date, "empty entry not imported");
+    }
+    Ok(())
+}
+
+/// Day One escapes markdown punctuation in exported text (`Done\.`)
+fn unescape(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match chars.peek() {
+            Some(next) if c == '\\' && next.is_ascii_punctuation() => {}
+            _ => output.push(c),
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::TaskState;
+
+    #[test]
+    fn test_import_dayone_export() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("Journal.json");
+        fs::write(
+            &path,
+            r##"{"entries": [
+                {"creationDate": "2024-03-15T12:00:00Z", "text": "# Launch day\n- [x] Ship it\nCelebrated\\.",
+                 "tags": ["work"], "photos": [{"identifier": "abc"}]},
+                {"creationDate": "2024-03-16T12:00:00Z", "text": ""}
+            ]}"##,
+        )
+        .unwrap();
+
+        let result = import_dayone(temp_dir.path()).unwrap();
+        let entry = result.entries().next().unwrap();
+        let notes = entry.get_bullets(&BulletType::Note);
+        assert_eq!(notes[0].content, "Launch day #work");
+        assert!(notes[0].time.is_some());
+        assert_eq!(notes[1].content, "Celebrated.");
+        let tasks = entry.get_bullets(&BulletType::Task);
+        assert_eq!(tasks[0].task_state, Some(TaskState::Completed));
+        assert_eq!(result.bullet_count(), 3);
+        assert_eq!(result.skipped.len(), 2);
+    }
+}
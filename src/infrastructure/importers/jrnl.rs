@@ -0,0 +1,112 @@
+use super::ImportResult;
+use crate::entities::{Bullet, BulletType};
+use anyhow::{Context, Result};
+use chrono::NaiveDateTime;
+use std::fs;
+use std::path::Path;
+
+/// Import a jrnl plain-text journal (or `jrnl --export txt` output), where each
+/// entry starts with a `[YYYY-MM-DD HH:MM] Title` line. The title becomes a
+/// timed note and each body line a sub-bullet of it; jrnl's `@tags` become
+/// `#tags`.
+pub fn import_jrnl(path: &Path) -> Result<ImportResult> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read jrnl file: {}", path.display()))?;
+    Ok(parse_jrnl(&content))
+}
+
+fn parse_jrnl(content: &str) -> ImportResult {
+    let mut result = ImportResult::default();
+    let mut current: Option<NaiveDateTime> = None;
+    let mut stray_lines = 0;
+
+    for line in content.lines() {
+        if let Some((timestamp, title)) = parse_header(line) {
+            let (title, starred) = match title.strip_suffix(" *") {
+                Some(title) => (title, true),
+                None => (title, false),
+            };
+            if starred {
+                result.skip(timestamp.date(), "star dropped from entry");
+            }
+            result.add_bullet(
+                timestamp.date(),
+                0,
+                Bullet::new(convert_tags(title.trim()), BulletType::Note).at(timestamp.time()),
+            );
+            current = Some(timestamp);
+            continue;
+        }
+
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        match current {
+            Some(timestamp) => result.add_bullet(
+                timestamp.date(),
+                1,
+                Bullet::new(convert_tags(line), BulletType::Note),
+            ),
+            None => stray_lines += 1,
+        }
+    }
+
+    if stray_lines > 0 {
+        result.skipped.push(format!(
+            "{} lines before the first entry not imported",
+            stray_lines
+        ));
+    }
+    result
+}
+
+/// Split a `[YYYY-MM-DD HH:MM] Title` line into its timestamp and title
+fn parse_header(line: &str) -> Option<(NaiveDateTime, &str)> {
+    let rest = line.strip_prefix('[')?;
+    let (stamp, title) = rest.split_once("] ")?;
+    ["%Y-%m-%d %H:%M", "%Y-%m-%d %H:%M:%S", "%Y-%m-%d %I:%M %p"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(stamp, format).ok())
+        .map(|timestamp| (timestamp, title))
+}
+
+/// Turn jrnl `@tag` markers into `#tag`, leaving e-mail addresses alone
+fn convert_tags(text: &str) -> String {
+    text.split(' ')
+        .map(|word| match word.strip_prefix('@') {
+            Some(tag) if tag.starts_with(|c: char| c.is_alphanumeric()) => format!("#{}", tag),
+            _ => word.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, NaiveTime};
+
+    #[test]
+    fn test_parse_jrnl_entries() {
+        let result = parse_jrnl(
+            "[2024-03-15 09:30] Planning day @work\nDraft the launch post.\n\n\
+             [2024-03-15 18:00] Evening run *\n\
+             [2024-03-16 08:00 PM] Call with mom\n",
+        );
+
+        let entries: Vec<_> = result.entries().collect();
+        assert_eq!(entries.len(), 2);
+        let notes = entries[0].get_bullets(&BulletType::Note);
+        assert_eq!(notes.len(), 2);
+        assert_eq!(notes[0].content, "Planning day #work");
+        assert_eq!(notes[0].time, NaiveTime::from_hms_opt(9, 30, 0));
+        assert_eq!(notes[0].children[0].content, "Draft the launch post.");
+        assert_eq!(notes[1].content, "Evening run");
+        assert_eq!(
+            entries[1].date,
+            NaiveDate::from_ymd_opt(2024, 3, 16).unwrap()
+        );
+        assert_eq!(result.skipped.len(), 1);
+    }
+}
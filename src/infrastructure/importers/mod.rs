@@ -0,0 +1,108 @@
+pub mod dayone;
+pub mod jrnl;
+pub mod obsidian;
+
+pub use dayone::*;
+pub use jrnl::*;
+pub use obsidian::*;
+
+use crate::entities::{Bullet, BulletType, Entry, TaskState};
+use crate::infrastructure::MarkdownParser;
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+
+/// Entries converted from another journaling tool, along with notes about
+/// anything that couldn't be carried over
+#[derive(Debug, Default)]
+pub struct ImportResult {
+    entries: BTreeMap<NaiveDate, Entry>,
+    /// Human-readable notes on dropped or altered content, e.g.
+    /// "2024-03-15: 2 photos not imported"
+    pub skipped: Vec<String>,
+}
+
+impl ImportResult {
+    /// Converted entries, oldest first
+    pub fn entries(&self) -> impl Iterator<Item = &Entry> {
+        self.entries.values()
+    }
+
+    pub fn bullet_count(&self) -> usize {
+        self.entries.values().map(Entry::total_bullets).sum()
+    }
+
+    fn entry_mut(&mut self, date: NaiveDate) -> &mut Entry {
+        self.entries.entry(date).or_insert_with(|| Entry::new(date))
+    }
+
+    /// Add a bullet `depth` levels below the last bullet of its type on `date`
+    fn add_bullet(&mut self, date: NaiveDate, depth: usize, bullet: Bullet) {
+        let bullets = self.entry_mut(date).get_bullets_mut(&bullet.bullet_type);
+        MarkdownParser::attach(bullets, depth, bullet);
+    }
+
+    fn skip(&mut self, date: NaiveDate, what: impl std::fmt::Display) {
+        self.skipped.push(format!("{}: {}", date, what));
+    }
+}
+
+/// Bullet for a markdown list item or paragraph line. Checkboxes become tasks,
+/// anything else takes `default_type`.
+fn bullet_from_markdown(line: &str, default_type: BulletType) -> Bullet {
+    let item = line
+        .strip_prefix("- ")
+        .or_else(|| line.strip_prefix("* "))
+        .or_else(|| line.strip_prefix("+ "))
+        .unwrap_or(line);
+
+    let checkbox = match item.get(..4) {
+        Some("[ ] ") => Some(TaskState::Pending),
+        Some("[x] ") | Some("[X] ") => Some(TaskState::Completed),
+        Some("[>] ") => Some(TaskState::Migrated),
+        Some("[<] ") => Some(TaskState::Scheduled),
+        _ => None,
+    };
+    match checkbox {
+        Some(state) => Bullet::with_task_state(item[4..].trim(), BulletType::Task, state),
+        None => Bullet::new(item.trim(), default_type),
+    }
+}
+
+/// Replace `[[Page]]` and `[[Page|alias]]` wiki links with their text
+fn strip_wiki_links(line: &str) -> String {
+    let mut output = String::new();
+    let mut rest = line;
+    while let Some(start) = rest.find("[[") {
+        let Some(len) = rest[start..].find("]]") else {
+            break;
+        };
+        let link = &rest[start + 2..start + len];
+        output.push_str(&rest[..start]);
+        output.push_str(link.rsplit('|').next().unwrap_or(link));
+        rest = &rest[start + len + 2..];
+    }
+    output.push_str(rest);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bullet_from_markdown() {
+        let task = bullet_from_markdown("- [x] Ship it", BulletType::Note);
+        assert_eq!(task.bullet_type, BulletType::Task);
+        assert_eq!(task.task_state, Some(TaskState::Completed));
+        assert_eq!(task.content, "Ship it");
+
+        let note = bullet_from_markdown("* Went for a run", BulletType::Note);
+        assert_eq!(note.bullet_type, BulletType::Note);
+        assert_eq!(note.content, "Went for a run");
+
+        assert_eq!(
+            strip_wiki_links("Met [[Sam Smith|Sam]] about [[Launch]]"),
+            "Met Sam about Launch"
+        );
+    }
+}
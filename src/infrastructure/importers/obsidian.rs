@@ -0,0 +1,159 @@
+use super::{ImportResult, bullet_from_markdown, strip_wiki_links};
+use crate::entities::BulletType;
+use crate::infrastructure::MarkdownParser;
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use std::fs;
+use std::path::Path;
+
+/// Import Obsidian daily notes: every `YYYY-MM-DD.md` file under `path`.
+/// List items and paragraphs become bullets (checkboxes as tasks, `HH:MM`
+/// prefixes as times), typed by the heading above them when it names a
+/// journal section such as `## Events`. Wiki links keep only their text;
+/// frontmatter, embeds and code blocks aren't imported.
+pub fn import_obsidian(path: &Path) -> Result<ImportResult> {
+    let mut result = ImportResult::default();
+    let mut undated = 0;
+    visit_notes(path, &mut |file| {
+        let date = file
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .and_then(|s| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok());
+        match date {
+            Some(date) => {
+                let content = fs::read_to_string(file)
+                    .with_context(|| format!("Failed to read {}", file.display()))?;
+                convert_note(&mut result, date, &content);
+            }
+            None => undated += 1,
+        }
+        Ok(())
+    })?;
+
+    if undated > 0 {
+        result.skipped.push(format!(
+            "{} notes without a YYYY-MM-DD name not imported",
+            undated
+        ));
+    }
+    Ok(result)
+}
+
+/// Call `f` for every markdown file below `dir`, skipping `.obsidian` and
+/// other hidden directories
+fn visit_notes(dir: &Path, f: &mut impl FnMut(&Path) -> Result<()>) -> Result<()> {
+    let mut paths: Vec<_> = fs::read_dir(dir)
+        .with_context(|| format!("Failed to read {}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .collect();
+    paths.sort();
+
+    for path in paths {
+        let hidden = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| n.starts_with('.'));
+        if hidden {
+            continue;
+        }
+        if path.is_dir() {
+            visit_notes(&path, f)?;
+        } else if path.extension().and_then(|e| e.to_str()) == Some("md") {
+            f(&path)?;
+        }
+    }
+    Ok(())
+}
+
+fn convert_note(result: &mut ImportResult, date: NaiveDate, content: &str) {
+    let mut lines = content.lines().peekable();
+    if lines.peek().map(|l| l.trim()) == Some("---") {
+        lines.next();
+        for line in lines.by_ref() {
+            if line.trim() == "---" {
+                break;
+            }
+        }
+        result.skip(date, "frontmatter not imported");
+    }
+
+    let mut section = BulletType::Note;
+    let mut in_code_block = false;
+    for line in lines {
+        let depth = MarkdownParser::indent_depth(line);
+        let line = line.trim();
+
+        if line.starts_with("```") {
+            if !in_code_block {
+                result.skip(date, "code block not imported");
+            }
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block || line.is_empty() {
+            continue;
+        }
+        if line.starts_with("![[") {
+            result.skip(date, format!("embed {} not imported", line));
+            continue;
+        }
+
+        let heading = line.trim_start_matches('#');
+        if heading.len() < line.len() && heading.starts_with(' ') {
+            let heading = heading.trim();
+            match BulletType::ALL
+                .into_iter()
+                .find(|t| t.section_name().eq_ignore_ascii_case(heading))
+            {
+                Some(bullet_type) => section = bullet_type,
+                None => {
+                    section = BulletType::Note;
+                    result.skip(date, format!("heading '{}' dropped", heading));
+                }
+            }
+            continue;
+        }
+
+        let line = strip_wiki_links(line);
+        let mut bullet = bullet_from_markdown(&line, section);
+        let (time, text) = MarkdownParser::split_time_prefix(&bullet.content);
+        if let Some(time) = time {
+            bullet.content = text.to_string();
+            bullet.time = Some(time);
+        }
+        result.add_bullet(date, depth, bullet);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::TaskState;
+
+    #[test]
+    fn test_import_obsidian_daily_notes() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let daily = temp_dir.path().join("Daily");
+        fs::create_dir_all(&daily).unwrap();
+        fs::create_dir_all(temp_dir.path().join(".obsidian")).unwrap();
+        fs::write(
+            daily.join("2024-03-15.md"),
+            "---\ntags: [daily]\n---\n## Tasks\n- [ ] Review [[Launch Plan|launch]]\n  - Ask @sam\n## Events\n- 09:30 Standup\n## Log\nQuiet day\n![[photo.png]]\n",
+        )
+        .unwrap();
+        fs::write(temp_dir.path().join("Ideas.md"), "- Something\n").unwrap();
+
+        let result = import_obsidian(temp_dir.path()).unwrap();
+        let entry = result.entries().next().unwrap();
+        let tasks = entry.get_bullets(&BulletType::Task);
+        assert_eq!(tasks[0].content, "Review launch");
+        assert_eq!(tasks[0].task_state, Some(TaskState::Pending));
+        assert_eq!(tasks[0].children.len(), 1);
+        let events = entry.get_bullets(&BulletType::Event);
+        assert_eq!(events[0].content, "Standup");
+        assert!(events[0].time.is_some());
+        assert_eq!(entry.get_bullets(&BulletType::Note)[0].content, "Quiet day");
+        // Frontmatter, the Log heading, the embed and Ideas.md
+        assert_eq!(result.skipped.len(), 4);
+    }
+}
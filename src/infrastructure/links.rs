@@ -0,0 +1,78 @@
+use anyhow::{Context, Result};
+use std::process::{Command, Stdio};
+
+/// Fetch a page and return the text of its `<title>` element, if any
+pub fn fetch_title(url: &str) -> Result<Option<String>> {
+    let html = ureq::get(url)
+        .call()
+        .with_context(|| format!("Failed to fetch {}", url))?
+        .body_mut()
+        .read_to_string()
+        .with_context(|| format!("Failed to read {}", url))?;
+    Ok(extract_title(&html))
+}
+
+/// Text of the first `<title>` element, with whitespace collapsed and the
+/// common HTML entities decoded
+pub fn extract_title(html: &str) -> Option<String> {
+    let lower = html.to_lowercase();
+    let open = lower.find("<title")?;
+    let start = open + lower[open..].find('>')? + 1;
+    let end = start + lower[start..].find("</title")?;
+
+    let title = html[start..end]
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&");
+    (!title.is_empty()).then_some(title)
+}
+
+/// Open a URL in the default browser without waiting for it
+pub fn open_url(url: &str) -> Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else {
+        Command::new("xdg-open")
+    };
+
+    command
+        .arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("Failed to open {}", url))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{Bullet, BulletType};
+
+    #[test]
+    fn test_urls_and_titles() {
+        let bullet = Bullet::new(
+            "Read https://example.com/post, then (http://foo.org/a?b=1) https://",
+            BulletType::Note,
+        );
+        assert_eq!(
+            bullet.urls(),
+            vec!["https://example.com/post", "http://foo.org/a?b=1"]
+        );
+
+        let html = "<html><head><TITLE lang=\"en\">\n  Rust &amp; Friends\n</TITLE></head></html>";
+        assert_eq!(extract_title(html), Some("Rust & Friends".to_string()));
+        assert_eq!(extract_title("<title> </title>"), None);
+        assert_eq!(extract_title("<p>no title</p>"), None);
+    }
+}
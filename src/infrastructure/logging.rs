@@ -0,0 +1,59 @@
+use anyhow::Context;
+use std::path::Path;
+use tracing::level_filters::LevelFilter;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::prelude::*;
+
+/// Daily log files kept in `logs/` before the oldest is removed
+const KEPT_LOG_FILES: usize = 14;
+
+/// Send log events to stderr and to daily-rotated `logs/journo.YYYY-MM-DD.log`
+/// files in the journal directory. Stderr shows warnings, `-v` info and `-vv`
+/// debug output; the log file always records at least info so failed hooks and
+/// migrations can be looked into afterwards. Events from the `log` crate (used
+/// by dependencies) are forwarded as well.
+pub fn init_logging(journal_dir: &Path, verbosity: u8, color: bool) {
+    let stderr_level = match verbosity {
+        0 => LevelFilter::WARN,
+        1 => LevelFilter::INFO,
+        2 => LevelFilter::DEBUG,
+        _ => LevelFilter::TRACE,
+    };
+    let stderr_layer = tracing_subscriber::fmt::layer()
+        .with_writer(std::io::stderr)
+        .with_ansi(color)
+        .without_time()
+        .with_target(false)
+        .with_filter(stderr_level);
+
+    let file_layer = match log_file_appender(journal_dir) {
+        Ok(appender) => Some(
+            tracing_subscriber::fmt::layer()
+                .with_writer(appender)
+                .with_ansi(false)
+                .with_filter(stderr_level.max(LevelFilter::INFO)),
+        ),
+        Err(e) => {
+            eprintln!("Warning: not writing a log file: {}", e);
+            None
+        }
+    };
+
+    // Only fails if a subscriber is already installed, e.g. in tests
+    let _ = tracing_subscriber::registry()
+        .with(stderr_layer)
+        .with(file_layer)
+        .try_init();
+}
+
+fn log_file_appender(journal_dir: &Path) -> anyhow::Result<RollingFileAppender> {
+    let logs_dir = journal_dir.join("logs");
+    std::fs::create_dir_all(&logs_dir)
+        .with_context(|| format!("Failed to create {}", logs_dir.display()))?;
+    Ok(RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix("journo")
+        .filename_suffix("log")
+        .max_log_files(KEPT_LOG_FILES)
+        .build(logs_dir)?)
+}
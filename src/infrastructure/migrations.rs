@@ -0,0 +1,219 @@
+use anyhow::{Context, Result};
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use tracing::debug;
+
+/// SQL flavors with their own storage backend. Migrations are shared by
+/// default; a dialect overrides one by putting a file with the same version in
+/// `migrations/<dialect>/`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SqlDialect {
+    DuckDb,
+    Sqlite,
+    Postgres,
+}
+
+impl SqlDialect {
+    fn overrides_dir(self) -> &'static str {
+        match self {
+            SqlDialect::DuckDb => "duckdb",
+            SqlDialect::Sqlite => "sqlite",
+            SqlDialect::Postgres => "postgres",
+        }
+    }
+}
+
+/// A schema migration file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Migration {
+    pub version: i32,
+    pub name: String,
+    pub sql: String,
+}
+
+/// Schema migration versions known to the binary versus recorded in the database
+#[derive(Debug, Clone, PartialEq)]
+pub struct MigrationStatus {
+    pub applied: Vec<i32>,
+    pub available: Vec<i32>,
+}
+
+impl MigrationStatus {
+    pub fn pending(&self) -> Vec<i32> {
+        self.available
+            .iter()
+            .filter(|v| !self.applied.contains(v))
+            .copied()
+            .collect()
+    }
+
+    pub fn latest_applied(&self) -> Option<i32> {
+        self.applied.iter().max().copied()
+    }
+}
+
+/// Statements creating the table that records applied migrations and the
+/// key-value `schema_state` table, valid in every dialect
+pub const MIGRATIONS_TABLE_SQL: &str = r#"
+    CREATE TABLE IF NOT EXISTS migrations (
+        version INTEGER PRIMARY KEY,
+        name TEXT NOT NULL,
+        applied_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+    );
+    CREATE TABLE IF NOT EXISTS schema_state (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL
+    );
+"#;
+
+/// `schema_state` key holding the `migrations_fingerprint` of the last
+/// complete migration run
+pub const MIGRATIONS_FINGERPRINT_KEY: &str = "migrations_fingerprint";
+
+fn migrations_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("migrations")
+}
+
+/// Cheap stand-in for the migrations a dialect would discover: the package
+/// version and the name and modification time of every migration file, so
+/// adding, removing or editing one changes it. Opening storage skips the
+/// migration run when the database recorded the same fingerprint, so startup
+/// doesn't read every migration file and query the applied versions.
+pub fn migrations_fingerprint(dialect: SqlDialect) -> String {
+    let shared = migrations_dir();
+    format!(
+        "{}:{:016x}",
+        env!("CARGO_PKG_VERSION"),
+        files_fingerprint(&[shared.clone(), shared.join(dialect.overrides_dir())])
+    )
+}
+
+/// Hash of the names and modification times of the `.sql` files in `dirs`,
+/// any of which may not exist
+fn files_fingerprint(dirs: &[PathBuf]) -> u64 {
+    let mut files = Vec::new();
+    for dir in dirs {
+        let Ok(entries) = fs::read_dir(dir) else {
+            continue;
+        };
+        for path in entries.filter_map(|entry| entry.ok().map(|entry| entry.path())) {
+            let Some(name) = path.file_name().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            if !name.ends_with(".sql") {
+                continue;
+            }
+            let modified = fs::metadata(&path)
+                .and_then(|meta| meta.modified())
+                .ok()
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map_or(0, |since| since.as_nanos());
+            files.push((name.to_string(), modified));
+        }
+    }
+    files.sort();
+
+    let mut hasher = DefaultHasher::new();
+    files.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Migrations for a dialect ordered by version, with dialect-specific files
+/// taking the place of shared ones of the same version
+pub fn discover_migrations(dialect: SqlDialect) -> Result<Vec<Migration>> {
+    let migrations_dir = migrations_dir();
+    debug!(
+        "Looking for {:?} migrations in: {:?}",
+        dialect, migrations_dir
+    );
+
+    let mut migrations = read_migrations(&migrations_dir)?;
+    for specific in read_migrations(&migrations_dir.join(dialect.overrides_dir()))? {
+        migrations.retain(|m| m.version != specific.version);
+        migrations.push(specific);
+    }
+
+    migrations.sort_by_key(|m| m.version);
+    debug!("Discovered {} migration files", migrations.len());
+    Ok(migrations)
+}
+
+/// `NNNN_name.sql` files directly inside `dir`, which may not exist
+fn read_migrations(dir: &Path) -> Result<Vec<Migration>> {
+    if !dir.exists() {
+        debug!("Migrations directory {:?} does not exist, skipping", dir);
+        return Ok(vec![]);
+    }
+
+    let mut migrations = Vec::new();
+    let entries = fs::read_dir(dir)
+        .with_context(|| format!("Failed to read migrations directory: {}", dir.display()))?;
+
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().and_then(|s| s.to_str()) != Some("sql") {
+            continue;
+        }
+        let Some(filename) = path.file_name().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        let Some(Ok(version)) = filename.split('_').next().map(str::parse::<i32>) else {
+            continue;
+        };
+
+        let name = filename
+            .strip_suffix(".sql")
+            .unwrap_or(filename)
+            .to_string();
+        let sql = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read migration file: {}", path.display()))?;
+        migrations.push(Migration { version, name, sql });
+    }
+
+    Ok(migrations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dialect_overrides_replace_shared_migrations() {
+        let shared = discover_migrations(SqlDialect::DuckDb).unwrap();
+        let sqlite = discover_migrations(SqlDialect::Sqlite).unwrap();
+
+        let versions = |migrations: &[Migration]| -> Vec<i32> {
+            migrations.iter().map(|m| m.version).collect()
+        };
+        assert_eq!(versions(&shared), versions(&sqlite));
+
+        let initial = |migrations: &[Migration]| migrations[0].sql.clone();
+        assert!(initial(&shared).contains("nextval"));
+        assert!(!initial(&sqlite).contains("nextval"));
+    }
+
+    #[test]
+    fn test_fingerprint_changes_when_a_migration_is_edited() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let dirs = [
+            temp_dir.path().to_path_buf(),
+            temp_dir.path().join("sqlite"),
+        ];
+        let empty = files_fingerprint(&dirs);
+
+        let path = temp_dir.path().join("0001_init.sql");
+        fs::write(&path, "CREATE TABLE a (id INTEGER);").unwrap();
+        let added = files_fingerprint(&dirs);
+        assert_ne!(added, empty);
+        assert_eq!(files_fingerprint(&dirs), added);
+
+        // Editing a file in place leaves its directory's modification time alone
+        let file = fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(UNIX_EPOCH + std::time::Duration::from_secs(60))
+            .unwrap();
+        assert_ne!(files_fingerprint(&dirs), added);
+    }
+}
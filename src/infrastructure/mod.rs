@@ -1,18 +1,55 @@
+#[cfg(feature = "async-storage")]
+pub mod async_storage;
+pub mod calendar;
+#[cfg(feature = "duckdb")]
 pub mod duckdb_storage;
+pub mod exporters;
 pub mod filesystem;
+pub mod holidays;
 pub mod hooks;
+pub mod importers;
+pub mod links;
+pub mod logging;
+pub mod migrations;
 pub mod parser;
 pub mod plugins;
+#[cfg(feature = "postgres")]
+pub mod postgres_storage;
+pub mod query;
+pub mod reconcile;
 pub mod repository;
+// A PostgreSQL-only build uses just a few of the shared statements
+#[cfg_attr(not(any(feature = "duckdb", feature = "sqlite")), allow(dead_code))]
+mod sql;
+#[cfg(feature = "sqlite")]
+pub mod sqlite_storage;
 pub mod storage;
 
-#[cfg(test)]
+#[cfg(all(test, feature = "duckdb"))]
 pub mod test_utils;
 
+#[cfg(feature = "async-storage")]
+pub use async_storage::*;
+pub use calendar::*;
+#[cfg(feature = "duckdb")]
 pub use duckdb_storage::*;
+pub use exporters::*;
+// `importers` has an `obsidian` module too; the name refers to the exporter
+pub use exporters::obsidian;
 pub use filesystem::*;
+pub use holidays::*;
 pub use hooks::*;
+pub use importers::*;
+pub use links::*;
+pub use logging::*;
+pub use migrations::*;
 pub use parser::*;
 pub use plugins::*;
+#[cfg(feature = "postgres")]
+pub use postgres_storage::*;
+pub use query::*;
+pub use reconcile::*;
 pub use repository::*;
+#[cfg(feature = "sqlite")]
+pub use sqlite_storage::*;
 pub use storage::*;
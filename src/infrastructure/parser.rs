@@ -1,26 +1,104 @@
-use crate::entities::{Bullet, BulletType, Entry};
+use crate::entities::{Bullet, BulletType, Entry, Prompt, TaskState};
 use anyhow::Result;
-use chrono::NaiveDate;
+use chrono::{NaiveDate, NaiveTime};
 
-pub struct MarkdownParser;
+/// Header of the section holding an entry's free-written body. It's always
+/// last and everything below it is kept verbatim, headers included.
+pub const FREEWRITE_HEADER: &str = "# Freewrite";
+
+pub struct MarkdownParser {
+    /// Order sections are written in; parsing accepts them in any order
+    section_order: Vec<BulletType>,
+}
+
+/// Content the parser had to drop, grouped by where it appeared
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseWarning {
+    /// Unrecognized header the lines were under, or `None` before the first header
+    pub header: Option<String>,
+    pub lines: usize,
+}
+
+impl std::fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let noun = if self.lines == 1 { "line" } else { "lines" };
+        match &self.header {
+            Some(header) => write!(f, "{} {} ignored under '{}'", self.lines, noun, header),
+            None => write!(f, "{} {} ignored before the first header", self.lines, noun),
+        }
+    }
+}
+
+impl Default for MarkdownParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl MarkdownParser {
     pub fn new() -> Self {
-        Self
+        Self {
+            section_order: BulletType::ALL.to_vec(),
+        }
+    }
+
+    /// Write sections in this order, e.g. Notes first; types left out follow
+    /// in their canonical order
+    pub fn with_section_order(mut self, order: &[BulletType]) -> Self {
+        self.section_order = BulletType::ordered(order);
+        self
+    }
+
+    pub fn section_order(&self) -> &[BulletType] {
+        &self.section_order
     }
 
     pub fn parse(&self, date: NaiveDate, content: &str) -> Result<Entry> {
+        Ok(self.parse_with_report(date, content)?.0)
+    }
+
+    /// Parse an entry, also reporting lines that were dropped because they
+    /// appear before the first header or under an unrecognized one
+    pub fn parse_with_report(
+        &self,
+        date: NaiveDate,
+        content: &str,
+    ) -> Result<(Entry, Vec<ParseWarning>)> {
         let mut entry = Entry::new(date);
         let mut current_bullet_type: Option<BulletType> = None;
-
-        for line in content.lines() {
+        let mut current_header: Option<String> = None;
+        let mut current_session: Option<NaiveTime> = None;
+        let mut warnings: Vec<ParseWarning> = Vec::new();
+        let mut lines = content.lines();
+        let mut freewrite = false;
+
+        for line in lines.by_ref() {
+            let depth = Self::indent_depth(line);
             let line = line.trim();
 
             if line.is_empty() {
                 continue;
             }
 
-            if line.starts_with('#') {
+            // Prompt comments from templates aren't part of the entry
+            if line.starts_with("<!--") {
+                continue;
+            }
+
+            // `## HH:MM` starts a session; its sections follow as usual
+            if let Some(session) = Self::session_header(line) {
+                current_session = Some(session);
+                current_bullet_type = None;
+                current_header = Some(line.to_string());
+                continue;
+            }
+
+            if line.eq_ignore_ascii_case(FREEWRITE_HEADER) {
+                freewrite = true;
+                break;
+            }
+
+            if Self::is_section_header(line) {
                 current_bullet_type = match line.to_lowercase().as_str() {
                     "# tasks" => Some(BulletType::Task),
                     "# events" => Some(BulletType::Event),
@@ -31,74 +109,241 @@ impl MarkdownParser {
                     "# missteps" => Some(BulletType::Misstep),
                     _ => None,
                 };
+                current_header = Some(line.to_string());
                 continue;
             }
 
             if let Some(bullet_type) = current_bullet_type {
-                let bullet = Bullet::new(line, bullet_type);
-                entry.add_bullet(bullet);
+                let (state, line) = match bullet_type {
+                    BulletType::Task | BulletType::Priority => Self::split_state_marker(line),
+                    _ => (None, line),
+                };
+                let (time, end_time, content) = Self::split_time_range_prefix(line);
+                let mut bullet = Bullet::new(content, bullet_type);
+                bullet.time = time;
+                bullet.end_time = end_time;
+                bullet.session = current_session;
+                if state.is_some() {
+                    bullet.task_state = state;
+                }
+                let bullets = entry.get_bullets_mut(&bullet_type);
+                // Sub-bullets can't hang from a bullet of an earlier session
+                let depth = match bullets.last() {
+                    Some(last) if last.session != current_session => 0,
+                    _ => depth,
+                };
+                Self::attach(bullets, depth, bullet);
+            } else {
+                match warnings.last_mut() {
+                    Some(warning) if warning.header == current_header => warning.lines += 1,
+                    _ => warnings.push(ParseWarning {
+                        header: current_header.clone(),
+                        lines: 1,
+                    }),
+                }
             }
         }
 
-        Ok(entry)
+        if freewrite {
+            let body = lines.collect::<Vec<_>>().join("\n");
+            let body = body.trim();
+            if !body.is_empty() {
+                entry.body = Some(body.to_string());
+            }
+        }
+
+        Ok((entry, warnings))
+    }
+
+    /// Whether a line is a `# Name` section header. A `#` directly followed
+    /// by a word, as in `#weight:82.5`, is bullet content.
+    fn is_section_header(line: &str) -> bool {
+        line.strip_prefix('#')
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(char::is_whitespace))
+    }
+
+    /// Start time of a `## HH:MM` session header
+    fn session_header(line: &str) -> Option<NaiveTime> {
+        let time = line.strip_prefix("##")?.trim();
+        if time.len() != 5 {
+            return None;
+        }
+        NaiveTime::parse_from_str(time, "%H:%M").ok()
+    }
+
+    /// Nesting level of a line: one level per two leading spaces or per tab
+    pub(crate) fn indent_depth(line: &str) -> usize {
+        let mut spaces = 0;
+        let mut depth = 0;
+        for c in line.chars() {
+            match c {
+                ' ' => spaces += 1,
+                '\t' => depth += 1,
+                _ => break,
+            }
+        }
+        depth + spaces / 2
+    }
+
+    /// Add a bullet `depth` levels below the last bullet of the list. Levels
+    /// without a parent to hang from are collapsed.
+    pub(crate) fn attach(bullets: &mut Vec<Bullet>, depth: usize, bullet: Bullet) {
+        match bullets.last_mut() {
+            Some(parent) if depth > 0 => Self::attach(&mut parent.children, depth - 1, bullet),
+            _ => bullets.push(bullet),
+        }
+    }
+
+    /// Write a bullet and its children, indenting two spaces per level
+    fn push_bullet(content: &mut String, bullet: &Bullet, depth: usize) {
+        content.push_str(&format!(
+            "{}{}\n",
+            "  ".repeat(depth),
+            Self::bullet_line(bullet)
+        ));
+        for child in &bullet.children {
+            Self::push_bullet(content, child, depth + 1);
+        }
+    }
+
+    /// Split a leading `HH:MM ` time of day off a bullet line
+    pub fn split_time_prefix(line: &str) -> (Option<NaiveTime>, &str) {
+        if let Some((prefix, rest)) = line.split_once(' ')
+            && prefix.len() == 5
+            && let Ok(time) = NaiveTime::parse_from_str(prefix, "%H:%M")
+        {
+            return (Some(time), rest.trim_start());
+        }
+        (None, line)
+    }
+
+    /// Split a leading `HH:MM ` time or `HH:MM-HH:MM ` time block off a
+    /// bullet line. A block has to end after it starts.
+    pub fn split_time_range_prefix(line: &str) -> (Option<NaiveTime>, Option<NaiveTime>, &str) {
+        if let Some((prefix, rest)) = line.split_once(' ')
+            && let Some((start, end)) = prefix.split_once('-')
+            && start.len() == 5
+            && end.len() == 5
+            && let (Ok(start), Ok(end)) = (
+                NaiveTime::parse_from_str(start, "%H:%M"),
+                NaiveTime::parse_from_str(end, "%H:%M"),
+            )
+            && start < end
+        {
+            return (Some(start), Some(end), rest.trim_start());
+        }
+        let (time, rest) = Self::split_time_prefix(line);
+        (time, None, rest)
+    }
+
+    /// Split a leading task state marker (`[x]`, `[>]`, `[<]` or `[ ]`) off a task line
+    fn split_state_marker(line: &str) -> (Option<TaskState>, &str) {
+        let state = match line.get(..3) {
+            Some("[x]") | Some("[X]") => TaskState::Completed,
+            Some("[>]") => TaskState::Migrated,
+            Some("[<]") => TaskState::Scheduled,
+            Some("[ ]") => TaskState::Pending,
+            _ => return (None, line),
+        };
+        (Some(state), line[3..].trim_start())
+    }
+
+    /// Markdown line for a bullet, including its state marker and time prefix
+    fn bullet_line(bullet: &Bullet) -> String {
+        let marker = match bullet.task_state {
+            Some(TaskState::Completed) => "[x] ",
+            Some(TaskState::Migrated) => "[>] ",
+            Some(TaskState::Scheduled) => "[<] ",
+            Some(TaskState::Pending) | None => "",
+        };
+        match bullet.time_label() {
+            Some(time) => format!("{}{} {}", marker, time, bullet.content),
+            None => format!("{}{}", marker, bullet.content),
+        }
     }
 
     pub fn serialize(&self, entry: &Entry) -> Result<String> {
+        Ok(Self::with_sessions(entry, |entry| {
+            self.serialize_sections(entry, false)
+        }))
+    }
+
+    /// Serialize entry for editing - always shows all headers for better UX
+    pub fn serialize_for_editing(&self, entry: &Entry) -> Result<String> {
+        // Sessions only list their non-empty sections, so adding a session
+        // doesn't repeat the whole template
+        let mut first = true;
+        Ok(Self::with_sessions(entry, |entry| {
+            let all_headers = std::mem::take(&mut first);
+            self.serialize_sections(entry, all_headers)
+        }))
+    }
+
+    /// Bullets outside sessions, then each `## HH:MM` session in time order,
+    /// then the free-written body
+    fn with_sessions(entry: &Entry, mut serialize: impl FnMut(&Entry) -> String) -> String {
+        let mut content = serialize(&entry.session(None));
+        for session in entry.sessions() {
+            content.push_str(&format!("## {}\n\n", session.format("%H:%M")));
+            content.push_str(&serialize(&entry.session(Some(session))));
+        }
+        if let Some(body) = &entry.body {
+            content.push_str(&format!("{}\n\n{}\n", FREEWRITE_HEADER, body));
+        }
+        content
+    }
+
+    fn serialize_sections(&self, entry: &Entry, all_headers: bool) -> String {
         let mut content = String::new();
 
-        let sections = [
-            (BulletType::Task, "# Tasks"),
-            (BulletType::Event, "# Events"),
-            (BulletType::Note, "# Notes"),
-            (BulletType::Priority, "# Priority"),
-            (BulletType::Inspiration, "# Inspiration"),
-            (BulletType::Insight, "# Insights"),
-            (BulletType::Misstep, "# Missteps"),
-        ];
-
-        for (bullet_type, section_header) in sections {
-            let bullets = entry.get_bullets(&bullet_type);
-            if !bullets.is_empty() {
-                content.push_str(&format!("{}\n", section_header));
+        for bullet_type in &self.section_order {
+            let bullets = entry.get_bullets(bullet_type);
+            if all_headers || !bullets.is_empty() {
+                content.push_str(&format!("# {}\n", bullet_type.section_name()));
                 for bullet in bullets {
-                    content.push_str(&format!("{}\n", bullet.content));
+                    Self::push_bullet(&mut content, bullet, 0);
                 }
                 content.push('\n');
             }
         }
 
-        Ok(content)
+        content
     }
 
-    /// Serialize entry for editing - always shows all headers for better UX
-    pub fn serialize_for_editing(&self, entry: &Entry) -> Result<String> {
-        let mut content = String::new();
+    /// Insert each question as an HTML comment below the header of the section
+    /// its answers belong to, so they're parsed as bullets of that type
+    pub fn insert_prompts(content: &str, prompts: &[Prompt]) -> String {
+        let mut result = String::with_capacity(content.len());
+        for line in content.lines() {
+            result.push_str(line);
+            result.push('\n');
 
-        let sections = [
-            (BulletType::Task, "# Tasks"),
-            (BulletType::Event, "# Events"),
-            (BulletType::Note, "# Notes"),
-            (BulletType::Priority, "# Priority"),
-            (BulletType::Inspiration, "# Inspiration"),
-            (BulletType::Insight, "# Insights"),
-            (BulletType::Misstep, "# Missteps"),
-        ];
-
-        for (bullet_type, section_header) in sections {
-            let bullets = entry.get_bullets(&bullet_type);
-            content.push_str(&format!("{}\n", section_header));
-            for bullet in bullets {
-                content.push_str(&format!("{}\n", bullet.content));
+            for prompt in prompts {
+                let header = format!("# {}", prompt.bullet_type.section_name());
+                if line.trim().eq_ignore_ascii_case(&header) {
+                    result.push_str(&format!("<!-- {} -->\n", prompt.question));
+                }
             }
-            content.push('\n');
         }
-
-        Ok(content)
+        result
     }
 
     /// Generate empty template for new entries
-    pub fn empty_template() -> String {
-        "# Tasks\n\n# Events\n\n# Notes\n\n# Priority\n\n# Inspiration\n\n# Insights\n\n# Missteps\n\n".to_string()
+    pub fn empty_template(&self) -> String {
+        self.section_order
+            .iter()
+            .map(|bullet_type| format!("# {}\n\n", bullet_type.section_name()))
+            .collect()
+    }
+
+    /// Empty template for a new session starting at `session`, appended to
+    /// the day's entry
+    pub fn session_template(&self, session: NaiveTime) -> String {
+        format!(
+            "## {}\n\n{}",
+            session.format("%H:%M"),
+            self.empty_template()
+        )
     }
 }
 
@@ -109,7 +354,7 @@ mod tests {
 
     #[test]
     fn test_empty_template_generation() {
-        let template = MarkdownParser::empty_template();
+        let template = MarkdownParser::new().empty_template();
 
         let expected = "# Tasks\n\n# Events\n\n# Notes\n\n# Priority\n\n# Inspiration\n\n# Insights\n\n# Missteps\n\n";
         assert_eq!(template, expected);
@@ -158,7 +403,7 @@ mod tests {
     fn test_parse_template_creates_empty_entry() {
         let parser = MarkdownParser::new();
         let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
-        let template = MarkdownParser::empty_template();
+        let template = parser.empty_template();
 
         let entry = parser.parse(date, &template).unwrap();
 
@@ -286,6 +531,35 @@ mod tests {
         assert!(event_line < insight_line);
     }
 
+    #[test]
+    fn test_custom_section_order() {
+        let parser =
+            MarkdownParser::new().with_section_order(&[BulletType::Note, BulletType::Insight]);
+        let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let mut entry = Entry::new(date);
+        entry.add_bullet(Bullet::new("Task", BulletType::Task));
+        entry.add_bullet(Bullet::new("Note", BulletType::Note));
+        entry.add_bullet(Bullet::new("Insight", BulletType::Insight));
+
+        assert!(
+            parser
+                .empty_template()
+                .starts_with("# Notes\n\n# Insights\n\n# Tasks\n\n# Events\n\n")
+        );
+        let markdown = parser.serialize(&entry).unwrap();
+        assert_eq!(
+            markdown,
+            "# Notes\nNote\n\n# Insights\nInsight\n\n# Tasks\nTask\n\n"
+        );
+        assert_eq!(parser.parse(date, &markdown).unwrap(), entry);
+        assert!(
+            parser
+                .serialize_for_editing(&entry)
+                .unwrap()
+                .ends_with("# Missteps\n\n")
+        );
+    }
+
     #[test]
     fn test_serialize_for_editing_shows_all_headers() {
         let parser = MarkdownParser::new();
@@ -331,6 +605,120 @@ mod tests {
         assert_eq!(events[0].content, "Meeting today");
     }
 
+    #[test]
+    fn test_time_prefix_round_trip() {
+        let parser = MarkdownParser::new();
+        let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+
+        let content = "# Events\n09:30 Standup\nLunch at 12\n9:30 not a time\n10:00-11:30 Deep work\n12:00-11:00 Backwards\n";
+        let entry = parser.parse(date, content).unwrap();
+
+        let events = entry.get_bullets(&BulletType::Event);
+        assert_eq!(events[0].content, "Standup");
+        assert_eq!(events[0].time, NaiveTime::from_hms_opt(9, 30, 0));
+        assert_eq!(events[0].end_time, None);
+        assert_eq!(events[1].time, None);
+        assert_eq!(events[2].content, "9:30 not a time");
+        assert_eq!(events[2].time, None);
+        assert_eq!(events[3].content, "Deep work");
+        assert_eq!(events[3].time, NaiveTime::from_hms_opt(10, 0, 0));
+        assert_eq!(events[3].end_time, NaiveTime::from_hms_opt(11, 30, 0));
+        assert_eq!(events[4].content, "12:00-11:00 Backwards");
+
+        let markdown = parser.serialize(&entry).unwrap();
+        assert!(markdown.contains("09:30 Standup\n"));
+        assert!(markdown.contains("10:00-11:30 Deep work\n"));
+        assert_eq!(parser.parse(date, &markdown).unwrap(), entry);
+    }
+
+    #[test]
+    fn test_task_state_markers_round_trip() {
+        let parser = MarkdownParser::new();
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let content = "# Tasks\n[x] 09:00 Write draft\n[>] Call bank\nReview !after:draft\n\n# Notes\n[x] marks the spot\n";
+
+        let entry = parser.parse(date, content).unwrap();
+        let tasks = entry.get_bullets(&BulletType::Task);
+        assert_eq!(tasks[0].task_state, Some(TaskState::Completed));
+        assert_eq!(tasks[0].content, "Write draft");
+        assert_eq!(tasks[1].task_state, Some(TaskState::Migrated));
+        assert_eq!(tasks[2].task_state, Some(TaskState::Pending));
+        assert_eq!(
+            entry.get_bullets(&BulletType::Note)[0].content,
+            "[x] marks the spot"
+        );
+
+        let reparsed = parser
+            .parse(date, &parser.serialize(&entry).unwrap())
+            .unwrap();
+        assert_eq!(reparsed, entry);
+    }
+
+    #[test]
+    fn test_nested_bullets_round_trip() {
+        let parser = MarkdownParser::new();
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let content =
+            "# Tasks\nPlan trip\n  Book flights\n    [x] Compare prices\n\tPack\nClean desk\n";
+
+        let entry = parser.parse(date, content).unwrap();
+        let tasks = entry.get_bullets(&BulletType::Task);
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[0].children.len(), 2);
+        assert_eq!(tasks[0].children[0].content, "Book flights");
+        let grandchild = &tasks[0].children[0].children[0];
+        assert_eq!(grandchild.content, "Compare prices");
+        assert_eq!(grandchild.task_state, Some(TaskState::Completed));
+        assert_eq!(tasks[0].children[1].content, "Pack");
+        assert_eq!(entry.total_bullets(), 5);
+
+        let serialized = parser.serialize(&entry).unwrap();
+        assert!(serialized.contains("Plan trip\n  Book flights\n    [x] Compare prices\n  Pack\n"));
+        assert_eq!(parser.parse(date, &serialized).unwrap(), entry);
+    }
+
+    #[test]
+    fn test_parse_report_for_dropped_lines() {
+        let parser = MarkdownParser::new();
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let content = "Stray line\n# Tasks\nKept\n# Stuff\nFirst\nSecond\n# Notes\nAlso kept\n";
+
+        let (entry, warnings) = parser.parse_with_report(date, content).unwrap();
+        assert_eq!(entry.total_bullets(), 2);
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(
+            warnings[0].to_string(),
+            "1 line ignored before the first header"
+        );
+        assert_eq!(warnings[1].to_string(), "2 lines ignored under '# Stuff'");
+
+        let (_, warnings) = parser
+            .parse_with_report(date, &parser.empty_template())
+            .unwrap();
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_prompts_are_skipped_when_parsing() {
+        let parser = MarkdownParser::new();
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let prompts = vec![Prompt::new("What went well?", BulletType::Insight)];
+
+        let buffer = MarkdownParser::insert_prompts(&parser.empty_template(), &prompts);
+        assert!(buffer.contains("# Insights\n<!-- What went well? -->\n"));
+
+        let answered = buffer.replace(
+            "<!-- What went well? -->\n",
+            "<!-- What went well? -->\nShipped the release\n",
+        );
+        let entry = parser.parse(date, &answered).unwrap();
+        assert_eq!(entry.total_bullets(), 1);
+        assert_eq!(
+            entry.get_bullets(&BulletType::Insight)[0].content,
+            "Shipped the release"
+        );
+    }
+
     #[test]
     fn test_case_insensitive_headers() {
         let parser = MarkdownParser::new();
@@ -345,4 +733,77 @@ mod tests {
         assert_eq!(entry.get_bullets(&BulletType::Event).len(), 1);
         assert_eq!(entry.get_bullets(&BulletType::Note).len(), 1);
     }
+
+    #[test]
+    fn test_sessions_round_trip() {
+        let parser = MarkdownParser::new();
+        let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let morning = NaiveTime::from_hms_opt(8, 30, 0).unwrap();
+        let evening = NaiveTime::from_hms_opt(21, 0, 0).unwrap();
+
+        let content = "# Tasks\nUnsorted\n\n## 08:30\n# Tasks\n  Plan the day\n# Notes\nSlept well\n\n## 21:00\n# Notes\nQuiet evening\n";
+        let entry = parser.parse(date, content).unwrap();
+        assert_eq!(entry.sessions(), vec![morning, evening]);
+        let tasks = entry.get_bullets(&BulletType::Task);
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[0].session, None);
+        assert_eq!(tasks[1].session, Some(morning));
+        assert_eq!(entry.session(Some(evening)).total_bullets(), 1);
+
+        let serialized = parser.serialize(&entry).unwrap();
+        assert_eq!(
+            serialized,
+            "# Tasks\nUnsorted\n\n## 08:30\n\n# Tasks\nPlan the day\n\n# Notes\nSlept well\n\n## 21:00\n\n# Notes\nQuiet evening\n\n"
+        );
+        assert_eq!(parser.parse(date, &serialized).unwrap(), entry);
+
+        let editing = parser.serialize_for_editing(&entry).unwrap();
+        assert!(editing.starts_with("# Tasks\nUnsorted\n\n# Events\n"));
+        assert!(editing.ends_with("## 21:00\n\n# Notes\nQuiet evening\n\n"));
+    }
+
+    #[test]
+    fn test_freewrite_body_round_trip() {
+        let parser = MarkdownParser::new();
+        let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+
+        let content = "# Tasks\nCall mom\n\n# freewrite\n\nCouldn't sleep.\n\n# Not a header\n  indented thought\n\n";
+        let entry = parser.parse(date, content).unwrap();
+        assert_eq!(entry.total_bullets(), 1);
+        assert_eq!(
+            entry.body.as_deref(),
+            Some("Couldn't sleep.\n\n# Not a header\n  indented thought")
+        );
+
+        let serialized = parser.serialize(&entry).unwrap();
+        assert_eq!(
+            serialized,
+            "# Tasks\nCall mom\n\n# Freewrite\n\nCouldn't sleep.\n\n# Not a header\n  indented thought\n"
+        );
+        assert_eq!(parser.parse(date, &serialized).unwrap(), entry);
+
+        let blank = parser.parse(date, "# Freewrite\n\n").unwrap();
+        assert!(blank.body.is_none());
+        assert!(blank.is_empty());
+    }
+
+    #[test]
+    fn test_bullets_starting_with_hash_round_trip() {
+        let parser = MarkdownParser::new();
+        let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let mut entry = Entry::new(date);
+        entry.add_bullet(Bullet::new("#weight:82.5", BulletType::Note));
+        entry.add_bullet(Bullet::new("#gym", BulletType::Note));
+        entry.add_bullet(Bullet::new("#mood 7 after the run", BulletType::Note));
+        entry.add_bullet(Bullet::new("Slept well", BulletType::Note));
+
+        let serialized = parser.serialize(&entry).unwrap();
+        let (parsed, warnings) = parser.parse_with_report(date, &serialized).unwrap();
+        assert!(warnings.is_empty());
+        assert_eq!(parsed, entry);
+        assert_eq!(
+            parsed.get_bullets(&BulletType::Note)[0].metrics(),
+            vec![("weight".to_string(), 82.5)]
+        );
+    }
 }
@@ -2,7 +2,7 @@ use crate::entities::Entry;
 use crate::infrastructure::storage::JournalStorage;
 use crate::infrastructure::{DuckDbStorage, WriteContext, WriteHook};
 use anyhow::{Context, Result};
-use std::path::PathBuf;
+use std::path::Path;
 use std::sync::Arc;
 
 /// Plugin that syncs entry writes to DuckDB storage
@@ -11,8 +11,8 @@ pub struct DuckDbSyncHook {
 }
 
 impl DuckDbSyncHook {
-    pub fn new(journal_dir: &PathBuf) -> Result<Self> {
-        let db_path = journal_dir.join("journal.db");
+    /// Open (or create) the DuckDB database at `db_path`
+    pub fn new(db_path: &Path) -> Result<Self> {
         let storage =
             Arc::new(DuckDbStorage::new(db_path).context("Failed to initialize DuckDB storage")?);
 
@@ -0,0 +1,369 @@
+use crate::entities::{Bullet, Entry};
+use crate::infrastructure::PreWriteHook;
+use anyhow::Result;
+use chrono::{Datelike, NaiveDate};
+use std::str::FromStr;
+
+/// Month names recognized in dates such as "March 15, 2024" or "15 Mar 2024"
+const MONTHS: [&str; 12] = [
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+
+/// One normalization applied to bullet content before it's saved, listed
+/// under `[format] steps` in config.toml
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormatStep {
+    /// Drop leading and trailing whitespace
+    Trim,
+    /// Turn runs of spaces and tabs into a single space
+    CollapseSpaces,
+    /// Uppercase the first letter, unless the first word is a URL or mixes
+    /// cases like "iPhone"
+    Capitalize,
+    /// Rewrite dates like "3/15/2024", "15.03.2024" or "March 15, 2024" as
+    /// 2024-03-15; slashes are read month first unless that's impossible
+    IsoDates,
+    /// Warn about bullets longer than a number of characters, or wrap them
+    /// into sub-bullets
+    MaxLength { limit: usize, wrap: bool },
+}
+
+impl FromStr for FormatStep {
+    type Err = String;
+
+    /// Step names as written in config.toml; `max-length` starts out as a
+    /// 120 character warning, see `[format] max_length` and `overflow`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "trim" => Ok(FormatStep::Trim),
+            "collapse-spaces" => Ok(FormatStep::CollapseSpaces),
+            "capitalize" => Ok(FormatStep::Capitalize),
+            "iso-dates" => Ok(FormatStep::IsoDates),
+            "max-length" => Ok(FormatStep::MaxLength {
+                limit: 120,
+                wrap: false,
+            }),
+            other => Err(format!(
+                "unknown format step '{}' (expected trim, collapse-spaces, capitalize, iso-dates or max-length)",
+                other
+            )),
+        }
+    }
+}
+
+/// Pre-write hook applying one `FormatStep` to every bullet of an entry;
+/// `[format] steps` registers one per step, in the order listed
+pub struct FormatHook {
+    step: FormatStep,
+}
+
+impl FormatHook {
+    pub fn new(step: FormatStep) -> Self {
+        Self { step }
+    }
+
+    /// Apply the step to a single bullet's content, leaving sub-bullets alone.
+    /// Returns a warning for content it can only complain about.
+    fn format_bullet(&self, bullet: &mut Bullet) -> Option<String> {
+        match self.step {
+            FormatStep::Trim => {
+                bullet.content = bullet.content.trim().to_string();
+            }
+            FormatStep::CollapseSpaces => {
+                bullet.content = collapse_spaces(&bullet.content);
+            }
+            FormatStep::Capitalize => {
+                bullet.content = capitalize(&bullet.content);
+            }
+            FormatStep::IsoDates => {
+                bullet.content = iso_dates(&bullet.content);
+            }
+            FormatStep::MaxLength { limit, wrap } => {
+                let length = bullet.content.chars().count();
+                if length <= limit {
+                    return None;
+                }
+                if !wrap {
+                    return Some(format!(
+                        "bullet is {} characters long (limit {}): {}",
+                        length,
+                        limit,
+                        bullet.content.chars().take(40).collect::<String>()
+                    ));
+                }
+                let mut lines = wrap_words(&bullet.content, limit).into_iter();
+                bullet.content = lines.next().unwrap_or_default();
+                let (bullet_type, session) = (bullet.bullet_type, bullet.session);
+                let continuations: Vec<Bullet> = lines
+                    .map(|line| Bullet {
+                        task_state: None,
+                        session,
+                        ..Bullet::new(line, bullet_type)
+                    })
+                    .collect();
+                bullet.children.splice(0..0, continuations);
+            }
+        }
+        None
+    }
+}
+
+impl PreWriteHook for FormatHook {
+    fn before_write(&self, entry: &mut Entry) -> Result<Vec<String>> {
+        let mut warnings = Vec::new();
+        for bullets in entry.bullets.values_mut() {
+            for bullet in bullets {
+                bullet.visit_mut(&mut |bullet| warnings.extend(self.format_bullet(bullet)));
+            }
+        }
+        Ok(warnings)
+    }
+
+    fn name(&self) -> &str {
+        match self.step {
+            FormatStep::Trim => "trim",
+            FormatStep::CollapseSpaces => "collapse-spaces",
+            FormatStep::Capitalize => "capitalize",
+            FormatStep::IsoDates => "iso-dates",
+            FormatStep::MaxLength { .. } => "max-length",
+        }
+    }
+}
+
+fn collapse_spaces(text: &str) -> String {
+    let mut collapsed = String::with_capacity(text.len());
+    let mut in_space = false;
+    for c in text.chars() {
+        if c == ' ' || c == '\t' {
+            if !in_space {
+                collapsed.push(' ');
+            }
+            in_space = true;
+        } else {
+            collapsed.push(c);
+            in_space = false;
+        }
+    }
+    collapsed
+}
+
+fn capitalize(text: &str) -> String {
+    let first_word = text.split_whitespace().next().unwrap_or("");
+    let mixed_case = first_word.chars().skip(1).any(char::is_uppercase);
+    if first_word.contains("://") || mixed_case {
+        return text.to_string();
+    }
+
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) if first.is_lowercase() => first.to_uppercase().chain(chars).collect(),
+        _ => text.to_string(),
+    }
+}
+
+/// Byte ranges of the whitespace-separated words in `text`
+fn word_spans(text: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut start = None;
+    for (i, c) in text.char_indices() {
+        match (c.is_whitespace(), start) {
+            (true, Some(s)) => {
+                spans.push((s, i));
+                start = None;
+            }
+            (false, None) => start = Some(i),
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        spans.push((s, text.len()));
+    }
+    spans
+}
+
+/// A word without the punctuation that may follow it in a sentence
+fn strip_trailing(word: &str) -> &str {
+    word.trim_end_matches([',', '.', ';', ':', ')', '!', '?'])
+}
+
+/// A numeric date such as `3/15/2024`, `15.03.2024` or `2024/03/15`
+fn numeric_date(word: &str) -> Option<NaiveDate> {
+    let formats: &[&str] = if word.contains('/') {
+        &["%Y/%m/%d", "%m/%d/%Y", "%d/%m/%Y"]
+    } else if word.contains('.') {
+        &["%d.%m.%Y"]
+    } else {
+        &[]
+    };
+    // Two-digit years would parse as the first century
+    formats.iter().find_map(|format| {
+        NaiveDate::parse_from_str(word, format)
+            .ok()
+            .filter(|date| date.year() >= 1000)
+    })
+}
+
+fn month_number(word: &str) -> Option<u32> {
+    let word = word.trim_end_matches('.').to_lowercase();
+    if word.len() < 3 {
+        return None;
+    }
+    MONTHS
+        .iter()
+        .position(|month| *month == word || (word.len() == 3 && month.starts_with(&word)))
+        .map(|i| i as u32 + 1)
+}
+
+/// Day of the month, allowing "15th" and a trailing comma
+fn day_number(word: &str) -> Option<u32> {
+    let word = word.trim_end_matches(',');
+    let word = ["st", "nd", "rd", "th"]
+        .iter()
+        .find_map(|suffix| word.strip_suffix(suffix))
+        .unwrap_or(word);
+    word.parse().ok().filter(|day| (1..=31).contains(day))
+}
+
+fn year_number(word: &str) -> Option<i32> {
+    let word = strip_trailing(word);
+    if word.len() == 4 {
+        word.parse().ok()
+    } else {
+        None
+    }
+}
+
+fn iso_dates(text: &str) -> String {
+    let spans = word_spans(text);
+    let word = |i: usize| &text[spans[i].0..spans[i].1];
+    // (first word, last word, date, punctuation to keep after it)
+    let mut replacements: Vec<(usize, usize, NaiveDate, &str)> = Vec::new();
+
+    let mut i = 0;
+    while i < spans.len() {
+        let bare = strip_trailing(word(i));
+        if let Some(date) = numeric_date(bare) {
+            replacements.push((i, i, date, &word(i)[bare.len()..]));
+            i += 1;
+            continue;
+        }
+
+        if i + 2 < spans.len() {
+            let (a, b, c) = (word(i), word(i + 1), word(i + 2));
+            let month_first = month_number(a).zip(day_number(b));
+            let day_first = day_number(a).zip(month_number(b)).map(|(d, m)| (m, d));
+            if let (Some((month, day)), Some(year)) = (month_first.or(day_first), year_number(c))
+                && let Some(date) = NaiveDate::from_ymd_opt(year, month, day)
+            {
+                replacements.push((i, i + 2, date, &c[strip_trailing(c).len()..]));
+                i += 3;
+                continue;
+            }
+        }
+        i += 1;
+    }
+
+    let mut result = text.to_string();
+    for (first, last, date, trailing) in replacements.into_iter().rev() {
+        result.replace_range(
+            spans[first].0..spans[last].1,
+            &format!("{}{}", date.format("%Y-%m-%d"), trailing),
+        );
+    }
+    result
+}
+
+/// Break `text` into lines of at most `limit` characters at word boundaries;
+/// words longer than the limit get a line of their own
+fn wrap_words(text: &str, limit: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > limit {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::BulletType;
+
+    fn format(steps: &[FormatStep], content: &str) -> (Entry, Vec<String>) {
+        let mut entry = Entry::new(NaiveDate::from_ymd_opt(2024, 3, 15).unwrap());
+        entry.add_bullet(Bullet::new(content, BulletType::Note));
+        let mut warnings = Vec::new();
+        for step in steps {
+            warnings.extend(FormatHook::new(*step).before_write(&mut entry).unwrap());
+        }
+        (entry, warnings)
+    }
+
+    #[test]
+    fn test_format_steps() {
+        let (entry, _) = format(
+            &[
+                FormatStep::Trim,
+                FormatStep::CollapseSpaces,
+                FormatStep::Capitalize,
+                FormatStep::IsoDates,
+            ],
+            "  call  the bank about 3/15/2024,   then 15.03.2024 and March 5th, 2024.  ",
+        );
+        assert_eq!(
+            entry.get_bullets(&BulletType::Note)[0].content,
+            "Call the bank about 2024-03-15, then 2024-03-15 and 2024-03-05."
+        );
+
+        assert_eq!(capitalize("iPhone died"), "iPhone died");
+        assert_eq!(capitalize("https://example.com"), "https://example.com");
+        assert_eq!(
+            iso_dates("due 13/2/2024 or 2 Feb 24"),
+            "due 2024-02-13 or 2 Feb 24"
+        );
+        assert_eq!(iso_dates("version 1.2.3"), "version 1.2.3");
+    }
+
+    #[test]
+    fn test_max_length_warns_or_wraps() {
+        let content = "one two three four five six";
+        let warn = FormatStep::MaxLength {
+            limit: 10,
+            wrap: false,
+        };
+        let (entry, warnings) = format(&[warn], content);
+        assert_eq!(entry.get_bullets(&BulletType::Note)[0].content, content);
+        assert_eq!(warnings.len(), 1);
+
+        let wrap = FormatStep::MaxLength {
+            limit: 10,
+            wrap: true,
+        };
+        let (entry, warnings) = format(&[wrap], content);
+        let bullet = &entry.get_bullets(&BulletType::Note)[0];
+        assert!(warnings.is_empty());
+        assert_eq!(bullet.content, "one two");
+        let continued: Vec<&str> = bullet.children.iter().map(|c| c.content.as_str()).collect();
+        assert_eq!(continued, vec!["three four", "five six"]);
+    }
+}
@@ -0,0 +1,96 @@
+use crate::entities::Entry;
+use crate::infrastructure::{EntryRepository, FileSystemRepository, WriteContext, WriteHook};
+use anyhow::{Context, Result};
+
+/// Plugin that writes every saved entry to its markdown file under `data/`,
+/// keeping the files greppable next to the database copy. A file that already
+/// holds the entry is left alone, so an edit synced in from the file (by
+/// `journo watch`) isn't written straight back over the user's formatting.
+pub struct MarkdownMirrorHook {
+    files: FileSystemRepository,
+}
+
+impl MarkdownMirrorHook {
+    pub fn new(files: FileSystemRepository) -> Self {
+        Self { files }
+    }
+}
+
+impl WriteHook for MarkdownMirrorHook {
+    fn on_entry_written(&self, _context: &WriteContext, entry: &Entry) -> Result<()> {
+        // A file that doesn't parse is rewritten from the database copy
+        if let Ok(Some(existing)) = self.files.load(entry.date)
+            && existing == *entry
+        {
+            return Ok(());
+        }
+        self.files
+            .save(entry.clone())
+            .with_context(|| format!("Failed to mirror {} to markdown", entry.date))
+    }
+
+    fn name(&self) -> &str {
+        "markdown-mirror"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{Bullet, BulletType};
+    use chrono::NaiveDate;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_mirror_writes_entry_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let files =
+            || FileSystemRepository::new(temp_dir.path().join("data"), temp_dir.path().into());
+        let hook = MarkdownMirrorHook::new(files());
+
+        let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let mut entry = Entry::new(date);
+        entry.add_bullet(Bullet::new("Ship the release", BulletType::Task));
+        let context = WriteContext {
+            date,
+            entry_path: temp_dir.path().join("journal.db"),
+            journal_dir: temp_dir.path().into(),
+            content: String::new(),
+        };
+        hook.on_entry_written(&context, &entry).unwrap();
+
+        let mirrored = files().load(date).unwrap().unwrap();
+        assert_eq!(
+            mirrored.get_bullets(&BulletType::Task)[0].content,
+            "Ship the release"
+        );
+    }
+
+    #[test]
+    fn test_mirror_keeps_a_file_already_holding_the_entry() {
+        let temp_dir = TempDir::new().unwrap();
+        let files =
+            || FileSystemRepository::new(temp_dir.path().join("data"), temp_dir.path().into());
+        let hook = MarkdownMirrorHook::new(files());
+
+        let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let path = files().entry_path(date);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let edited = "# Tasks\n\n\nShip the release\n";
+        std::fs::write(&path, edited).unwrap();
+        let entry = files().load(date).unwrap().unwrap();
+        let context = WriteContext {
+            date,
+            entry_path: temp_dir.path().join("journal.db"),
+            journal_dir: temp_dir.path().into(),
+            content: String::new(),
+        };
+        hook.on_entry_written(&context, &entry).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), edited);
+
+        let mut changed = entry.clone();
+        changed.add_bullet(Bullet::new("Tag the build", BulletType::Task));
+        hook.on_entry_written(&context, &changed).unwrap();
+        assert_ne!(std::fs::read_to_string(&path).unwrap(), edited);
+    }
+}
@@ -1,5 +1,15 @@
+#[cfg(feature = "duckdb")]
 pub mod duckdb_sync;
+pub mod format;
+pub mod markdown_mirror;
 pub mod simple_logger;
+pub mod snapshot;
+pub mod spellcheck;
 
+#[cfg(feature = "duckdb")]
 pub use duckdb_sync::*;
+pub use format::*;
+pub use markdown_mirror::*;
 pub use simple_logger::*;
+pub use snapshot::*;
+pub use spellcheck::*;
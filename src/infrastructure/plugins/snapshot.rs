@@ -0,0 +1,242 @@
+use crate::entities::Entry;
+use crate::infrastructure::{WriteContext, WriteHook};
+use anyhow::{Context, Result};
+use chrono::{Datelike, Local, NaiveDate, NaiveDateTime};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const SNAPSHOT_FORMAT: &str = "journal-%Y%m%d-%H%M%S.db";
+const WRITE_COUNT_FILE: &str = "write-count";
+
+/// How many snapshots to keep: the newest one of each of the last
+/// `keep_daily` days and of each of the last `keep_weekly` weeks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    pub keep_daily: usize,
+    pub keep_weekly: usize,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            keep_daily: 7,
+            keep_weekly: 4,
+        }
+    }
+}
+
+/// A database copy in the backups directory
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot {
+    pub path: PathBuf,
+    pub taken_at: NaiveDateTime,
+    /// Size in bytes, including the write-ahead log if one was copied
+    pub size: u64,
+}
+
+/// Plugin that copies the database to `backups/` on every Nth write and
+/// prunes old copies according to a retention policy
+pub struct SnapshotHook {
+    db_path: PathBuf,
+    every: u32,
+    retention: RetentionPolicy,
+}
+
+impl SnapshotHook {
+    pub fn new(db_path: impl Into<PathBuf>, every: u32, retention: RetentionPolicy) -> Self {
+        Self {
+            db_path: db_path.into(),
+            every: every.max(1),
+            retention,
+        }
+    }
+
+    /// Bump the persistent write counter, returning whether a snapshot is due
+    fn snapshot_due(&self, backups_dir: &Path) -> Result<bool> {
+        let counter_path = backups_dir.join(WRITE_COUNT_FILE);
+        let count = fs::read_to_string(&counter_path)
+            .ok()
+            .and_then(|s| s.trim().parse::<u32>().ok())
+            .unwrap_or(0)
+            + 1;
+
+        let due = count >= self.every;
+        let next = if due { 0 } else { count };
+        fs::write(&counter_path, next.to_string())
+            .with_context(|| format!("Failed to write {}", counter_path.display()))?;
+        Ok(due)
+    }
+}
+
+impl WriteHook for SnapshotHook {
+    fn on_entry_written(&self, context: &WriteContext, _entry: &Entry) -> Result<()> {
+        let backups_dir = backups_dir(&context.journal_dir);
+        fs::create_dir_all(&backups_dir)
+            .with_context(|| format!("Failed to create {}", backups_dir.display()))?;
+
+        if self.snapshot_due(&backups_dir)? {
+            take_snapshot(&context.journal_dir, &self.db_path)?;
+            prune_snapshots(&context.journal_dir, self.retention, false)?;
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &str {
+        "Snapshot"
+    }
+}
+
+pub fn backups_dir(journal_dir: &Path) -> PathBuf {
+    journal_dir.join("backups")
+}
+
+/// Copy the database at `db_path`, and its write-ahead log if present, into `backups/`
+pub fn take_snapshot(journal_dir: &Path, db_path: &Path) -> Result<PathBuf> {
+    let backups_dir = backups_dir(journal_dir);
+    fs::create_dir_all(&backups_dir)
+        .with_context(|| format!("Failed to create {}", backups_dir.display()))?;
+
+    let snapshot_path = backups_dir.join(Local::now().format(SNAPSHOT_FORMAT).to_string());
+    fs::copy(db_path, &snapshot_path)
+        .with_context(|| format!("Failed to copy {}", db_path.display()))?;
+
+    // Recent writes may still only be in the WAL, which DuckDB replays on open
+    let wal = wal_path(db_path);
+    if wal.exists() {
+        fs::copy(&wal, wal_path(&snapshot_path))
+            .with_context(|| format!("Failed to copy {}", wal.display()))?;
+    }
+
+    Ok(snapshot_path)
+}
+
+/// All snapshots in `backups/`, newest first
+pub fn list_snapshots(journal_dir: &Path) -> Result<Vec<Snapshot>> {
+    let backups_dir = backups_dir(journal_dir);
+    if !backups_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut snapshots = Vec::new();
+    for file in fs::read_dir(&backups_dir)? {
+        let path = file?.path();
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        let Ok(taken_at) = NaiveDateTime::parse_from_str(name, SNAPSHOT_FORMAT) else {
+            continue;
+        };
+        let wal_size = fs::metadata(wal_path(&path)).map_or(0, |m| m.len());
+        let size = fs::metadata(&path)?.len() + wal_size;
+        snapshots.push(Snapshot {
+            path,
+            taken_at,
+            size,
+        });
+    }
+
+    snapshots.sort_by_key(|s| std::cmp::Reverse(s.taken_at));
+    Ok(snapshots)
+}
+
+/// Delete snapshots the retention policy doesn't keep, returning them
+pub fn prune_snapshots(
+    journal_dir: &Path,
+    retention: RetentionPolicy,
+    dry_run: bool,
+) -> Result<Vec<Snapshot>> {
+    let snapshots = list_snapshots(journal_dir)?;
+    let keep = snapshots_to_keep(&snapshots, retention);
+
+    let pruned: Vec<Snapshot> = snapshots
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| !keep.contains(i))
+        .map(|(_, snapshot)| snapshot)
+        .collect();
+
+    if !dry_run {
+        for snapshot in &pruned {
+            fs::remove_file(&snapshot.path)
+                .with_context(|| format!("Failed to remove {}", snapshot.path.display()))?;
+            let wal = wal_path(&snapshot.path);
+            if wal.exists() {
+                fs::remove_file(&wal)
+                    .with_context(|| format!("Failed to remove {}", wal.display()))?;
+            }
+        }
+    }
+
+    Ok(pruned)
+}
+
+/// Indexes of the snapshots (sorted newest first) kept by the policy
+fn snapshots_to_keep(snapshots: &[Snapshot], retention: RetentionPolicy) -> HashSet<usize> {
+    let mut keep = HashSet::new();
+    let mut days: Vec<NaiveDate> = Vec::new();
+    let mut weeks: Vec<(i32, u32)> = Vec::new();
+
+    for (i, snapshot) in snapshots.iter().enumerate() {
+        let day = snapshot.taken_at.date();
+        if !days.contains(&day) && days.len() < retention.keep_daily {
+            days.push(day);
+            keep.insert(i);
+        }
+
+        let week = day.iso_week();
+        let week = (week.year(), week.week());
+        if !weeks.contains(&week) && weeks.len() < retention.keep_weekly {
+            weeks.push(week);
+            keep.insert(i);
+        }
+    }
+
+    keep
+}
+
+fn wal_path(db_path: &Path) -> PathBuf {
+    let mut path = db_path.as_os_str().to_owned();
+    path.push(".wal");
+    PathBuf::from(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retention_keeps_newest_per_day_and_week() {
+        let snapshot = |day: u32, hour: u32| Snapshot {
+            path: PathBuf::new(),
+            taken_at: NaiveDate::from_ymd_opt(2024, 3, day)
+                .unwrap()
+                .and_hms_opt(hour, 0, 0)
+                .unwrap(),
+            size: 0,
+        };
+        // Newest first: two on the 30th, then one per week going back
+        let snapshots = vec![
+            snapshot(30, 18),
+            snapshot(30, 9),
+            snapshot(29, 12),
+            snapshot(20, 12),
+            snapshot(13, 12),
+            snapshot(6, 12),
+            snapshot(1, 12),
+        ];
+
+        let keep = snapshots_to_keep(
+            &snapshots,
+            RetentionPolicy {
+                keep_daily: 2,
+                keep_weekly: 3,
+            },
+        );
+
+        // Daily: 30th (18:00) and 29th; weekly: weeks of the 30th, 20th and 13th
+        let mut keep: Vec<usize> = keep.into_iter().collect();
+        keep.sort();
+        assert_eq!(keep, vec![0, 2, 3, 4]);
+    }
+}
@@ -0,0 +1,195 @@
+use crate::entities::Entry;
+use crate::infrastructure::PreWriteHook;
+use anyhow::{Context, Result};
+use std::collections::{BTreeSet, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Wordlist used when `[spellcheck]` is enabled without naming one
+pub const DEFAULT_WORDLIST: &str = "/usr/share/dict/words";
+
+/// Per-journal words accepted on top of the wordlist, one per line
+pub const CUSTOM_DICTIONARY_FILE: &str = "dictionary.txt";
+
+/// Plugin that checks bullet content against a hunspell-style wordlist before
+/// every save and warns about words it doesn't know. Tags, mentions, URLs,
+/// acronyms and anything with digits are left alone.
+pub struct SpellcheckHook {
+    words: HashSet<String>,
+    /// Journal whose `dictionary.txt` is accepted too
+    journal_dir: Option<PathBuf>,
+}
+
+impl SpellcheckHook {
+    /// Accept exactly the given words, ignoring case
+    pub fn new<I, S>(words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self {
+            words: words
+                .into_iter()
+                .map(|w| w.as_ref().trim().to_lowercase())
+                .filter(|w| !w.is_empty())
+                .collect(),
+            journal_dir: None,
+        }
+    }
+
+    /// Also accept the words of the journal's custom dictionary
+    pub fn with_custom_dictionary(mut self, journal_dir: impl Into<PathBuf>) -> Self {
+        self.journal_dir = Some(journal_dir.into());
+        self
+    }
+
+    /// Read a wordlist with one word per line; hunspell `.dic` files work too,
+    /// as the leading count and `/FLAGS` suffixes are dropped
+    pub fn from_wordlist(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read wordlist {}", path.display()))?;
+        Ok(Self::new(dictionary_words(&content)))
+    }
+
+    /// Words in `entry` found neither in the wordlist nor in `custom`, sorted
+    /// and deduplicated
+    pub fn misspellings(&self, entry: &Entry, custom: &HashSet<String>) -> Vec<String> {
+        let mut unknown = BTreeSet::new();
+        for bullet in entry.all_bullets() {
+            for word in checkable_words(&bullet.content) {
+                let lower = word.to_lowercase();
+                if !self.words.contains(&lower) && !custom.contains(&lower) {
+                    unknown.insert(word.to_string());
+                }
+            }
+        }
+        unknown.into_iter().collect()
+    }
+}
+
+impl PreWriteHook for SpellcheckHook {
+    fn before_write(&self, entry: &mut Entry) -> Result<Vec<String>> {
+        // Re-read on every save so words added while the TUI is open count
+        let custom = match &self.journal_dir {
+            Some(dir) => load_custom_dictionary(dir)?,
+            None => HashSet::new(),
+        };
+        let unknown = self.misspellings(entry, &custom);
+        if unknown.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut warning = format!(
+            "Possible misspellings on {}: {}",
+            entry.date,
+            unknown.join(", ")
+        );
+        if let Some(dir) = &self.journal_dir {
+            warning.push_str(&format!(
+                " (add words to {})",
+                custom_dictionary_path(dir).display()
+            ));
+        }
+        Ok(vec![warning])
+    }
+
+    fn name(&self) -> &str {
+        "Spellcheck"
+    }
+}
+
+pub fn custom_dictionary_path(journal_dir: &Path) -> PathBuf {
+    journal_dir.join(CUSTOM_DICTIONARY_FILE)
+}
+
+/// Lowercased words of the journal's custom dictionary, empty if it doesn't exist
+pub fn load_custom_dictionary(journal_dir: &Path) -> Result<HashSet<String>> {
+    let path = custom_dictionary_path(journal_dir);
+    if !path.exists() {
+        return Ok(HashSet::new());
+    }
+    let content =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    Ok(dictionary_words(&content)
+        .map(|w| w.to_lowercase())
+        .collect())
+}
+
+fn dictionary_words(content: &str) -> impl Iterator<Item = &str> {
+    content
+        .lines()
+        .filter_map(|line| line.split('/').next())
+        .map(str::trim)
+        .filter(|w| !w.is_empty() && !w.starts_with('#') && !w.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Words of bullet content worth looking up
+fn checkable_words(content: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    for token in content.split_whitespace() {
+        let skipped = token.starts_with(['#', '@', '!'])
+            || token.contains("://")
+            || token.contains('@')
+            || token.chars().any(|c| c.is_ascii_digit());
+        if skipped {
+            continue;
+        }
+        for word in token.split(|c: char| !c.is_alphabetic() && c != '\'') {
+            let word = word.trim_matches('\'');
+            let word = word.strip_suffix("'s").unwrap_or(word);
+            let acronym = word.chars().all(|c| c.is_uppercase());
+            if word.chars().count() > 1 && !acronym {
+                words.push(word);
+            }
+        }
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{Bullet, BulletType};
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_misspellings_skip_markup_and_custom_words() {
+        let hook = SpellcheckHook::new(["call", "the", "about", "plan", "read", "don't"]);
+        let mut entry = Entry::new(NaiveDate::from_ymd_opt(2024, 3, 15).unwrap());
+        entry.add_bullet(Bullet::new(
+            "Call @anna about teh #q3-plan, don't forget NASA's 10am https://exmaple.com",
+            BulletType::Task,
+        ));
+        entry.add_bullet(
+            Bullet::new("Read the plan", BulletType::Note).with_child(Bullet::new(
+                "Recieve notes from Kubernetes",
+                BulletType::Note,
+            )),
+        );
+
+        let custom: HashSet<String> = ["kubernetes".to_string()].into();
+        assert_eq!(
+            hook.misspellings(&entry, &custom),
+            vec!["Recieve", "forget", "from", "notes", "teh"]
+        );
+    }
+
+    #[test]
+    fn test_warns_before_write() {
+        let hook = SpellcheckHook::new(["read", "the", "plan"]);
+        let mut entry = Entry::new(NaiveDate::from_ymd_opt(2024, 3, 15).unwrap());
+        entry.add_bullet(Bullet::new("Read the plan", BulletType::Note));
+        assert!(hook.before_write(&mut entry).unwrap().is_empty());
+
+        entry.add_bullet(Bullet::new("Read teh plan", BulletType::Note));
+        assert_eq!(
+            hook.before_write(&mut entry).unwrap(),
+            vec!["Possible misspellings on 2024-03-15: teh"]
+        );
+    }
+
+    #[test]
+    fn test_hunspell_dic_format() {
+        let words: Vec<&str> = dictionary_words("3\nhello/MS\nworld\n\n# comment\n").collect();
+        assert_eq!(words, vec!["hello", "world"]);
+    }
+}
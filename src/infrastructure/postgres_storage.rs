@@ -0,0 +1,1462 @@
+use crate::entities::{
+    Bullet, BulletHit, BulletType, CrossReference, DateRange, Entry, EntryTimestamps,
+    ExternalEvent, Link, MetricValue, MoodCheckIn, MoodPeriod, PendingTask, PeriodStats,
+    PersonSummary, TaskState,
+};
+use crate::infrastructure::Query;
+use crate::infrastructure::migrations::{
+    MIGRATIONS_FINGERPRINT_KEY, MIGRATIONS_TABLE_SQL, Migration, MigrationStatus, SqlDialect,
+    discover_migrations, migrations_fingerprint,
+};
+use crate::infrastructure::repository::EntryRepository;
+use crate::infrastructure::sql::{
+    BUMP_VERSION_SQL, CHANGE_WATERMARK_SQL, FORGET_CREATED_SQL, INSERT_BODY_SQL, TOUCH_ENTRY_SQL,
+    bullet_from_row, convert_bullet_sql, nest_bullets,
+};
+use crate::infrastructure::storage::{JournalStorage, TableFormat, query_by_search};
+use anyhow::{Context, Result};
+use chrono::{Local, NaiveDate, NaiveDateTime, NaiveTime};
+use native_tls::{Certificate, TlsConnector};
+use postgres::types::ToSql;
+use postgres::{Client, GenericClient, Row};
+use postgres_native_tls::MakeTlsConnector;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing::{debug, info};
+
+/// Columns selected for a bullet, in the order `bullet_from_pg_row` reads them
+const BULLET_COLUMNS: &str =
+    "content, type, task_state, CAST(time AS TEXT), CAST(end_time AS TEXT)";
+
+/// Storage on a PostgreSQL server, so one journal can be used from several
+/// machines. Built with the `postgres` feature; connections use TLS whenever
+/// the server offers it (or always with `sslmode=require` in the URL).
+pub struct PostgresStorage {
+    client: Mutex<Client>,
+}
+
+impl PostgresStorage {
+    /// Connect with a libpq-style connection string or `postgres://` URL,
+    /// trusting the system roots plus `ca_cert` (a PEM file) if given
+    pub fn connect(url: &str, ca_cert: Option<&Path>) -> Result<Self> {
+        let client = Self::open_client(url, ca_cert)?;
+        debug!("PostgreSQL connection opened");
+
+        Self::from_client(client)
+    }
+
+    fn open_client(url: &str, ca_cert: Option<&Path>) -> Result<Client> {
+        let mut builder = TlsConnector::builder();
+        if let Some(path) = ca_cert {
+            let pem = fs::read(path)
+                .with_context(|| format!("Failed to read CA certificate: {}", path.display()))?;
+            builder.add_root_certificate(
+                Certificate::from_pem(&pem).context("Invalid CA certificate")?,
+            );
+        }
+        let tls = MakeTlsConnector::new(builder.build().context("Failed to set up TLS")?);
+
+        Client::connect(url, tls).context("Failed to connect to PostgreSQL")
+    }
+
+    fn from_client(client: Client) -> Result<Self> {
+        let storage = Self {
+            client: Mutex::new(client),
+        };
+        storage.initialize()?;
+        info!("PostgreSQL storage initialized successfully");
+        Ok(storage)
+    }
+}
+
+impl JournalStorage for PostgresStorage {
+    fn initialize(&self) -> Result<()> {
+        debug!("Setting up migration system");
+        self.client
+            .lock()
+            .unwrap()
+            .batch_execute(MIGRATIONS_TABLE_SQL)
+            .context("Failed to create migrations table")?;
+        let fingerprint = migrations_fingerprint(SqlDialect::Postgres);
+        if self.recorded_fingerprint()?.as_deref() == Some(fingerprint.as_str()) {
+            debug!("Migrations unchanged since the last run, skipping");
+            return Ok(());
+        }
+        debug!("Running migrations");
+        self.run_migrations()?;
+        self.record_fingerprint(&fingerprint)?;
+        debug!("Storage initialization complete");
+        Ok(())
+    }
+
+    fn backend_info(&self) -> &str {
+        "PostgreSQL Storage Backend v1.0"
+    }
+
+    fn migration_status(&self) -> Result<MigrationStatus> {
+        let available = discover_migrations(SqlDialect::Postgres)?
+            .into_iter()
+            .map(|m| m.version)
+            .collect();
+        let mut applied: Vec<i32> = self.get_applied_migrations()?.into_iter().collect();
+        applied.sort();
+
+        Ok(MigrationStatus { applied, available })
+    }
+
+    fn maintenance(&self) -> Result<()> {
+        debug!("Starting database maintenance operations");
+        let mut client = self.client.lock().unwrap();
+        client
+            .batch_execute("VACUUM ANALYZE;")
+            .context("Failed to perform maintenance operations")?;
+        info!("Database maintenance completed successfully");
+        Ok(())
+    }
+
+    fn load_entry(&self, date: NaiveDate) -> Result<Option<Entry>> {
+        debug!("Loading entry for date: {}", date);
+        let mut client = self.client.lock().unwrap();
+        let rows = client
+            .query(
+                &format!(
+                    "SELECT id, parent_id, {}, session, COALESCE(body, FALSE) FROM bullets WHERE date = $1 ORDER BY id",
+                    BULLET_COLUMNS
+                ),
+                &[&date],
+            )
+            .context("Failed to load bullets")?;
+        if rows.is_empty() {
+            debug!("No bullets found for date: {}", date);
+            return Ok(None);
+        }
+
+        let mut bullet_rows = Vec::new();
+        let mut body = None;
+        for row in &rows {
+            if row.try_get(8)? {
+                body = Some(row.try_get(2)?);
+            } else if let Some(mut bullet) = bullet_from_pg_row(row, 2)? {
+                bullet.session = row.try_get(7)?;
+                bullet_rows.push((row.try_get(0)?, row.try_get(1)?, bullet));
+            }
+        }
+
+        let mut entry = Entry::new(date);
+        entry.body = body;
+        for bullet in nest_bullets(bullet_rows) {
+            entry.add_bullet(bullet);
+        }
+        Ok(Some(entry))
+    }
+
+    fn load_entries(&self, range: DateRange) -> Result<Vec<Entry>> {
+        debug!(
+            "Loading entries for range: {} to {}",
+            range.start(),
+            range.end()
+        );
+        let mut client = self.client.lock().unwrap();
+        let rows = client
+            .query(
+                &format!(
+                    "SELECT date, id, parent_id, {}, session, COALESCE(body, FALSE) FROM bullets WHERE date BETWEEN $1 AND $2 ORDER BY date, id",
+                    BULLET_COLUMNS
+                ),
+                &[&range.start(), &range.end()],
+            )
+            .context("Failed to load bullets")?;
+
+        let mut rows_by_date: HashMap<NaiveDate, Vec<(i64, Option<i64>, Bullet)>> = HashMap::new();
+        let mut bodies: HashMap<NaiveDate, String> = HashMap::new();
+        for row in &rows {
+            if row.try_get(9)? {
+                let date = row.try_get(0)?;
+                bodies.insert(date, row.try_get(3)?);
+                rows_by_date.entry(date).or_default();
+            } else if let Some(mut bullet) = bullet_from_pg_row(row, 3)? {
+                bullet.session = row.try_get(8)?;
+                rows_by_date.entry(row.try_get(0)?).or_default().push((
+                    row.try_get(1)?,
+                    row.try_get(2)?,
+                    bullet,
+                ));
+            }
+        }
+
+        let mut entries: Vec<Entry> = rows_by_date
+            .into_iter()
+            .map(|(date, bullet_rows)| {
+                let mut entry = Entry::new(date);
+                entry.body = bodies.remove(&date);
+                for bullet in nest_bullets(bullet_rows) {
+                    entry.add_bullet(bullet);
+                }
+                entry
+            })
+            .collect();
+        entries.sort_by_key(|e| e.date);
+        Ok(entries)
+    }
+
+    fn list_dates(&self, range: DateRange) -> Result<Vec<NaiveDate>> {
+        let mut client = self.client.lock().unwrap();
+        let rows = client
+            .query(
+                "SELECT DISTINCT date FROM bullets WHERE date BETWEEN $1 AND $2 ORDER BY date",
+                &[&range.start(), &range.end()],
+            )
+            .context("Failed to list dates")?;
+        rows.iter()
+            .map(|row| row.try_get(0).context("Failed to read date"))
+            .collect()
+    }
+
+    fn save_entry(&self, entry: &Entry) -> Result<()> {
+        let mut client = self.client.lock().unwrap();
+        let mut tx = client
+            .transaction()
+            .context("Failed to start transaction")?;
+        write_entry(&mut tx, entry)?;
+        tx.commit().context("Failed to commit entry")
+    }
+
+    fn save_entries(&self, entries: &[Entry]) -> Result<()> {
+        debug!("Saving {} entries in one transaction", entries.len());
+        let mut client = self.client.lock().unwrap();
+        let mut tx = client
+            .transaction()
+            .context("Failed to start transaction")?;
+        for entry in entries {
+            write_entry(&mut tx, entry)?;
+        }
+        tx.commit().context("Failed to commit entries")
+    }
+
+    fn delete_entry(&self, date: NaiveDate) -> Result<()> {
+        debug!("Deleting entry for date: {}", date);
+        let mut client = self.client.lock().unwrap();
+        let mut tx = client
+            .transaction()
+            .context("Failed to start transaction")?;
+        delete_date(&mut tx, date)?;
+        tx.execute(&numbered_placeholders(FORGET_CREATED_SQL), &[&date])
+            .context("Failed to clear entry timestamps")?;
+        tx.commit().context("Failed to commit entry deletion")
+    }
+
+    fn entry_version(&self, date: NaiveDate) -> Result<u64> {
+        let mut client = self.client.lock().unwrap();
+        let version: Option<i64> = client
+            .query_opt(
+                "SELECT version FROM entry_versions WHERE date = $1",
+                &[&date],
+            )
+            .context("Failed to load entry version")?
+            .map(|row| row.try_get(0))
+            .transpose()?;
+        Ok(version.unwrap_or(0) as u64)
+    }
+
+    fn change_watermark(&self) -> Result<u64> {
+        let mut client = self.client.lock().unwrap();
+        let watermark: i64 = client
+            .query_one(CHANGE_WATERMARK_SQL, &[])
+            .context("Failed to load change watermark")?
+            .try_get(0)?;
+        Ok(watermark as u64)
+    }
+
+    fn entry_timestamps(&self, date: NaiveDate) -> Result<Option<EntryTimestamps>> {
+        let mut client = self.client.lock().unwrap();
+        client
+            .query_opt(
+                "SELECT created_at, updated_at FROM entry_versions \
+                 WHERE date = $1 AND created_at IS NOT NULL",
+                &[&date],
+            )
+            .context("Failed to load entry timestamps")?
+            .map(|row| -> Result<EntryTimestamps> {
+                Ok(EntryTimestamps {
+                    created_at: row.try_get(0)?,
+                    updated_at: row.try_get(1)?,
+                })
+            })
+            .transpose()
+    }
+
+    fn search_entries(&self, query: &str) -> Result<Vec<Entry>> {
+        debug!("Searching entries with query: '{}'", query);
+        let dates: Vec<NaiveDate> = {
+            let mut client = self.client.lock().unwrap();
+            client
+                .query(
+                    "SELECT DISTINCT date FROM bullets WHERE content ILIKE $1 ORDER BY date DESC",
+                    &[&format!("%{}%", query)],
+                )
+                .context("Failed to search entries")?
+                .iter()
+                .map(|row| row.try_get(0))
+                .collect::<Result<_, _>>()?
+        };
+
+        let mut entries = Vec::new();
+        for date in dates {
+            if let Some(entry) = self.load_entry(date)? {
+                entries.push(entry);
+            }
+        }
+        Ok(entries)
+    }
+
+    fn search_bullets(
+        &self,
+        query: &str,
+        types: &[BulletType],
+        states: &[TaskState],
+        range: Option<DateRange>,
+    ) -> Result<Vec<BulletHit>> {
+        debug!(
+            "Searching bullets for '{}' (types: {:?}, states: {:?}, range: {:?})",
+            query, types, states, range
+        );
+        let types: Vec<String> = types.iter().map(|t| t.to_string()).collect();
+        let states: Vec<String> = states.iter().map(|s| s.to_string()).collect();
+        let pattern = format!("%{}%", query);
+
+        let mut sql = format!(
+            "SELECT date, {} FROM bullets WHERE content ILIKE $1 AND NOT COALESCE(body, FALSE)",
+            BULLET_COLUMNS
+        );
+        let mut values: Vec<&(dyn ToSql + Sync)> = vec![&pattern];
+        if !types.is_empty() {
+            values.push(&types);
+            sql.push_str(&format!(" AND type = ANY(${})", values.len()));
+        }
+        if !states.is_empty() {
+            values.push(&states);
+            sql.push_str(&format!(" AND task_state = ANY(${})", values.len()));
+        }
+        let bounds = range.map(|r| (r.start(), r.end()));
+        if let Some((start, end)) = &bounds {
+            values.push(start);
+            values.push(end);
+            sql.push_str(&format!(
+                " AND date BETWEEN ${} AND ${}",
+                values.len() - 1,
+                values.len()
+            ));
+        }
+        sql.push_str(" ORDER BY date, id");
+
+        let mut client = self.client.lock().unwrap();
+        let rows = client
+            .query(&sql, &values)
+            .context("Failed to search bullets")?;
+        hits_from_rows(&rows)
+    }
+
+    fn query_bullets(&self, query: &Query) -> Result<Vec<BulletHit>> {
+        query_by_search(self, query)
+    }
+
+    fn bullet_ids(&self, date: NaiveDate, bullet_type: &BulletType) -> Result<Vec<i64>> {
+        let mut client = self.client.lock().unwrap();
+        client
+            .query(
+                "SELECT id FROM bullets WHERE date = $1 AND type = $2 AND NOT COALESCE(body, FALSE) ORDER BY id",
+                &[&date, &bullet_type.to_string()],
+            )
+            .context("Failed to query bullet ids")?
+            .iter()
+            .map(|row| row.try_get(0).context("Failed to read bullet id"))
+            .collect()
+    }
+
+    fn load_bullet(&self, id: i64) -> Result<Option<(NaiveDate, Bullet)>> {
+        let mut client = self.client.lock().unwrap();
+        let Some(row) = client
+            .query_opt(
+                &format!(
+                    "SELECT date, {} FROM bullets WHERE id = $1 AND NOT COALESCE(body, FALSE)",
+                    BULLET_COLUMNS
+                ),
+                &[&id],
+            )
+            .context("Failed to query bullet")?
+        else {
+            return Ok(None);
+        };
+        let date: NaiveDate = row.try_get(0)?;
+        Ok(bullet_from_pg_row(&row, 1)?.map(|b| (date, b)))
+    }
+
+    fn convert_bullet(&self, id: i64, bullet_type: BulletType) -> Result<()> {
+        debug!("Converting bullet {} to {}", id, bullet_type);
+        let mut client = self.client.lock().unwrap();
+        let mut tx = client
+            .transaction()
+            .context("Failed to start transaction")?;
+        let date =
+            bullet_date(&mut tx, id)?.with_context(|| format!("No bullet with id {}", id))?;
+        tx.execute(
+            &numbered_placeholders(&convert_bullet_sql(bullet_type)),
+            &[&bullet_type.to_string(), &id],
+        )
+        .context("Failed to convert bullet")?;
+        touch_entry(&mut tx, date)?;
+        tx.commit().context("Failed to commit bullet conversion")
+    }
+
+    fn set_task_states(&self, ids: &[i64], task_state: TaskState) -> Result<()> {
+        debug!("Setting {} tasks to {}", ids.len(), task_state);
+        let mut client = self.client.lock().unwrap();
+        let mut tx = client
+            .transaction()
+            .context("Failed to start transaction")?;
+        let state = task_state.to_string();
+        let mut dates = BTreeSet::new();
+        for id in ids {
+            let changed = tx
+                .execute(
+                    "UPDATE bullets SET task_state = $1, updated_at = CURRENT_TIMESTAMP WHERE id = $2 AND type IN ('task', 'priority')",
+                    &[&state, id],
+                )
+                .context("Failed to update task state")?;
+            if changed == 0 {
+                anyhow::bail!("No task with id {}", id);
+            }
+            dates.extend(bullet_date(&mut tx, *id)?);
+        }
+        for date in dates {
+            touch_entry(&mut tx, date)?;
+        }
+        tx.commit().context("Failed to commit task states")
+    }
+
+    fn pending_tasks_before(&self, before: NaiveDate) -> Result<Vec<BulletHit>> {
+        let mut client = self.client.lock().unwrap();
+        let rows = client
+            .query(
+                &format!(
+                    "SELECT date, {} FROM bullets WHERE task_state = 'pending' AND date < $1 AND type IN ('task', 'priority') AND NOT COALESCE(body, FALSE) ORDER BY date, id",
+                    BULLET_COLUMNS
+                ),
+                &[&before],
+            )
+            .context("Failed to query pending tasks")?;
+
+        let mut tasks = Vec::new();
+        for row in &rows {
+            let date: NaiveDate = row.try_get(0)?;
+            if let Some(bullet) = bullet_from_pg_row(row, 1)? {
+                tasks.push(BulletHit { date, bullet });
+            }
+        }
+        Ok(tasks)
+    }
+
+    fn pending_tasks(&self) -> Result<Vec<PendingTask>> {
+        let mut client = self.client.lock().unwrap();
+        let rows = client
+            .query(
+                &format!(
+                    "SELECT id, date, {} FROM bullets WHERE task_state = 'pending' AND type IN ('task', 'priority') AND NOT COALESCE(body, FALSE) ORDER BY date, id",
+                    BULLET_COLUMNS
+                ),
+                &[],
+            )
+            .context("Failed to query pending tasks")?;
+
+        let mut tasks = Vec::new();
+        for row in &rows {
+            if let Some(bullet) = bullet_from_pg_row(row, 2)? {
+                tasks.push(PendingTask {
+                    id: Some(row.try_get(0)?),
+                    date: row.try_get(1)?,
+                    bullet,
+                });
+            }
+        }
+        Ok(tasks)
+    }
+
+    fn task_dependencies(&self, date: NaiveDate) -> Result<Vec<(i64, String, String)>> {
+        let mut client = self.client.lock().unwrap();
+        client
+            .query(
+                "SELECT d.bullet_id, b.content, d.blocker FROM task_dependencies d JOIN bullets b ON b.id = d.bullet_id WHERE d.date = $1 ORDER BY d.bullet_id",
+                &[&date],
+            )
+            .context("Failed to query task dependencies")?
+            .iter()
+            .map(|row| Ok((row.try_get(0)?, row.try_get(1)?, row.try_get(2)?)))
+            .collect()
+    }
+
+    fn update_bullet(&self, id: i64, content: &str, task_state: Option<TaskState>) -> Result<()> {
+        debug!("Updating bullet {} (state: {:?})", id, task_state);
+        let mut client = self.client.lock().unwrap();
+        let mut tx = client
+            .transaction()
+            .context("Failed to start transaction")?;
+
+        let date =
+            bullet_date(&mut tx, id)?.with_context(|| format!("No bullet with id {}", id))?;
+        tx.execute(
+            "UPDATE bullets SET content = $1, task_state = $2, updated_at = CURRENT_TIMESTAMP WHERE id = $3 AND NOT COALESCE(body, FALSE)",
+            &[&content, &task_state.map(|s| s.to_string()), &id],
+        )
+        .context("Failed to update bullet")?;
+        touch_entry(&mut tx, date)?;
+
+        let link_titles: HashMap<String, String> = tx
+            .query(
+                "SELECT url, title FROM links WHERE bullet_id = $1 AND title IS NOT NULL",
+                &[&id],
+            )
+            .context("Failed to load link titles")?
+            .iter()
+            .map(|row| -> Result<(String, String)> { Ok((row.try_get(0)?, row.try_get(1)?)) })
+            .collect::<Result<_>>()?;
+        for table in [
+            "mentions",
+            "contexts",
+            "metrics",
+            "task_dependencies",
+            "links",
+        ] {
+            tx.execute(
+                &format!("DELETE FROM {} WHERE bullet_id = $1", table),
+                &[&id],
+            )
+            .with_context(|| format!("Failed to clear {} of bullet", table))?;
+        }
+
+        // Derived rows depend only on the content, so the type doesn't matter
+        let bullet = Bullet::new(content, BulletType::Note);
+        insert_derived_rows(&mut tx, id, date, &bullet, &link_titles)?;
+
+        tx.commit().context("Failed to commit bullet update")
+    }
+
+    fn list_people(&self) -> Result<Vec<PersonSummary>> {
+        let mut client = self.client.lock().unwrap();
+        client
+            .query(
+                "SELECT name, COUNT(DISTINCT bullet_id), MAX(date)
+                 FROM mentions GROUP BY name ORDER BY COUNT(DISTINCT bullet_id) DESC, name",
+                &[],
+            )
+            .context("Failed to query people")?
+            .iter()
+            .map(|row| -> Result<PersonSummary> {
+                Ok(PersonSummary {
+                    name: row.try_get(0)?,
+                    mentions: row.try_get::<_, i64>(1)? as u64,
+                    last_seen: row.try_get(2)?,
+                })
+            })
+            .collect()
+    }
+
+    fn list_contexts(&self) -> Result<Vec<String>> {
+        let mut client = self.client.lock().unwrap();
+        client
+            .query(
+                "SELECT name FROM contexts GROUP BY name ORDER BY COUNT(*) DESC, name",
+                &[],
+            )
+            .context("Failed to query contexts")?
+            .iter()
+            .map(|row| row.try_get(0).context("Failed to read context"))
+            .collect()
+    }
+
+    fn list_metrics(&self) -> Result<Vec<String>> {
+        let mut client = self.client.lock().unwrap();
+        client
+            .query(
+                "SELECT name FROM metrics GROUP BY name ORDER BY COUNT(*) DESC, name",
+                &[],
+            )
+            .context("Failed to query metrics")?
+            .iter()
+            .map(|row| row.try_get(0).context("Failed to read metric"))
+            .collect()
+    }
+
+    fn metric_values(&self, name: &str, range: Option<DateRange>) -> Result<Vec<MetricValue>> {
+        let mut client = self.client.lock().unwrap();
+        let name = name.trim_start_matches('#').to_lowercase();
+        let sql = "SELECT date, value FROM metrics WHERE name = $1";
+        let rows = match range {
+            Some(range) => client.query(
+                &format!(
+                    "{} AND date BETWEEN $2 AND $3 ORDER BY date, bullet_id",
+                    sql
+                ),
+                &[&name, &range.start(), &range.end()],
+            ),
+            None => client.query(&format!("{} ORDER BY date, bullet_id", sql), &[&name]),
+        }
+        .context("Failed to query metric")?;
+
+        rows.iter()
+            .map(|row| -> Result<MetricValue> {
+                Ok(MetricValue {
+                    date: row.try_get(0)?,
+                    value: row.try_get(1)?,
+                })
+            })
+            .collect()
+    }
+
+    fn find_bullets_mentioning(&self, name: &str) -> Result<Vec<BulletHit>> {
+        let mut client = self.client.lock().unwrap();
+        let rows = client
+            .query(
+                "SELECT b.date, b.content, b.type, b.task_state, CAST(b.time AS TEXT), CAST(b.end_time AS TEXT)
+                 FROM bullets b JOIN mentions m ON m.bullet_id = b.id
+                 WHERE m.name = $1 ORDER BY b.date, b.id",
+                &[&name.to_lowercase()],
+            )
+            .context("Failed to query mentions")?;
+        hits_from_rows(&rows)
+    }
+
+    fn list_links(&self, range: Option<DateRange>) -> Result<Vec<Link>> {
+        let mut client = self.client.lock().unwrap();
+        let sql = "SELECT l.date, l.url, l.title, b.content
+             FROM links l JOIN bullets b ON b.id = l.bullet_id";
+        let rows = match range {
+            Some(range) => client.query(
+                &format!(
+                    "{} WHERE l.date BETWEEN $1 AND $2 ORDER BY l.date, b.id",
+                    sql
+                ),
+                &[&range.start(), &range.end()],
+            ),
+            None => client.query(&format!("{} ORDER BY l.date, b.id", sql), &[]),
+        }
+        .context("Failed to query links")?;
+
+        rows.iter()
+            .map(|row| -> Result<Link> {
+                Ok(Link {
+                    date: row.try_get(0)?,
+                    url: row.try_get(1)?,
+                    title: row.try_get(2)?,
+                    content: row.try_get(3)?,
+                })
+            })
+            .collect()
+    }
+
+    fn set_link_title(&self, url: &str, title: &str) -> Result<()> {
+        let mut client = self.client.lock().unwrap();
+        client
+            .execute(
+                "UPDATE links SET title = $1 WHERE url = $2",
+                &[&title, &url],
+            )
+            .context("Failed to update link title")?;
+        Ok(())
+    }
+
+    fn daily_bullet_counts(&self, range: DateRange) -> Result<HashMap<NaiveDate, u64>> {
+        let mut client = self.client.lock().unwrap();
+        client
+            .query(
+                "SELECT date, COUNT(*) FROM bullets WHERE date BETWEEN $1 AND $2 AND NOT COALESCE(body, FALSE) GROUP BY date",
+                &[&range.start(), &range.end()],
+            )
+            .context("Failed to query daily counts")?
+            .iter()
+            .map(|row| -> Result<(NaiveDate, u64)> {
+                Ok((row.try_get(0)?, row.try_get::<_, i64>(1)? as u64))
+            })
+            .collect()
+    }
+
+    fn period_stats(&self, range: DateRange) -> Result<PeriodStats> {
+        let mut client = self.client.lock().unwrap();
+        let row = client
+            .query_one(
+                r"SELECT
+                    COUNT(DISTINCT date),
+                    COUNT(*) FILTER (WHERE type IN ('task', 'priority') AND task_state = 'completed'),
+                    COUNT(*) FILTER (WHERE type IN ('task', 'priority') AND task_state = 'pending'),
+                    COUNT(*) FILTER (WHERE type = 'event'),
+                    CAST(COALESCE(SUM(array_length(regexp_split_to_array(btrim(content), '\s+'), 1))
+                        FILTER (WHERE btrim(content) <> ''), 0) AS BIGINT)
+                 FROM bullets WHERE date BETWEEN $1 AND $2",
+                &[&range.start(), &range.end()],
+            )
+            .context("Failed to compute period stats")?;
+
+        let count = |i: usize| -> Result<u64> { Ok(row.try_get::<_, i64>(i)? as u64) };
+        Ok(PeriodStats {
+            days_with_entries: count(0)?,
+            tasks_completed: count(1)?,
+            tasks_pending: count(2)?,
+            events: count(3)?,
+            words: count(4)?,
+        })
+    }
+
+    fn count_entries(&self) -> Result<u64> {
+        let mut client = self.client.lock().unwrap();
+        let count: i64 = client
+            .query_one("SELECT COUNT(DISTINCT date) FROM bullets", &[])?
+            .try_get(0)?;
+        Ok(count as u64)
+    }
+
+    fn entry_date_span(&self) -> Result<Option<(NaiveDate, NaiveDate)>> {
+        let mut client = self.client.lock().unwrap();
+        let row = client.query_one("SELECT MIN(date), MAX(date) FROM bullets", &[])?;
+        let first: Option<NaiveDate> = row.try_get(0)?;
+        let last: Option<NaiveDate> = row.try_get(1)?;
+        Ok(first.zip(last))
+    }
+
+    fn adjacent_entry_date(&self, date: NaiveDate, forward: bool) -> Result<Option<NaiveDate>> {
+        let sql = if forward {
+            "SELECT MIN(date) FROM bullets WHERE date > $1"
+        } else {
+            "SELECT MAX(date) FROM bullets WHERE date < $1"
+        };
+        let mut client = self.client.lock().unwrap();
+        let row = client.query_one(sql, &[&date])?;
+        Ok(row.try_get(0)?)
+    }
+
+    fn find_entries_with_tasks(&self, range: DateRange) -> Result<Vec<Entry>> {
+        self.find_entries_by_type("task", range)
+    }
+
+    fn find_entries_with_events(&self, range: DateRange) -> Result<Vec<Entry>> {
+        self.find_entries_by_type("event", range)
+    }
+
+    fn find_entries_with_priorities(&self, range: DateRange) -> Result<Vec<Entry>> {
+        self.find_entries_by_type("priority", range)
+    }
+
+    fn refresh_metadata(&self, _date: NaiveDate, _entry: &Entry) -> Result<()> {
+        Ok(())
+    }
+
+    fn record_entry_save(
+        &self,
+        date: NaiveDate,
+        saved_at: NaiveDateTime,
+        word_count: usize,
+        word_delta: i64,
+    ) -> Result<()> {
+        let mut client = self.client.lock().unwrap();
+        client
+            .execute(
+                "INSERT INTO entry_saves (date, saved_at, word_count, word_delta) VALUES ($1, $2, $3, $4)",
+                &[&date, &saved_at, &(word_count as i64), &word_delta],
+            )
+            .context("Failed to record entry save")?;
+        Ok(())
+    }
+
+    fn save_counts_by_hour(&self) -> Result<Vec<(u32, u64)>> {
+        let mut client = self.client.lock().unwrap();
+        client
+            .query(
+                "SELECT CAST(EXTRACT(HOUR FROM saved_at) AS INTEGER) AS h, COUNT(*)
+                 FROM entry_saves GROUP BY h ORDER BY h",
+                &[],
+            )
+            .context("Failed to query save hours")?
+            .iter()
+            .map(|row| -> Result<(u32, u64)> {
+                Ok((
+                    row.try_get::<_, i32>(0)? as u32,
+                    row.try_get::<_, i64>(1)? as u64,
+                ))
+            })
+            .collect()
+    }
+
+    fn record_focus_session(
+        &self,
+        date: NaiveDate,
+        task: &str,
+        started_at: NaiveDateTime,
+        duration_seconds: u64,
+        completed: bool,
+    ) -> Result<()> {
+        let mut client = self.client.lock().unwrap();
+        client
+            .execute(
+                "INSERT INTO focus_sessions (date, task, started_at, duration_seconds, completed) VALUES ($1, $2, $3, $4, $5)",
+                &[&date, &task, &started_at, &(duration_seconds as i64), &completed],
+            )
+            .context("Failed to record focus session")?;
+        Ok(())
+    }
+
+    fn focus_time_by_task(&self, range: DateRange) -> Result<Vec<(String, u64)>> {
+        let mut client = self.client.lock().unwrap();
+        client
+            .query(
+                "SELECT task, CAST(SUM(duration_seconds) AS BIGINT) AS total FROM focus_sessions
+                 WHERE date BETWEEN $1 AND $2 GROUP BY task ORDER BY total DESC, task",
+                &[&range.start(), &range.end()],
+            )
+            .context("Failed to query focus time")?
+            .iter()
+            .map(|row| -> Result<(String, u64)> {
+                Ok((row.try_get(0)?, row.try_get::<_, i64>(1)? as u64))
+            })
+            .collect()
+    }
+
+    fn replace_external_events(&self, source: &str, events: &[ExternalEvent]) -> Result<()> {
+        debug!("Replacing {} external events from {}", events.len(), source);
+        let mut client = self.client.lock().unwrap();
+        let mut tx = client
+            .transaction()
+            .context("Failed to start transaction")?;
+
+        tx.execute("DELETE FROM external_events WHERE source = $1", &[&source])
+            .context("Failed to delete previous external events")?;
+        let insert = tx
+            .prepare(
+                "INSERT INTO external_events (source, uid, date, start_time, end_time, summary, location)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7)",
+            )
+            .context("Failed to prepare external event insert")?;
+        for event in events {
+            tx.execute(
+                &insert,
+                &[
+                    &source,
+                    &event.uid,
+                    &event.date,
+                    &event.start,
+                    &event.end,
+                    &event.summary,
+                    &event.location,
+                ],
+            )
+            .context("Failed to insert external event")?;
+        }
+
+        tx.commit().context("Failed to commit external events")
+    }
+
+    fn load_external_events(&self, date: NaiveDate) -> Result<Vec<ExternalEvent>> {
+        let mut client = self.client.lock().unwrap();
+        client
+            .query(
+                "SELECT uid, start_time, end_time, summary, location
+                 FROM external_events WHERE date = $1
+                 ORDER BY start_time NULLS FIRST, summary",
+                &[&date],
+            )
+            .context("Failed to query external events")?
+            .iter()
+            .map(|row| -> Result<ExternalEvent> {
+                Ok(ExternalEvent {
+                    uid: row.try_get(0)?,
+                    date,
+                    start: row.try_get::<_, Option<NaiveTime>>(1)?,
+                    end: row.try_get::<_, Option<NaiveTime>>(2)?,
+                    summary: row.try_get(3)?,
+                    location: row.try_get(4)?,
+                })
+            })
+            .collect()
+    }
+
+    fn set_pinned(&self, date: NaiveDate, pinned: bool) -> Result<()> {
+        let mut client = self.client.lock().unwrap();
+        let sql = if pinned {
+            "INSERT INTO pins (date) VALUES ($1) ON CONFLICT (date) DO NOTHING"
+        } else {
+            "DELETE FROM pins WHERE date = $1"
+        };
+        client
+            .execute(sql, &[&date])
+            .context("Failed to update pin")?;
+        Ok(())
+    }
+
+    fn pinned_dates(&self) -> Result<Vec<NaiveDate>> {
+        let mut client = self.client.lock().unwrap();
+        client
+            .query("SELECT date FROM pins ORDER BY date", &[])
+            .context("Failed to query pins")?
+            .iter()
+            .map(|row| row.try_get(0).context("Failed to read date"))
+            .collect()
+    }
+
+    fn record_mood(&self, date: NaiveDate, period: MoodPeriod, score: u8) -> Result<()> {
+        debug!("Recording {} mood {} for date: {}", period, score, date);
+        let mut client = self.client.lock().unwrap();
+        client
+            .execute(
+                "INSERT INTO moods (date, period, score) VALUES ($1, $2, $3) \
+                 ON CONFLICT (date, period) DO UPDATE SET score = EXCLUDED.score, recorded_at = EXCLUDED.recorded_at",
+                &[&date, &period.to_string(), &(score as i32)],
+            )
+            .context("Failed to record mood")?;
+        Ok(())
+    }
+
+    fn load_moods(&self, range: DateRange) -> Result<Vec<MoodCheckIn>> {
+        let mut client = self.client.lock().unwrap();
+        let rows = client
+            .query(
+                "SELECT date, period, score FROM moods WHERE date BETWEEN $1 AND $2 \
+                 ORDER BY date, period DESC",
+                &[&range.start(), &range.end()],
+            )
+            .context("Failed to query moods")?;
+
+        let mut moods = Vec::new();
+        for row in rows {
+            let period: String = row.try_get(1)?;
+            let score: i32 = row.try_get(2)?;
+            let Ok(period) = period.parse() else {
+                continue;
+            };
+            moods.push(MoodCheckIn {
+                date: row.try_get(0)?,
+                period,
+                score: score as u8,
+            });
+        }
+        Ok(moods)
+    }
+
+    fn record_cross_reference(&self, reference: &CrossReference) -> Result<()> {
+        debug!(
+            "Recording follow-up on {} of {}",
+            reference.date, reference.target_date
+        );
+        let mut client = self.client.lock().unwrap();
+        client
+            .execute(
+                "INSERT INTO cross_references (date, content, target_date, target_content) \
+                 VALUES ($1, $2, $3, $4) \
+                 ON CONFLICT (date, content) DO UPDATE SET target_date = EXCLUDED.target_date, \
+                 target_content = EXCLUDED.target_content, recorded_at = EXCLUDED.recorded_at",
+                &[
+                    &reference.date,
+                    &reference.content,
+                    &reference.target_date,
+                    &reference.target_content,
+                ],
+            )
+            .context("Failed to record cross reference")?;
+        Ok(())
+    }
+
+    fn load_cross_references(&self, date: NaiveDate) -> Result<Vec<CrossReference>> {
+        let mut client = self.client.lock().unwrap();
+        let rows = client
+            .query(
+                "SELECT content, target_date, target_content FROM cross_references \
+                 WHERE date = $1 ORDER BY content",
+                &[&date],
+            )
+            .context("Failed to query cross references")?;
+
+        let mut references = Vec::new();
+        for row in rows {
+            references.push(CrossReference {
+                date,
+                content: row.try_get(0)?,
+                target_date: row.try_get(1)?,
+                target_content: row.try_get(2)?,
+            });
+        }
+        Ok(references)
+    }
+
+    fn record_prompt_shown(&self, prompt: &str, date: NaiveDate) -> Result<()> {
+        let mut client = self.client.lock().unwrap();
+        client
+            .execute(
+                "INSERT INTO prompt_history (prompt, shown_on) VALUES ($1, $2) \
+                 ON CONFLICT (prompt, shown_on) DO NOTHING",
+                &[&prompt, &date],
+            )
+            .context("Failed to record prompt")?;
+        Ok(())
+    }
+
+    fn prompts_shown_since(&self, since: NaiveDate) -> Result<Vec<String>> {
+        let mut client = self.client.lock().unwrap();
+        client
+            .query(
+                "SELECT DISTINCT prompt FROM prompt_history WHERE shown_on >= $1",
+                &[&since],
+            )
+            .context("Failed to query prompt history")?
+            .iter()
+            .map(|row| row.try_get(0).context("Failed to read prompt"))
+            .collect()
+    }
+
+    fn record_migration_review(&self, week_start: NaiveDate) -> Result<bool> {
+        let mut client = self.client.lock().unwrap();
+        let inserted = client
+            .execute(
+                "INSERT INTO migration_reviews (week_start) VALUES ($1) \
+                 ON CONFLICT (week_start) DO NOTHING",
+                &[&week_start],
+            )
+            .context("Failed to record migration review")?;
+        Ok(inserted > 0)
+    }
+
+    fn export_tables(
+        &self,
+        _dir: &Path,
+        format: TableFormat,
+        _range: Option<DateRange>,
+    ) -> Result<Vec<PathBuf>> {
+        anyhow::bail!(
+            "{} exports of the raw tables need storage_backend = \"duckdb\"",
+            format
+        )
+    }
+}
+
+impl PostgresStorage {
+    fn run_migrations(&self) -> Result<()> {
+        let applied = self.get_applied_migrations()?;
+        let mut client = self.client.lock().unwrap();
+
+        for Migration { version, name, sql } in discover_migrations(SqlDialect::Postgres)? {
+            if applied.contains(&version) {
+                debug!("Migration {} already applied, skipping", version);
+                continue;
+            }
+
+            info!("Applying migration {}: {}", version, name);
+            // DDL is transactional in PostgreSQL, so a failed migration leaves no trace
+            let mut tx = client.transaction()?;
+            tx.batch_execute(&sql)
+                .with_context(|| format!("Failed to apply migration {}: {}", version, name))?;
+            tx.execute(
+                "INSERT INTO migrations (version, name) VALUES ($1, $2)",
+                &[&version, &name],
+            )
+            .with_context(|| format!("Failed to record migration {} as applied", name))?;
+            tx.commit()?;
+        }
+
+        info!("All migrations completed successfully");
+        Ok(())
+    }
+
+    /// Fingerprint stored by the last complete migration run, see
+    /// `migrations_fingerprint`
+    fn recorded_fingerprint(&self) -> Result<Option<String>> {
+        let mut client = self.client.lock().unwrap();
+        let row = client
+            .query_opt(
+                "SELECT value FROM schema_state WHERE key = $1",
+                &[&MIGRATIONS_FINGERPRINT_KEY],
+            )
+            .context("Failed to read schema state")?;
+        row.map(|row| row.try_get(0).context("Failed to read schema state"))
+            .transpose()
+    }
+
+    fn record_fingerprint(&self, fingerprint: &str) -> Result<()> {
+        let mut client = self.client.lock().unwrap();
+        client
+            .execute(
+                "INSERT INTO schema_state (key, value) VALUES ($1, $2) \
+                 ON CONFLICT (key) DO UPDATE SET value = excluded.value",
+                &[&MIGRATIONS_FINGERPRINT_KEY, &fingerprint],
+            )
+            .context("Failed to record schema state")?;
+        Ok(())
+    }
+
+    fn get_applied_migrations(&self) -> Result<HashSet<i32>> {
+        let mut client = self.client.lock().unwrap();
+        client
+            .query("SELECT version FROM migrations", &[])
+            .context("Failed to query applied migrations")?
+            .iter()
+            .map(|row| row.try_get(0).context("Failed to read migration version"))
+            .collect()
+    }
+
+    fn find_entries_by_type(&self, bullet_type: &str, range: DateRange) -> Result<Vec<Entry>> {
+        let dates: Vec<NaiveDate> = {
+            let mut client = self.client.lock().unwrap();
+            client
+                .query(
+                    "SELECT DISTINCT date FROM bullets WHERE type = $1 AND date BETWEEN $2 AND $3 AND NOT COALESCE(body, FALSE) ORDER BY date",
+                    &[&bullet_type, &range.start(), &range.end()],
+                )
+                .context("Failed to prepare type-based query")?
+                .iter()
+                .map(|row| row.try_get(0))
+                .collect::<Result<_, _>>()?
+        };
+
+        let mut entries = Vec::new();
+        for date in dates {
+            if let Some(entry) = self.load_entry(date)? {
+                entries.push(entry);
+            }
+        }
+        Ok(entries)
+    }
+}
+
+/// Bullet from the `BULLET_COLUMNS` of a row, starting at column `first`
+fn bullet_from_pg_row(row: &Row, first: usize) -> Result<Option<Bullet>> {
+    let type_str: String = row.try_get(first + 1)?;
+    Ok(bullet_from_row(
+        row.try_get(first)?,
+        &type_str,
+        row.try_get(first + 2)?,
+        row.try_get(first + 3)?,
+        row.try_get(first + 4)?,
+    ))
+}
+
+/// Search hits from rows of a date followed by `BULLET_COLUMNS`
+fn hits_from_rows(rows: &[Row]) -> Result<Vec<BulletHit>> {
+    let mut hits = Vec::new();
+    for row in rows {
+        if let Some(bullet) = bullet_from_pg_row(row, 1)? {
+            hits.push(BulletHit {
+                date: row.try_get(0)?,
+                bullet,
+            });
+        }
+    }
+    Ok(hits)
+}
+
+/// Turn the `?` placeholders of statements shared with the other backends
+/// into Postgres' `$1, $2, ...`
+fn numbered_placeholders(sql: &str) -> String {
+    let mut numbered = String::with_capacity(sql.len());
+    let mut n = 0;
+    for c in sql.chars() {
+        if c == '?' {
+            n += 1;
+            numbered.push_str(&format!("${}", n));
+        } else {
+            numbered.push(c);
+        }
+    }
+    numbered
+}
+
+/// Replace the bullets stored for `entry`'s date, along with everything
+/// derived from them
+fn write_entry(tx: &mut impl GenericClient, entry: &Entry) -> Result<()> {
+    debug!(
+        "Saving entry for date: {} with {} total bullets",
+        entry.date,
+        entry.total_bullets()
+    );
+    let date = entry.date;
+
+    // Keep titles fetched for links that survive the edit
+    let link_titles: HashMap<String, String> = tx
+        .query(
+            "SELECT url, title FROM links WHERE date = $1 AND title IS NOT NULL",
+            &[&date],
+        )
+        .context("Failed to load link titles")?
+        .iter()
+        .map(|row| -> Result<(String, String)> { Ok((row.try_get(0)?, row.try_get(1)?)) })
+        .collect::<Result<_>>()?;
+
+    delete_date(tx, date)?;
+    let now = Local::now().naive_local();
+    tx.execute(
+        &numbered_placeholders(TOUCH_ENTRY_SQL),
+        &[&now, &now, &date],
+    )
+    .context("Failed to record entry timestamps")?;
+
+    let insert = tx
+        .prepare(
+            "INSERT INTO bullets (date, content, type, task_state, time, end_time, parent_id, session) VALUES ($1, $2, $3, $4, $5, $6, $7, $8) RETURNING id",
+        )
+        .context("Failed to prepare insert statement")?;
+
+    let mut bullet_count = 0;
+    for (bullet_type, bullets) in &entry.bullets {
+        // Depth-first so parents get lower ids than their children and
+        // siblings keep their order
+        let mut pending: Vec<(&Bullet, Option<i64>)> =
+            bullets.iter().rev().map(|b| (b, None)).collect();
+        while let Some((bullet, parent_id)) = pending.pop() {
+            let bullet_id: i64 = tx
+                .query_one(
+                    &insert,
+                    &[
+                        &date,
+                        &bullet.content,
+                        &bullet_type.to_string(),
+                        &bullet.task_state.map(|s| s.to_string()),
+                        &bullet.time,
+                        &bullet.end_time,
+                        &parent_id,
+                        &bullet.session,
+                    ],
+                )
+                .context("Failed to insert bullet")?
+                .try_get(0)?;
+            bullet_count += 1;
+            pending.extend(bullet.children.iter().rev().map(|c| (c, Some(bullet_id))));
+
+            insert_derived_rows(tx, bullet_id, date, bullet, &link_titles)?;
+        }
+    }
+
+    if let Some(body) = &entry.body {
+        tx.execute(&numbered_placeholders(INSERT_BODY_SQL), &[&date, body])
+            .context("Failed to insert entry body")?;
+    }
+
+    info!(
+        "Successfully saved {} bullets for date: {}",
+        bullet_count, entry.date
+    );
+    Ok(())
+}
+
+/// Date of the entry holding bullet `id`, if there is one
+fn bullet_date(client: &mut impl GenericClient, id: i64) -> Result<Option<NaiveDate>> {
+    client
+        .query_opt(
+            "SELECT date FROM bullets WHERE id = $1 AND NOT COALESCE(body, FALSE)",
+            &[&id],
+        )
+        .context("Failed to look up bullet")?
+        .map(|row| row.try_get(0))
+        .transpose()
+        .map_err(Into::into)
+}
+
+/// Bump the version of the entry on `date` and stamp it as edited now
+fn touch_entry(client: &mut impl GenericClient, date: NaiveDate) -> Result<()> {
+    client
+        .execute(&numbered_placeholders(BUMP_VERSION_SQL), &[&date])
+        .context("Failed to bump entry version")?;
+    let now = Local::now().naive_local();
+    client
+        .execute(
+            &numbered_placeholders(TOUCH_ENTRY_SQL),
+            &[&now, &now, &date],
+        )
+        .context("Failed to record entry timestamps")?;
+    Ok(())
+}
+
+/// Remove a date's bullets and every row derived from them
+fn delete_date(client: &mut impl GenericClient, date: NaiveDate) -> Result<()> {
+    for table in [
+        "bullets",
+        "mentions",
+        "contexts",
+        "metrics",
+        "task_dependencies",
+        "links",
+    ] {
+        client
+            .execute(&format!("DELETE FROM {} WHERE date = $1", table), &[&date])
+            .with_context(|| format!("Failed to delete existing {}", table))?;
+    }
+    // Every save and delete goes through here
+    client
+        .execute(&numbered_placeholders(BUMP_VERSION_SQL), &[&date])
+        .context("Failed to bump entry version")?;
+    Ok(())
+}
+
+/// Insert the mentions, contexts, metrics, dependencies and links found in a
+/// bullet's content
+fn insert_derived_rows(
+    client: &mut impl GenericClient,
+    bullet_id: i64,
+    date: NaiveDate,
+    bullet: &Bullet,
+    link_titles: &HashMap<String, String>,
+) -> Result<()> {
+    for name in bullet.mentions() {
+        client
+            .execute(
+                "INSERT INTO mentions (bullet_id, date, name) VALUES ($1, $2, $3)",
+                &[&bullet_id, &date, &name],
+            )
+            .context("Failed to insert mention")?;
+    }
+    for name in bullet.contexts() {
+        client
+            .execute(
+                "INSERT INTO contexts (bullet_id, date, name) VALUES ($1, $2, $3)",
+                &[&bullet_id, &date, &name],
+            )
+            .context("Failed to insert context")?;
+    }
+    for (name, value) in bullet.metrics() {
+        client
+            .execute(
+                "INSERT INTO metrics (bullet_id, date, name, value) VALUES ($1, $2, $3, $4)",
+                &[&bullet_id, &date, &name, &value],
+            )
+            .context("Failed to insert metric")?;
+    }
+    for blocker in bullet.blockers() {
+        client
+            .execute(
+                "INSERT INTO task_dependencies (bullet_id, date, blocker) VALUES ($1, $2, $3)",
+                &[&bullet_id, &date, &blocker],
+            )
+            .context("Failed to insert task dependency")?;
+    }
+    for url in bullet.urls() {
+        client
+            .execute(
+                "INSERT INTO links (bullet_id, date, url, title) VALUES ($1, $2, $3, $4)",
+                &[&bullet_id, &date, &url, &link_titles.get(&url)],
+            )
+            .context("Failed to insert link")?;
+    }
+    Ok(())
+}
+
+impl EntryRepository for PostgresStorage {
+    fn load(&self, date: NaiveDate) -> Result<Option<Entry>> {
+        self.load_entry(date)
+    }
+
+    fn save(&self, entry: Entry) -> Result<()> {
+        self.save_entry(&entry)
+    }
+
+    fn list_dates(&self, range: DateRange) -> Result<Vec<NaiveDate>> {
+        JournalStorage::list_dates(self, range)
+    }
+}
+
+/// These run against a real server, e.g.
+/// `docker run --rm -e POSTGRES_PASSWORD=journo -p 5432:5432 postgres:16` and
+/// `JOURNO_TEST_POSTGRES_URL="host=localhost user=postgres password=journo" cargo test --features postgres -- --ignored`.
+/// Each test works in a schema of its own, dropped when it finishes.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestDatabase {
+        storage: PostgresStorage,
+        url: String,
+        schema: String,
+    }
+
+    impl TestDatabase {
+        fn new(name: &str) -> Self {
+            let url = std::env::var("JOURNO_TEST_POSTGRES_URL")
+                .expect("JOURNO_TEST_POSTGRES_URL must point at a test server");
+            let schema = format!("journo_test_{}_{}", name, std::process::id());
+            let mut client = PostgresStorage::open_client(&url, None).unwrap();
+            client
+                .batch_execute(&format!(
+                    "DROP SCHEMA IF EXISTS {0} CASCADE; CREATE SCHEMA {0}; SET search_path TO {0};",
+                    schema
+                ))
+                .unwrap();
+            Self {
+                storage: PostgresStorage::from_client(client).unwrap(),
+                url,
+                schema,
+            }
+        }
+    }
+
+    impl Drop for TestDatabase {
+        fn drop(&mut self) {
+            if let Ok(mut client) = PostgresStorage::open_client(&self.url, None) {
+                let _ = client.batch_execute(&format!("DROP SCHEMA {} CASCADE", self.schema));
+            }
+        }
+    }
+
+    #[test]
+    #[ignore = "needs a PostgreSQL server in JOURNO_TEST_POSTGRES_URL"]
+    fn test_entry_round_trip() {
+        let db = TestDatabase::new("round_trip");
+        let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let mut entry = Entry::new(date);
+        entry.add_bullet(
+            Bullet::new("Plan launch with @sam", BulletType::Task)
+                .at(NaiveTime::from_hms_opt(9, 30, 0).unwrap())
+                .with_child(Bullet::new("Read https://example.com", BulletType::Task)),
+        );
+        entry.add_bullet(Bullet::new("Standup", BulletType::Event));
+        db.storage.save_entry(&entry).unwrap();
+
+        assert_eq!(db.storage.load_entry(date).unwrap(), Some(entry.clone()));
+        assert_eq!(db.storage.list_dates(DateRange::day(date)).unwrap(), [date]);
+        assert_eq!(db.storage.list_people().unwrap()[0].name, "sam");
+        assert_eq!(db.storage.list_links(None).unwrap().len(), 1);
+        assert!(db.storage.migration_status().unwrap().pending().is_empty());
+
+        db.storage.delete_entry(date).unwrap();
+        assert_eq!(db.storage.load_entry(date).unwrap(), None);
+        assert!(db.storage.list_links(None).unwrap().is_empty());
+    }
+
+    #[test]
+    #[ignore = "needs a PostgreSQL server in JOURNO_TEST_POSTGRES_URL"]
+    fn test_search_update_and_stats() {
+        let db = TestDatabase::new("search");
+        let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let mut entry = Entry::new(date);
+        entry.add_bullet(Bullet::new("Write draft", BulletType::Task));
+        entry.add_bullet(Bullet::new("Draft   review  meeting", BulletType::Event));
+        db.storage.save_entry(&entry).unwrap();
+
+        let hits = db
+            .storage
+            .search_bullets(
+                "draft",
+                &[BulletType::Task],
+                &[],
+                Some(DateRange::day(date)),
+            )
+            .unwrap();
+        assert_eq!(hits.len(), 1);
+
+        let id = db.storage.bullet_ids(date, &BulletType::Task).unwrap()[0];
+        db.storage
+            .update_bullet(id, "Write draft for @alex", Some(TaskState::Completed))
+            .unwrap();
+        let (_, bullet) = db.storage.load_bullet(id).unwrap().unwrap();
+        assert_eq!(bullet.task_state, Some(TaskState::Completed));
+        assert_eq!(db.storage.find_bullets_mentioning("Alex").unwrap().len(), 1);
+
+        let stats = db.storage.period_stats(DateRange::day(date)).unwrap();
+        assert_eq!(stats.days_with_entries, 1);
+        assert_eq!(stats.tasks_completed, 1);
+        assert_eq!(stats.events, 1);
+        assert_eq!(stats.words, 7);
+    }
+}
@@ -0,0 +1,336 @@
+use crate::entities::{Bullet, BulletType, DateRange, TaskState};
+use anyhow::{Result, anyhow, bail};
+use chrono::NaiveDate;
+use std::collections::HashMap;
+
+/// Filter keys understood by `Query::parse`, for error messages
+const KEYS: &str = "type, state, tag, context, after, before or view";
+
+/// A parsed search such as `type:task state:pending "invoice" after:2024-01-01 tag:work`.
+///
+/// Bare words and quoted phrases must all appear in the content, ignoring
+/// case. `type:` and `state:` take comma-separated values and match any of
+/// them, while every `tag:` and `context:` has to be present. `after:` and
+/// `before:` are inclusive. `#tag` and `@@context` are shorthands for the
+/// matching filters, and `view:name` pulls in a saved view.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Query {
+    pub text: Vec<String>,
+    pub types: Vec<BulletType>,
+    pub states: Vec<TaskState>,
+    pub tags: Vec<String>,
+    pub contexts: Vec<String>,
+    pub after: Option<NaiveDate>,
+    pub before: Option<NaiveDate>,
+}
+
+impl Query {
+    /// Parse a query without saved views
+    pub fn parse(input: &str) -> Result<Self> {
+        Self::parse_with_views(input, &HashMap::new())
+    }
+
+    /// Parse a query, expanding `view:name` from the `[views]` config table
+    pub fn parse_with_views(input: &str, views: &HashMap<String, String>) -> Result<Self> {
+        let mut query = Self::default();
+        for token in tokenize(input)? {
+            let (key, value) = match token {
+                Token::Phrase(phrase) => {
+                    query.text.push(phrase);
+                    continue;
+                }
+                Token::Word(word) => match word.split_once(':') {
+                    // Times like 09:30 are plain text
+                    Some((key, value))
+                        if !key.is_empty() && key.chars().all(|c| c.is_ascii_alphabetic()) =>
+                    {
+                        (key.to_lowercase(), value.to_string())
+                    }
+                    _ => {
+                        query.push_word(&word)?;
+                        continue;
+                    }
+                },
+                Token::Filter(key, value) => (key.to_lowercase(), value),
+            };
+            if value.is_empty() {
+                bail!("missing value after '{}:'", key);
+            }
+
+            match key.as_str() {
+                "type" => {
+                    for name in value.split(',') {
+                        let bullet_type: BulletType = name.parse().map_err(|e| anyhow!("{}", e))?;
+                        query.types.push(bullet_type);
+                    }
+                }
+                "state" => {
+                    for name in value.split(',') {
+                        let state: TaskState = name.parse().map_err(|e| anyhow!("{}", e))?;
+                        query.states.push(state);
+                    }
+                }
+                "tag" => query.tags.push(name_value(&key, &value, '#')?),
+                "context" => query.contexts.push(name_value(&key, &value, '@')?),
+                "after" => query.after = Some(date_value(&key, &value)?),
+                "before" => query.before = Some(date_value(&key, &value)?),
+                "view" => {
+                    let saved = views
+                        .get(&value)
+                        .ok_or_else(|| anyhow!("no saved view named '{}' under [views]", value))?;
+                    let view = Self::parse(saved)
+                        .map_err(|e| anyhow!("in saved view '{}': {}", value, e))?;
+                    query.merge(view);
+                }
+                _ => bail!("unknown filter '{}:', expected {}", key, KEYS),
+            }
+        }
+        Ok(query)
+    }
+
+    /// A bare word, which may be a `#tag` or `@@context` shorthand
+    fn push_word(&mut self, word: &str) -> Result<()> {
+        if let Some(context) = word.strip_prefix("@@") {
+            self.contexts.push(name_value("context", context, '@')?);
+        } else if let Some(tag) = word.strip_prefix('#').filter(|tag| !tag.is_empty()) {
+            self.tags.push(name_value("tag", tag, '#')?);
+        } else {
+            self.text.push(word.to_string());
+        }
+        Ok(())
+    }
+
+    /// Add another query's filters to this one
+    fn merge(&mut self, other: Query) {
+        self.text.extend(other.text);
+        self.types.extend(other.types);
+        self.states.extend(other.states);
+        self.tags.extend(other.tags);
+        self.contexts.extend(other.contexts);
+        self.after = self.after.max(other.after);
+        self.before = match (self.before, other.before) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+    }
+
+    /// Narrow the date bounds to a range, keeping any tighter bounds
+    pub fn restrict_to(&mut self, range: Option<DateRange>) {
+        if let Some(range) = range {
+            self.merge(Query {
+                after: Some(range.start()),
+                before: Some(range.end()),
+                ..Default::default()
+            });
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Whether a day falls between `after:` and `before:`
+    pub fn includes_date(&self, date: NaiveDate) -> bool {
+        self.after.is_none_or(|after| date >= after)
+            && self.before.is_none_or(|before| date <= before)
+    }
+
+    /// Whether a bullet written on `date` matches every filter. Sub-bullets
+    /// aren't looked at.
+    pub fn matches(&self, date: NaiveDate, bullet: &Bullet) -> bool {
+        if !self.includes_date(date)
+            || (!self.types.is_empty() && !self.types.contains(&bullet.bullet_type))
+            || (!self.states.is_empty()
+                && !bullet
+                    .task_state
+                    .is_some_and(|state| self.states.contains(&state)))
+        {
+            return false;
+        }
+        let tags = bullet.tags();
+        let contexts = bullet.contexts();
+        self.tags.iter().all(|tag| tags.contains(tag))
+            && self
+                .contexts
+                .iter()
+                .all(|context| contexts.contains(context))
+            && self.matches_text(&bullet.content)
+    }
+
+    /// Whether every word and phrase appears in `text`, ignoring case
+    pub fn matches_text(&self, text: &str) -> bool {
+        let text = text.to_lowercase();
+        self.text
+            .iter()
+            .all(|term| text.contains(&term.to_lowercase()))
+    }
+
+    /// Whether only words and phrases are given, so things other than
+    /// bullets, like calendar events, can match too
+    pub fn is_text_only(&self) -> bool {
+        self.types.is_empty()
+            && self.states.is_empty()
+            && self.tags.is_empty()
+            && self.contexts.is_empty()
+    }
+}
+
+enum Token {
+    Word(String),
+    /// A quoted phrase on its own
+    Phrase(String),
+    /// `key:"quoted value"`
+    Filter(String, String),
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+
+        let mut word = String::new();
+        let mut quoted = None;
+        while let Some(&(i, c)) = chars.peek() {
+            if c.is_whitespace() {
+                break;
+            }
+            chars.next();
+            if c != '"' {
+                word.push(c);
+                continue;
+            }
+            let mut phrase = String::new();
+            loop {
+                match chars.next() {
+                    Some((_, '"')) => break,
+                    Some((_, c)) => phrase.push(c),
+                    None => bail!("unterminated quote at column {}", i + 1),
+                }
+            }
+            quoted = Some(phrase);
+            break;
+        }
+
+        tokens.push(match quoted {
+            None => Token::Word(word),
+            Some(phrase) if word.is_empty() => Token::Phrase(phrase),
+            Some(phrase) => match word.strip_suffix(':') {
+                Some(key) => Token::Filter(key.to_string(), phrase),
+                None => bail!(
+                    "unexpected quote at column {}, quote the whole phrase instead",
+                    start + word.len() + 1
+                ),
+            },
+        });
+    }
+    Ok(tokens)
+}
+
+/// A tag or context name, with an optional leading `#` or `@@` and lowercased
+fn name_value(key: &str, value: &str, sigil: char) -> Result<String> {
+    let name = value.trim_start_matches(sigil).to_lowercase();
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+    {
+        bail!(
+            "invalid {} '{}', use letters, digits, '-' and '_'",
+            key,
+            value
+        );
+    }
+    Ok(name)
+}
+
+fn date_value(key: &str, value: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| anyhow!("invalid date '{}' for {}:, expected YYYY-MM-DD", value, key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_parse_query() {
+        let query =
+            Query::parse(r#"type:task,priority state:pending "the invoice" after:2024-01-01 tag:Work @@home pay"#)
+                .unwrap();
+        assert_eq!(query.types, vec![BulletType::Task, BulletType::Priority]);
+        assert_eq!(query.states, vec![TaskState::Pending]);
+        assert_eq!(query.text, vec!["the invoice", "pay"]);
+        assert_eq!(query.after, Some(date("2024-01-01")));
+        assert_eq!(query.tags, vec!["work"]);
+        assert_eq!(query.contexts, vec!["home"]);
+
+        let query = Query::parse(r##"#work context:"@@office""##).unwrap();
+        assert_eq!(query.tags, vec!["work"]);
+        assert_eq!(query.contexts, vec!["office"]);
+        assert!(Query::parse("  ").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = |input: &str| Query::parse(input).unwrap_err().to_string();
+        assert_eq!(
+            error(r#"pay "the invoice"#),
+            "unterminated quote at column 5"
+        );
+        assert_eq!(
+            error("after:2024-13-01"),
+            "invalid date '2024-13-01' for after:, expected YYYY-MM-DD"
+        );
+        assert_eq!(
+            error("when:today"),
+            "unknown filter 'when:', expected type, state, tag, context, after, before or view"
+        );
+        assert_eq!(error("type:tasks"), "unknown bullet type: tasks");
+        assert_eq!(error("tag:"), "missing value after 'tag:'");
+        assert_eq!(
+            error("view:work"),
+            "no saved view named 'work' under [views]"
+        );
+    }
+
+    #[test]
+    fn test_saved_views_merge() {
+        let views = HashMap::from([(
+            "work".to_string(),
+            "type:task tag:work after:2024-01-01".to_string(),
+        )]);
+        let mut query = Query::parse_with_views("view:work invoice", &views).unwrap();
+        query.restrict_to(Some(DateRange::month(2024, 3)));
+        assert_eq!(query.types, vec![BulletType::Task]);
+        assert_eq!(query.text, vec!["invoice"]);
+        assert_eq!(query.after, Some(date("2024-03-01")));
+        assert_eq!(query.before, Some(date("2024-03-31")));
+    }
+
+    #[test]
+    fn test_query_matches_bullet() {
+        let query = Query::parse("type:task state:pending tag:work invoice").unwrap();
+        let day = date("2024-03-15");
+        assert!(query.matches(day, &Bullet::new("Send #work Invoice", BulletType::Task)));
+        assert!(!query.matches(
+            day,
+            &Bullet::new("Send #workshop invoice", BulletType::Task)
+        ));
+        assert!(!query.matches(day, &Bullet::new("Send #work invoice", BulletType::Note)));
+        assert!(!query.matches(
+            day,
+            &Bullet::new("Send #work invoice", BulletType::Task).complete()
+        ));
+
+        let query = Query::parse("before:2024-03-14").unwrap();
+        assert!(!query.matches(day, &Bullet::new("Anything", BulletType::Note)));
+    }
+}
@@ -0,0 +1,323 @@
+use crate::entities::{Bullet, BulletType, DateRange, Entry};
+use crate::infrastructure::storage::JournalStorage;
+use crate::infrastructure::{EntryRepository, FileSystemRepository, MarkdownParser};
+use anyhow::Result;
+use chrono::NaiveDate;
+use std::collections::BTreeSet;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+/// How to resolve an entry whose markdown file and database copy differ
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergePolicy {
+    /// Overwrite the database with the markdown file
+    PreferFile,
+    /// Overwrite the markdown file with the database copy
+    PreferDb,
+    /// Keep whichever side was changed last: the file's modification time
+    /// against the time the database copy was last saved
+    PreferNewer,
+    /// Keep the database bullets and append any bullets only found in the file
+    Merge,
+}
+
+impl FromStr for MergePolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "file" | "prefer-file" => Ok(MergePolicy::PreferFile),
+            "db" | "prefer-db" => Ok(MergePolicy::PreferDb),
+            "newer" | "prefer-newer" => Ok(MergePolicy::PreferNewer),
+            "merge" => Ok(MergePolicy::Merge),
+            _ => Err(format!("unknown merge policy: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Divergence {
+    /// Entry exists only as a markdown file
+    OnlyInFile(Entry),
+    /// Entry exists only in the database
+    OnlyInDb(Entry),
+    /// Both exist but their content hashes differ
+    Different { file: Entry, db: Entry },
+}
+
+impl Divergence {
+    pub fn date(&self) -> NaiveDate {
+        match self {
+            Divergence::OnlyInFile(entry) | Divergence::OnlyInDb(entry) => entry.date,
+            Divergence::Different { db, .. } => db.date,
+        }
+    }
+}
+
+/// Detects and resolves differences between the markdown files and the database
+pub struct Reconciler<'a> {
+    files: &'a FileSystemRepository,
+    storage: &'a dyn JournalStorage,
+    parser: MarkdownParser,
+}
+
+impl<'a> Reconciler<'a> {
+    pub fn new(files: &'a FileSystemRepository, storage: &'a dyn JournalStorage) -> Self {
+        Self {
+            files,
+            storage,
+            parser: MarkdownParser::new(),
+        }
+    }
+
+    /// Hash of the canonical markdown serialization, independent of bullet map ordering
+    pub fn content_hash(&self, entry: &Entry) -> Result<u64> {
+        let mut hasher = DefaultHasher::new();
+        self.parser.serialize(entry)?.hash(&mut hasher);
+        Ok(hasher.finish())
+    }
+
+    /// Compare every entry present in either store
+    pub fn find_divergences(&self) -> Result<Vec<Divergence>> {
+        let mut dates: BTreeSet<NaiveDate> = self.files.all_dates()?.into_iter().collect();
+        if let Some((first, last)) = self.storage.entry_date_span()? {
+            let range = DateRange::between(first, last);
+            dates.extend(self.storage.list_dates(range)?);
+        }
+
+        let mut divergences = Vec::new();
+        for date in dates {
+            let file_entry = self.files.load(date)?.filter(|e| !e.is_empty());
+            let db_entry = self.storage.load_entry(date)?;
+
+            match (file_entry, db_entry) {
+                (Some(file), Some(db)) => {
+                    if self.content_hash(&file)? != self.content_hash(&db)? {
+                        divergences.push(Divergence::Different { file, db });
+                    }
+                }
+                (Some(file), None) => divergences.push(Divergence::OnlyInFile(file)),
+                (None, Some(db)) => divergences.push(Divergence::OnlyInDb(db)),
+                (None, None) => {}
+            }
+        }
+
+        Ok(divergences)
+    }
+
+    /// Resolve a divergence, writing the result to both stores. Entries missing
+    /// from one side are always copied over; the policy only matters for conflicts.
+    pub fn resolve(&self, divergence: &Divergence, policy: MergePolicy) -> Result<Entry> {
+        let resolved = match divergence {
+            Divergence::OnlyInFile(entry) | Divergence::OnlyInDb(entry) => entry.clone(),
+            Divergence::Different { file, db } => match policy {
+                MergePolicy::PreferFile => file.clone(),
+                MergePolicy::PreferDb => db.clone(),
+                MergePolicy::PreferNewer => {
+                    if self.file_is_newer(db.date)? {
+                        file.clone()
+                    } else {
+                        db.clone()
+                    }
+                }
+                MergePolicy::Merge => merge_entries(db, file),
+            },
+        };
+
+        self.storage.save_entry(&resolved)?;
+        self.files.save(resolved.clone())?;
+        Ok(resolved)
+    }
+
+    /// Whether the markdown file was modified after the database copy was
+    /// last saved; files win when the database has no timestamp
+    fn file_is_newer(&self, date: NaiveDate) -> Result<bool> {
+        let Some(file_modified) = self.files.modified_at(date)? else {
+            return Ok(false);
+        };
+        Ok(match self.storage.entry_timestamps(date)? {
+            Some(timestamps) => file_modified > timestamps.updated_at,
+            None => true,
+        })
+    }
+}
+
+/// Bullet-by-bullet merge: keep everything in `base`, then append bullets from
+/// `other` whose content isn't already present under the same type. `other`'s
+/// free-written body is only taken when `base` has none.
+pub fn merge_entries(base: &Entry, other: &Entry) -> Entry {
+    let mut merged = base.clone();
+    if merged.body.is_none() {
+        merged.body = other.body.clone();
+    }
+    for bullet_type in BulletType::ALL {
+        for bullet in other.get_bullets(&bullet_type) {
+            let exists = merged
+                .get_bullets(&bullet_type)
+                .iter()
+                .any(|b| b.content == bullet.content);
+            if !exists {
+                merged.add_bullet(bullet.clone());
+            }
+        }
+    }
+    merged
+}
+
+/// Three-way merge of two edits of an entry made from a common `base` (`None`
+/// when the entry didn't exist yet), matching bullets by type and content.
+/// Bullets `theirs` added are appended, bullets it removed are dropped and
+/// bullets it changed (e.g. a completed task) take its version, unless `ours`
+/// changed the same bullet, in which case ours wins; the free-written body is
+/// taken the same way. Returns the merged entry and the number of bullets
+/// where a change in `theirs` was overridden.
+pub fn three_way_merge(base: Option<&Entry>, theirs: &Entry, ours: &Entry) -> (Entry, usize) {
+    let mut merged = Entry::new(ours.date);
+    let base_body = base.and_then(|e| e.body.as_ref());
+    merged.body = if ours.body.as_ref() != base_body {
+        ours.body.clone()
+    } else {
+        theirs.body.clone()
+    };
+    let mut overridden = 0;
+    for bullet_type in BulletType::ALL {
+        let base_bullets = base.map_or(&[][..], |e| e.get_bullets(&bullet_type));
+        let their_bullets = theirs.get_bullets(&bullet_type);
+        let mut bullets = ours.get_bullets(&bullet_type).to_vec();
+        let position =
+            |list: &[Bullet], content: &str| list.iter().position(|b| b.content == content);
+
+        for theirs in their_bullets {
+            match position(base_bullets, &theirs.content).map(|i| &base_bullets[i]) {
+                // Added by them
+                None => {
+                    if position(&bullets, &theirs.content).is_none() {
+                        bullets.push(theirs.clone());
+                    }
+                }
+                // Changed by them
+                Some(original) if original != theirs => match position(&bullets, &theirs.content) {
+                    Some(i) if bullets[i] == *original => bullets[i] = theirs.clone(),
+                    Some(i) if bullets[i] == *theirs => {}
+                    _ => overridden += 1,
+                },
+                Some(_) => {}
+            }
+        }
+
+        // Removed by them
+        for original in base_bullets {
+            if position(their_bullets, &original.content).is_some() {
+                continue;
+            }
+            match position(&bullets, &original.content) {
+                Some(i) if bullets[i] == *original => {
+                    bullets.remove(i);
+                }
+                Some(_) => overridden += 1,
+                None => {}
+            }
+        }
+
+        for bullet in bullets {
+            merged.add_bullet(bullet);
+        }
+    }
+    (merged, overridden)
+}
+
+#[cfg(all(test, feature = "duckdb"))]
+mod tests {
+    use super::*;
+    use crate::entities::TaskState;
+    use crate::infrastructure::test_utils::test_harness::TestStorage;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_reconcile_detects_and_merges() {
+        let test_storage = TestStorage::new();
+        let storage = test_storage.storage();
+        let temp_dir = TempDir::new().unwrap();
+        let files =
+            FileSystemRepository::new(temp_dir.path().join("data"), temp_dir.path().to_path_buf());
+
+        let only_file = NaiveDate::from_ymd_opt(2024, 3, 14).unwrap();
+        let conflict = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+
+        let mut file_entry = Entry::new(only_file);
+        file_entry.add_bullet(Bullet::new("Written by hand", BulletType::Note));
+        files.save(file_entry).unwrap();
+
+        let mut db_version = Entry::new(conflict);
+        db_version.add_bullet(Bullet::new("Shared task", BulletType::Task));
+        storage.save_entry(&db_version).unwrap();
+
+        let mut file_version = db_version.clone();
+        file_version.add_bullet(Bullet::new("Added in file", BulletType::Event));
+        files.save(file_version).unwrap();
+
+        let reconciler = Reconciler::new(&files, storage);
+        let divergences = reconciler.find_divergences().unwrap();
+        assert_eq!(divergences.len(), 2);
+        assert!(matches!(divergences[0], Divergence::OnlyInFile(_)));
+        assert!(matches!(divergences[1], Divergence::Different { .. }));
+
+        for divergence in &divergences {
+            reconciler.resolve(divergence, MergePolicy::Merge).unwrap();
+        }
+
+        assert!(reconciler.find_divergences().unwrap().is_empty());
+        let merged = storage.load_entry(conflict).unwrap().unwrap();
+        assert_eq!(merged.total_bullets(), 2);
+        assert!(storage.load_entry(only_file).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_three_way_merge() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let mut base = Entry::new(date);
+        base.add_bullet(Bullet::new("Dentist", BulletType::Task));
+        base.add_bullet(Bullet::new("Taxes", BulletType::Task));
+        base.add_bullet(Bullet::new("Old note", BulletType::Note));
+
+        // Quick capture while the editor was open: completed a task, removed
+        // the note and added an event
+        let mut theirs = Entry::new(date);
+        theirs.add_bullet(Bullet::with_task_state(
+            "Dentist",
+            BulletType::Task,
+            TaskState::Completed,
+        ));
+        theirs.add_bullet(Bullet::new("Taxes", BulletType::Task));
+        theirs.add_bullet(Bullet::new("Standup", BulletType::Event));
+
+        // The editor completed the other task and added a note
+        let mut ours = base.clone();
+        ours.get_bullets_mut(&BulletType::Task)[1].task_state = Some(TaskState::Completed);
+        ours.add_bullet(Bullet::new("Written in the editor", BulletType::Note));
+
+        let (merged, overridden) = three_way_merge(Some(&base), &theirs, &ours);
+        assert_eq!(overridden, 0);
+        let tasks = merged.get_bullets(&BulletType::Task);
+        assert!(
+            tasks
+                .iter()
+                .all(|t| t.task_state == Some(TaskState::Completed))
+        );
+        assert_eq!(merged.get_bullets(&BulletType::Event)[0].content, "Standup");
+        let notes = merged.get_bullets(&BulletType::Note);
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes[0].content, "Written in the editor");
+
+        // Both sides changing the same bullet keeps ours
+        let mut ours = base.clone();
+        ours.get_bullets_mut(&BulletType::Task)[0].task_state = Some(TaskState::Migrated);
+        let (merged, overridden) = three_way_merge(Some(&base), &theirs, &ours);
+        assert_eq!(overridden, 1);
+        assert_eq!(
+            merged.get_bullets(&BulletType::Task)[0].task_state,
+            Some(TaskState::Migrated)
+        );
+    }
+}
@@ -0,0 +1,419 @@
+// SQL and row mapping shared by the DuckDB and SQLite backends, which accept
+// the same statements once dates and times are cast to text. PostgreSQL reuses
+// the entry version statements through `numbered_placeholders`.
+
+use crate::entities::{Bullet, BulletHit, BulletType, Entry, TaskState};
+use anyhow::{Context, Result};
+use chrono::{NaiveDate, NaiveTime};
+use std::collections::{BTreeMap, HashMap};
+
+/// Format timestamps are written in and read back from DuckDB and SQLite
+pub(crate) const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Tables holding rows derived from bullet content, keyed by `bullet_id` and
+/// `date` and rebuilt whenever their bullet changes
+pub(crate) const DERIVED_TABLES: [&str; 5] = [
+    "mentions",
+    "contexts",
+    "metrics",
+    "task_dependencies",
+    "links",
+];
+
+/// Upsert bumping the version of an entry, valid in every dialect once `?`
+/// placeholders are adapted. `updated_at` comes from the proposed row's
+/// default, as DuckDB can't bind `CURRENT_TIMESTAMP` in `DO UPDATE`.
+pub(crate) const BUMP_VERSION_SQL: &str = "INSERT INTO entry_versions (date, version) VALUES (?, 1) \
+     ON CONFLICT (date) DO UPDATE SET version = entry_versions.version + 1, updated_at = excluded.updated_at";
+
+/// Stamp a saved entry with the local time of the save, run after
+/// `BUMP_VERSION_SQL` with `(now, now, date)`. The first save's time is kept.
+pub(crate) const TOUCH_ENTRY_SQL: &str = "UPDATE entry_versions \
+     SET created_at = COALESCE(created_at, ?), updated_at = ? WHERE date = ?";
+
+/// Query behind `change_watermark`; the cast keeps the sum a plain integer in
+/// every dialect
+pub(crate) const CHANGE_WATERMARK_SQL: &str =
+    "SELECT CAST(COALESCE(SUM(version), 0) AS BIGINT) FROM entry_versions";
+
+/// Store an entry's free-written body as a note flagged `body`, run with
+/// `(date, text)`. Queries for bullets leave it out with
+/// `NOT COALESCE(body, FALSE)`; those for dates count it, so a day holding
+/// only free writing is still an entry.
+pub(crate) const INSERT_BODY_SQL: &str =
+    "INSERT INTO bullets (date, content, type, body) VALUES (?, ?, 'note', TRUE)";
+
+/// Forget when a deleted entry was created, so writing the day again starts afresh
+pub(crate) const FORGET_CREATED_SQL: &str =
+    "UPDATE entry_versions SET created_at = NULL WHERE date = ?";
+
+/// Statement behind `convert_bullet`, run with `(type, id)`
+pub(crate) fn convert_bullet_sql(bullet_type: BulletType) -> String {
+    let task_state = if matches!(bullet_type, BulletType::Task | BulletType::Priority) {
+        "COALESCE(task_state, 'pending')"
+    } else {
+        "NULL"
+    };
+    format!(
+        "UPDATE bullets SET type = ?, task_state = {}, updated_at = CURRENT_TIMESTAMP \
+         WHERE id IN (WITH RECURSIVE subtree(id) AS (SELECT id FROM bullets \
+         WHERE id = ? AND NOT COALESCE(body, FALSE) \
+         UNION ALL SELECT b.id FROM bullets b JOIN subtree s ON b.parent_id = s.id) \
+         SELECT id FROM subtree)",
+        task_state
+    )
+}
+
+// Entries and their bullets
+
+/// Bullets of one entry as `BulletRow`s, run with `(date)`
+pub(crate) const ENTRY_BULLETS_SQL: &str = "SELECT id, parent_id, content, type, task_state, \
+     CAST(time AS VARCHAR), CAST(end_time AS VARCHAR), CAST(session AS VARCHAR), \
+     COALESCE(body, FALSE) FROM bullets WHERE date = ? ORDER BY id";
+
+/// Date and `BulletRow` of every bullet in a range, run with `(start, end)`
+pub(crate) const RANGE_BULLETS_SQL: &str = "SELECT CAST(date AS VARCHAR), id, parent_id, \
+     content, type, task_state, CAST(time AS VARCHAR), CAST(end_time AS VARCHAR), \
+     CAST(session AS VARCHAR), COALESCE(body, FALSE) FROM bullets \
+     WHERE date BETWEEN ? AND ? ORDER BY date, id";
+
+/// Dates with entries in a range, run with `(start, end)`
+pub(crate) const LIST_DATES_SQL: &str = "SELECT DISTINCT CAST(date AS VARCHAR) AS date \
+     FROM bullets WHERE date BETWEEN ? AND ? ORDER BY date";
+
+/// Dates with bullets of a type in a range, run with `(type, start, end)`
+pub(crate) const DATES_WITH_TYPE_SQL: &str = "SELECT DISTINCT CAST(date AS VARCHAR) AS date \
+     FROM bullets WHERE type = ? AND date BETWEEN ? AND ? AND NOT COALESCE(body, FALSE) \
+     ORDER BY date";
+
+pub(crate) const DELETE_BULLETS_SQL: &str = "DELETE FROM bullets WHERE date = ?";
+
+/// Add a bullet, run with `(date, content, type, task_state, time, end_time,
+/// parent_id, session)` and returning its id
+pub(crate) const INSERT_BULLET_SQL: &str = "INSERT INTO bullets \
+     (date, content, type, task_state, time, end_time, parent_id, session) \
+     VALUES (?, ?, ?, ?, ?, ?, ?, ?) RETURNING id";
+
+pub(crate) const INSERT_MENTION_SQL: &str =
+    "INSERT INTO mentions (bullet_id, date, name) VALUES (?, ?, ?)";
+
+pub(crate) const INSERT_CONTEXT_SQL: &str =
+    "INSERT INTO contexts (bullet_id, date, name) VALUES (?, ?, ?)";
+
+pub(crate) const INSERT_METRIC_SQL: &str =
+    "INSERT INTO metrics (bullet_id, date, name, value) VALUES (?, ?, ?, ?)";
+
+pub(crate) const INSERT_DEPENDENCY_SQL: &str =
+    "INSERT INTO task_dependencies (bullet_id, date, blocker) VALUES (?, ?, ?)";
+
+pub(crate) const INSERT_LINK_SQL: &str =
+    "INSERT INTO links (bullet_id, date, url, title) VALUES (?, ?, ?, ?)";
+
+/// Fetched link titles of an entry, kept across a rewrite, run with `(date)`
+pub(crate) const ENTRY_LINK_TITLES_SQL: &str =
+    "SELECT url, title FROM links WHERE date = ? AND title IS NOT NULL";
+
+/// Fetched link titles of one bullet, run with `(id)`
+pub(crate) const BULLET_LINK_TITLES_SQL: &str =
+    "SELECT url, title FROM links WHERE bullet_id = ? AND title IS NOT NULL";
+
+pub(crate) const ENTRY_VERSION_SQL: &str = "SELECT version FROM entry_versions WHERE date = ?";
+
+pub(crate) const COUNT_ENTRIES_SQL: &str = "SELECT COUNT(DISTINCT date) FROM bullets";
+
+pub(crate) const ENTRY_DATE_SPAN_SQL: &str =
+    "SELECT CAST(MIN(date) AS VARCHAR), CAST(MAX(date) AS VARCHAR) FROM bullets";
+
+pub(crate) const NEXT_ENTRY_DATE_SQL: &str =
+    "SELECT CAST(MIN(date) AS VARCHAR) FROM bullets WHERE date > ?";
+
+pub(crate) const PREVIOUS_ENTRY_DATE_SQL: &str =
+    "SELECT CAST(MAX(date) AS VARCHAR) FROM bullets WHERE date < ?";
+
+/// Bullets per day in a range, run with `(start, end)`
+pub(crate) const DAILY_BULLET_COUNTS_SQL: &str = "SELECT CAST(date AS VARCHAR), COUNT(*) \
+     FROM bullets WHERE date BETWEEN ? AND ? AND NOT COALESCE(body, FALSE) GROUP BY date";
+
+// Single bullets
+
+/// Date of the entry holding a bullet, run with `(id)`
+pub(crate) const BULLET_DATE_SQL: &str =
+    "SELECT CAST(date AS VARCHAR) FROM bullets WHERE id = ? AND NOT COALESCE(body, FALSE)";
+
+/// Ids of an entry's bullets of one type, run with `(date, type)`
+pub(crate) const BULLET_IDS_SQL: &str =
+    "SELECT id FROM bullets WHERE date = ? AND type = ? AND NOT COALESCE(body, FALSE) ORDER BY id";
+
+/// Columns `HitRow` holds, in order, for searches assembled at runtime
+pub(crate) const HIT_COLUMNS: &str = "CAST(date AS VARCHAR), content, type, task_state, \
+     CAST(time AS VARCHAR), CAST(end_time AS VARCHAR)";
+
+/// A bullet as a `HitRow`, run with `(id)`
+pub(crate) const LOAD_BULLET_SQL: &str = "SELECT CAST(date AS VARCHAR), content, type, \
+     task_state, CAST(time AS VARCHAR), CAST(end_time AS VARCHAR) FROM bullets \
+     WHERE id = ? AND NOT COALESCE(body, FALSE)";
+
+/// Rewrite a bullet's text and state, run with `(content, task_state, id)`
+pub(crate) const UPDATE_BULLET_SQL: &str = "UPDATE bullets SET content = ?, task_state = ?, \
+     updated_at = CURRENT_TIMESTAMP WHERE id = ? AND NOT COALESCE(body, FALSE)";
+
+/// Run with `(task_state, id)`; only touches tasks and priorities
+pub(crate) const SET_TASK_STATE_SQL: &str = "UPDATE bullets SET task_state = ?, \
+     updated_at = CURRENT_TIMESTAMP WHERE id = ? AND type IN ('task', 'priority')";
+
+/// Pending tasks of earlier days as `HitRow`s, run with `(date)`
+pub(crate) const PENDING_TASKS_BEFORE_SQL: &str = "SELECT CAST(date AS VARCHAR), content, type, \
+     task_state, CAST(time AS VARCHAR), CAST(end_time AS VARCHAR) FROM bullets \
+     WHERE task_state = 'pending' AND date < ? AND type IN ('task', 'priority') \
+     AND NOT COALESCE(body, FALSE) ORDER BY date, id";
+
+/// Every pending task as its id followed by a `HitRow`
+pub(crate) const PENDING_TASKS_SQL: &str = "SELECT id, CAST(date AS VARCHAR), content, type, \
+     task_state, CAST(time AS VARCHAR), CAST(end_time AS VARCHAR) FROM bullets \
+     WHERE task_state = 'pending' AND type IN ('task', 'priority') \
+     AND NOT COALESCE(body, FALSE) ORDER BY date, id";
+
+/// Blockers of an entry's tasks with the task text, run with `(date)`
+pub(crate) const TASK_DEPENDENCIES_SQL: &str = "SELECT d.bullet_id, b.content, d.blocker \
+     FROM task_dependencies d JOIN bullets b ON b.id = d.bullet_id \
+     WHERE d.date = ? ORDER BY d.bullet_id";
+
+// Mentions, contexts, metrics and links
+
+pub(crate) const LIST_PEOPLE_SQL: &str = "SELECT name, COUNT(DISTINCT bullet_id), \
+     CAST(MAX(date) AS VARCHAR) FROM mentions GROUP BY name \
+     ORDER BY COUNT(DISTINCT bullet_id) DESC, name";
+
+/// Bullets mentioning a person as `HitRow`s, run with `(name)`
+pub(crate) const BULLETS_MENTIONING_SQL: &str = "SELECT CAST(b.date AS VARCHAR), b.content, \
+     b.type, b.task_state, CAST(b.time AS VARCHAR), CAST(b.end_time AS VARCHAR) \
+     FROM bullets b JOIN mentions m ON m.bullet_id = b.id WHERE m.name = ? ORDER BY b.date, b.id";
+
+pub(crate) const LIST_CONTEXTS_SQL: &str =
+    "SELECT name FROM contexts GROUP BY name ORDER BY COUNT(*) DESC, name";
+
+pub(crate) const LIST_METRICS_SQL: &str =
+    "SELECT name FROM metrics GROUP BY name ORDER BY COUNT(*) DESC, name";
+
+/// Readings of a metric, run with `(name)`; filters and ordering are appended
+pub(crate) const METRIC_VALUES_SQL: &str =
+    "SELECT CAST(date AS VARCHAR), value FROM metrics WHERE name = ?";
+
+/// Links with their bullet's text; filters and ordering are appended
+pub(crate) const LIST_LINKS_SQL: &str = "SELECT CAST(l.date AS VARCHAR), l.url, l.title, \
+     b.content FROM links l JOIN bullets b ON b.id = l.bullet_id";
+
+/// Run with `(title, url)`
+pub(crate) const SET_LINK_TITLE_SQL: &str = "UPDATE links SET title = ? WHERE url = ?";
+
+// Saves, focus sessions, calendar events, pins, moods and prompts
+
+pub(crate) const INSERT_ENTRY_SAVE_SQL: &str =
+    "INSERT INTO entry_saves (date, saved_at, word_count, word_delta) VALUES (?, ?, ?, ?)";
+
+pub(crate) const INSERT_FOCUS_SESSION_SQL: &str = "INSERT INTO focus_sessions \
+     (date, task, started_at, duration_seconds, completed) VALUES (?, ?, ?, ?, ?)";
+
+/// Focus seconds per task in a range, longest first, run with `(start, end)`
+pub(crate) const FOCUS_TIME_BY_TASK_SQL: &str = "SELECT task, \
+     CAST(SUM(duration_seconds) AS BIGINT) AS total FROM focus_sessions \
+     WHERE date BETWEEN ? AND ? GROUP BY task ORDER BY total DESC, task";
+
+pub(crate) const DELETE_EXTERNAL_EVENTS_SQL: &str = "DELETE FROM external_events WHERE source = ?";
+
+pub(crate) const INSERT_EXTERNAL_EVENT_SQL: &str = "INSERT INTO external_events \
+     (source, uid, date, start_time, end_time, summary, location) VALUES (?, ?, ?, ?, ?, ?, ?)";
+
+/// Calendar events of a day, all-day ones first, run with `(date)`
+pub(crate) const EXTERNAL_EVENTS_SQL: &str = "SELECT uid, CAST(start_time AS VARCHAR), \
+     CAST(end_time AS VARCHAR), summary, location FROM external_events WHERE date = ? \
+     ORDER BY start_time NULLS FIRST, summary";
+
+pub(crate) const PIN_SQL: &str = "INSERT INTO pins (date) VALUES (?) ON CONFLICT (date) DO NOTHING";
+
+pub(crate) const UNPIN_SQL: &str = "DELETE FROM pins WHERE date = ?";
+
+pub(crate) const PINNED_DATES_SQL: &str = "SELECT CAST(date AS VARCHAR) FROM pins ORDER BY date";
+
+/// Run with `(date, period, score)`; a second check-in replaces the first
+pub(crate) const RECORD_MOOD_SQL: &str = "INSERT INTO moods (date, period, score) VALUES (?, ?, ?) \
+     ON CONFLICT (date, period) DO UPDATE SET score = excluded.score, recorded_at = excluded.recorded_at";
+
+pub(crate) const MOODS_SQL: &str = "SELECT CAST(date AS VARCHAR), period, score FROM moods \
+     WHERE date BETWEEN ? AND ? ORDER BY date, period DESC";
+
+/// Run with `(date, content, target_date, target_content)`
+pub(crate) const RECORD_CROSS_REFERENCE_SQL: &str = "INSERT INTO cross_references \
+     (date, content, target_date, target_content) VALUES (?, ?, ?, ?) \
+     ON CONFLICT (date, content) DO UPDATE SET target_date = excluded.target_date, \
+     target_content = excluded.target_content, recorded_at = excluded.recorded_at";
+
+pub(crate) const CROSS_REFERENCES_SQL: &str = "SELECT content, CAST(target_date AS VARCHAR), \
+     target_content FROM cross_references WHERE date = ? ORDER BY content";
+
+pub(crate) const RECORD_PROMPT_SQL: &str = "INSERT INTO prompt_history (prompt, shown_on) \
+     VALUES (?, ?) ON CONFLICT (prompt, shown_on) DO NOTHING";
+
+pub(crate) const PROMPTS_SINCE_SQL: &str =
+    "SELECT DISTINCT prompt FROM prompt_history WHERE shown_on >= ?";
+
+pub(crate) const RECORD_MIGRATION_REVIEW_SQL: &str = "INSERT INTO migration_reviews (week_start) \
+     VALUES (?) ON CONFLICT (week_start) DO NOTHING";
+
+// Migrations
+
+pub(crate) const SCHEMA_STATE_SQL: &str = "SELECT value FROM schema_state WHERE key = ?";
+
+pub(crate) const RECORD_SCHEMA_STATE_SQL: &str = "INSERT INTO schema_state (key, value) \
+     VALUES (?, ?) ON CONFLICT (key) DO UPDATE SET value = excluded.value";
+
+pub(crate) const APPLIED_MIGRATIONS_SQL: &str = "SELECT version FROM migrations ORDER BY version";
+
+pub(crate) const RECORD_MIGRATION_SQL: &str =
+    "INSERT INTO migrations (version, name) VALUES (?, ?)";
+
+// Row mapping
+
+/// A bullet as `ENTRY_BULLETS_SQL` selects it: id, parent id, content, type,
+/// task state, time, end time, session and whether it holds the entry body
+pub(crate) type BulletRow = (
+    i64,
+    Option<i64>,
+    String,
+    String,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    bool,
+);
+
+/// A bullet with its date, as `HIT_COLUMNS` selects it: date, content, type,
+/// task state, time and end time
+pub(crate) type HitRow = (
+    String,
+    String,
+    String,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+);
+
+/// A date column read back as text
+pub(crate) fn parse_stored_date(value: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .with_context(|| format!("Failed to parse date '{}' from database", value))
+}
+
+/// Build a bullet from its stored column values, skipping unknown types
+pub(crate) fn bullet_from_row(
+    content: String,
+    type_str: &str,
+    task_state_str: Option<String>,
+    time_str: Option<String>,
+    end_time_str: Option<String>,
+) -> Option<Bullet> {
+    let bullet_type = type_str.parse::<BulletType>().ok()?;
+    let task_state = task_state_str.and_then(|s| s.parse::<TaskState>().ok());
+
+    Some(Bullet {
+        content,
+        bullet_type,
+        task_state,
+        time: stored_time(time_str),
+        end_time: stored_time(end_time_str),
+        children: Vec::new(),
+        session: None,
+    })
+}
+
+/// A `TIME` column read back as `HH:MM:SS` text
+pub(crate) fn stored_time(value: Option<String>) -> Option<NaiveTime> {
+    value.and_then(|s| NaiveTime::parse_from_str(&s, "%H:%M:%S").ok())
+}
+
+/// The search hit a `HitRow` describes, `None` for an unknown bullet type
+pub(crate) fn hit_from_row(row: HitRow) -> Result<Option<BulletHit>> {
+    let (date_str, content, type_str, task_state_str, time_str, end_time_str) = row;
+    let date = parse_stored_date(&date_str)?;
+    Ok(
+        bullet_from_row(content, &type_str, task_state_str, time_str, end_time_str)
+            .map(|bullet| BulletHit { date, bullet }),
+    )
+}
+
+/// Rebuild the entry for `date` from its `BulletRow`s ordered by id, `None`
+/// when there are none
+pub(crate) fn entry_from_rows(date: NaiveDate, rows: Vec<BulletRow>) -> Option<Entry> {
+    if rows.is_empty() {
+        return None;
+    }
+
+    let mut entry = Entry::new(date);
+    let mut bullet_rows = Vec::new();
+    for (id, parent_id, content, type_str, task_state_str, time_str, end_time_str, session, body) in
+        rows
+    {
+        if body {
+            entry.body = Some(content);
+            continue;
+        }
+        let Some(mut bullet) =
+            bullet_from_row(content, &type_str, task_state_str, time_str, end_time_str)
+        else {
+            continue;
+        };
+        bullet.session = stored_time(session);
+        bullet_rows.push((id, parent_id, bullet));
+    }
+
+    for bullet in nest_bullets(bullet_rows) {
+        entry.add_bullet(bullet);
+    }
+    Some(entry)
+}
+
+/// Group `(date, BulletRow)` rows from `RANGE_BULLETS_SQL` into entries sorted
+/// by date
+pub(crate) fn entries_from_rows(rows: Vec<(String, BulletRow)>) -> Result<Vec<Entry>> {
+    let mut rows_by_date: BTreeMap<NaiveDate, Vec<BulletRow>> = BTreeMap::new();
+    for (date_str, row) in rows {
+        rows_by_date
+            .entry(parse_stored_date(&date_str)?)
+            .or_default()
+            .push(row);
+    }
+    Ok(rows_by_date
+        .into_iter()
+        .filter_map(|(date, rows)| entry_from_rows(date, rows))
+        .collect())
+}
+
+/// Rebuild bullet trees from `(id, parent_id, bullet)` rows ordered by id.
+/// Bullets whose parent wasn't loaded are kept at the top level.
+pub(crate) fn nest_bullets(rows: Vec<(i64, Option<i64>, Bullet)>) -> Vec<Bullet> {
+    let ids: std::collections::HashSet<i64> = rows.iter().map(|(id, _, _)| *id).collect();
+    let mut children: HashMap<i64, Vec<Bullet>> = HashMap::new();
+    let mut roots = Vec::new();
+
+    // Children always come after their parent, so walking backwards finishes
+    // every subtree before its parent is reached
+    for (id, parent_id, mut bullet) in rows.into_iter().rev() {
+        if let Some(mut nested) = children.remove(&id) {
+            nested.reverse();
+            bullet.children = nested;
+        }
+        match parent_id.filter(|p| ids.contains(p)) {
+            Some(parent_id) => children.entry(parent_id).or_default().push(bullet),
+            None => roots.push(bullet),
+        }
+    }
+
+    roots.reverse();
+    roots
+}
+
+/// Comma-separated list of `n` SQL parameter placeholders
+pub(crate) fn placeholders(n: usize) -> String {
+    vec!["?"; n].join(", ")
+}
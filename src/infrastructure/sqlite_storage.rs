@@ -0,0 +1,1615 @@
+use crate::entities::{
+    Bullet, BulletHit, BulletType, CrossReference, DateRange, Entry, EntryTimestamps,
+    ExternalEvent, Link, MetricValue, MoodCheckIn, MoodPeriod, PendingTask, PeriodStats,
+    PersonSummary, TaskState,
+};
+use crate::infrastructure::Query;
+use crate::infrastructure::migrations::{
+    MIGRATIONS_FINGERPRINT_KEY, MIGRATIONS_TABLE_SQL, Migration, MigrationStatus, SqlDialect,
+    discover_migrations, migrations_fingerprint,
+};
+use crate::infrastructure::repository::EntryRepository;
+use crate::infrastructure::sql::*;
+use crate::infrastructure::storage::{JournalStorage, TableFormat, query_by_search};
+use anyhow::{Context, Result};
+use chrono::{Local, NaiveDate, NaiveDateTime};
+use rusqlite::functions::FunctionFlags;
+use rusqlite::{Connection, OptionalExtension, Row, params, params_from_iter};
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing::{debug, info};
+
+/// Storage for environments where DuckDB is too heavy, built with the
+/// `sqlite` feature. Runs the statements in `sql` that DuckDB runs too,
+/// applying the overrides in `migrations/sqlite/` for schema SQLite can't run.
+pub struct SqliteStorage {
+    conn: Mutex<Connection>,
+}
+
+impl SqliteStorage {
+    pub fn new<P: AsRef<Path>>(db_path: P) -> Result<Self> {
+        let db_path = db_path.as_ref();
+
+        // Create parent directory if it doesn't exist
+        if let Some(parent) = db_path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+        }
+
+        let conn = Connection::open(db_path)?;
+        debug!("SQLite connection opened");
+
+        Self::from_connection(conn)
+    }
+
+    /// Open a throwaway in-memory database, mainly for tests
+    pub fn in_memory() -> Result<Self> {
+        let conn = Connection::open_in_memory()?;
+        debug!("In-memory SQLite connection opened");
+
+        Self::from_connection(conn)
+    }
+
+    fn from_connection(conn: Connection) -> Result<Self> {
+        // SQLite has no regexp_extract_all, so words are counted in Rust
+        conn.create_scalar_function(
+            "word_count",
+            1,
+            FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC,
+            |ctx| {
+                let content: String = ctx.get(0)?;
+                Ok(content.split_whitespace().count() as i64)
+            },
+        )
+        .context("Failed to register word_count function")?;
+
+        let storage = Self {
+            conn: Mutex::new(conn),
+        };
+        storage.initialize()?;
+        info!("SQLite storage initialized successfully");
+        Ok(storage)
+    }
+}
+
+impl JournalStorage for SqliteStorage {
+    fn initialize(&self) -> Result<()> {
+        debug!("Setting up migration system");
+        self.set_up_migration_system()?;
+        let fingerprint = migrations_fingerprint(SqlDialect::Sqlite);
+        if self.recorded_fingerprint()?.as_deref() == Some(fingerprint.as_str()) {
+            debug!("Migrations unchanged since the last run, skipping");
+            return Ok(());
+        }
+        debug!("Running migrations");
+        self.run_migrations()?;
+        self.record_fingerprint(&fingerprint)?;
+        debug!("Storage initialization complete");
+        Ok(())
+    }
+
+    fn backend_info(&self) -> &str {
+        "SQLite Storage Backend v1.0"
+    }
+
+    fn migration_status(&self) -> Result<MigrationStatus> {
+        let available = discover_migrations(SqlDialect::Sqlite)?
+            .into_iter()
+            .map(|m| m.version)
+            .collect();
+        let mut applied: Vec<i32> = self.get_applied_migrations()?.into_iter().collect();
+        applied.sort();
+
+        Ok(MigrationStatus { applied, available })
+    }
+
+    fn maintenance(&self) -> Result<()> {
+        debug!("Starting database maintenance operations");
+        let conn = self.conn.lock().unwrap();
+        conn.execute_batch("VACUUM; ANALYZE;")
+            .context("Failed to perform maintenance operations")?;
+        info!("Database maintenance completed successfully");
+        Ok(())
+    }
+
+    fn load_entry(&self, date: NaiveDate) -> Result<Option<Entry>> {
+        debug!("Loading entry for date: {}", date);
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare(ENTRY_BULLETS_SQL)
+            .context("Failed to prepare select statement")?;
+
+        let date_str = date.format("%Y-%m-%d").to_string();
+        debug!("Querying bullets for date: {}", date_str);
+        let rows = stmt
+            .query_map(params![date_str], |row| bullet_row(row, 0))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let entry = entry_from_rows(date, rows);
+        match &entry {
+            Some(entry) => debug!(
+                "Loaded entry for {} with {} bullets",
+                date,
+                entry.total_bullets()
+            ),
+            None => debug!("No bullets found for date: {}", date),
+        }
+        Ok(entry)
+    }
+
+    fn load_entries(&self, range: DateRange) -> Result<Vec<Entry>> {
+        debug!(
+            "Loading entries for range: {} to {}",
+            range.start(),
+            range.end()
+        );
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare(RANGE_BULLETS_SQL)
+            .context("Failed to prepare select statement")?;
+
+        let rows = stmt
+            .query_map(
+                params![
+                    range.start().format("%Y-%m-%d").to_string(),
+                    range.end().format("%Y-%m-%d").to_string()
+                ],
+                |row| Ok((row.get::<_, String>(0)?, bullet_row(row, 1)?)),
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let entries = entries_from_rows(rows)?;
+        debug!(
+            "Loaded {} entries in range {} to {}",
+            entries.len(),
+            range.start(),
+            range.end()
+        );
+        Ok(entries)
+    }
+
+    fn list_dates(&self, range: DateRange) -> Result<Vec<NaiveDate>> {
+        debug!(
+            "Listing dates for range: {} to {}",
+            range.start(),
+            range.end()
+        );
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare(LIST_DATES_SQL)
+            .context("Failed to prepare select statement")?;
+
+        let rows = stmt.query_map(
+            params![
+                range.start().format("%Y-%m-%d").to_string(),
+                range.end().format("%Y-%m-%d").to_string()
+            ],
+            |row| {
+                let date_str: String = row.get(0)?;
+                Ok(date_str)
+            },
+        )?;
+
+        let mut dates = Vec::new();
+        for date_str in rows {
+            dates.push(parse_stored_date(&date_str?)?);
+        }
+
+        debug!(
+            "Found {} dates in range {} to {}",
+            dates.len(),
+            range.start(),
+            range.end()
+        );
+        Ok(dates)
+    }
+
+    fn save_entry(&self, entry: &Entry) -> Result<()> {
+        // The day's rows are deleted and reinserted, so a failure halfway
+        // must not leave the entry empty
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction().context("Failed to start transaction")?;
+        write_entry(&tx, entry)?;
+        tx.commit().context("Failed to commit entry")?;
+        Ok(())
+    }
+
+    fn save_entries(&self, entries: &[Entry]) -> Result<()> {
+        debug!("Saving {} entries in one transaction", entries.len());
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction().context("Failed to start transaction")?;
+        for entry in entries {
+            write_entry(&tx, entry)?;
+        }
+        tx.commit().context("Failed to commit entries")?;
+        Ok(())
+    }
+
+    fn delete_entry(&self, date: NaiveDate) -> Result<()> {
+        debug!("Deleting entry for date: {}", date);
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction().context("Failed to start transaction")?;
+        let date_str = date.format("%Y-%m-%d").to_string();
+
+        let affected_rows = tx
+            .execute(DELETE_BULLETS_SQL, params![date_str])
+            .context("Failed to delete entry")?;
+        for table in DERIVED_TABLES {
+            tx.execute(
+                &format!("DELETE FROM {} WHERE date = ?", table),
+                params![date_str],
+            )
+            .with_context(|| format!("Failed to delete {}", table))?;
+        }
+        tx.execute(BUMP_VERSION_SQL, params![date_str])
+            .context("Failed to bump entry version")?;
+        tx.execute(FORGET_CREATED_SQL, params![date_str])
+            .context("Failed to clear entry timestamps")?;
+
+        tx.commit().context("Failed to commit entry deletion")?;
+        info!("Deleted {} bullets for date: {}", affected_rows, date);
+        Ok(())
+    }
+
+    fn entry_version(&self, date: NaiveDate) -> Result<u64> {
+        let conn = self.conn.lock().unwrap();
+        let version: Option<i64> = conn
+            .query_row(
+                ENTRY_VERSION_SQL,
+                params![date.format("%Y-%m-%d").to_string()],
+                |row| row.get(0),
+            )
+            .optional()
+            .context("Failed to load entry version")?;
+        Ok(version.unwrap_or(0) as u64)
+    }
+
+    fn change_watermark(&self) -> Result<u64> {
+        let conn = self.conn.lock().unwrap();
+        let watermark: i64 = conn
+            .query_row(CHANGE_WATERMARK_SQL, [], |row| row.get(0))
+            .context("Failed to load change watermark")?;
+        Ok(watermark as u64)
+    }
+
+    fn entry_timestamps(&self, date: NaiveDate) -> Result<Option<EntryTimestamps>> {
+        let conn = self.conn.lock().unwrap();
+        let row: Option<(String, String)> = conn
+            .query_row(
+                "SELECT created_at, updated_at FROM entry_versions \
+                 WHERE date = ? AND created_at IS NOT NULL",
+                params![date.format("%Y-%m-%d").to_string()],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()
+            .context("Failed to load entry timestamps")?;
+        row.map(|(created_at, updated_at)| {
+            Ok(EntryTimestamps {
+                created_at: NaiveDateTime::parse_from_str(&created_at, TIMESTAMP_FORMAT)?,
+                updated_at: NaiveDateTime::parse_from_str(&updated_at, TIMESTAMP_FORMAT)?,
+            })
+        })
+        .transpose()
+    }
+
+    fn search_entries(&self, query: &str) -> Result<Vec<Entry>> {
+        debug!("Searching entries with query: '{}'", query);
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare("SELECT DISTINCT date FROM bullets WHERE content LIKE ? ORDER BY date DESC")
+            .context("Failed to prepare search statement")?;
+
+        let rows = stmt.query_map(params![format!("%{}%", query)], |row| {
+            let date_str: String = row.get(0)?;
+            Ok(date_str)
+        })?;
+
+        let mut entries = Vec::new();
+        for date_str in rows {
+            let date = parse_stored_date(&date_str?)?;
+            if let Some(entry) = self.load_entry(date)? {
+                entries.push(entry);
+            }
+        }
+
+        info!("Search for '{}' returned {} entries", query, entries.len());
+        Ok(entries)
+    }
+
+    fn search_bullets(
+        &self,
+        query: &str,
+        types: &[BulletType],
+        states: &[TaskState],
+        range: Option<DateRange>,
+    ) -> Result<Vec<BulletHit>> {
+        debug!(
+            "Searching bullets for '{}' (types: {:?}, states: {:?}, range: {:?})",
+            query, types, states, range
+        );
+        let mut sql = format!(
+            "SELECT {} FROM bullets WHERE content LIKE ? AND NOT COALESCE(body, FALSE)",
+            HIT_COLUMNS
+        );
+        let mut values = vec![format!("%{}%", query)];
+
+        if !types.is_empty() {
+            sql.push_str(&format!(" AND type IN ({})", placeholders(types.len())));
+            values.extend(types.iter().map(|t| t.to_string()));
+        }
+        if !states.is_empty() {
+            sql.push_str(&format!(
+                " AND task_state IN ({})",
+                placeholders(states.len())
+            ));
+            values.extend(states.iter().map(|s| s.to_string()));
+        }
+        if let Some(range) = &range {
+            sql.push_str(" AND date BETWEEN ? AND ?");
+            values.push(range.start().format("%Y-%m-%d").to_string());
+            values.push(range.end().format("%Y-%m-%d").to_string());
+        }
+        sql.push_str(" ORDER BY date, id");
+
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare(&sql)
+            .context("Failed to prepare bullet search statement")?;
+
+        let rows = stmt.query_map(params_from_iter(values.iter()), |row| hit_row(row, 0))?;
+
+        let mut hits = Vec::new();
+        for row in rows {
+            hits.extend(hit_from_row(row?)?);
+        }
+
+        info!("Bullet search for '{}' returned {} hits", query, hits.len());
+        Ok(hits)
+    }
+
+    fn query_bullets(&self, query: &Query) -> Result<Vec<BulletHit>> {
+        query_by_search(self, query)
+    }
+
+    fn bullet_ids(&self, date: NaiveDate, bullet_type: &BulletType) -> Result<Vec<i64>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare(BULLET_IDS_SQL)
+            .context("Failed to prepare bullet id query")?;
+        let rows = stmt.query_map(
+            params![date.format("%Y-%m-%d").to_string(), bullet_type.to_string()],
+            |row| row.get(0),
+        )?;
+        rows.collect::<Result<Vec<i64>, _>>()
+            .context("Failed to read bullet ids")
+    }
+
+    fn load_bullet(&self, id: i64) -> Result<Option<(NaiveDate, Bullet)>> {
+        let conn = self.conn.lock().unwrap();
+        let row = conn
+            .query_row(LOAD_BULLET_SQL, params![id], |row| hit_row(row, 0))
+            .optional()
+            .context("Failed to load bullet")?;
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        Ok(hit_from_row(row)?.map(|hit| (hit.date, hit.bullet)))
+    }
+
+    fn convert_bullet(&self, id: i64, bullet_type: BulletType) -> Result<()> {
+        debug!("Converting bullet {} to {}", id, bullet_type);
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction().context("Failed to start transaction")?;
+        let date_str =
+            bullet_date(&tx, id)?.with_context(|| format!("No bullet with id {}", id))?;
+        tx.execute(
+            &convert_bullet_sql(bullet_type),
+            params![bullet_type.to_string(), id],
+        )
+        .context("Failed to convert bullet")?;
+        touch_entry(&tx, &date_str)?;
+        tx.commit().context("Failed to commit bullet conversion")
+    }
+
+    fn set_task_states(&self, ids: &[i64], task_state: TaskState) -> Result<()> {
+        debug!("Setting {} tasks to {}", ids.len(), task_state);
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction().context("Failed to start transaction")?;
+        let mut dates = BTreeSet::new();
+        for id in ids {
+            let changed = tx
+                .execute(SET_TASK_STATE_SQL, params![task_state.to_string(), id])
+                .context("Failed to update task state")?;
+            if changed == 0 {
+                anyhow::bail!("No task with id {}", id);
+            }
+            dates.extend(bullet_date(&tx, *id)?);
+        }
+        for date_str in &dates {
+            touch_entry(&tx, date_str)?;
+        }
+        tx.commit().context("Failed to commit task states")
+    }
+
+    fn pending_tasks_before(&self, before: NaiveDate) -> Result<Vec<BulletHit>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare(PENDING_TASKS_BEFORE_SQL)
+            .context("Failed to prepare pending task query")?;
+        let rows = stmt.query_map(params![before.format("%Y-%m-%d").to_string()], |row| {
+            hit_row(row, 0)
+        })?;
+
+        let mut tasks = Vec::new();
+        for row in rows {
+            tasks.extend(hit_from_row(row?)?);
+        }
+        Ok(tasks)
+    }
+
+    fn pending_tasks(&self) -> Result<Vec<PendingTask>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare(PENDING_TASKS_SQL)
+            .context("Failed to prepare pending task query")?;
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, hit_row(row, 1)?)))?;
+
+        let mut tasks = Vec::new();
+        for row in rows {
+            let (id, row) = row?;
+            if let Some(BulletHit { date, bullet }) = hit_from_row(row)? {
+                tasks.push(PendingTask {
+                    id: Some(id),
+                    date,
+                    bullet,
+                });
+            }
+        }
+        Ok(tasks)
+    }
+
+    fn task_dependencies(&self, date: NaiveDate) -> Result<Vec<(i64, String, String)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare(TASK_DEPENDENCIES_SQL)
+            .context("Failed to prepare task dependency query")?;
+        let rows = stmt.query_map(params![date.format("%Y-%m-%d").to_string()], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+        })?;
+        rows.collect::<Result<_, _>>()
+            .context("Failed to load task dependencies")
+    }
+
+    fn update_bullet(&self, id: i64, content: &str, task_state: Option<TaskState>) -> Result<()> {
+        debug!("Updating bullet {} (state: {:?})", id, task_state);
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction().context("Failed to start transaction")?;
+
+        let date_str =
+            bullet_date(&tx, id)?.with_context(|| format!("No bullet with id {}", id))?;
+        tx.execute(
+            UPDATE_BULLET_SQL,
+            params![content, task_state.map(|s| s.to_string()), id],
+        )
+        .context("Failed to update bullet")?;
+        touch_entry(&tx, &date_str)?;
+
+        // Derived rows depend only on the content, so the type doesn't matter
+        let bullet = Bullet::new(content, BulletType::Note);
+        let mut link_titles: HashMap<String, String> = HashMap::new();
+        {
+            let mut stmt = tx
+                .prepare(BULLET_LINK_TITLES_SQL)
+                .context("Failed to prepare link title query")?;
+            let rows = stmt.query_map(params![id], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?;
+            for row in rows {
+                let (url, title) = row?;
+                link_titles.insert(url, title);
+            }
+        }
+        for table in DERIVED_TABLES {
+            tx.execute(
+                &format!("DELETE FROM {} WHERE bullet_id = ?", table),
+                params![id],
+            )
+            .with_context(|| format!("Failed to clear {} of bullet", table))?;
+        }
+        for name in bullet.mentions() {
+            tx.execute(INSERT_MENTION_SQL, params![id, date_str, name])
+                .context("Failed to insert mention")?;
+        }
+        for name in bullet.contexts() {
+            tx.execute(INSERT_CONTEXT_SQL, params![id, date_str, name])
+                .context("Failed to insert context")?;
+        }
+        for (name, value) in bullet.metrics() {
+            tx.execute(INSERT_METRIC_SQL, params![id, date_str, name, value])
+                .context("Failed to insert metric")?;
+        }
+        for blocker in bullet.blockers() {
+            tx.execute(INSERT_DEPENDENCY_SQL, params![id, date_str, blocker])
+                .context("Failed to insert task dependency")?;
+        }
+        for url in bullet.urls() {
+            tx.execute(
+                INSERT_LINK_SQL,
+                params![id, date_str, url, link_titles.get(&url)],
+            )
+            .context("Failed to insert link")?;
+        }
+
+        tx.commit().context("Failed to commit bullet update")
+    }
+
+    fn list_people(&self) -> Result<Vec<PersonSummary>> {
+        debug!("Listing mentioned people");
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare(LIST_PEOPLE_SQL)
+            .context("Failed to prepare people query")?;
+
+        let rows = stmt.query_map([], |row| {
+            let name: String = row.get(0)?;
+            let mentions: i64 = row.get(1)?;
+            let last_seen: String = row.get(2)?;
+            Ok((name, mentions, last_seen))
+        })?;
+
+        let mut people = Vec::new();
+        for row in rows {
+            let (name, mentions, last_seen) = row?;
+            people.push(PersonSummary {
+                name,
+                mentions: mentions as u64,
+                last_seen: parse_stored_date(&last_seen)?,
+            });
+        }
+        Ok(people)
+    }
+
+    fn list_contexts(&self) -> Result<Vec<String>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare(LIST_CONTEXTS_SQL)
+            .context("Failed to prepare contexts query")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    fn list_metrics(&self) -> Result<Vec<String>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare(LIST_METRICS_SQL)
+            .context("Failed to prepare metrics query")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    fn metric_values(&self, name: &str, range: Option<DateRange>) -> Result<Vec<MetricValue>> {
+        debug!("Loading metric '{}' (range: {:?})", name, range);
+        let conn = self.conn.lock().unwrap();
+        let mut sql = String::from(METRIC_VALUES_SQL);
+        let mut values: Vec<String> = vec![name.trim_start_matches('#').to_lowercase()];
+        if let Some(range) = &range {
+            sql.push_str(" AND date BETWEEN ? AND ?");
+            values.push(range.start().format("%Y-%m-%d").to_string());
+            values.push(range.end().format("%Y-%m-%d").to_string());
+        }
+        sql.push_str(" ORDER BY date, bullet_id");
+
+        let mut stmt = conn
+            .prepare(&sql)
+            .context("Failed to prepare metric query")?;
+        let rows = stmt.query_map(params_from_iter(values.iter()), |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, f64>(1)?))
+        })?;
+
+        let mut readings = Vec::new();
+        for row in rows {
+            let (date_str, value) = row?;
+            readings.push(MetricValue {
+                date: parse_stored_date(&date_str)?,
+                value,
+            });
+        }
+        Ok(readings)
+    }
+
+    fn find_bullets_mentioning(&self, name: &str) -> Result<Vec<BulletHit>> {
+        debug!("Finding bullets mentioning '{}'", name);
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare(BULLETS_MENTIONING_SQL)
+            .context("Failed to prepare mention query")?;
+
+        let rows = stmt.query_map(params![name.to_lowercase()], |row| hit_row(row, 0))?;
+
+        let mut hits = Vec::new();
+        for row in rows {
+            hits.extend(hit_from_row(row?)?);
+        }
+        Ok(hits)
+    }
+
+    fn list_links(&self, range: Option<DateRange>) -> Result<Vec<Link>> {
+        debug!("Listing links (range: {:?})", range);
+        let conn = self.conn.lock().unwrap();
+        let mut sql = String::from(LIST_LINKS_SQL);
+        let mut values: Vec<String> = Vec::new();
+        if let Some(range) = &range {
+            sql.push_str(" WHERE l.date BETWEEN ? AND ?");
+            values.push(range.start().format("%Y-%m-%d").to_string());
+            values.push(range.end().format("%Y-%m-%d").to_string());
+        }
+        sql.push_str(" ORDER BY l.date, b.id");
+
+        let mut stmt = conn.prepare(&sql).context("Failed to prepare link query")?;
+        let rows = stmt.query_map(params_from_iter(values.iter()), |row| {
+            let date_str: String = row.get(0)?;
+            let url: String = row.get(1)?;
+            let title: Option<String> = row.get(2)?;
+            let content: String = row.get(3)?;
+            Ok((date_str, url, title, content))
+        })?;
+
+        let mut links = Vec::new();
+        for row in rows {
+            let (date_str, url, title, content) = row?;
+            links.push(Link {
+                date: parse_stored_date(&date_str)?,
+                url,
+                title,
+                content,
+            });
+        }
+        Ok(links)
+    }
+
+    fn set_link_title(&self, url: &str, title: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(SET_LINK_TITLE_SQL, params![title, url])
+            .context("Failed to update link title")?;
+        Ok(())
+    }
+
+    fn daily_bullet_counts(&self, range: DateRange) -> Result<HashMap<NaiveDate, u64>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare(DAILY_BULLET_COUNTS_SQL)
+            .context("Failed to prepare daily count query")?;
+        let rows = stmt.query_map(
+            params![
+                range.start().format("%Y-%m-%d").to_string(),
+                range.end().format("%Y-%m-%d").to_string()
+            ],
+            |row| {
+                let date_str: String = row.get(0)?;
+                let count: i64 = row.get(1)?;
+                Ok((date_str, count))
+            },
+        )?;
+
+        let mut counts = HashMap::new();
+        for row in rows {
+            let (date_str, count) = row?;
+            counts.insert(parse_stored_date(&date_str)?, count as u64);
+        }
+        Ok(counts)
+    }
+
+    fn period_stats(&self, range: DateRange) -> Result<PeriodStats> {
+        let conn = self.conn.lock().unwrap();
+        let (days, completed, pending, events, words): (i64, i64, i64, i64, i64) = conn
+            .query_row(
+                "SELECT
+                    COUNT(DISTINCT date),
+                    COUNT(*) FILTER (WHERE type IN ('task', 'priority') AND task_state = 'completed'),
+                    COUNT(*) FILTER (WHERE type IN ('task', 'priority') AND task_state = 'pending'),
+                    COUNT(*) FILTER (WHERE type = 'event'),
+                    COALESCE(SUM(word_count(content)), 0)
+                 FROM bullets WHERE date BETWEEN ? AND ?",
+                params![
+                    range.start().format("%Y-%m-%d").to_string(),
+                    range.end().format("%Y-%m-%d").to_string()
+                ],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
+            )
+            .context("Failed to compute period stats")?;
+
+        Ok(PeriodStats {
+            days_with_entries: days as u64,
+            tasks_completed: completed as u64,
+            tasks_pending: pending as u64,
+            events: events as u64,
+            words: words as u64,
+        })
+    }
+
+    fn count_entries(&self) -> Result<u64> {
+        debug!("Counting total entries");
+        let conn = self.conn.lock().unwrap();
+        let count: i64 = conn.query_row(COUNT_ENTRIES_SQL, [], |row| row.get(0))?;
+
+        debug!("Total entries count: {}", count);
+        Ok(count as u64)
+    }
+
+    fn entry_date_span(&self) -> Result<Option<(NaiveDate, NaiveDate)>> {
+        debug!("Querying first and last entry dates");
+        let conn = self.conn.lock().unwrap();
+        let (first, last): (Option<String>, Option<String>) =
+            conn.query_row(ENTRY_DATE_SPAN_SQL, [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })?;
+
+        match (first, last) {
+            (Some(first), Some(last)) => Ok(Some((
+                parse_stored_date(&first)?,
+                parse_stored_date(&last)?,
+            ))),
+            _ => Ok(None),
+        }
+    }
+
+    fn adjacent_entry_date(&self, date: NaiveDate, forward: bool) -> Result<Option<NaiveDate>> {
+        let sql = if forward {
+            NEXT_ENTRY_DATE_SQL
+        } else {
+            PREVIOUS_ENTRY_DATE_SQL
+        };
+        let conn = self.conn.lock().unwrap();
+        let adjacent: Option<String> =
+            conn.query_row(sql, params![date.format("%Y-%m-%d").to_string()], |row| {
+                row.get(0)
+            })?;
+        adjacent.as_deref().map(parse_stored_date).transpose()
+    }
+
+    fn find_entries_with_tasks(&self, range: DateRange) -> Result<Vec<Entry>> {
+        self.find_entries_by_type("task", range)
+    }
+
+    fn find_entries_with_events(&self, range: DateRange) -> Result<Vec<Entry>> {
+        self.find_entries_by_type("event", range)
+    }
+
+    fn find_entries_with_priorities(&self, range: DateRange) -> Result<Vec<Entry>> {
+        self.find_entries_by_type("priority", range)
+    }
+
+    // TODO probably will want to do something with this!
+    fn refresh_metadata(&self, _date: NaiveDate, _entry: &Entry) -> Result<()> {
+        Ok(())
+    }
+
+    fn record_entry_save(
+        &self,
+        date: NaiveDate,
+        saved_at: NaiveDateTime,
+        word_count: usize,
+        word_delta: i64,
+    ) -> Result<()> {
+        debug!(
+            "Recording save for date: {} ({} words, delta {})",
+            date, word_count, word_delta
+        );
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            INSERT_ENTRY_SAVE_SQL,
+            params![
+                date.format("%Y-%m-%d").to_string(),
+                saved_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+                word_count as i64,
+                word_delta
+            ],
+        )
+        .context("Failed to record entry save")?;
+        Ok(())
+    }
+
+    fn save_counts_by_hour(&self) -> Result<Vec<(u32, u64)>> {
+        debug!("Counting entry saves by hour of day");
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare("SELECT CAST(strftime('%H', saved_at) AS INTEGER) AS h, COUNT(*) FROM entry_saves GROUP BY h ORDER BY h")
+            .context("Failed to prepare save hour query")?;
+
+        let rows = stmt.query_map([], |row| {
+            let hour: i64 = row.get(0)?;
+            let count: i64 = row.get(1)?;
+            Ok((hour as u32, count as u64))
+        })?;
+
+        let mut counts = Vec::new();
+        for row in rows {
+            counts.push(row?);
+        }
+        Ok(counts)
+    }
+
+    fn record_focus_session(
+        &self,
+        date: NaiveDate,
+        task: &str,
+        started_at: NaiveDateTime,
+        duration_seconds: u64,
+        completed: bool,
+    ) -> Result<()> {
+        debug!(
+            "Recording {}s focus session on '{}' for date: {}",
+            duration_seconds, task, date
+        );
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            INSERT_FOCUS_SESSION_SQL,
+            params![
+                date.format("%Y-%m-%d").to_string(),
+                task,
+                started_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+                duration_seconds as i64,
+                completed
+            ],
+        )
+        .context("Failed to record focus session")?;
+        Ok(())
+    }
+
+    fn focus_time_by_task(&self, range: DateRange) -> Result<Vec<(String, u64)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare(FOCUS_TIME_BY_TASK_SQL)
+            .context("Failed to prepare focus time query")?;
+        let rows = stmt.query_map(
+            params![
+                range.start().format("%Y-%m-%d").to_string(),
+                range.end().format("%Y-%m-%d").to_string()
+            ],
+            |row| {
+                let task: String = row.get(0)?;
+                let seconds: i64 = row.get(1)?;
+                Ok((task, seconds as u64))
+            },
+        )?;
+
+        let mut totals = Vec::new();
+        for row in rows {
+            totals.push(row?);
+        }
+        Ok(totals)
+    }
+
+    fn replace_external_events(&self, source: &str, events: &[ExternalEvent]) -> Result<()> {
+        debug!("Replacing {} external events from {}", events.len(), source);
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction().context("Failed to start transaction")?;
+
+        tx.execute(DELETE_EXTERNAL_EVENTS_SQL, params![source])
+            .context("Failed to delete previous external events")?;
+        {
+            let mut stmt = tx
+                .prepare(INSERT_EXTERNAL_EVENT_SQL)
+                .context("Failed to prepare external event insert")?;
+            for event in events {
+                stmt.execute(params![
+                    source,
+                    event.uid,
+                    event.date.format("%Y-%m-%d").to_string(),
+                    event.start.map(|t| t.format("%H:%M:%S").to_string()),
+                    event.end.map(|t| t.format("%H:%M:%S").to_string()),
+                    event.summary,
+                    event.location
+                ])
+                .context("Failed to insert external event")?;
+            }
+        }
+
+        tx.commit().context("Failed to commit external events")?;
+        Ok(())
+    }
+
+    fn load_external_events(&self, date: NaiveDate) -> Result<Vec<ExternalEvent>> {
+        debug!("Loading external events for date: {}", date);
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare(EXTERNAL_EVENTS_SQL)
+            .context("Failed to prepare external events query")?;
+
+        let rows = stmt.query_map(params![date.format("%Y-%m-%d").to_string()], |row| {
+            let uid: Option<String> = row.get(0)?;
+            let start: Option<String> = row.get(1)?;
+            let end: Option<String> = row.get(2)?;
+            let summary: String = row.get(3)?;
+            let location: Option<String> = row.get(4)?;
+            Ok((uid, start, end, summary, location))
+        })?;
+
+        let mut events = Vec::new();
+        for row in rows {
+            let (uid, start, end, summary, location) = row?;
+            events.push(ExternalEvent {
+                uid,
+                date,
+                start: stored_time(start),
+                end: stored_time(end),
+                summary,
+                location,
+            });
+        }
+        Ok(events)
+    }
+
+    fn set_pinned(&self, date: NaiveDate, pinned: bool) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let sql = if pinned { PIN_SQL } else { UNPIN_SQL };
+        conn.execute(sql, params![date.format("%Y-%m-%d").to_string()])
+            .context("Failed to update pin")?;
+        Ok(())
+    }
+
+    fn pinned_dates(&self) -> Result<Vec<NaiveDate>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare(PINNED_DATES_SQL)
+            .context("Failed to prepare pins query")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+
+        let mut dates = Vec::new();
+        for date_str in rows {
+            dates.push(parse_stored_date(&date_str?)?);
+        }
+        Ok(dates)
+    }
+
+    fn record_mood(&self, date: NaiveDate, period: MoodPeriod, score: u8) -> Result<()> {
+        debug!("Recording {} mood {} for date: {}", period, score, date);
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            RECORD_MOOD_SQL,
+            params![
+                date.format("%Y-%m-%d").to_string(),
+                period.to_string(),
+                score as i64
+            ],
+        )
+        .context("Failed to record mood")?;
+        Ok(())
+    }
+
+    fn load_moods(&self, range: DateRange) -> Result<Vec<MoodCheckIn>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare(MOODS_SQL)
+            .context("Failed to prepare moods query")?;
+        let rows = stmt.query_map(
+            params![
+                range.start().format("%Y-%m-%d").to_string(),
+                range.end().format("%Y-%m-%d").to_string()
+            ],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, i64>(2)?,
+                ))
+            },
+        )?;
+
+        let mut moods = Vec::new();
+        for row in rows {
+            let (date_str, period, score) = row?;
+            let Ok(period) = period.parse() else {
+                continue;
+            };
+            moods.push(MoodCheckIn {
+                date: parse_stored_date(&date_str)?,
+                period,
+                score: score as u8,
+            });
+        }
+        Ok(moods)
+    }
+
+    fn record_cross_reference(&self, reference: &CrossReference) -> Result<()> {
+        debug!(
+            "Recording follow-up on {} of {}",
+            reference.date, reference.target_date
+        );
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            RECORD_CROSS_REFERENCE_SQL,
+            params![
+                reference.date.format("%Y-%m-%d").to_string(),
+                reference.content,
+                reference.target_date.format("%Y-%m-%d").to_string(),
+                reference.target_content
+            ],
+        )
+        .context("Failed to record cross reference")?;
+        Ok(())
+    }
+
+    fn load_cross_references(&self, date: NaiveDate) -> Result<Vec<CrossReference>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare(CROSS_REFERENCES_SQL)
+            .context("Failed to prepare cross references query")?;
+        let rows = stmt.query_map(params![date.format("%Y-%m-%d").to_string()], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?;
+
+        let mut references = Vec::new();
+        for row in rows {
+            let (content, target_date, target_content) = row?;
+            references.push(CrossReference {
+                date,
+                content,
+                target_date: parse_stored_date(&target_date)?,
+                target_content,
+            });
+        }
+        Ok(references)
+    }
+
+    fn record_prompt_shown(&self, prompt: &str, date: NaiveDate) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            RECORD_PROMPT_SQL,
+            params![prompt, date.format("%Y-%m-%d").to_string()],
+        )
+        .context("Failed to record prompt")?;
+        Ok(())
+    }
+
+    fn prompts_shown_since(&self, since: NaiveDate) -> Result<Vec<String>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare(PROMPTS_SINCE_SQL)
+            .context("Failed to prepare prompt history query")?;
+        let rows = stmt.query_map(params![since.format("%Y-%m-%d").to_string()], |row| {
+            row.get::<_, String>(0)
+        })?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    fn record_migration_review(&self, week_start: NaiveDate) -> Result<bool> {
+        let conn = self.conn.lock().unwrap();
+        let inserted = conn
+            .execute(
+                RECORD_MIGRATION_REVIEW_SQL,
+                params![week_start.format("%Y-%m-%d").to_string()],
+            )
+            .context("Failed to record migration review")?;
+        Ok(inserted > 0)
+    }
+
+    fn export_tables(
+        &self,
+        _dir: &Path,
+        format: TableFormat,
+        _range: Option<DateRange>,
+    ) -> Result<Vec<PathBuf>> {
+        anyhow::bail!(
+            "{} exports of the raw tables need storage_backend = \"duckdb\"",
+            format
+        )
+    }
+}
+
+impl SqliteStorage {
+    fn set_up_migration_system(&self) -> Result<()> {
+        debug!("Setting up migration system");
+        let conn = self.conn.lock().unwrap();
+        conn.execute_batch(MIGRATIONS_TABLE_SQL)
+            .context("Failed to create migrations table")?;
+        debug!("Migration system table created/verified");
+        Ok(())
+    }
+
+    fn run_migrations(&self) -> Result<()> {
+        debug!("Running database migrations");
+        let migrations = discover_migrations(SqlDialect::Sqlite)?;
+        let applied = self.get_applied_migrations()?;
+
+        debug!(
+            "Found {} total migrations, {} already applied",
+            migrations.len(),
+            applied.len()
+        );
+
+        for Migration { version, name, sql } in migrations {
+            if !applied.contains(&version) {
+                info!("Applying migration {}: {}", version, name);
+                self.apply_migration(version, &name, &sql)
+                    .with_context(|| format!("Failed to apply migration {}: {}", version, name))?;
+            } else {
+                debug!("Migration {} already applied, skipping", version);
+            }
+        }
+
+        info!("All migrations completed successfully");
+        Ok(())
+    }
+
+    /// Fingerprint stored by the last complete migration run, see
+    /// `migrations_fingerprint`
+    fn recorded_fingerprint(&self) -> Result<Option<String>> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            SCHEMA_STATE_SQL,
+            params![MIGRATIONS_FINGERPRINT_KEY],
+            |row| row.get(0),
+        )
+        .optional()
+        .context("Failed to read schema state")
+    }
+
+    fn record_fingerprint(&self, fingerprint: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            RECORD_SCHEMA_STATE_SQL,
+            params![MIGRATIONS_FINGERPRINT_KEY, fingerprint],
+        )
+        .context("Failed to record schema state")?;
+        Ok(())
+    }
+
+    fn get_applied_migrations(&self) -> Result<std::collections::HashSet<i32>> {
+        debug!("Querying applied migrations from database");
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare(APPLIED_MIGRATIONS_SQL)
+            .context("Failed to prepare migration query")?;
+
+        let rows = stmt.query_map([], |row| {
+            let version: i32 = row.get(0)?;
+            Ok(version)
+        })?;
+
+        let mut applied = std::collections::HashSet::new();
+        for version in rows {
+            let v = version?;
+            applied.insert(v);
+            debug!("Found applied migration version: {}", v);
+        }
+
+        debug!("Total applied migrations: {}", applied.len());
+        Ok(applied)
+    }
+
+    fn apply_migration(&self, version: i32, name: &str, sql_content: &str) -> Result<()> {
+        debug!("Applying migration {} ({})", version, name);
+        let conn = self.conn.lock().unwrap();
+
+        // Execute the migration SQL
+        debug!("Executing migration SQL for {}", name);
+        conn.execute_batch(sql_content)
+            .with_context(|| format!("Failed to execute migration SQL for {}", name))?;
+        debug!("Migration SQL executed successfully for {}", name);
+
+        // Record the migration as applied
+        debug!("Recording migration {} as applied", version);
+        conn.execute(RECORD_MIGRATION_SQL, params![version, name])
+            .with_context(|| format!("Failed to record migration {} as applied", name))?;
+
+        info!("Migration {} ({}) applied successfully", version, name);
+        Ok(())
+    }
+
+    fn find_entries_by_type(&self, bullet_type: &str, range: DateRange) -> Result<Vec<Entry>> {
+        debug!(
+            "Finding entries with bullet type '{}' in range {} to {}",
+            bullet_type,
+            range.start(),
+            range.end()
+        );
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare(DATES_WITH_TYPE_SQL)
+            .context("Failed to prepare type-based query")?;
+
+        let rows = stmt.query_map(
+            params![
+                bullet_type,
+                range.start().format("%Y-%m-%d").to_string(),
+                range.end().format("%Y-%m-%d").to_string()
+            ],
+            |row| {
+                let date_str: String = row.get(0)?;
+                Ok(date_str)
+            },
+        )?;
+
+        let mut entries = Vec::new();
+        for date_str in rows {
+            let date = parse_stored_date(&date_str?)?;
+            if let Some(entry) = self.load_entry(date)? {
+                entries.push(entry);
+            }
+        }
+
+        debug!(
+            "Found {} entries with bullet type '{}'",
+            entries.len(),
+            bullet_type
+        );
+        Ok(entries)
+    }
+}
+
+/// Read the `BulletRow` starting at column `first`
+fn bullet_row(row: &Row, first: usize) -> rusqlite::Result<BulletRow> {
+    Ok((
+        row.get(first)?,
+        row.get(first + 1)?,
+        row.get(first + 2)?,
+        row.get(first + 3)?,
+        row.get(first + 4)?,
+        row.get(first + 5)?,
+        row.get(first + 6)?,
+        row.get(first + 7)?,
+        row.get(first + 8)?,
+    ))
+}
+
+/// Read the `HitRow` starting at column `first`
+fn hit_row(row: &Row, first: usize) -> rusqlite::Result<HitRow> {
+    Ok((
+        row.get(first)?,
+        row.get(first + 1)?,
+        row.get(first + 2)?,
+        row.get(first + 3)?,
+        row.get(first + 4)?,
+        row.get(first + 5)?,
+    ))
+}
+
+/// Date of the entry holding bullet `id`, if there is one
+fn bullet_date(conn: &Connection, id: i64) -> Result<Option<String>> {
+    conn.query_row(BULLET_DATE_SQL, params![id], |row| row.get(0))
+        .optional()
+        .context("Failed to look up bullet")
+}
+
+/// Bump the version of the entry on `date_str` and stamp it as edited now
+fn touch_entry(conn: &Connection, date_str: &str) -> Result<()> {
+    conn.execute(BUMP_VERSION_SQL, params![date_str])
+        .context("Failed to bump entry version")?;
+    let now = Local::now()
+        .naive_local()
+        .format(TIMESTAMP_FORMAT)
+        .to_string();
+    conn.execute(TOUCH_ENTRY_SQL, params![now, now, date_str])
+        .context("Failed to record entry timestamps")?;
+    Ok(())
+}
+
+/// Replace the bullets stored for `entry`'s date, along with everything
+/// derived from them
+fn write_entry(conn: &Connection, entry: &Entry) -> Result<()> {
+    debug!(
+        "Saving entry for date: {} with {} total bullets",
+        entry.date,
+        entry.total_bullets()
+    );
+    let date_str = entry.date.format("%Y-%m-%d").to_string();
+
+    // Keep titles fetched for links that survive the edit
+    let mut link_titles: HashMap<String, String> = HashMap::new();
+    {
+        let mut stmt = conn
+            .prepare(ENTRY_LINK_TITLES_SQL)
+            .context("Failed to prepare link title query")?;
+        let rows = stmt.query_map(params![date_str], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        for row in rows {
+            let (url, title) = row?;
+            link_titles.insert(url, title);
+        }
+    }
+
+    // Delete existing bullets for this date
+    debug!("Deleting existing bullets for date: {}", date_str);
+    conn.execute(DELETE_BULLETS_SQL, params![date_str])
+        .context("Failed to delete existing bullets")?;
+    for table in DERIVED_TABLES {
+        conn.execute(
+            &format!("DELETE FROM {} WHERE date = ?", table),
+            params![date_str],
+        )
+        .with_context(|| format!("Failed to delete existing {}", table))?;
+    }
+    touch_entry(conn, &date_str)?;
+
+    // Insert all bullets for this entry
+    let mut stmt = conn
+        .prepare(INSERT_BULLET_SQL)
+        .context("Failed to prepare insert statement")?;
+    let mut mention_stmt = conn
+        .prepare(INSERT_MENTION_SQL)
+        .context("Failed to prepare mention insert statement")?;
+    let mut context_stmt = conn
+        .prepare(INSERT_CONTEXT_SQL)
+        .context("Failed to prepare context insert statement")?;
+    let mut metric_stmt = conn
+        .prepare(INSERT_METRIC_SQL)
+        .context("Failed to prepare metric insert statement")?;
+    let mut dependency_stmt = conn
+        .prepare(INSERT_DEPENDENCY_SQL)
+        .context("Failed to prepare task dependency insert statement")?;
+    let mut link_stmt = conn
+        .prepare(INSERT_LINK_SQL)
+        .context("Failed to prepare link insert statement")?;
+
+    let mut bullet_count = 0;
+    for (bullet_type, bullets) in &entry.bullets {
+        debug!(
+            "Inserting {} bullets of type: {}",
+            bullets.len(),
+            bullet_type
+        );
+        // Depth-first so parents get lower ids than their children and
+        // siblings keep their order
+        let mut pending: Vec<(&Bullet, Option<i64>)> =
+            bullets.iter().rev().map(|b| (b, None)).collect();
+        while let Some((bullet, parent_id)) = pending.pop() {
+            let task_state_str = bullet.task_state.as_ref().map(|s| s.to_string());
+            debug!(
+                "Inserting bullet: {} (type: {}, state: {:?})",
+                bullet.content, bullet_type, task_state_str
+            );
+            let bullet_id: i64 = stmt
+                .query_row(
+                    params![
+                        date_str,
+                        bullet.content,
+                        bullet_type.to_string(),
+                        task_state_str,
+                        bullet.time.map(|t| t.format("%H:%M:%S").to_string()),
+                        bullet.end_time.map(|t| t.format("%H:%M:%S").to_string()),
+                        parent_id,
+                        bullet.session.map(|t| t.format("%H:%M:%S").to_string())
+                    ],
+                    |row| row.get(0),
+                )
+                .context("Failed to insert bullet")?;
+            bullet_count += 1;
+            pending.extend(bullet.children.iter().rev().map(|c| (c, Some(bullet_id))));
+
+            for name in bullet.mentions() {
+                mention_stmt
+                    .execute(params![bullet_id, date_str, name])
+                    .context("Failed to insert mention")?;
+            }
+
+            for name in bullet.contexts() {
+                context_stmt
+                    .execute(params![bullet_id, date_str, name])
+                    .context("Failed to insert context")?;
+            }
+
+            for (name, value) in bullet.metrics() {
+                metric_stmt
+                    .execute(params![bullet_id, date_str, name, value])
+                    .context("Failed to insert metric")?;
+            }
+
+            for blocker in bullet.blockers() {
+                dependency_stmt
+                    .execute(params![bullet_id, date_str, blocker])
+                    .context("Failed to insert task dependency")?;
+            }
+
+            for url in bullet.urls() {
+                let title = link_titles.get(&url);
+                link_stmt
+                    .execute(params![bullet_id, date_str, url, title])
+                    .context("Failed to insert link")?;
+            }
+        }
+    }
+
+    if let Some(body) = &entry.body {
+        conn.execute(INSERT_BODY_SQL, params![date_str, body])
+            .context("Failed to insert entry body")?;
+    }
+
+    info!(
+        "Successfully saved {} bullets for date: {}",
+        bullet_count, entry.date
+    );
+    Ok(())
+}
+
+// Bridge implementation for backwards compatibility with Journal
+impl EntryRepository for SqliteStorage {
+    fn load(&self, date: NaiveDate) -> Result<Option<Entry>> {
+        self.load_entry(date)
+    }
+
+    fn save(&self, entry: Entry) -> Result<()> {
+        self.save_entry(&entry)
+    }
+
+    fn list_dates(&self, range: DateRange) -> Result<Vec<NaiveDate>> {
+        JournalStorage::list_dates(self, range)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveTime;
+
+    #[test]
+    fn test_entry_round_trip_and_stats() {
+        let storage = SqliteStorage::in_memory().unwrap();
+        let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let mut entry = Entry::new(date);
+        entry.add_bullet(
+            Bullet::new("Plan launch with @sam", BulletType::Task)
+                .at(NaiveTime::from_hms_opt(9, 30, 0).unwrap())
+                .with_child(Bullet::new("Read https://example.com", BulletType::Task)),
+        );
+        entry.add_bullet(Bullet::new("Standup", BulletType::Event));
+        storage.save_entry(&entry).unwrap();
+
+        assert_eq!(storage.load_entry(date).unwrap(), Some(entry.clone()));
+        assert_eq!(storage.list_people().unwrap()[0].name, "sam");
+        assert_eq!(storage.list_links(None).unwrap().len(), 1);
+        assert!(storage.migration_status().unwrap().pending().is_empty());
+
+        let stats = storage.period_stats(DateRange::day(date)).unwrap();
+        assert_eq!(stats.tasks_pending, 2);
+        assert_eq!(stats.events, 1);
+        assert_eq!(stats.words, entry.word_count() as u64);
+    }
+
+    #[test]
+    fn test_entry_body_round_trip() {
+        let storage = SqliteStorage::in_memory().unwrap();
+        let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let mut entry = Entry::new(date);
+        entry.add_bullet(Bullet::new("Standup", BulletType::Event));
+        entry.body = Some("Couldn't sleep.\n\nKept thinking about the move.".to_string());
+        storage.save_entry(&entry).unwrap();
+
+        assert_eq!(storage.load_entry(date).unwrap(), Some(entry.clone()));
+        assert_eq!(
+            storage.load_entries(DateRange::day(date)).unwrap(),
+            vec![entry]
+        );
+        assert!(
+            storage
+                .search_bullets("sleep", &[], &[], None)
+                .unwrap()
+                .is_empty()
+        );
+        assert!(
+            storage
+                .bullet_ids(date, &BulletType::Note)
+                .unwrap()
+                .is_empty()
+        );
+
+        let mut body_only = Entry::new(date);
+        body_only.body = Some("Just thoughts".to_string());
+        storage.save_entry(&body_only).unwrap();
+        assert_eq!(storage.load_entry(date).unwrap(), Some(body_only));
+    }
+
+    #[test]
+    fn test_initialize_skips_current_migrations() {
+        let storage = SqliteStorage::in_memory().unwrap();
+        let fingerprint = migrations_fingerprint(SqlDialect::Sqlite);
+        assert_eq!(storage.recorded_fingerprint().unwrap(), Some(fingerprint));
+
+        storage.initialize().unwrap();
+        assert!(storage.migration_status().unwrap().pending().is_empty());
+    }
+
+    #[test]
+    fn test_change_watermark_grows_with_writes() {
+        let storage = SqliteStorage::in_memory().unwrap();
+        let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        assert_eq!(storage.change_watermark().unwrap(), 0);
+
+        let mut entry = Entry::new(date);
+        entry.add_bullet(Bullet::new("Captured", BulletType::Note));
+        storage.save_entry(&entry).unwrap();
+        let saved = storage.change_watermark().unwrap();
+        assert!(saved > 0);
+
+        storage.delete_entry(date).unwrap();
+        assert!(storage.change_watermark().unwrap() > saved);
+    }
+
+    #[test]
+    fn test_bullet_edits_bump_entry_version() {
+        let storage = SqliteStorage::in_memory().unwrap();
+        let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let mut entry = Entry::new(date);
+        entry.add_bullet(Bullet::new("Book flights", BulletType::Task));
+        storage.save_entry(&entry).unwrap();
+        let id = storage.bullet_ids(date, &BulletType::Task).unwrap()[0];
+
+        let mut version = storage.entry_version(date).unwrap();
+        let mut assert_bumped = || {
+            let bumped = storage.entry_version(date).unwrap();
+            assert!(bumped > version);
+            version = bumped;
+        };
+        storage
+            .update_bullet(id, "Book trains", Some(TaskState::Pending))
+            .unwrap();
+        assert_bumped();
+        storage
+            .set_task_states(&[id], TaskState::Completed)
+            .unwrap();
+        assert_bumped();
+        storage.convert_bullet(id, BulletType::Note).unwrap();
+        assert_bumped();
+    }
+
+    #[test]
+    fn test_adjacent_entry_date() {
+        let storage = SqliteStorage::in_memory().unwrap();
+        let day = |d| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+        for date in [day(1), day(20)] {
+            let mut entry = Entry::new(date);
+            entry.add_bullet(Bullet::new("Wrote", BulletType::Note));
+            storage.save_entry(&entry).unwrap();
+        }
+
+        assert_eq!(
+            storage.adjacent_entry_date(day(10), true).unwrap(),
+            Some(day(20))
+        );
+        assert_eq!(
+            storage.adjacent_entry_date(day(10), false).unwrap(),
+            Some(day(1))
+        );
+        assert_eq!(storage.adjacent_entry_date(day(1), false).unwrap(), None);
+    }
+
+    #[test]
+    fn test_convert_bullet_moves_sub_bullets_along() {
+        let storage = SqliteStorage::in_memory().unwrap();
+        let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let mut entry = Entry::new(date);
+        entry.add_bullet(
+            Bullet::new("Ship the release", BulletType::Task)
+                .with_child(Bullet::new("Write the changelog", BulletType::Task)),
+        );
+        entry.add_bullet(Bullet::new("Deadlines slip", BulletType::Note));
+        storage.save_entry(&entry).unwrap();
+
+        let task_id = storage.bullet_ids(date, &BulletType::Task).unwrap()[0];
+        storage.convert_bullet(task_id, BulletType::Note).unwrap();
+        let note_id = storage.bullet_ids(date, &BulletType::Note).unwrap()[2];
+        storage
+            .convert_bullet(note_id, BulletType::Insight)
+            .unwrap();
+        assert!(storage.convert_bullet(9999, BulletType::Note).is_err());
+
+        let loaded = storage.load_entry(date).unwrap().unwrap();
+        assert!(loaded.get_bullets(&BulletType::Task).is_empty());
+        let notes = loaded.get_bullets(&BulletType::Note);
+        assert_eq!(notes[0].content, "Ship the release");
+        assert_eq!(notes[0].task_state, None);
+        assert_eq!(notes[0].children[0].bullet_type, BulletType::Note);
+        assert_eq!(loaded.get_bullets(&BulletType::Insight)[0].task_state, None);
+        assert_eq!(
+            storage.load_bullet(task_id).unwrap().unwrap().1.content,
+            "Ship the release"
+        );
+    }
+}
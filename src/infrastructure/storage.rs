@@ -1,15 +1,28 @@
-use crate::entities::{DateRange, Entry};
-use anyhow::Result;
-use chrono::NaiveDate;
+use crate::entities::{
+    Bullet, BulletHit, BulletType, CrossReference, DateRange, Entry, EntryTimestamps,
+    ExternalEvent, Link, MetricValue, MoodCheckIn, MoodPeriod, PendingTask, PeriodStats,
+    PersonSummary, TaskState,
+};
+use crate::infrastructure::migrations::MigrationStatus;
+use crate::infrastructure::{EntryRepository, Query};
+use anyhow::{Context, Result};
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 /// Combined storage interface that includes both entry and metadata operations
-pub trait JournalStorage {
+pub trait JournalStorage: Send + Sync {
     /// Initialize the storage backend (create tables, indexes, etc.)
     fn initialize(&self) -> Result<()>;
 
     /// Get storage backend information
     fn backend_info(&self) -> &str;
 
+    /// Schema migrations applied to the database versus known to the binary
+    fn migration_status(&self) -> Result<MigrationStatus>;
+
     /// Perform maintenance operations (vacuum, optimize, etc.)
     fn maintenance(&self) -> Result<()>;
     ///
@@ -19,25 +32,384 @@ pub trait JournalStorage {
     /// Load multiple entries within a date range
     fn load_entries(&self, range: DateRange) -> Result<Vec<Entry>>;
 
+    /// Call `f` with each entry in a range, oldest first, loading only
+    /// `ENTRY_BATCH_DAYS` days at a time so walking years of entries doesn't
+    /// hold them all in memory; stops at the first error `f` returns
+    fn for_each_entry(
+        &self,
+        range: DateRange,
+        f: &mut dyn FnMut(Entry) -> Result<()>,
+    ) -> Result<()> {
+        let mut start = range.start();
+        while start <= range.end() {
+            let end = (start + Duration::days(ENTRY_BATCH_DAYS - 1)).min(range.end());
+            for entry in self.load_entries(DateRange::between(start, end))? {
+                f(entry)?;
+            }
+            let Some(next) = end.succ_opt() else {
+                break;
+            };
+            start = next;
+        }
+        Ok(())
+    }
+
     /// List all dates that have entries within a range
     fn list_dates(&self, range: DateRange) -> Result<Vec<NaiveDate>>;
 
     /// Save or update an entry
     fn save_entry(&self, entry: &Entry) -> Result<()>;
 
+    /// Save several entries in one transaction, so either all of them are
+    /// stored or none are
+    fn save_entries(&self, entries: &[Entry]) -> Result<()>;
+
     /// Delete an entry by date
     fn delete_entry(&self, date: NaiveDate) -> Result<()>;
 
+    /// Number of times the entry for `date` was saved or deleted, 0 if never;
+    /// a change between loading and saving an entry means a concurrent edit
+    fn entry_version(&self, date: NaiveDate) -> Result<u64>;
+
+    /// Total of all entry versions, which grows with every save or delete
+    /// made through any connection, e.g. by the daemon while the TUI is open
+    fn change_watermark(&self) -> Result<u64>;
+
+    /// Whether other processes can write to the journal while this storage is
+    /// open, so polling `change_watermark` can pick up their saves
+    fn allows_concurrent_writers(&self) -> bool {
+        true
+    }
+
+    /// When the entry for `date` was first saved and last changed; `None` for
+    /// entries never saved since timestamps were recorded, or deleted since
+    fn entry_timestamps(&self, date: NaiveDate) -> Result<Option<EntryTimestamps>>;
+
     /// Search entries by text content
     fn search_entries(&self, query: &str) -> Result<Vec<Entry>>;
 
+    /// Search individual bullets by content, optionally restricted to the given
+    /// bullet types, task states and date range (empty filters match everything)
+    fn search_bullets(
+        &self,
+        query: &str,
+        types: &[BulletType],
+        states: &[TaskState],
+        range: Option<DateRange>,
+    ) -> Result<Vec<BulletHit>>;
+
+    /// Bullets matching a parsed query, see `Query`
+    fn query_bullets(&self, query: &Query) -> Result<Vec<BulletHit>>;
+
+    /// Database ids of a date's bullets of one type, in document order with
+    /// nested bullets right after their parent
+    fn bullet_ids(&self, date: NaiveDate, bullet_type: &BulletType) -> Result<Vec<i64>>;
+
+    /// A single bullet by database id along with its date, without children
+    fn load_bullet(&self, id: i64) -> Result<Option<(NaiveDate, Bullet)>>;
+
+    /// Database id of the bullet `reference` points to on `date`: an id, or
+    /// `<type>:<n>` for the n-th bullet of a type
+    fn resolve_bullet(&self, date: NaiveDate, reference: &str) -> Result<i64> {
+        if let Ok(id) = reference.parse::<i64>() {
+            return match self.load_bullet(id)? {
+                Some((bullet_date, _)) if bullet_date == date => Ok(id),
+                Some((bullet_date, _)) => {
+                    anyhow::bail!("Bullet {} belongs to {}, not {}", id, bullet_date, date)
+                }
+                None => anyhow::bail!("No bullet with id {}", id),
+            };
+        }
+
+        let Some((type_str, index)) = reference.split_once(':') else {
+            anyhow::bail!(
+                "Invalid bullet reference '{}': use an id or <type>:<n>, e.g. task:2",
+                reference
+            );
+        };
+        let bullet_type: BulletType = type_str.parse().map_err(anyhow::Error::msg)?;
+        let index: usize = index
+            .parse()
+            .with_context(|| format!("Invalid bullet index '{}'", index))?;
+
+        let ids = self.bullet_ids(date, &bullet_type)?;
+        index
+            .checked_sub(1)
+            .and_then(|i| ids.get(i).copied())
+            .with_context(|| {
+                format!(
+                    "{} has {} {} bullets, no #{}",
+                    date,
+                    ids.len(),
+                    bullet_type,
+                    index
+                )
+            })
+    }
+
+    /// Change the content and task state of a single bullet in place, keeping
+    /// its mentions, dependencies and links in sync
+    fn update_bullet(&self, id: i64, content: &str, task_state: Option<TaskState>) -> Result<()>;
+
+    /// Change the type of a bullet and its sub-bullets, keeping their ids.
+    /// Task states carry over between tasks and priorities, start out pending
+    /// for bullets that become one and are cleared for the rest.
+    fn convert_bullet(&self, id: i64, bullet_type: BulletType) -> Result<()>;
+
+    /// Set the state of several tasks or priorities in one transaction,
+    /// changing nothing if any id isn't one
+    fn set_task_states(&self, ids: &[i64], task_state: TaskState) -> Result<()>;
+
+    /// Pending tasks and priorities written before `before`, oldest first
+    fn pending_tasks_before(&self, before: NaiveDate) -> Result<Vec<BulletHit>>;
+
+    /// Every pending task and priority with its id, oldest first, for
+    /// resolving `!after:` references
+    fn pending_tasks(&self) -> Result<Vec<PendingTask>>;
+
+    /// `!after:` references recorded in `task_dependencies` for the tasks
+    /// written on `date`, as the task's id and content with one reference each
+    fn task_dependencies(&self, date: NaiveDate) -> Result<Vec<(i64, String, String)>>;
+
+    /// Pending tasks on any day that the tasks written on `date` wait for,
+    /// keyed by the waiting task's content
+    fn pending_blockers(&self, date: NaiveDate) -> Result<HashMap<String, Vec<PendingTask>>> {
+        let dependencies = self.task_dependencies(date)?;
+        if dependencies.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let pending = self.pending_tasks()?;
+        let mut blocked: HashMap<String, Vec<PendingTask>> = HashMap::new();
+        for (id, content, reference) in dependencies {
+            let blockers = blocked.entry(content).or_default();
+            for task in PendingTask::resolve_blockers(date, &[reference], &pending) {
+                if task.id != Some(id) && !blockers.contains(task) {
+                    blockers.push(task.clone());
+                }
+            }
+        }
+        blocked.retain(|_, blockers| !blockers.is_empty());
+        Ok(blocked)
+    }
+
+    /// People mentioned with `@name`, most frequently mentioned first
+    fn list_people(&self) -> Result<Vec<PersonSummary>>;
+
+    /// Contexts used as `@@context` markers, most used first
+    fn list_contexts(&self) -> Result<Vec<String>>;
+
+    /// Names of the metrics recorded as `#name:value`, most used first
+    fn list_metrics(&self) -> Result<Vec<String>>;
+
+    /// Readings of a metric (name without the leading #), oldest first,
+    /// optionally within a date range
+    fn metric_values(&self, name: &str, range: Option<DateRange>) -> Result<Vec<MetricValue>>;
+
+    /// Every bullet mentioning the given person (name without the leading @)
+    fn find_bullets_mentioning(&self, name: &str) -> Result<Vec<BulletHit>>;
+
+    /// Links collected from bullets, oldest first, optionally within a date range
+    fn list_links(&self, range: Option<DateRange>) -> Result<Vec<Link>>;
+
+    /// Remember the fetched page title of a URL
+    fn set_link_title(&self, url: &str, title: &str) -> Result<()>;
+
+    /// Number of bullets written on each day of a range that has any
+    fn daily_bullet_counts(&self, range: DateRange) -> Result<HashMap<NaiveDate, u64>>;
+
+    /// Task, event and word totals for a range, computed in one query
+    fn period_stats(&self, range: DateRange) -> Result<PeriodStats>;
+
     /// Count total number of entries
     fn count_entries(&self) -> Result<u64>;
 
+    /// Dates of the first and last entries, if any exist
+    fn entry_date_span(&self) -> Result<Option<(NaiveDate, NaiveDate)>>;
+
+    /// Nearest date with bullets after `date` (or before it, unless `forward`)
+    fn adjacent_entry_date(&self, date: NaiveDate, forward: bool) -> Result<Option<NaiveDate>>;
+
     /// Get entries with specific bullet types
     fn find_entries_with_tasks(&self, range: DateRange) -> Result<Vec<Entry>>;
     fn find_entries_with_events(&self, range: DateRange) -> Result<Vec<Entry>>;
     fn find_entries_with_priorities(&self, range: DateRange) -> Result<Vec<Entry>>;
 
     fn refresh_metadata(&self, date: NaiveDate, entry: &Entry) -> Result<()>;
+
+    /// Record an editor save along with the resulting word count and delta
+    fn record_entry_save(
+        &self,
+        date: NaiveDate,
+        saved_at: NaiveDateTime,
+        word_count: usize,
+        word_delta: i64,
+    ) -> Result<()>;
+
+    /// Count recorded saves grouped by hour of day (0-23), ordered by hour
+    fn save_counts_by_hour(&self) -> Result<Vec<(u32, u64)>>;
+
+    /// Record time spent on a task in focus mode
+    fn record_focus_session(
+        &self,
+        date: NaiveDate,
+        task: &str,
+        started_at: NaiveDateTime,
+        duration_seconds: u64,
+        completed: bool,
+    ) -> Result<()>;
+
+    /// Seconds spent in focus mode per task over a range, longest first
+    fn focus_time_by_task(&self, range: DateRange) -> Result<Vec<(String, u64)>>;
+
+    /// Replace every event previously imported from `source` with `events`
+    fn replace_external_events(&self, source: &str, events: &[ExternalEvent]) -> Result<()>;
+
+    /// Imported calendar events on a date, all-day events first, then by start time
+    fn load_external_events(&self, date: NaiveDate) -> Result<Vec<ExternalEvent>>;
+
+    /// Pin a day as a favorite, or unpin it
+    fn set_pinned(&self, date: NaiveDate, pinned: bool) -> Result<()>;
+
+    /// Pinned days, oldest first
+    fn pinned_dates(&self) -> Result<Vec<NaiveDate>>;
+
+    /// Record a 1-5 mood check-in, replacing an earlier one for the same
+    /// date and period
+    fn record_mood(&self, date: NaiveDate, period: MoodPeriod, score: u8) -> Result<()>;
+
+    /// Mood check-ins in the range, by date with mornings first
+    fn load_moods(&self, range: DateRange) -> Result<Vec<MoodCheckIn>>;
+
+    /// Record a bullet as a follow-up, replacing what the same bullet was
+    /// recorded to follow up on before
+    fn record_cross_reference(&self, reference: &CrossReference) -> Result<()>;
+
+    /// Follow-ups recorded for bullets on `date`
+    fn load_cross_references(&self, date: NaiveDate) -> Result<Vec<CrossReference>>;
+
+    /// Note that a journaling prompt was handed out on `date`
+    fn record_prompt_shown(&self, prompt: &str, date: NaiveDate) -> Result<()>;
+
+    /// Journaling prompts handed out on or after `since`
+    fn prompts_shown_since(&self, since: NaiveDate) -> Result<Vec<String>>;
+
+    /// Note that the week starting on `week_start` had its migration review,
+    /// returning false if it already had one
+    fn record_migration_review(&self, week_start: NaiveDate) -> Result<bool>;
+
+    /// Write each of `EXPORTED_TABLES` to a file in `dir` with the database's
+    /// own bulk export, optionally limited to a date range, returning the
+    /// files written; backends without one fail
+    fn export_tables(
+        &self,
+        dir: &Path,
+        format: TableFormat,
+        range: Option<DateRange>,
+    ) -> Result<Vec<PathBuf>>;
+}
+
+/// Days of entries `JournalStorage::for_each_entry` loads at once
+pub const ENTRY_BATCH_DAYS: i64 = 31;
+
+/// File formats raw tables are exported in for analysis elsewhere
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableFormat {
+    /// With a header row
+    Csv,
+    Parquet,
+}
+
+impl TableFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            TableFormat::Csv => "csv",
+            TableFormat::Parquet => "parquet",
+        }
+    }
+}
+
+impl fmt::Display for TableFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.extension())
+    }
+}
+
+/// Tables `export_tables` writes, all keyed by `date` and, apart from
+/// `bullets` itself, by `bullet_id`
+pub const EXPORTED_TABLES: [&str; 5] = ["bullets", "mentions", "contexts", "metrics", "links"];
+
+/// Column reference written beside exported tables as `SCHEMA.md`
+pub const EXPORTED_SCHEMA: &str = "\
+# Journal tables
+
+One file per table. Every table has the `date` (YYYY-MM-DD) of the entry its
+row belongs to; the derived tables point at their bullet through `bullet_id`.
+
+## bullets
+
+| column | type | description |
+| --- | --- | --- |
+| id | integer | bullet id |
+| date | date | entry date |
+| content | text | bullet text as written |
+| type | text | task, event, note, priority, inspiration, insight or misstep |
+| task_state | text | pending, completed, migrated or scheduled; empty for other types |
+| created_at | timestamp | when the bullet was first stored |
+| updated_at | timestamp | when the bullet last changed |
+| time | time | start of a `HH:MM` or `HH:MM-HH:MM` time block |
+| parent_id | integer | id of the bullet this one is nested under |
+| end_time | time | end of a time block |
+| session | time | start of the writing session the bullet belongs to |
+| body | boolean | true for the note holding the entry's free-written body |
+
+## mentions
+
+`@name` mentions: `bullet_id`, `date`, `name` (lowercased, without the `@`).
+
+## contexts
+
+`@@context` markers: `bullet_id`, `date`, `name` (lowercased, without the `@@`).
+
+## metrics
+
+`#name:value` readings: `bullet_id`, `date`, `name` (lowercased, without the
+`#`) and `value` (number).
+
+## links
+
+URLs in bullets: `bullet_id`, `date`, `url` and `title` (the page title, once
+fetched with `journo links --fetch-titles`).
+";
+
+/// `query_bullets` for backends that don't compile queries to SQL: search
+/// narrowed by the first word, types, states and dates, then filtered in Rust
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+pub(crate) fn query_by_search(
+    storage: &dyn JournalStorage,
+    query: &Query,
+) -> Result<Vec<BulletHit>> {
+    let text = query.text.first().map(String::as_str).unwrap_or("");
+    let range = match (query.after, query.before) {
+        (Some(after), Some(before)) => Some(DateRange::between(after, before)),
+        _ => None,
+    };
+    let mut hits = storage.search_bullets(text, &query.types, &query.states, range)?;
+    hits.retain(|hit| query.matches(hit.date, &hit.bullet));
+    Ok(hits)
+}
+
+// Lets a shared storage handle back the `Journal` cache
+impl EntryRepository for Arc<dyn JournalStorage> {
+    fn load(&self, date: NaiveDate) -> Result<Option<Entry>> {
+        self.load_entry(date)
+    }
+
+    fn save(&self, entry: Entry) -> Result<()> {
+        self.save_entry(&entry)
+    }
+
+    fn list_dates(&self, range: DateRange) -> Result<Vec<NaiveDate>> {
+        JournalStorage::list_dates(self.as_ref(), range)
+    }
 }
@@ -7,32 +7,27 @@
 /// ## Usage Examples
 ///
 /// ```rust
-/// use crate::infrastructure::test_utils::test_harness::TestStorage;
+/// use crate::infrastructure::test_utils::test_harness::{self, TestStorage};
 ///
-/// #[test]
-/// fn my_test() {
-///     let test_storage = TestStorage::new();
-///     let storage = test_storage.storage();
-///     
-///     // Use storage for testing...
-///     // Database is automatically cleaned up when test_storage is dropped
-/// }
+/// let test_storage = TestStorage::new();
+/// let storage = test_storage.storage();
+/// // Use storage for testing...
+/// // Database is automatically cleaned up when test_storage is dropped
 ///
 /// // Or using the functional approach:
-/// #[test]
-/// fn my_test() {
-///     test_harness::with_test_storage(|test_storage| {
-///         // Use test_storage here...
-///     });
-/// }
+/// test_harness::with_test_storage(|test_storage| {
+///     // Use test_storage here...
+/// });
 /// ```
 #[cfg(test)]
 pub mod test_harness {
+    use crate::application::{Config, JournalApp};
     use crate::entities::{Bullet, BulletType, Entry};
     use crate::infrastructure::DuckDbStorage;
     use anyhow::Result;
     use chrono::NaiveDate;
     use std::path::PathBuf;
+    use std::sync::Arc;
     use tempfile::TempDir;
 
     /// Test harness that creates a fresh DuckDB instance for each test
@@ -42,6 +37,12 @@ pub mod test_harness {
         _temp_dir: TempDir, // Keep temp dir alive
     }
 
+    impl Default for TestStorage {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
     impl TestStorage {
         /// Create a new test storage instance with fresh DuckDB database
         pub fn new() -> Self {
@@ -116,6 +117,24 @@ pub mod test_harness {
         };
     }
 
+    /// App over a fresh in-memory DuckDB, with its journal directory in a
+    /// temp dir that lives as long as the returned handle
+    pub fn test_app() -> (JournalApp, TempDir) {
+        test_app_with(|_| {})
+    }
+
+    /// Like `test_app`, with `configure` applied to the default config
+    pub fn test_app_with(configure: impl FnOnce(&mut Config)) -> (JournalApp, TempDir) {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let mut config = Config::load(temp_dir.path().into());
+        configure(&mut config);
+        let storage = DuckDbStorage::in_memory().expect("Failed to open in-memory DuckDB");
+        (
+            JournalApp::with_storage(Arc::new(storage), config),
+            temp_dir,
+        )
+    }
+
     /// Run a test with fresh test storage
     pub fn with_test_storage<F, R>(test_fn: F) -> R
     where